@@ -579,6 +579,53 @@ where
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Body that is one of two different body types
+///
+/// Active variant is selected at runtime, both variants are sent as is.
+pub enum EitherBody<L, R> {
+    Left(L),
+    Right(R),
+}
+
+impl<L, R> MessageBody for EitherBody<L, R>
+where
+    L: MessageBody,
+    R: MessageBody,
+{
+    #[inline]
+    fn size(&self) -> BodySize {
+        match self {
+            EitherBody::Left(body) => body.size(),
+            EitherBody::Right(body) => body.size(),
+        }
+    }
+
+    #[inline]
+    fn poll_next_chunk(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Rc<dyn Error>>>> {
+        match self {
+            EitherBody::Left(body) => body.poll_next_chunk(cx),
+            EitherBody::Right(body) => body.poll_next_chunk(cx),
+        }
+    }
+}
+
+impl<L, R> From<EitherBody<L, R>> for Body
+where
+    L: Into<Body>,
+    R: Into<Body>,
+{
+    fn from(body: EitherBody<L, R>) -> Body {
+        match body {
+            EitherBody::Left(body) => body.into(),
+            EitherBody::Right(body) => body.into(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{future::poll_fn, io};
@@ -657,6 +704,29 @@ mod tests {
         assert!(poll_fn(|cx| (&b""[..]).poll_next_chunk(cx)).await.is_none());
     }
 
+    #[ntex::test]
+    async fn test_either() {
+        let mut body = EitherBody::<_, Vec<u8>>::Left(Bytes::from("test"));
+        assert_eq!(body.size(), BodySize::Sized(4));
+        assert_eq!(
+            poll_fn(|cx| body.poll_next_chunk(cx)).await.unwrap().ok(),
+            Some(Bytes::from("test"))
+        );
+        assert!(poll_fn(|cx| body.poll_next_chunk(cx)).await.is_none());
+
+        let mut body = EitherBody::<Bytes, _>::Right(Vec::from("test2"));
+        assert_eq!(body.size(), BodySize::Sized(5));
+        assert_eq!(
+            poll_fn(|cx| body.poll_next_chunk(cx)).await.unwrap().ok(),
+            Some(Bytes::from("test2"))
+        );
+
+        let body = Body::from(EitherBody::<&'static str, Vec<u8>>::Left("test"));
+        assert_eq!(body.get_ref(), b"test");
+        let body = Body::from(EitherBody::<&'static str, _>::Right(Vec::from("test2")));
+        assert_eq!(body.get_ref(), b"test2");
+    }
+
     #[ntex::test]
     async fn test_vec() {
         assert_eq!(Body::from(Vec::from("test")).size(), BodySize::Sized(4));
@@ -27,6 +27,7 @@ pub use http::header::{HeaderName, InvalidHeaderName};
 
 /// Represents supported types of content encodings
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
 pub enum ContentEncoding {
     /// Automatically select encoding based on encoding negotiation
     Auto,
@@ -38,6 +39,8 @@ pub enum ContentEncoding {
     Gzip,
     /// Indicates the identity function (i.e. no compression, nor modification)
     Identity,
+    /// A format using the Zstandard algorithm
+    Zstd,
 }
 
 impl ContentEncoding {
@@ -54,6 +57,7 @@ impl ContentEncoding {
             ContentEncoding::Br => "br",
             ContentEncoding::Gzip => "gzip",
             ContentEncoding::Deflate => "deflate",
+            ContentEncoding::Zstd => "zstd",
             ContentEncoding::Identity | ContentEncoding::Auto => "identity",
         }
     }
@@ -63,6 +67,7 @@ impl ContentEncoding {
     pub fn quality(self) -> f64 {
         match self {
             ContentEncoding::Br => 1.1,
+            ContentEncoding::Zstd => 1.05,
             ContentEncoding::Gzip => 1.0,
             ContentEncoding::Deflate => 0.9,
             ContentEncoding::Identity | ContentEncoding::Auto => 0.1,
@@ -80,6 +85,8 @@ impl<'a> From<&'a str> for ContentEncoding {
             ContentEncoding::Gzip
         } else if s.eq_ignore_ascii_case("deflate") {
             ContentEncoding::Deflate
+        } else if s.eq_ignore_ascii_case("zstd") {
+            ContentEncoding::Zstd
         } else {
             ContentEncoding::Identity
         }
@@ -95,6 +102,9 @@ mod tests {
         assert!(ContentEncoding::Br.is_compressed());
         assert!(!ContentEncoding::Identity.is_compressed());
         assert!(!ContentEncoding::Auto.is_compressed());
+        assert!(ContentEncoding::Zstd.is_compressed());
+        assert_eq!(ContentEncoding::from(" ZSTD"), ContentEncoding::Zstd);
+        assert_eq!(ContentEncoding::Zstd.as_str(), "zstd");
         assert_eq!(format!("{:?}", ContentEncoding::Identity), "Identity");
     }
 }
@@ -37,6 +37,17 @@ impl From<http::HeaderMap> for HeaderMap {
     }
 }
 
+/// Convert `HeaderMap` to a `http::HeaderMap`
+impl From<HeaderMap> for http::HeaderMap {
+    fn from(map: HeaderMap) -> http::HeaderMap {
+        let mut new_map = http::HeaderMap::with_capacity(map.len());
+        for (h, v) in &map {
+            new_map.append(h.clone(), v.into());
+        }
+        new_map
+    }
+}
+
 #[doc(hidden)]
 pub mod compat {
     pub use http::header::InvalidHeaderName;
@@ -675,4 +675,15 @@ mod tests {
             Some(&HeaderValue::from_static("gzip"))
         );
     }
+
+    #[test]
+    fn test_into_http() {
+        let mut map = HeaderMap::new();
+        map.append(ACCEPT_ENCODING, HeaderValue::from_static("gzip"));
+        map.append(ACCEPT_ENCODING, HeaderValue::from_static("br"));
+
+        let map2 = http::HeaderMap::from(map);
+        let values: Vec<_> = map2.get_all(ACCEPT_ENCODING).iter().collect();
+        assert_eq!(values, ["gzip", "br"]);
+    }
 }
@@ -0,0 +1,210 @@
+use std::time::{Duration, Instant};
+use std::{collections::HashMap, io, net::SocketAddr, sync::Mutex};
+
+use ntex_service::cfg::{CfgContext, Configuration};
+use ntex_util::time::{Millis, Seconds};
+
+#[derive(Debug)]
+/// DNS resolver configuration
+///
+/// Resolved addresses could be cached in memory, cache is shared between
+/// all workers that use same configuration. System resolver does not provide
+/// records ttl, so configured ttl is used for all records.
+///
+/// If host resolves to multiple addresses, connector uses "happy eyeballs"
+/// algorithm ([RFC 8305](https://www.rfc-editor.org/rfc/rfc8305)), addresses
+/// of different families are interleaved and next connection attempt starts
+/// if previous one is not completed within attempt delay.
+///
+/// ```rust
+/// use ntex_net::connect::ResolverConfig;
+/// use ntex_service::cfg::SharedCfg;
+/// use ntex_util::time::Seconds;
+///
+/// let cfg: SharedCfg = SharedCfg::new("CLIENT")
+///     .add(ResolverConfig::new().set_cache_size(1024).set_cache_ttl(Seconds(30)))
+///     .into();
+/// ```
+pub struct ResolverConfig {
+    cache_size: usize,
+    cache_ttl: Seconds,
+    negative_ttl: Seconds,
+    attempt_delay: Millis,
+    cache: Mutex<HashMap<String, Entry>>,
+
+    config: CfgContext,
+}
+
+#[derive(Debug)]
+struct Entry {
+    expires: Instant,
+    result: Result<Vec<SocketAddr>, (io::ErrorKind, String)>,
+}
+
+impl Default for ResolverConfig {
+    fn default() -> Self {
+        ResolverConfig::new()
+    }
+}
+
+impl Configuration for ResolverConfig {
+    const NAME: &str = "DNS Resolver Configuration";
+
+    fn ctx(&self) -> &CfgContext {
+        &self.config
+    }
+
+    fn set_ctx(&mut self, ctx: CfgContext) {
+        self.config = ctx;
+    }
+}
+
+impl ResolverConfig {
+    #[must_use]
+    /// Create default resolver configuration, cache is disabled
+    pub fn new() -> Self {
+        ResolverConfig {
+            cache_size: 0,
+            cache_ttl: Seconds(60),
+            negative_ttl: Seconds(5),
+            attempt_delay: Millis(250),
+            cache: Mutex::new(HashMap::new()),
+            config: CfgContext::default(),
+        }
+    }
+
+    #[must_use]
+    /// Set max number of cached hosts
+    ///
+    /// Zero value disables cache. By default cache is disabled.
+    pub fn set_cache_size(mut self, size: usize) -> Self {
+        self.cache_size = size;
+        self
+    }
+
+    #[must_use]
+    /// Set ttl for resolved addresses
+    ///
+    /// By default ttl is 60 seconds.
+    pub fn set_cache_ttl(mut self, ttl: Seconds) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    #[must_use]
+    /// Set ttl for failed lookups
+    ///
+    /// Zero value disables negative caching. By default ttl is 5 seconds.
+    pub fn set_negative_ttl(mut self, ttl: Seconds) -> Self {
+        self.negative_ttl = ttl;
+        self
+    }
+
+    #[must_use]
+    /// Set connection attempt delay
+    ///
+    /// Delay between starting connection attempts to different addresses
+    /// of the host. Zero value disables concurrent attempts, addresses
+    /// are tried sequentially. By default delay is 250 milliseconds.
+    pub fn set_attempt_delay(mut self, delay: Millis) -> Self {
+        self.attempt_delay = delay;
+        self
+    }
+
+    /// Connection attempt delay
+    pub fn attempt_delay(&self) -> Millis {
+        self.attempt_delay
+    }
+
+    /// Get cached lookup result
+    pub(super) fn get(&self, host: &str) -> Option<Result<Vec<SocketAddr>, io::Error>> {
+        if self.cache_size == 0 {
+            return None;
+        }
+
+        let mut cache = self.cache.lock().unwrap();
+        match cache.get(host) {
+            Some(entry) if entry.expires > Instant::now() => Some(
+                entry
+                    .result
+                    .clone()
+                    .map_err(|(kind, msg)| io::Error::new(kind, msg)),
+            ),
+            Some(_) => {
+                cache.remove(host);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Store lookup result
+    pub(super) fn set(&self, host: &str, result: Result<&[SocketAddr], &io::Error>) {
+        let ttl = match result {
+            Ok(addrs) if !addrs.is_empty() => self.cache_ttl,
+            _ => self.negative_ttl,
+        };
+        if self.cache_size == 0 || ttl.is_zero() {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut cache = self.cache.lock().unwrap();
+        if cache.len() >= self.cache_size && !cache.contains_key(host) {
+            cache.retain(|_, entry| entry.expires > now);
+
+            // evict entry that expires first
+            if cache.len() >= self.cache_size
+                && let Some(key) = cache
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.expires)
+                    .map(|(key, _)| key.clone())
+            {
+                cache.remove(&key);
+            }
+        }
+        cache.insert(
+            host.to_string(),
+            Entry {
+                expires: now + Duration::from(ttl),
+                result: result
+                    .map(<[SocketAddr]>::to_vec)
+                    .map_err(|e| (e.kind(), e.to_string())),
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache() {
+        let addr: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+        let err = io::Error::new(io::ErrorKind::NotFound, "not found");
+
+        let cfg = ResolverConfig::new();
+        cfg.set("localhost:8080", Ok(&[addr]));
+        assert!(cfg.get("localhost:8080").is_none());
+
+        let cfg = ResolverConfig::new().set_cache_size(2);
+        cfg.set("localhost:8080", Ok(&[addr]));
+        assert_eq!(cfg.get("localhost:8080").unwrap().unwrap(), vec![addr]);
+
+        cfg.set("unknown:8080", Err(&err));
+        let res = cfg.get("unknown:8080").unwrap();
+        assert_eq!(res.unwrap_err().kind(), io::ErrorKind::NotFound);
+
+        // evict
+        cfg.set("localhost:80", Ok(&[addr]));
+        assert_eq!(cfg.cache.lock().unwrap().len(), 2);
+        assert!(cfg.get("localhost:80").is_some());
+
+        let cfg = ResolverConfig::new()
+            .set_cache_size(2)
+            .set_negative_ttl(Seconds::ZERO);
+        cfg.set("unknown:8080", Err(&err));
+        assert!(cfg.get("unknown:8080").is_none());
+    }
+}
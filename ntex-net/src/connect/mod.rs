@@ -1,10 +1,12 @@
 //! Tcp connector service
+mod config;
 mod error;
 mod message;
 mod resolve;
 mod service;
 mod uri;
 
+pub use self::config::ResolverConfig;
 pub use self::error::{ConnectError, ConnectServiceError};
 pub use self::message::{Address, Connect};
 pub use self::service::{Connector, ConnectorService};
@@ -4,12 +4,13 @@ use ntex_error::Error;
 use ntex_rt::spawn_blocking;
 use ntex_util::future::Either;
 
-use super::{Address, Connect, ConnectError};
+use super::{Address, Connect, ConnectError, ResolverConfig};
 
 /// Lookup ip addresses for provided host
 pub(crate) async fn lookup<T: Address>(
     mut req: Connect<T>,
     tag: &str,
+    cfg: &ResolverConfig,
 ) -> Result<Connect<T>, Error<ConnectError>> {
     if req.addr.is_some() || req.req.addr().is_some() {
         Ok(req)
@@ -25,8 +26,23 @@ pub(crate) async fn lookup<T: Address>(
             format!("{}:{}", req.host(), req.port())
         };
 
-        let fut = spawn_blocking(move || net::ToSocketAddrs::to_socket_addrs(&host));
-        match fut.await {
+        let result = if let Some(result) = cfg.get(&host) {
+            log::trace!("{tag}: DNS Resolver - using cached result for {host:?}");
+            Ok(result.map(Vec::into_iter))
+        } else {
+            spawn_blocking(move || {
+                let result = net::ToSocketAddrs::to_socket_addrs(&host)
+                    .map(|ips| ips.collect::<Vec<_>>());
+                (host, result)
+            })
+            .await
+            .map(|(host, result)| {
+                cfg.set(&host, result.as_deref());
+                result.map(Vec::into_iter)
+            })
+        };
+
+        match result {
             Ok(Ok(ips)) => {
                 let port = req.port();
                 req = req.set_addrs(ips.map(|mut ip| {
@@ -76,16 +92,21 @@ mod tests {
     #[allow(clippy::clone_on_copy)]
     #[ntex::test]
     async fn resolver() {
-        let res = lookup(Connect::new("www.rust-lang.org"), "").await;
+        let cfg = ResolverConfig::new();
+        let res = lookup(Connect::new("www.rust-lang.org"), "", &cfg).await;
         assert!(res.is_ok());
 
-        let res = lookup(Connect::new("---11213"), "").await;
+        let res = lookup(Connect::new("---11213"), "", &cfg).await;
         assert!(res.is_err());
 
         let addr: net::SocketAddr = "127.0.0.1:8080".parse().unwrap();
-        let res = lookup(Connect::new("www.rust-lang.org").set_addrs(vec![addr]), "")
-            .await
-            .unwrap();
+        let res = lookup(
+            Connect::new("www.rust-lang.org").set_addrs(vec![addr]),
+            "",
+            &cfg,
+        )
+        .await
+        .unwrap();
         let addrs: Vec<_> = res.addrs().collect();
         assert_eq!(addrs.len(), 1);
         assert!(addrs.contains(&addr));
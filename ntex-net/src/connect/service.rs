@@ -1,12 +1,16 @@
-use std::{collections::VecDeque, io, marker::PhantomData, net::SocketAddr};
+use std::{
+    collections::VecDeque, future::Future, future::poll_fn, io, marker::PhantomData,
+};
+use std::{net::SocketAddr, pin::Pin, task::Poll};
 
 use ntex_error::Error;
 use ntex_io::{Io, IoConfig, types};
 use ntex_service::cfg::{Cfg, SharedCfg};
 use ntex_service::{Service, ServiceCtx, ServiceFactory};
-use ntex_util::{future::Either, time::timeout_checked};
+use ntex_util::future::Either;
+use ntex_util::time::{Millis, Sleep, timeout_checked};
 
-use super::{Address, Connect, ConnectError, ConnectServiceError, resolve};
+use super::{Address, Connect, ConnectError, ConnectServiceError, ResolverConfig, resolve};
 
 #[derive(Copy, Clone, Debug)]
 /// Basic tcp stream connector
@@ -16,6 +20,7 @@ pub struct Connector<T>(PhantomData<T>);
 /// Basic tcp stream connector
 pub struct ConnectorService<T> {
     cfg: Cfg<IoConfig>,
+    resolver: Cfg<ResolverConfig>,
     shared: SharedCfg,
     _t: PhantomData<T>,
 }
@@ -45,6 +50,7 @@ impl<T> ConnectorService<T> {
     pub fn with(cfg: SharedCfg) -> Self {
         ConnectorService {
             cfg: cfg.get(),
+            resolver: cfg.get(),
             shared: cfg,
             _t: PhantomData,
         }
@@ -65,7 +71,7 @@ impl<T: Address> ConnectorService<T> {
     {
         timeout_checked(self.cfg.connect_timeout(), async {
             // resolve first
-            let msg = resolve::lookup(message.into(), self.shared.tag())
+            let msg = resolve::lookup(message.into(), self.shared.tag(), &self.resolver)
                 .await
                 .map_err(Error::into_error)?;
 
@@ -73,13 +79,25 @@ impl<T: Address> ConnectorService<T> {
             let Connect { req, addr, .. } = msg;
 
             if let Some(addr) = addr {
-                connect(req, port, addr, self.shared.clone())
-                    .await
-                    .map_err(Error::into_error)
+                connect(
+                    req,
+                    port,
+                    addr,
+                    self.resolver.attempt_delay(),
+                    self.shared.clone(),
+                )
+                .await
+                .map_err(Error::into_error)
             } else if let Some(addr) = req.addr() {
-                connect(req, addr.port(), Either::Left(addr), self.shared.clone())
-                    .await
-                    .map_err(Error::into_error)
+                connect(
+                    req,
+                    addr.port(),
+                    Either::Left(addr),
+                    self.resolver.attempt_delay(),
+                    self.shared.clone(),
+                )
+                .await
+                .map_err(Error::into_error)
             } else {
                 log::error!("{}: TCP connector: got unresolved address", self.cfg.tag());
                 Err(ConnectError::Unresolved)
@@ -125,6 +143,7 @@ pub struct Connector2<T>(PhantomData<T>);
 /// Basic tcp stream connector
 pub struct ConnectorService2<T> {
     cfg: Cfg<IoConfig>,
+    resolver: Cfg<ResolverConfig>,
     shared: SharedCfg,
     _t: PhantomData<T>,
 }
@@ -154,6 +173,7 @@ impl<T> ConnectorService2<T> {
     pub fn with(cfg: SharedCfg) -> Self {
         ConnectorService2 {
             cfg: cfg.get(),
+            resolver: cfg.get(),
             shared: cfg,
             _t: PhantomData,
         }
@@ -174,15 +194,30 @@ impl<T: Address> ConnectorService2<T> {
     {
         timeout_checked(self.cfg.connect_timeout(), async {
             // resolve first
-            let msg = resolve::lookup(message.into(), self.shared.tag()).await?;
+            let msg =
+                resolve::lookup(message.into(), self.shared.tag(), &self.resolver).await?;
 
             let port = msg.port();
             let Connect { req, addr, .. } = msg;
 
             if let Some(addr) = addr {
-                connect(req, port, addr, self.shared.clone()).await
+                connect(
+                    req,
+                    port,
+                    addr,
+                    self.resolver.attempt_delay(),
+                    self.shared.clone(),
+                )
+                .await
             } else if let Some(addr) = req.addr() {
-                connect(req, addr.port(), Either::Left(addr), self.shared.clone()).await
+                connect(
+                    req,
+                    addr.port(),
+                    Either::Left(addr),
+                    self.resolver.attempt_delay(),
+                    self.shared.clone(),
+                )
+                .await
             } else {
                 Err(Error::from(ConnectError::Unresolved))
             }
@@ -228,6 +263,7 @@ async fn connect<T: Address>(
     req: T,
     port: u16,
     addr: Either<SocketAddr, VecDeque<SocketAddr>>,
+    delay: Millis,
     cfg: SharedCfg,
 ) -> Result<Io, Error<ConnectError>> {
     log::trace!(
@@ -240,6 +276,11 @@ async fn connect<T: Address>(
         Either::Left(addr) => crate::tcp_connect(addr, cfg.clone())
             .await
             .map_err(ConnectError::from)?,
+        Either::Right(addrs) if !delay.is_zero() => {
+            happy_eyeballs(&req, interleave(addrs), delay, &cfg)
+                .await
+                .map_err(ConnectError::from)?
+        }
         Either::Right(mut addrs) => loop {
             let addr = addrs.pop_front().unwrap();
 
@@ -267,3 +308,128 @@ async fn connect<T: Address>(
     );
     Ok(io)
 }
+
+/// Race connection attempts, next attempt starts after delay
+/// or if all previous attempts failed
+async fn happy_eyeballs<T: Address>(
+    req: &T,
+    mut addrs: VecDeque<SocketAddr>,
+    delay: Millis,
+    cfg: &SharedCfg,
+) -> io::Result<Io> {
+    let timer = Sleep::new(delay);
+    let mut attempts = Vec::new();
+    let mut last_err = None;
+    if let Some(addr) = addrs.pop_front() {
+        attempts.push(Box::pin(crate::tcp_connect(addr, cfg.clone())));
+    }
+
+    poll_fn(|cx| {
+        loop {
+            let mut idx = 0;
+            while idx < attempts.len() {
+                match Pin::as_mut(&mut attempts[idx]).poll(cx) {
+                    Poll::Ready(Ok(io)) => return Poll::Ready(Ok(io)),
+                    Poll::Ready(Err(err)) => {
+                        log::trace!(
+                            "{}: TCP connector - failed to connect to {:?} err: {err:?}",
+                            cfg.tag(),
+                            req.host(),
+                        );
+                        attempts.swap_remove(idx);
+                        last_err = Some(err);
+                    }
+                    Poll::Pending => idx += 1,
+                }
+            }
+
+            if addrs.is_empty() {
+                return if attempts.is_empty() {
+                    Poll::Ready(Err(last_err.take().unwrap_or_else(|| {
+                        io::Error::new(io::ErrorKind::NotFound, "No addresses")
+                    })))
+                } else {
+                    Poll::Pending
+                };
+            }
+
+            // start next attempt
+            if attempts.is_empty() || timer.poll_elapsed(cx).is_ready() {
+                let addr = addrs.pop_front().unwrap();
+                attempts.push(Box::pin(crate::tcp_connect(addr, cfg.clone())));
+                timer.reset(delay);
+            } else {
+                return Poll::Pending;
+            }
+        }
+    })
+    .await
+}
+
+/// Interleave addresses of different families, starting with family
+/// of the first address
+fn interleave(addrs: VecDeque<SocketAddr>) -> VecDeque<SocketAddr> {
+    let first_v6 = addrs.front().is_some_and(SocketAddr::is_ipv6);
+    let (mut first, mut second): (VecDeque<_>, VecDeque<_>) = addrs
+        .into_iter()
+        .partition(|addr| addr.is_ipv6() == first_v6);
+
+    let mut result = VecDeque::with_capacity(first.len() + second.len());
+    while !first.is_empty() || !second.is_empty() {
+        result.extend(first.pop_front());
+        result.extend(second.pop_front());
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interleave() {
+        let addrs: VecDeque<SocketAddr> = [
+            "[::1]:80",
+            "[::2]:80",
+            "[::3]:80",
+            "127.0.0.1:80",
+            "127.0.0.2:80",
+        ]
+        .into_iter()
+        .map(|addr| addr.parse().unwrap())
+        .collect();
+
+        let result: Vec<String> = interleave(addrs)
+            .into_iter()
+            .map(|addr| addr.to_string())
+            .collect();
+        assert_eq!(
+            result,
+            [
+                "[::1]:80",
+                "127.0.0.1:80",
+                "[::2]:80",
+                "127.0.0.2:80",
+                "[::3]:80"
+            ]
+        );
+    }
+
+    #[ntex::test]
+    async fn test_happy_eyeballs() {
+        let lst = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = lst.local_addr().unwrap();
+
+        // unroutable address, attempt does not complete
+        let addrs = VecDeque::from(["10.255.255.1:80".parse().unwrap(), addr]);
+        let io = happy_eyeballs(&"localhost", addrs, Millis(50), &SharedCfg::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            io.query::<types::PeerAddr>()
+                .get()
+                .map(types::PeerAddr::into_inner),
+            Some(addr)
+        );
+    }
+}
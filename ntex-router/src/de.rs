@@ -110,20 +110,14 @@ impl<'de, T: ResourcePath + 'de> Deserializer<'de> for PathDeserializer<'de, T>
     where
         V: Visitor<'de>,
     {
-        if self.path.len() < len {
-            Err(de::value::Error::custom(
-                format!(
-                    "wrong number of parameters: {} expected {}",
-                    self.path.len(),
-                    len
-                )
-                .as_str(),
-            ))
-        } else {
-            visitor.visit_seq(ParamsSeq {
-                params: self.path.iter(),
-            })
-        }
+        // missing trailing parameters are allowed for optional values
+        visitor.visit_seq(ParamsSeq {
+            params: self.path.iter(),
+            missing: Some(Missing {
+                found: self.path.len(),
+                expected: len,
+            }),
+        })
     }
 
     fn deserialize_tuple_struct<V>(
@@ -135,20 +129,14 @@ impl<'de, T: ResourcePath + 'de> Deserializer<'de> for PathDeserializer<'de, T>
     where
         V: Visitor<'de>,
     {
-        if self.path.len() < len {
-            Err(de::value::Error::custom(
-                format!(
-                    "wrong number of parameters: {} expected {}",
-                    self.path.len(),
-                    len
-                )
-                .as_str(),
-            ))
-        } else {
-            visitor.visit_seq(ParamsSeq {
-                params: self.path.iter(),
-            })
-        }
+        // missing trailing parameters are allowed for optional values
+        visitor.visit_seq(ParamsSeq {
+            params: self.path.iter(),
+            missing: Some(Missing {
+                found: self.path.len(),
+                expected: len,
+            }),
+        })
     }
 
     fn deserialize_enum<V>(
@@ -191,6 +179,7 @@ impl<'de, T: ResourcePath + 'de> Deserializer<'de> for PathDeserializer<'de, T>
     {
         visitor.visit_seq(ParamsSeq {
             params: self.path.iter(),
+            missing: None,
         })
     }
 
@@ -422,6 +411,7 @@ impl<'de> Deserializer<'de> for Value<'de> {
 
 struct ParamsSeq<'de, T: ResourcePath> {
     params: PathIter<'de, T>,
+    missing: Option<Missing>,
 }
 
 impl<'de, T: ResourcePath> de::SeqAccess<'de> for ParamsSeq<'de, T> {
@@ -433,11 +423,48 @@ impl<'de, T: ResourcePath> de::SeqAccess<'de> for ParamsSeq<'de, T> {
     {
         match self.params.next() {
             Some(item) => Ok(Some(seed.deserialize(Value { value: item.1 })?)),
-            None => Ok(None),
+            None => match self.missing {
+                Some(missing) => Ok(Some(seed.deserialize(missing)?)),
+                None => Ok(None),
+            },
         }
     }
 }
 
+/// Deserializer for missing trailing parameter, only `Option<T>` is supported
+#[derive(Copy, Clone)]
+struct Missing {
+    found: usize,
+    expected: usize,
+}
+
+impl<'de> Deserializer<'de> for Missing {
+    type Error = de::value::Error;
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_none()
+    }
+
+    fn deserialize_any<V>(self, _: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(de::value::Error::custom(format!(
+            "wrong number of parameters: {} expected {}",
+            self.found, self.expected
+        )))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes
+            byte_buf unit unit_struct newtype_struct seq tuple
+            tuple_struct map struct enum identifier ignored_any
+    }
+}
+
 struct ValueEnum<'de> {
     value: &'de str,
 }
@@ -670,6 +697,27 @@ mod tests {
         assert!(format!("{i:?}").contains("unknown variant"));
     }
 
+    #[test]
+    fn test_extract_optional() {
+        let mut path = Path::new("/5/");
+        path.segments = vec![("id", PathItem::Static("5"))];
+
+        let s: (u32, Option<String>) =
+            de::Deserialize::deserialize(PathDeserializer::new(&path)).unwrap();
+        assert_eq!(s, (5, None));
+
+        path.segments.push(("format", PathItem::Static("json")));
+        let s: (u32, Option<String>) =
+            de::Deserialize::deserialize(PathDeserializer::new(&path)).unwrap();
+        assert_eq!(s, (5, Some("json".to_string())));
+
+        let mut path = Path::new("/5/");
+        path.segments = vec![("id", PathItem::Static("5"))];
+        let s: Result<(u32, String), de::value::Error> =
+            de::Deserialize::deserialize(PathDeserializer::new(&path));
+        assert!(format!("{s:?}").contains("wrong number of parameters: 1 expected 2"));
+    }
+
     #[test]
     fn test_extract_errors() {
         let mut path = Path::new("/name/");
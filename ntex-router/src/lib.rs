@@ -11,12 +11,14 @@
 
 //! Resource path matching library.
 mod de;
+mod metadata;
 mod path;
 mod resource;
 mod router;
 mod tree;
 
 pub use self::de::PathDeserializer;
+pub use self::metadata::Metadata;
 pub use self::path::{Path, PathIter};
 pub use self::resource::ResourceDef;
 pub use self::router::{ResourceId, Router, RouterBuilder};
@@ -0,0 +1,75 @@
+use std::any::{Any, TypeId};
+use std::{collections::HashMap, fmt, sync::Arc};
+
+/// Typed metadata storage of a resource definition
+///
+/// Metadata does not affect path matching, it could be used for attaching
+/// arbitrary information to a resource, for example api documentation.
+#[derive(Clone, Default)]
+pub struct Metadata(HashMap<TypeId, Arc<dyn Any + Send + Sync>>);
+
+impl Metadata {
+    /// Insert value, previous value of the same type is replaced
+    pub fn insert<T: Send + Sync + 'static>(&mut self, val: T) {
+        self.0.insert(TypeId::of::<T>(), Arc::new(val));
+    }
+
+    /// Get a reference to a value of type `T`
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.0
+            .get(&TypeId::of::<T>())
+            .and_then(|val| val.downcast_ref::<T>())
+    }
+
+    /// Check if storage contains value of type `T`
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.0.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Remove value of type `T`
+    pub fn remove<T: 'static>(&mut self) {
+        self.0.remove(&TypeId::of::<T>());
+    }
+
+    /// Check if storage is empty
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Debug for Metadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Metadata")
+            .field("len", &self.0.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Doc(&'static str);
+
+    #[test]
+    fn test_metadata() {
+        let mut m = Metadata::default();
+        assert!(m.is_empty());
+        assert!(m.get::<Doc>().is_none());
+
+        m.insert(Doc("summary"));
+        m.insert(1u32);
+        assert!(m.contains::<Doc>());
+        assert_eq!(m.get::<Doc>(), Some(&Doc("summary")));
+        assert_eq!(m.get::<u32>(), Some(&1));
+
+        let m2 = m.clone();
+        m.insert(Doc("other"));
+        m.remove::<u32>();
+        assert_eq!(m.get::<Doc>(), Some(&Doc("other")));
+        assert!(!m.contains::<u32>());
+        assert_eq!(m2.get::<Doc>(), Some(&Doc("summary")));
+        assert!(format!("{m2:?}").contains("Metadata"));
+    }
+}
@@ -4,7 +4,7 @@ use std::hash::{Hash, Hasher};
 
 use regex::{Regex, escape};
 
-use super::IntoPattern;
+use super::{IntoPattern, Metadata};
 
 #[derive(Clone, Debug)]
 pub(super) struct Segments {
@@ -22,6 +22,7 @@ pub struct ResourceDef {
     name: String,
     pattern: String,
     elements: Vec<PathElement>,
+    metadata: Metadata,
     pub(super) prefix: bool,
 }
 
@@ -93,6 +94,9 @@ impl ResourceDef {
     /// with segment separator. Static segments could be
     /// case insensitive.
     ///
+    /// Trailing dynamic segments could be optional, `/items/{id}/{format?}`
+    /// matches both `/items/1` and `/items/1/json`.
+    ///
     /// Panics if path pattern is malformed.
     pub fn new<T: IntoPattern>(path: T) -> Self {
         let set = path.patterns();
@@ -101,10 +105,14 @@ impl ResourceDef {
         let mut elements = Vec::new();
 
         for path in set {
-            p.clone_from(&path);
-            let (pelems, elems) = ResourceDef::parse(&path);
-            tp.push(pelems);
-            elements = elems;
+            for (idx, path) in expand_optional(&path).into_iter().enumerate() {
+                let (pelems, elems) = ResourceDef::parse(&path);
+                tp.push(pelems);
+                if idx == 0 {
+                    elements = elems;
+                }
+            }
+            p = path;
         }
 
         ResourceDef {
@@ -113,6 +121,7 @@ impl ResourceDef {
             id: 0,
             name: String::new(),
             pattern: p,
+            metadata: Metadata::default(),
             prefix: false,
         }
     }
@@ -159,10 +168,14 @@ impl ResourceDef {
         let mut elements = Vec::new();
 
         for path in patterns {
-            p.clone_from(&path);
-            let (pelems, elems) = ResourceDef::parse(&path);
-            tp.push(pelems);
-            elements = elems;
+            for (idx, path) in expand_optional(&path).into_iter().enumerate() {
+                let (pelems, elems) = ResourceDef::parse(&path);
+                tp.push(pelems);
+                if idx == 0 {
+                    elements = elems;
+                }
+            }
+            p = path;
         }
 
         ResourceDef {
@@ -171,6 +184,7 @@ impl ResourceDef {
             id: 0,
             name: String::new(),
             pattern: p,
+            metadata: Metadata::default(),
             prefix: true,
         }
     }
@@ -185,6 +199,16 @@ impl ResourceDef {
         &mut self.name
     }
 
+    /// Resource metadata
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Mutable reference to a metadata of a resource definition.
+    pub fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+
     /// Path pattern of the resource
     pub fn pattern(&self) -> &str {
         &self.pattern
@@ -454,6 +478,36 @@ impl From<String> for ResourceDef {
     }
 }
 
+/// Expand trailing optional segments, `/{id}/{name?}` expands
+/// to `/{id}/{name}` and `/{id}`. Full pattern goes first.
+fn expand_optional(path: &str) -> Vec<String> {
+    let mut base = path;
+    let mut optional = Vec::new();
+
+    while let Some(head) = base.strip_suffix("?}")
+        && let Some(idx) = head.rfind("/{")
+        && !head[idx + 2..].is_empty()
+        && !head[idx + 2..].contains([':', '{', '}', '/'])
+    {
+        optional.push(&head[idx..]);
+        base = &base[..idx];
+    }
+
+    let mut patterns = Vec::with_capacity(optional.len() + 1);
+    for n in (0..=optional.len()).rev() {
+        let mut p = base.to_string();
+        for seg in optional.iter().rev().take(n) {
+            p.push_str(seg);
+            p.push('}');
+        }
+        if p.is_empty() && !optional.is_empty() {
+            p.push('/');
+        }
+        patterns.push(p);
+    }
+    patterns
+}
+
 pub(crate) fn insert_slash(path: &str) -> String {
     let mut path = path.to_owned();
     if !path.is_empty() && !path.starts_with('/') {
@@ -632,6 +686,37 @@ mod tests {
         assert_eq!(tree.find(&mut Path::new("/user/2345/sdg")), None);
     }
 
+    #[test]
+    fn test_optional_param() {
+        let re = ResourceDef::new("/items/{id}/{format?}");
+        assert_eq!(re.pattern(), "/items/{id}/{format?}");
+        let tree = Tree::new(&re, 1);
+
+        let mut resource = Path::new("/items/5");
+        assert_eq!(tree.find(&mut resource), Some(1));
+        assert_eq!(resource.get("id").unwrap(), "5");
+        assert!(resource.get("format").is_none());
+
+        let mut resource = Path::new("/items/5/json");
+        assert_eq!(tree.find(&mut resource), Some(1));
+        assert_eq!(resource.get("id").unwrap(), "5");
+        assert_eq!(resource.get("format").unwrap(), "json");
+
+        assert_eq!(tree.find(&mut Path::new("/items")), None);
+        assert_eq!(tree.find(&mut Path::new("/items/5/json/1")), None);
+
+        let tree = Tree::new(&ResourceDef::new("/{a?}/{b?}"), 1);
+        assert_eq!(tree.find(&mut Path::new("/")), Some(1));
+        assert_eq!(tree.find(&mut Path::new("/1")), Some(1));
+        let mut resource = Path::new("/1/2");
+        assert_eq!(tree.find(&mut resource), Some(1));
+        assert_eq!(resource.get("b").unwrap(), "2");
+
+        // lazy regex is not an optional segment
+        assert_eq!(expand_optional("/{id:.*?}"), vec!["/{id:.*?}".to_string()]);
+        assert_eq!(expand_optional(""), vec![String::new()]);
+    }
+
     #[cfg(feature = "http")]
     #[test]
     fn test_parse_urlencoded() {
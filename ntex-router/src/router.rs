@@ -1,6 +1,6 @@
 #![allow(clippy::missing_panics_doc)]
 use super::tree::Tree;
-use super::{IntoPattern, Resource, ResourceDef, ResourcePath};
+use super::{IntoPattern, Path, Resource, ResourceDef, ResourcePath};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ResourceId(u16);
@@ -38,6 +38,27 @@ impl<T, U> Router<T, U> {
         }
     }
 
+    /// Find matching resource without modifying resource path.
+    ///
+    /// Returns matched resource definition, path parameters are not extracted.
+    pub fn recognize_ref<R, P>(&self, resource: &R) -> Option<(&T, &ResourceDef)>
+    where
+        R: Resource<P>,
+        P: ResourcePath,
+    {
+        let mut path = Path::new(resource.path());
+        if let Some(idx) = if self.insensitive {
+            self.tree.find_insensitive(&mut path)
+        } else {
+            self.tree.find(&mut path)
+        } {
+            let item = &self.resources[idx];
+            Some((&item.1, &item.0))
+        } else {
+            None
+        }
+    }
+
     pub fn recognize_mut<R, P>(&mut self, resource: &mut R) -> Option<(&mut T, ResourceId)>
     where
         R: Resource<P>,
@@ -83,6 +104,51 @@ impl<T, U> Router<T, U> {
         }
     }
 
+    /// Find matching resource with sync and async checks.
+    ///
+    /// `check_async` is called for matched resource after `check` succeeds,
+    /// if it fails next matching resource is checked.
+    pub async fn recognize_checked_async<R, P, F, A>(
+        &self,
+        resource: &mut R,
+        check: F,
+        check_async: A,
+    ) -> Option<(&T, ResourceId)>
+    where
+        F: Fn(&R, Option<&U>) -> bool,
+        A: AsyncFn(&R, Option<&U>) -> bool,
+        R: Resource<P>,
+        P: ResourcePath,
+    {
+        let mut rejected = Vec::new();
+        loop {
+            // keep path state, failed match must not affect next attempt
+            let path = resource.resource_path();
+            let (skip, segments) = (path.skip, path.segments.clone());
+
+            let idx = {
+                let checked = |idx: usize, res: &R| {
+                    !rejected.contains(&idx) && check(res, self.resources[idx].2.as_ref())
+                };
+                if self.insensitive {
+                    self.tree.find_checked_insensitive(resource, &checked)
+                } else {
+                    self.tree.find_checked(resource, &checked)
+                }
+            }?;
+
+            let item = &self.resources[idx];
+            if check_async(&*resource, item.2.as_ref()).await {
+                return Some((&item.1, ResourceId(item.0.id())));
+            }
+
+            rejected.push(idx);
+            let path = resource.resource_path();
+            path.skip = skip;
+            path.segments = segments;
+        }
+    }
+
     pub fn recognize_mut_checked<R, P, F>(
         &mut self,
         resource: &mut R,
@@ -181,6 +247,9 @@ impl<T, U> RouterBuilder<T, U> {
 
 #[cfg(test)]
 mod tests {
+    use std::task::{Context, Poll, Waker};
+    use std::{future::Future, pin::pin};
+
     use crate::path::Path;
     use crate::router::{ResourceId, Router};
 
@@ -216,6 +285,14 @@ mod tests {
         assert_eq!(info, ResourceId(0));
         assert!(path.is_empty());
 
+        let path = Path::new("/name/value");
+        let (h, rdef) = router.recognize_ref(&path).unwrap();
+        assert_eq!(*h, 11);
+        assert_eq!(rdef.id(), 1);
+        assert_eq!(rdef.pattern(), "/name/{val}");
+        assert!(path.is_empty());
+        assert!(router.recognize_ref(&Path::new("/unknown")).is_none());
+
         let mut path = Path::new("/name/value");
         let (h, info) = router.recognize_mut(&mut path).unwrap();
         assert_eq!(*h, 11);
@@ -284,6 +361,40 @@ mod tests {
         assert_eq!(*h, 11);
     }
 
+    #[test]
+    fn test_recognizer_specificity() {
+        let mut router = Router::<usize>::build();
+        router.path("/files/{tail}*", 9);
+        router.path("/files/{name}", 10);
+        router.path("/files/special", 11);
+        router.path("/files/{name}/info", 12);
+        router.path("/files/special/meta", 13);
+        let router = router.finish();
+
+        let mut path = Path::new("/files/special");
+        let (h, _) = router.recognize(&mut path).unwrap();
+        assert_eq!(*h, 11);
+
+        let mut path = Path::new("/files/other");
+        let (h, _) = router.recognize(&mut path).unwrap();
+        assert_eq!(*h, 10);
+        assert_eq!(path.get("name").unwrap(), "other");
+
+        let mut path = Path::new("/files/special/info");
+        let (h, _) = router.recognize(&mut path).unwrap();
+        assert_eq!(*h, 12);
+        assert_eq!(path.get("name").unwrap(), "special");
+
+        let mut path = Path::new("/files/special/meta");
+        let (h, _) = router.recognize(&mut path).unwrap();
+        assert_eq!(*h, 13);
+
+        let mut path = Path::new("/files/a/b/c");
+        let (h, _) = router.recognize(&mut path).unwrap();
+        assert_eq!(*h, 9);
+        assert_eq!(path.get("tail").unwrap(), "a/b/c");
+    }
+
     #[test]
     fn test_recognizer_3() {
         let mut router = Router::<usize>::build();
@@ -412,6 +523,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_recognizer_checked_async() {
+        fn block_on<F: Future>(fut: F) -> F::Output {
+            let mut cx = Context::from_waker(Waker::noop());
+            let mut fut = pin!(fut);
+            loop {
+                if let Poll::Ready(res) = fut.as_mut().poll(&mut cx) {
+                    return res;
+                }
+            }
+        }
+
+        let mut router = Router::<usize, usize>::build();
+        router.path("/name/{id}", 10).2 = Some(0);
+        router.path("/name/{val}", 11).2 = Some(1);
+        router.path("/name/{val}", 12).2 = Some(2);
+        let router = router.finish();
+
+        let mut p = Path::new("/name/test");
+        let res = block_on(router.recognize_checked_async(
+            &mut p,
+            |_, v| v != Some(&2),
+            async |_, v| v != Some(&0),
+        ));
+        assert_eq!(*res.unwrap().0, 11);
+        assert_eq!(p.get("val"), Some("test"));
+        assert_eq!(p.get("id"), None);
+        assert_eq!(p.segments.len(), 1);
+
+        let mut p = Path::new("/name/test");
+        let res = block_on(router.recognize_checked_async(
+            &mut p,
+            |_, _| true,
+            async |_, _| false,
+        ));
+        assert!(res.is_none());
+        assert!(p.is_empty());
+    }
+
     #[test]
     fn test_recognizer_checked_insensitive() {
         let mut router = Router::<usize, usize>::build();
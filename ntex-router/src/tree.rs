@@ -138,6 +138,41 @@ impl Tree {
             }
             self.items
                 .push(Item::Subtree(Tree::child(key[p..].to_vec(), Some(value))));
+            self.sort_subtrees();
+        }
+    }
+
+    /// Order subtrees by specificity, static segments are checked before
+    /// dynamic segments and tail segments are checked last. Subtrees of
+    /// the same rank keep registration order, values keep their positions.
+    fn sort_subtrees(&mut self) {
+        let positions: Vec<_> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, item)| matches!(item, Item::Subtree(_)).then_some(idx))
+            .collect();
+
+        let mut subtrees: Vec<_> = positions
+            .iter()
+            .map(|idx| mem::replace(&mut self.items[*idx], Item::Subtree(Tree::default())))
+            .collect();
+        subtrees.sort_by_key(|item| match item {
+            Item::Subtree(tree) => tree.rank(),
+            Item::Value(_) => 0,
+        });
+
+        for (idx, item) in positions.into_iter().zip(subtrees) {
+            self.items[idx] = item;
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self.key.first() {
+            Some(Segment::Static(_)) => 0,
+            Some(Segment::Dynamic { tail: false, .. }) => 1,
+            Some(Segment::Dynamic { tail: true, .. }) => 2,
+            None => 3,
         }
     }
 
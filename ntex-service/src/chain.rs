@@ -1,14 +1,18 @@
 #![allow(clippy::type_complexity)]
-use std::{fmt, marker::PhantomData};
+use std::{fmt, marker::PhantomData, time::Duration};
 
 use crate::and_then::{AndThen, AndThenFactory};
 use crate::apply::{Apply, ApplyCtx, ApplyFactory};
 use crate::ctx::ServiceCtx;
-use crate::inspect::{Inspect, InspectErr, InspectErrFactory, InspectFactory};
+use crate::inspect::{
+    Inspect, InspectErr, InspectErrFactory, InspectFactory, Instrument, InstrumentFactory,
+    Tap, TapFactory,
+};
 use crate::map::{Map, MapFactory};
 use crate::map_err::{MapErr, MapErrFactory};
 use crate::map_init_err::MapInitErr;
 use crate::middleware::{ApplyMiddleware, Middleware};
+use crate::or_else::{OrElse, OrElseFactory};
 use crate::then::{Then, ThenFactory};
 use crate::{IntoService, IntoServiceFactory, Pipeline, Service, ServiceFactory};
 
@@ -78,6 +82,30 @@ impl<Svc: Service<Req>, Req> ServiceChain<Svc, Req> {
         }
     }
 
+    /// Call fallback service if call to this service fails with matching error.
+    ///
+    /// Predicate `f` is called with the error of this service, if it returns
+    /// `true` the same request is passed to the fallback service. Request
+    /// must be cloneable, because it is held until call to this service
+    /// resolves.
+    pub fn or_else_service<Next, F, P>(
+        self,
+        service: F,
+        f: P,
+    ) -> ServiceChain<OrElse<Svc, Next, P>, Req>
+    where
+        Self: Sized,
+        F: IntoService<Next, Req>,
+        Next: Service<Req, Response = Svc::Response, Error = Svc::Error>,
+        P: Fn(&Svc::Error) -> bool,
+        Req: Clone,
+    {
+        ServiceChain {
+            service: OrElse::new(self.service, service.into_service(), f),
+            _t: PhantomData,
+        }
+    }
+
     /// Map this service's output to a different type, returning a new service
     /// of the resulting type.
     ///
@@ -138,6 +166,35 @@ impl<Svc: Service<Req>, Req> ServiceChain<Svc, Req> {
         }
     }
 
+    /// Calls a function with a reference to the result of the call.
+    ///
+    /// Returns the original result. Useful for logging or metrics.
+    pub fn tap<F>(self, f: F) -> ServiceChain<Tap<Svc, F>, Req>
+    where
+        Self: Sized,
+        F: Fn(&Result<Svc::Response, Svc::Error>),
+    {
+        ServiceChain {
+            service: Tap::new(self.service, f),
+            _t: PhantomData,
+        }
+    }
+
+    /// Calls a function with elapsed time and a reference to the result of the call.
+    ///
+    /// Time is measured from the call start until the call future resolves.
+    /// Returns the original result.
+    pub fn instrument<F>(self, f: F) -> ServiceChain<Instrument<Svc, F>, Req>
+    where
+        Self: Sized,
+        F: Fn(Duration, &Result<Svc::Response, Svc::Error>),
+    {
+        ServiceChain {
+            service: Instrument::new(self.service, f),
+            _t: PhantomData,
+        }
+    }
+
     /// Use function as middleware for current service.
     ///
     /// Short version of `apply_fn(chain(...), fn)`
@@ -272,6 +329,33 @@ impl<Fac: ServiceFactory<Req, C>, Req, C> ServiceChainFactory<Fac, Req, C> {
         }
     }
 
+    /// Create chain factory that calls fallback service if call to this
+    /// service fails with matching error.
+    pub fn or_else_service<F, U, P>(
+        self,
+        factory: F,
+        f: P,
+    ) -> ServiceChainFactory<OrElseFactory<Fac, U, P>, Req, C>
+    where
+        Self: Sized,
+        C: Clone,
+        Req: Clone,
+        F: IntoServiceFactory<U, Req, C>,
+        U: ServiceFactory<
+                Req,
+                C,
+                Response = Fac::Response,
+                Error = Fac::Error,
+                InitError = Fac::InitError,
+            >,
+        P: Fn(&Fac::Error) -> bool + Clone,
+    {
+        ServiceChainFactory {
+            factory: OrElseFactory::new(self.factory, factory.into_factory(), f),
+            _t: PhantomData,
+        }
+    }
+
     /// Map this service's output to a different type, returning a new service
     /// of the resulting type.
     pub fn map<F, Res>(
@@ -349,6 +433,38 @@ impl<Fac: ServiceFactory<Req, C>, Req, C> ServiceChainFactory<Fac, Req, C> {
         }
     }
 
+    /// Calls a function with a reference to the result of the call.
+    ///
+    /// Returns the original result. Useful for logging or metrics.
+    pub fn tap<F>(self, f: F) -> ServiceChainFactory<TapFactory<Fac, F>, Req, C>
+    where
+        Self: Sized,
+        F: Fn(&Result<Fac::Response, Fac::Error>) + Clone,
+    {
+        ServiceChainFactory {
+            factory: TapFactory::new(self.factory, f),
+            _t: PhantomData,
+        }
+    }
+
+    /// Calls a function with elapsed time and a reference to the result of the call.
+    ///
+    /// Time is measured from the call start until the call future resolves.
+    /// Returns the original result.
+    pub fn instrument<F>(
+        self,
+        f: F,
+    ) -> ServiceChainFactory<InstrumentFactory<Fac, F>, Req, C>
+    where
+        Self: Sized,
+        F: Fn(Duration, &Result<Fac::Response, Fac::Error>) + Clone,
+    {
+        ServiceChainFactory {
+            factory: InstrumentFactory::new(self.factory, f),
+            _t: PhantomData,
+        }
+    }
+
     /// Create and return a new service value asynchronously and wrap into a container
     pub async fn pipeline(&self, cfg: C) -> Result<Pipeline<Fac::Service>, Fac::InitError>
     where
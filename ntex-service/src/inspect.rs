@@ -1,4 +1,4 @@
-use std::{fmt, task::Context};
+use std::{fmt, task::Context, time::Duration, time::Instant};
 
 use super::{Service, ServiceCtx, ServiceFactory};
 
@@ -132,6 +132,133 @@ where
     crate::forward_shutdown!(svc);
 }
 
+/// Service for the `tap` combinator.
+pub struct Tap<S, F> {
+    svc: S,
+    f: F,
+}
+
+impl<S, F> Tap<S, F> {
+    /// Create new `Tap` service combinator.
+    pub(crate) fn new<R>(svc: S, f: F) -> Self
+    where
+        S: Service<R>,
+        F: Fn(&Result<S::Response, S::Error>),
+    {
+        Self { svc, f }
+    }
+}
+
+impl<S, F> Clone for Tap<S, F>
+where
+    S: Clone,
+    F: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Tap {
+            svc: self.svc.clone(),
+            f: self.f.clone(),
+        }
+    }
+}
+
+impl<S, F> fmt::Debug for Tap<S, F>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tap")
+            .field("svc", &self.svc)
+            .field("tap", &std::any::type_name::<F>())
+            .finish()
+    }
+}
+
+impl<S, F, R> Service<R> for Tap<S, F>
+where
+    S: Service<R>,
+    F: Fn(&Result<S::Response, S::Error>),
+{
+    type Response = S::Response;
+    type Error = S::Error;
+
+    #[inline]
+    async fn call(&self, r: R, ctx: ServiceCtx<'_, Self>) -> Result<S::Response, S::Error> {
+        let res = ctx.call(&self.svc, r).await;
+        (self.f)(&res);
+        res
+    }
+
+    crate::forward_ready!(svc);
+    crate::forward_poll!(svc);
+    crate::forward_shutdown!(svc);
+}
+
+/// Service for the `instrument` combinator.
+pub struct Instrument<S, F> {
+    svc: S,
+    f: F,
+}
+
+impl<S, F> Instrument<S, F> {
+    /// Create new `Instrument` service combinator.
+    pub(crate) fn new<R>(svc: S, f: F) -> Self
+    where
+        S: Service<R>,
+        F: Fn(Duration, &Result<S::Response, S::Error>),
+    {
+        Self { svc, f }
+    }
+}
+
+impl<S, F> Clone for Instrument<S, F>
+where
+    S: Clone,
+    F: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Instrument {
+            svc: self.svc.clone(),
+            f: self.f.clone(),
+        }
+    }
+}
+
+impl<S, F> fmt::Debug for Instrument<S, F>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Instrument")
+            .field("svc", &self.svc)
+            .field("instrument", &std::any::type_name::<F>())
+            .finish()
+    }
+}
+
+impl<S, F, R> Service<R> for Instrument<S, F>
+where
+    S: Service<R>,
+    F: Fn(Duration, &Result<S::Response, S::Error>),
+{
+    type Response = S::Response;
+    type Error = S::Error;
+
+    #[inline]
+    async fn call(&self, r: R, ctx: ServiceCtx<'_, Self>) -> Result<S::Response, S::Error> {
+        let start = Instant::now();
+        let res = ctx.call(&self.svc, r).await;
+        (self.f)(start.elapsed(), &res);
+        res
+    }
+
+    crate::forward_ready!(svc);
+    crate::forward_poll!(svc);
+    crate::forward_shutdown!(svc);
+}
+
 /// Factory for the `inspect` combinator.
 pub struct InspectFactory<S, F> {
     s: S,
@@ -248,6 +375,122 @@ where
     }
 }
 
+/// Factory for the `tap` combinator.
+pub struct TapFactory<S, F> {
+    s: S,
+    f: F,
+}
+
+impl<S, F> TapFactory<S, F> {
+    /// Create new `TapFactory` factory instance.
+    pub(crate) fn new(s: S, f: F) -> Self {
+        Self { s, f }
+    }
+}
+
+impl<S, F> Clone for TapFactory<S, F>
+where
+    S: Clone,
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            s: self.s.clone(),
+            f: self.f.clone(),
+        }
+    }
+}
+
+impl<S, F> fmt::Debug for TapFactory<S, F>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TapFactory")
+            .field("factory", &self.s)
+            .field("tap", &std::any::type_name::<F>())
+            .finish()
+    }
+}
+
+impl<S, F, R, C> ServiceFactory<R, C> for TapFactory<S, F>
+where
+    S: ServiceFactory<R, C>,
+    F: Fn(&Result<S::Response, S::Error>) + Clone,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+
+    type Service = Tap<S::Service, F>;
+    type InitError = S::InitError;
+
+    #[inline]
+    async fn create(&self, cfg: C) -> Result<Self::Service, Self::InitError> {
+        self.s.create(cfg).await.map(|svc| Tap {
+            svc,
+            f: self.f.clone(),
+        })
+    }
+}
+
+/// Factory for the `instrument` combinator.
+pub struct InstrumentFactory<S, F> {
+    s: S,
+    f: F,
+}
+
+impl<S, F> InstrumentFactory<S, F> {
+    /// Create new `InstrumentFactory` factory instance.
+    pub(crate) fn new(s: S, f: F) -> Self {
+        Self { s, f }
+    }
+}
+
+impl<S, F> Clone for InstrumentFactory<S, F>
+where
+    S: Clone,
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            s: self.s.clone(),
+            f: self.f.clone(),
+        }
+    }
+}
+
+impl<S, F> fmt::Debug for InstrumentFactory<S, F>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InstrumentFactory")
+            .field("factory", &self.s)
+            .field("instrument", &std::any::type_name::<F>())
+            .finish()
+    }
+}
+
+impl<S, F, R, C> ServiceFactory<R, C> for InstrumentFactory<S, F>
+where
+    S: ServiceFactory<R, C>,
+    F: Fn(Duration, &Result<S::Response, S::Error>) + Clone,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+
+    type Service = Instrument<S::Service, F>;
+    type InitError = S::InitError;
+
+    #[inline]
+    async fn create(&self, cfg: C) -> Result<Self::Service, Self::InitError> {
+        self.s.create(cfg).await.map(|svc| Instrument {
+            svc,
+            f: self.f.clone(),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{cell::Cell, rc::Rc};
@@ -374,4 +617,91 @@ mod tests {
         srv.shutdown().await;
         assert_eq!(cnt.get(), 2);
     }
+
+    #[ntex::test]
+    async fn test_tap_service() {
+        let cnt = Rc::new(Cell::new(0));
+        let cnt2 = cnt.clone();
+        let srv = chain(Srv(false, false, cnt.clone()))
+            .tap(move |res: &Result<(), ()>| {
+                assert!(res.is_ok());
+                cnt2.set(cnt2.get() + 1);
+            })
+            .clone()
+            .into_pipeline();
+        let res = srv.call(()).await;
+        assert!(res.is_ok());
+
+        let _ = format!("{srv:?}");
+
+        srv.shutdown().await;
+        assert_eq!(cnt.get(), 2);
+    }
+
+    #[ntex::test]
+    async fn test_tap_factory() {
+        let cnt = Rc::new(Cell::new(0));
+        let cnt2 = cnt.clone();
+        let cnt3 = cnt.clone();
+        let new_srv = chain_factory(fn_factory(async move || {
+            Ok::<_, ()>(Srv(true, false, cnt2.clone()))
+        }))
+        .tap(move |res: &Result<(), ()>| {
+            assert!(res.is_err());
+            cnt3.set(cnt3.get() + 1);
+        })
+        .clone();
+        let srv = new_srv.pipeline(&()).await.unwrap();
+        let res = srv.call(()).await;
+        assert_eq!(res, Err(()));
+        let _ = format!("{new_srv:?}");
+        srv.shutdown().await;
+        assert_eq!(cnt.get(), 2);
+    }
+
+    #[ntex::test]
+    async fn test_instrument_service() {
+        let cnt = Rc::new(Cell::new(0));
+        let elapsed = Rc::new(Cell::new(Duration::ZERO));
+        let (cnt2, elapsed2) = (cnt.clone(), elapsed.clone());
+        let srv = chain(crate::fn_service(async |d: u32| {
+            ntex::time::sleep(ntex::time::Millis(d)).await;
+            Ok::<_, ()>(d)
+        }))
+        .instrument(move |dur, res: &Result<u32, ()>| {
+            assert!(res.is_ok());
+            cnt2.set(cnt2.get() + 1);
+            elapsed2.set(dur);
+        })
+        .clone()
+        .into_pipeline();
+        let res = srv.call(25).await;
+        assert_eq!(res, Ok(25));
+        assert_eq!(cnt.get(), 1);
+        assert!(elapsed.get() >= Duration::from_millis(25));
+
+        let _ = format!("{srv:?}");
+        srv.shutdown().await;
+    }
+
+    #[ntex::test]
+    async fn test_instrument_factory() {
+        let cnt = Rc::new(Cell::new(0));
+        let cnt2 = cnt.clone();
+        let cnt3 = cnt.clone();
+        let new_srv = chain_factory(fn_factory(async move || {
+            Ok::<_, ()>(Srv(true, false, cnt2.clone()))
+        }))
+        .instrument(move |_, res: &Result<(), ()>| {
+            assert!(res.is_err());
+            cnt3.set(cnt3.get() + 1);
+        })
+        .clone();
+        let srv = new_srv.pipeline(&()).await.unwrap();
+        let res = srv.call(()).await;
+        assert_eq!(res, Err(()));
+        let _ = format!("{new_srv:?}");
+        srv.shutdown().await;
+        assert_eq!(cnt.get(), 2);
+    }
 }
@@ -22,7 +22,10 @@ mod map_config;
 mod map_err;
 mod map_init_err;
 mod middleware;
+mod or_else;
+mod owned;
 mod pipeline;
+mod shared;
 mod then;
 mod util;
 
@@ -31,9 +34,11 @@ pub use self::chain::{chain, chain_factory};
 pub use self::ctx::ServiceCtx;
 pub use self::fn_service::{fn_factory, fn_factory_with_config, fn_service};
 pub use self::fn_shutdown::fn_shutdown;
-pub use self::map_config::{map_config, unit_config};
+pub use self::map_config::{map_config, provide_config, unit_config};
 pub use self::middleware::{Identity, Middleware, Stack, apply, fn_layer};
+pub use self::owned::{cloned, owned};
 pub use self::pipeline::{Pipeline, PipelineBinding, PipelineCall, PipelineSvc};
+pub use self::shared::shared;
 
 #[allow(unused_variables)]
 /// An asynchronous function from a `Request` to a `Response`.
@@ -385,11 +390,16 @@ pub mod dev {
         FnService, FnServiceConfig, FnServiceFactory, FnServiceNoConfig,
     };
     pub use crate::fn_shutdown::FnShutdown;
-    pub use crate::inspect::{InspectErr, InspectErrFactory};
+    pub use crate::inspect::{
+        InspectErr, InspectErrFactory, Instrument, InstrumentFactory, Tap, TapFactory,
+    };
     pub use crate::map::{Map, MapFactory};
-    pub use crate::map_config::{MapConfig, UnitConfig};
+    pub use crate::map_config::{MapConfig, ProvideConfig, UnitConfig};
     pub use crate::map_err::{MapErr, MapErrFactory};
     pub use crate::map_init_err::MapInitErr;
     pub use crate::middleware::{ApplyMiddleware, FnMiddleware};
+    pub use crate::or_else::{OrElse, OrElseFactory};
+    pub use crate::owned::{Cloned, Owned};
+    pub use crate::shared::{Shared, SharedService};
     pub use crate::then::{Then, ThenFactory};
 }
@@ -24,6 +24,19 @@ where
     UnitConfig::new(factory.into_factory())
 }
 
+/// Provide fixed config for service factory
+///
+/// Resulting service factory ignores external config argument and
+/// uses clone of provided config for service construction.
+pub fn provide_config<T, R, U, C>(factory: U, cfg: C) -> ProvideConfig<T, C>
+where
+    T: ServiceFactory<R, C>,
+    U: IntoServiceFactory<T, R, C>,
+    C: Clone,
+{
+    ProvideConfig::new(factory.into_factory(), cfg)
+}
+
 /// `map_config()` adapter service factory
 pub struct MapConfig<A, F, C, C2> {
     a: A,
@@ -112,6 +125,36 @@ where
     }
 }
 
+#[derive(Clone, Debug)]
+/// `provide_config()` config combinator
+pub struct ProvideConfig<A, C> {
+    factory: A,
+    cfg: C,
+}
+
+impl<A, C> ProvideConfig<A, C> {
+    /// Create new `ProvideConfig` combinator
+    pub(crate) fn new(factory: A, cfg: C) -> Self {
+        Self { factory, cfg }
+    }
+}
+
+impl<A, R, C, C2> ServiceFactory<R, C2> for ProvideConfig<A, C>
+where
+    A: ServiceFactory<R, C>,
+    C: Clone,
+{
+    type Response = A::Response;
+    type Error = A::Error;
+
+    type Service = A::Service;
+    type InitError = A::InitError;
+
+    async fn create(&self, _: C2) -> Result<Self::Service, Self::InitError> {
+        self.factory.create(self.cfg.clone()).await
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::redundant_closure)]
 mod tests {
@@ -148,4 +191,21 @@ mod tests {
             .unwrap();
         assert_eq!(svc.call(1).await.unwrap(), 1);
     }
+
+    #[ntex::test]
+    async fn test_provide_config() {
+        let factory = provide_config(
+            crate::fn_factory_with_config(|cfg: usize| async move {
+                Ok::<_, ()>(fn_service(move |item: usize| async move {
+                    Ok::<_, ()>(item + cfg)
+                }))
+            }),
+            10,
+        )
+        .clone();
+        let _ = format!("{factory:?}");
+
+        let svc = factory.pipeline(()).await.unwrap();
+        assert_eq!(svc.call(1).await.unwrap(), 11);
+    }
 }
@@ -0,0 +1,275 @@
+use std::fmt;
+
+use super::{Service, ServiceCtx, ServiceFactory, util};
+
+/// Service for the `or_else_service` combinator, calls fallback service
+/// if call to the primary service fails with matching error.
+///
+/// This is created by the `ServiceChain::or_else_service` method.
+pub struct OrElse<A, B, F> {
+    svc1: A,
+    svc2: B,
+    f: F,
+}
+
+impl<A, B, F> OrElse<A, B, F> {
+    /// Create new `.or_else_service()` combinator
+    pub(crate) fn new(svc1: A, svc2: B, f: F) -> Self {
+        Self { svc1, svc2, f }
+    }
+}
+
+impl<A, B, F> Clone for OrElse<A, B, F>
+where
+    A: Clone,
+    B: Clone,
+    F: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        OrElse {
+            svc1: self.svc1.clone(),
+            svc2: self.svc2.clone(),
+            f: self.f.clone(),
+        }
+    }
+}
+
+impl<A, B, F> fmt::Debug for OrElse<A, B, F>
+where
+    A: fmt::Debug,
+    B: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OrElse")
+            .field("svc1", &self.svc1)
+            .field("svc2", &self.svc2)
+            .field("predicate", &std::any::type_name::<F>())
+            .finish()
+    }
+}
+
+impl<A, B, F, R> Service<R> for OrElse<A, B, F>
+where
+    A: Service<R>,
+    B: Service<R, Response = A::Response, Error = A::Error>,
+    F: Fn(&A::Error) -> bool,
+    R: Clone,
+{
+    type Response = A::Response;
+    type Error = A::Error;
+
+    #[inline]
+    async fn ready(&self, ctx: ServiceCtx<'_, Self>) -> Result<(), Self::Error> {
+        util::ready(&self.svc1, &self.svc2, ctx).await
+    }
+
+    #[inline]
+    fn poll(&self, cx: &mut std::task::Context<'_>) -> Result<(), Self::Error> {
+        self.svc1.poll(cx)?;
+        self.svc2.poll(cx)
+    }
+
+    #[inline]
+    async fn shutdown(&self) {
+        util::shutdown(&self.svc1, &self.svc2).await;
+    }
+
+    #[inline]
+    async fn call(
+        &self,
+        req: R,
+        ctx: ServiceCtx<'_, Self>,
+    ) -> Result<Self::Response, Self::Error> {
+        match ctx.call(&self.svc1, req.clone()).await {
+            Err(err) if (self.f)(&err) => ctx.call(&self.svc2, req).await,
+            res => res,
+        }
+    }
+}
+
+/// `.or_else_service()` service factory combinator
+pub struct OrElseFactory<A, B, F> {
+    svc1: A,
+    svc2: B,
+    f: F,
+}
+
+impl<A, B, F> OrElseFactory<A, B, F> {
+    /// Create new factory for `OrElse` combinator
+    pub(crate) fn new(svc1: A, svc2: B, f: F) -> Self {
+        Self { svc1, svc2, f }
+    }
+}
+
+impl<A, B, F> Clone for OrElseFactory<A, B, F>
+where
+    A: Clone,
+    B: Clone,
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            svc1: self.svc1.clone(),
+            svc2: self.svc2.clone(),
+            f: self.f.clone(),
+        }
+    }
+}
+
+impl<A, B, F> fmt::Debug for OrElseFactory<A, B, F>
+where
+    A: fmt::Debug,
+    B: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OrElseFactory")
+            .field("svc1", &self.svc1)
+            .field("svc2", &self.svc2)
+            .field("predicate", &std::any::type_name::<F>())
+            .finish()
+    }
+}
+
+impl<A, B, F, R, C> ServiceFactory<R, C> for OrElseFactory<A, B, F>
+where
+    A: ServiceFactory<R, C>,
+    B: ServiceFactory<
+            R,
+            C,
+            Response = A::Response,
+            Error = A::Error,
+            InitError = A::InitError,
+        >,
+    F: Fn(&A::Error) -> bool + Clone,
+    R: Clone,
+    C: Clone,
+{
+    type Response = A::Response;
+    type Error = A::Error;
+
+    type Service = OrElse<A::Service, B::Service, F>;
+    type InitError = A::InitError;
+
+    async fn create(&self, cfg: C) -> Result<Self::Service, Self::InitError> {
+        Ok(OrElse {
+            svc1: self.svc1.create(cfg.clone()).await?,
+            svc2: self.svc2.create(cfg).await?,
+            f: self.f.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use ntex::util::lazy;
+
+    use crate::{Service, ServiceCtx, chain, chain_factory, fn_factory};
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    enum Error {
+        Miss,
+        Failed,
+    }
+
+    #[derive(Debug, Clone)]
+    struct Cache(Rc<Cell<usize>>);
+
+    impl Service<&'static str> for Cache {
+        type Response = &'static str;
+        type Error = Error;
+
+        async fn ready(&self, _: ServiceCtx<'_, Self>) -> Result<(), Self::Error> {
+            self.0.set(self.0.get() + 1);
+            Ok(())
+        }
+
+        async fn call(
+            &self,
+            req: &'static str,
+            _: ServiceCtx<'_, Self>,
+        ) -> Result<&'static str, Error> {
+            match req {
+                "cached" => Ok("cache"),
+                "failed" => Err(Error::Failed),
+                _ => Err(Error::Miss),
+            }
+        }
+
+        async fn shutdown(&self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct Origin(Rc<Cell<usize>>);
+
+    impl Service<&'static str> for Origin {
+        type Response = &'static str;
+        type Error = Error;
+
+        async fn ready(&self, _: ServiceCtx<'_, Self>) -> Result<(), Self::Error> {
+            self.0.set(self.0.get() + 1);
+            Ok(())
+        }
+
+        async fn call(
+            &self,
+            req: &'static str,
+            _: ServiceCtx<'_, Self>,
+        ) -> Result<&'static str, Error> {
+            Ok(req)
+        }
+
+        async fn shutdown(&self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[ntex::test]
+    async fn test_ready() {
+        let cnt = Rc::new(Cell::new(0));
+        let srv = chain(Cache(cnt.clone()))
+            .or_else_service(Origin(cnt.clone()), |err| *err == Error::Miss)
+            .into_pipeline();
+        assert_eq!(srv.ready().await, Ok(()));
+        assert_eq!(cnt.get(), 2);
+
+        assert!(lazy(|cx| srv.poll(cx)).await.is_ok());
+
+        srv.shutdown().await;
+        assert_eq!(cnt.get(), 4);
+        assert!(format!("{srv:?}").contains("OrElse"));
+    }
+
+    #[ntex::test]
+    async fn test_call() {
+        let srv = chain(Cache(Rc::default()))
+            .or_else_service(Origin(Rc::default()), |err| *err == Error::Miss)
+            .clone()
+            .into_pipeline();
+
+        assert_eq!(srv.call("cached").await, Ok("cache"));
+        assert_eq!(srv.call("origin").await, Ok("origin"));
+        assert_eq!(srv.call("failed").await, Err(Error::Failed));
+    }
+
+    #[ntex::test]
+    async fn test_factory() {
+        let factory =
+            chain_factory(fn_factory(|| async { Ok::<_, ()>(Cache(Rc::default())) }))
+                .or_else_service(
+                    fn_factory(|| async { Ok::<_, ()>(Origin(Rc::default())) }),
+                    |err: &Error| *err == Error::Miss,
+                )
+                .clone();
+        assert!(format!("{factory:?}").contains("OrElseFactory"));
+
+        let srv = factory.pipeline(&()).await.unwrap();
+        assert_eq!(srv.call("cached").await, Ok("cache"));
+        assert_eq!(srv.call("origin").await, Ok("origin"));
+        assert_eq!(srv.call("failed").await, Err(Error::Failed));
+    }
+}
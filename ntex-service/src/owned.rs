@@ -0,0 +1,228 @@
+use std::{fmt, marker::PhantomData, task::Context};
+
+use super::{Service, ServiceCtx};
+
+/// Adapt `Service<&mut T>` service to `Service<T>` service
+///
+/// Resulting service takes ownership of the request and passes mutable
+/// reference to the inner service. Request is dropped after inner service
+/// completes.
+///
+/// ```rust
+/// use ntex_service::{Pipeline, Service, ServiceCtx, owned};
+///
+/// struct Normalize;
+///
+/// impl<'a> Service<&'a mut String> for Normalize {
+///     type Response = usize;
+///     type Error = ();
+///
+///     async fn call(&self, req: &'a mut String, _: ServiceCtx<'_, Self>) -> Result<usize, ()> {
+///         req.make_ascii_lowercase();
+///         Ok(req.len())
+///     }
+/// }
+///
+/// #[ntex::main]
+/// async fn main() {
+///     let svc = Pipeline::new(owned(Normalize));
+///     assert_eq!(svc.call("TEST".to_string()).await, Ok(4));
+/// }
+/// ```
+pub fn owned<S, T>(service: S) -> Owned<S, T>
+where
+    S: for<'a> Service<&'a mut T>,
+{
+    Owned::new(service)
+}
+
+/// Adapt `Service<T>` service to `Service<&mut T>` service
+///
+/// Resulting service clones referenced request and passes it to the inner service.
+pub fn cloned<S, T>(service: S) -> Cloned<S, T>
+where
+    S: Service<T>,
+    T: Clone,
+{
+    Cloned::new(service)
+}
+
+/// `Service<T>` adapter for `Service<&mut T>` service
+pub struct Owned<S, T> {
+    service: S,
+    _t: PhantomData<fn(T)>,
+}
+
+impl<S, T> Owned<S, T> {
+    /// Create new `Owned` adapter
+    pub fn new(service: S) -> Self {
+        Self {
+            service,
+            _t: PhantomData,
+        }
+    }
+
+    /// Get reference to inner service
+    pub fn get_ref(&self) -> &S {
+        &self.service
+    }
+}
+
+impl<S: Clone, T> Clone for Owned<S, T> {
+    fn clone(&self) -> Self {
+        Self::new(self.service.clone())
+    }
+}
+
+impl<S: fmt::Debug, T> fmt::Debug for Owned<S, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Owned")
+            .field("service", &self.service)
+            .finish()
+    }
+}
+
+impl<S, T, Res, Err> Service<T> for Owned<S, T>
+where
+    S: for<'a> Service<&'a mut T, Response = Res, Error = Err>,
+{
+    type Response = Res;
+    type Error = Err;
+
+    #[inline]
+    async fn ready(&self, ctx: ServiceCtx<'_, Self>) -> Result<(), Err> {
+        ctx.ready::<S, &mut T>(&self.service).await
+    }
+
+    #[inline]
+    fn poll(&self, cx: &mut Context<'_>) -> Result<(), Err> {
+        Service::<&mut T>::poll(&self.service, cx)
+    }
+
+    #[inline]
+    async fn shutdown(&self) {
+        Service::<&mut T>::shutdown(&self.service).await;
+    }
+
+    #[inline]
+    async fn call(&self, mut req: T, ctx: ServiceCtx<'_, Self>) -> Result<Res, Err> {
+        let (idx, waiters) = ctx.inner();
+        self.service
+            .call(&mut req, ServiceCtx::new(idx, waiters))
+            .await
+    }
+}
+
+/// `Service<&mut T>` adapter for `Service<T>` service
+pub struct Cloned<S, T> {
+    service: S,
+    _t: PhantomData<fn(T)>,
+}
+
+impl<S, T> Cloned<S, T> {
+    /// Create new `Cloned` adapter
+    pub fn new(service: S) -> Self {
+        Self {
+            service,
+            _t: PhantomData,
+        }
+    }
+
+    /// Get reference to inner service
+    pub fn get_ref(&self) -> &S {
+        &self.service
+    }
+}
+
+impl<S: Clone, T> Clone for Cloned<S, T> {
+    fn clone(&self) -> Self {
+        Self::new(self.service.clone())
+    }
+}
+
+impl<S: fmt::Debug, T> fmt::Debug for Cloned<S, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cloned")
+            .field("service", &self.service)
+            .finish()
+    }
+}
+
+impl<'r, S, T> Service<&'r mut T> for Cloned<S, T>
+where
+    S: Service<T>,
+    T: Clone,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+
+    crate::forward_ready!(service);
+    crate::forward_poll!(service);
+    crate::forward_shutdown!(service);
+
+    #[inline]
+    async fn call(
+        &self,
+        req: &'r mut T,
+        ctx: ServiceCtx<'_, Self>,
+    ) -> Result<S::Response, S::Error> {
+        let (idx, waiters) = ctx.inner();
+        self.service
+            .call(req.clone(), ServiceCtx::new(idx, waiters))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Pipeline, ServiceFactory, fn_factory, fn_service};
+
+    #[derive(Debug, Clone)]
+    struct Normalize;
+
+    impl<'a> Service<&'a mut String> for Normalize {
+        type Response = usize;
+        type Error = ();
+
+        async fn call(
+            &self,
+            req: &'a mut String,
+            _: ServiceCtx<'_, Self>,
+        ) -> Result<usize, ()> {
+            req.make_ascii_lowercase();
+            Ok(req.len())
+        }
+    }
+
+    #[ntex::test]
+    async fn test_owned() {
+        let svc = Pipeline::new(owned(Normalize).clone());
+        assert_eq!(svc.ready().await, Ok(()));
+        assert_eq!(svc.call("TEST".to_string()).await, Ok(4));
+        assert!(format!("{:?}", svc.get_ref()).contains("Owned"));
+        svc.shutdown().await;
+
+        let factory = fn_factory(async || Ok::<_, ()>(owned(Normalize)));
+        let svc = factory.pipeline(()).await.unwrap();
+        assert_eq!(svc.call("AB".to_string()).await, Ok(2));
+    }
+
+    #[ntex::test]
+    async fn test_cloned() {
+        let svc = Pipeline::new(
+            cloned(fn_service(|s: String| async move { Ok::<_, ()>(s.len()) })).clone(),
+        );
+        let mut req = "abc".to_string();
+        assert_eq!(svc.ready().await, Ok(()));
+        assert_eq!(svc.call(&mut req).await, Ok(3));
+        assert_eq!(req, "abc");
+        assert!(format!("{:?}", svc.get_ref()).contains("Cloned"));
+
+        // round trip
+        let svc = Pipeline::new(owned(cloned(fn_service(|s: String| async move {
+            Ok::<_, ()>(s.len())
+        }))));
+        assert_eq!(svc.call("abcd".to_string()).await, Ok(4));
+    }
+}
@@ -0,0 +1,342 @@
+use std::task::{Context, Poll, Waker};
+use std::{cell::Cell, cell::RefCell, fmt, future::poll_fn, rc::Rc};
+
+use super::{IntoServiceFactory, Service, ServiceCtx, ServiceFactory};
+
+/// Create service once and share it between all `create()` calls
+///
+/// Inner factory is called only once, for the first `create()` call.
+/// Config of the subsequent calls is ignored, instead service created for
+/// the first call is returned. If inner factory fails, next `create()` call
+/// tries to create service again.
+///
+/// Inner service is shut down once, by the last `shutdown()` call of
+/// the created services. Services that are dropped without `shutdown()` call
+/// are not counted.
+///
+/// ```rust
+/// use ntex_service::{ServiceFactory, fn_factory, fn_service, shared};
+///
+/// #[ntex::main]
+/// async fn main() {
+///     let factory = shared(fn_factory(|| async {
+///         // expensive initialization
+///         Ok::<_, ()>(fn_service(|req: usize| async move { Ok::<_, ()>(req + 1) }))
+///     }));
+///
+///     // both services use same instance of inner service
+///     let srv1 = factory.pipeline(()).await.unwrap();
+///     let srv2 = factory.pipeline(()).await.unwrap();
+///     assert_eq!(srv1.call(1).await, Ok(2));
+///     assert_eq!(srv2.call(1).await, Ok(2));
+/// }
+/// ```
+pub fn shared<T, R, C, U>(factory: U) -> Shared<T, R, C>
+where
+    T: ServiceFactory<R, C>,
+    U: IntoServiceFactory<T, R, C>,
+{
+    Shared::new(factory.into_factory())
+}
+
+/// `shared()` service factory combinator
+pub struct Shared<T: ServiceFactory<R, C>, R, C>(Rc<Inner<T, R, C>>);
+
+struct Inner<T: ServiceFactory<R, C>, R, C> {
+    factory: T,
+    service: RefCell<Option<Rc<SharedInner<T::Service>>>>,
+    creating: Cell<bool>,
+    waiters: RefCell<Vec<Waker>>,
+}
+
+impl<T, R, C> Shared<T, R, C>
+where
+    T: ServiceFactory<R, C>,
+{
+    /// Create new `Shared` combinator
+    pub(crate) fn new(factory: T) -> Self {
+        Self(Rc::new(Inner {
+            factory,
+            service: RefCell::new(None),
+            creating: Cell::new(false),
+            waiters: RefCell::new(Vec::new()),
+        }))
+    }
+}
+
+impl<T, R, C> Clone for Shared<T, R, C>
+where
+    T: ServiceFactory<R, C>,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T, R, C> fmt::Debug for Shared<T, R, C>
+where
+    T: ServiceFactory<R, C> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Shared")
+            .field("factory", &self.0.factory)
+            .field("created", &self.0.service.borrow().is_some())
+            .finish()
+    }
+}
+
+impl<T, R, C> ServiceFactory<R, C> for Shared<T, R, C>
+where
+    T: ServiceFactory<R, C>,
+{
+    type Response = T::Response;
+    type Error = T::Error;
+
+    type Service = SharedService<T::Service>;
+    type InitError = T::InitError;
+
+    async fn create(&self, cfg: C) -> Result<Self::Service, Self::InitError> {
+        let inner = &self.0;
+        loop {
+            if let Some(svc) = inner.service.borrow().as_ref() {
+                return Ok(SharedService::new(svc.clone()));
+            }
+
+            if inner.creating.get() {
+                // wait for concurrent create() call
+                let mut registered = false;
+                poll_fn(|cx| {
+                    if registered {
+                        Poll::Ready(())
+                    } else {
+                        registered = true;
+                        inner.waiters.borrow_mut().push(cx.waker().clone());
+                        Poll::Pending
+                    }
+                })
+                .await;
+            } else {
+                let _guard = CreateGuard::new(inner);
+                let svc = Rc::new(SharedInner {
+                    service: inner.factory.create(cfg).await?,
+                    active: Cell::new(0),
+                });
+                *inner.service.borrow_mut() = Some(svc.clone());
+                return Ok(SharedService::new(svc));
+            }
+        }
+    }
+}
+
+/// Resets creating state and wakes up waiters, even if `create()` future is dropped
+struct CreateGuard<'a, T: ServiceFactory<R, C>, R, C>(&'a Inner<T, R, C>);
+
+impl<'a, T, R, C> CreateGuard<'a, T, R, C>
+where
+    T: ServiceFactory<R, C>,
+{
+    fn new(inner: &'a Inner<T, R, C>) -> Self {
+        inner.creating.set(true);
+        Self(inner)
+    }
+}
+
+impl<T, R, C> Drop for CreateGuard<'_, T, R, C>
+where
+    T: ServiceFactory<R, C>,
+{
+    fn drop(&mut self) {
+        self.0.creating.set(false);
+        for waker in self.0.waiters.borrow_mut().drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+/// Service shared by `Shared` factory
+pub struct SharedService<S> {
+    inner: Rc<SharedInner<S>>,
+    shutdown: Cell<bool>,
+}
+
+struct SharedInner<S> {
+    service: S,
+    // number of services that are not shut down
+    active: Cell<usize>,
+}
+
+impl<S> SharedService<S> {
+    fn new(inner: Rc<SharedInner<S>>) -> Self {
+        inner.active.set(inner.active.get() + 1);
+        Self {
+            inner,
+            shutdown: Cell::new(false),
+        }
+    }
+
+    /// Get reference to inner service
+    pub fn get_ref(&self) -> &S {
+        &self.inner.service
+    }
+
+    /// Mark service as shut down, returns `true` for the last active service
+    fn release(&self) -> bool {
+        if self.shutdown.replace(true) {
+            false
+        } else {
+            let active = self.inner.active.get() - 1;
+            self.inner.active.set(active);
+            active == 0
+        }
+    }
+}
+
+impl<S> Clone for SharedService<S> {
+    fn clone(&self) -> Self {
+        Self::new(self.inner.clone())
+    }
+}
+
+impl<S> Drop for SharedService<S> {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+impl<S: fmt::Debug> fmt::Debug for SharedService<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SharedService")
+            .field(&self.inner.service)
+            .finish()
+    }
+}
+
+impl<S, R> Service<R> for SharedService<S>
+where
+    S: Service<R>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+
+    #[inline]
+    async fn ready(&self, ctx: ServiceCtx<'_, Self>) -> Result<(), S::Error> {
+        ctx.ready(&self.inner.service).await
+    }
+
+    #[inline]
+    fn poll(&self, cx: &mut Context<'_>) -> Result<(), S::Error> {
+        self.inner.service.poll(cx)
+    }
+
+    #[inline]
+    async fn shutdown(&self) {
+        if self.release() {
+            self.inner.service.shutdown().await;
+        }
+    }
+
+    #[inline]
+    async fn call(
+        &self,
+        req: R,
+        ctx: ServiceCtx<'_, Self>,
+    ) -> Result<S::Response, S::Error> {
+        ctx.call_nowait(&self.inner.service, req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+    use crate::{fn_factory_with_config, fn_service};
+
+    #[ntex::test]
+    async fn test_shared() {
+        let counter = Rc::new(Cell::new(0));
+        let counter2 = counter.clone();
+        let factory = shared(fn_factory_with_config(move |cfg: usize| {
+            counter2.set(counter2.get() + 1);
+            async move {
+                ntex::time::sleep(ntex::time::Millis(10)).await;
+                Ok::<_, ()>(fn_service(move |req: usize| async move {
+                    Ok::<_, ()>(req + cfg)
+                }))
+            }
+        }));
+        let _ = format!("{factory:?}");
+
+        // concurrent create() calls
+        let (svc1, svc2) =
+            ntex::util::join(factory.pipeline(1), factory.clone().pipeline(2)).await;
+        let svc3 = factory.pipeline(3).await.unwrap();
+        assert_eq!(counter.get(), 1);
+
+        assert_eq!(svc1.unwrap().call(1).await, Ok(2));
+        assert_eq!(svc2.unwrap().call(1).await, Ok(2));
+        assert_eq!(svc3.call(1).await, Ok(2));
+    }
+
+    struct Srv(Rc<Cell<usize>>);
+
+    impl Service<()> for Srv {
+        type Response = ();
+        type Error = ();
+
+        async fn call(&self, _: (), _: ServiceCtx<'_, Self>) -> Result<(), ()> {
+            Ok(())
+        }
+
+        async fn shutdown(&self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[ntex::test]
+    async fn test_shared_shutdown() {
+        let counter = Rc::new(Cell::new(0));
+        let counter2 = counter.clone();
+        let factory = shared(fn_factory_with_config(move |_: ()| {
+            let srv = Srv(counter2.clone());
+            async move { Ok::<_, ()>(srv) }
+        }));
+
+        let svc1 = factory.create(()).await.unwrap();
+        let svc2 = factory.create(()).await.unwrap();
+        let svc3 = svc2.clone();
+        drop(factory.create(()).await.unwrap());
+
+        svc1.shutdown().await;
+        svc1.shutdown().await;
+        assert_eq!(counter.get(), 0);
+        svc2.shutdown().await;
+        assert_eq!(counter.get(), 0);
+
+        // last service shuts down inner service
+        svc3.shutdown().await;
+        assert_eq!(counter.get(), 1);
+    }
+
+    #[ntex::test]
+    async fn test_shared_err() {
+        let counter = Rc::new(Cell::new(0));
+        let counter2 = counter.clone();
+        let factory = shared(fn_factory_with_config(move |_: ()| {
+            counter2.set(counter2.get() + 1);
+            let fail = counter2.get() == 1;
+            async move {
+                if fail {
+                    Err(())
+                } else {
+                    Ok(fn_service(|req: usize| async move { Ok::<_, ()>(req) }))
+                }
+            }
+        }));
+
+        assert!(factory.pipeline(()).await.is_err());
+        assert!(factory.pipeline(()).await.is_ok());
+        assert!(factory.pipeline(()).await.is_ok());
+        assert_eq!(counter.get(), 2);
+    }
+}
@@ -1,3 +1,4 @@
+use std::sync::{Arc, PoisonError, RwLock};
 use std::{cell::RefCell, error::Error, fmt, io};
 
 use ntex_bytes::BytePages;
@@ -14,13 +15,28 @@ use crate::{MAX_SSL_ACCEPT_COUNTER, TlsConfig, openssl::SslFilter};
 ///
 /// `openssl` feature enables `Acceptor` type
 pub struct SslAcceptor {
-    acceptor: ssl::SslAcceptor,
+    acceptor: Arc<RwLock<ssl::SslAcceptor>>,
 }
 
 impl SslAcceptor {
     /// Create default openssl acceptor service
     pub fn new(acceptor: ssl::SslAcceptor) -> Self {
-        SslAcceptor { acceptor }
+        SslAcceptor {
+            acceptor: Arc::new(RwLock::new(acceptor)),
+        }
+    }
+
+    /// Replace openssl acceptor
+    ///
+    /// Acceptor is shared between all clones of the `SslAcceptor` and all services
+    /// created by them, including services of other worker threads. New acceptor
+    /// is used for subsequent tls handshakes, already established connections
+    /// are not affected. This allows to rotate certificates without server restart.
+    pub fn reload_tls(&self, acceptor: ssl::SslAcceptor) {
+        *self
+            .acceptor
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = acceptor;
     }
 }
 
@@ -58,7 +74,7 @@ impl<F: Filter> ServiceFactory<Io<F>, SharedCfg> for SslAcceptor {
 ///
 /// `openssl` feature enables `Acceptor` type
 pub struct SslAcceptorService {
-    acceptor: ssl::SslAcceptor,
+    acceptor: Arc<RwLock<ssl::SslAcceptor>>,
     cfg: Cfg<TlsConfig>,
     conns: Counter,
 }
@@ -80,7 +96,12 @@ impl<F: Filter> Service<Io<F>> for SslAcceptorService {
         _: ServiceCtx<'_, Self>,
     ) -> Result<Self::Response, Self::Error> {
         let _guard = self.conns.get();
-        let ctx_result = ssl::Ssl::new(self.acceptor.context());
+        let ctx_result = ssl::Ssl::new(
+            self.acceptor
+                .read()
+                .unwrap_or_else(PoisonError::into_inner)
+                .context(),
+        );
 
         time::timeout(self.cfg.handshake_timeout(), async {
             let ssl = ctx_result.map_err(super::map_to_ioerr)?;
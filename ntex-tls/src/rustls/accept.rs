@@ -1,4 +1,5 @@
-use std::{io, sync::Arc};
+use std::io;
+use std::sync::{Arc, PoisonError, RwLock};
 
 use tls_rustls::ServerConfig;
 
@@ -14,13 +15,25 @@ use crate::{MAX_SSL_ACCEPT_COUNTER, TlsConfig, rustls::TlsServerFilter};
 ///
 /// `rust-tls` feature enables `TlsAcceptor` type
 pub struct TlsAcceptor {
-    config: Arc<ServerConfig>,
+    config: Arc<RwLock<Arc<ServerConfig>>>,
 }
 
 impl TlsAcceptor {
     /// Create rustls based `Acceptor` service factory
     pub fn new(config: Arc<ServerConfig>) -> Self {
-        Self { config }
+        Self {
+            config: Arc::new(RwLock::new(config)),
+        }
+    }
+
+    /// Replace server config
+    ///
+    /// Config is shared between all clones of the acceptor and all services
+    /// created by them, including services of other worker threads. New config
+    /// is used for subsequent tls handshakes, already established connections
+    /// are not affected. This allows to rotate certificates without server restart.
+    pub fn reload_tls(&self, config: Arc<ServerConfig>) {
+        *self.config.write().unwrap_or_else(PoisonError::into_inner) = config;
     }
 }
 
@@ -51,7 +64,7 @@ impl<F: Filter> ServiceFactory<Io<F>, SharedCfg> for TlsAcceptor {
 /// `RusTLS` based `Acceptor` service
 pub struct TlsAcceptorService {
     cfg: Cfg<TlsConfig>,
-    config: Arc<ServerConfig>,
+    config: Arc<RwLock<Arc<ServerConfig>>>,
     conns: Counter,
 }
 
@@ -72,11 +85,11 @@ impl<F: Filter> Service<Io<F>> for TlsAcceptorService {
         _: ServiceCtx<'_, Self>,
     ) -> Result<Self::Response, Self::Error> {
         let _guard = self.conns.get();
-        super::TlsServerFilter::create(
-            io,
-            self.config.clone(),
-            self.cfg.handshake_timeout(),
-        )
-        .await
+        let config = self
+            .config
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        super::TlsServerFilter::create(io, config, self.cfg.handshake_timeout()).await
     }
 }
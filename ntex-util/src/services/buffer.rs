@@ -9,7 +9,20 @@ use crate::channel::oneshot;
 
 /// Buffer - service factory for service that can buffer incoming request.
 ///
+/// Service is ready while inner service is not ready but buffer has free
+/// space, calls made in this state wait in the buffer and are passed to the
+/// inner service in order as soon as it becomes ready. Service readiness is
+/// pending if buffer is full, that applies backpressure to the callers.
+///
 /// Default number of buffered requests is 16
+///
+/// ```rust
+/// use ntex_service::{chain_factory, fn_service};
+/// use ntex_util::services::buffer::Buffer;
+///
+/// let factory = chain_factory(fn_service(|req: u32| async move { Ok::<_, ()>(req) }))
+///     .apply(Buffer::default().buf_size(32));
+/// ```
 pub struct Buffer<R> {
     buf_size: usize,
     cancel_on_shutdown: bool,
@@ -401,6 +414,36 @@ mod tests {
         assert!(format!("{:?}", Buffer::<TestService>::default()).contains("Buffer"));
     }
 
+    #[ntex::test]
+    async fn test_buf_size() {
+        let inner = Rc::new(Inner {
+            ready: Cell::new(false),
+            waker: LocalWaker::default(),
+            count: Cell::new(0),
+        });
+
+        let srv = Pipeline::new(BufferService::new(3, TestService(inner.clone()))).bind();
+        for _ in 0..3 {
+            assert_eq!(lazy(|cx| srv.poll_ready(cx)).await, Poll::Ready(Ok(())));
+            let srv1 = srv.clone();
+            ntex::rt::spawn(async move {
+                let _ = srv1.call(()).await;
+            });
+            crate::time::sleep(Duration::from_millis(25)).await;
+        }
+        assert_eq!(inner.count.get(), 0);
+        assert_eq!(lazy(|cx| srv.poll_ready(cx)).await, Poll::Pending);
+
+        // buffered requests are passed to inner service in order
+        for idx in 1..=3 {
+            inner.ready.set(true);
+            inner.waker.wake();
+            assert_eq!(lazy(|cx| srv.poll_ready(cx)).await, Poll::Ready(Ok(())));
+            crate::time::sleep(Duration::from_millis(25)).await;
+            assert_eq!(inner.count.get(), idx);
+        }
+    }
+
     #[ntex::test]
     #[allow(clippy::redundant_clone)]
     async fn test_middleware() {
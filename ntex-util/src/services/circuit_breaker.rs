@@ -0,0 +1,401 @@
+//! Service that stops calling failing service for a while.
+//!
+//! `CircuitBreaker` opens circuit after configured number of consecutive
+//! failures. While circuit is open, calls fail immediately with
+//! `CircuitBreakerError::Open` error. After cool-down period one probe
+//! request is passed to inner service, successful probe closes circuit,
+//! failed probe opens it again.
+use std::{cell::Cell, fmt, time::Duration, time::Instant};
+
+use ntex_service::{Middleware, Service, ServiceCtx};
+
+use crate::time::{Millis, now};
+
+/// Stops calling failing service for a while.
+///
+/// `is_failure` closure classifies service errors, errors that are not
+/// failures reset failures counter same as successful responses.
+/// Circuit breaker is disabled if number of failures is set to 0.
+///
+/// ```rust
+/// use std::io;
+///
+/// use ntex_service::{ServiceFactory, apply, fn_factory, fn_service};
+/// use ntex_util::{services::circuit_breaker::CircuitBreaker, time::Seconds};
+///
+/// #[ntex::main]
+/// async fn main() {
+///     let factory = apply(
+///         // open circuit for 10 seconds after 5 consecutive failures
+///         CircuitBreaker::new(5, Seconds(10), |_: &io::Error| true),
+///         fn_factory(|| async {
+///             Ok::<_, ()>(fn_service(|_: ()| async { Ok::<_, io::Error>(()) }))
+///         }),
+///     );
+///     let srv = factory.pipeline(&()).await.unwrap();
+///     assert!(srv.call(()).await.is_ok());
+/// }
+/// ```
+#[derive(Clone)]
+pub struct CircuitBreaker<F> {
+    failures: u32,
+    cooldown: Millis,
+    is_failure: F,
+}
+
+/// Circuit breaker error
+#[derive(Debug, PartialEq, Eq)]
+pub enum CircuitBreakerError<E> {
+    /// Service error
+    Service(E),
+    /// Circuit is open, inner service is not called
+    Open,
+}
+
+impl<E> From<E> for CircuitBreakerError<E> {
+    fn from(err: E) -> Self {
+        CircuitBreakerError::Service(err)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for CircuitBreakerError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CircuitBreakerError::Service(e) => e.fmt(f),
+            CircuitBreakerError::Open => write!(f, "Circuit breaker is open"),
+        }
+    }
+}
+
+impl<E: fmt::Display + fmt::Debug> std::error::Error for CircuitBreakerError<E> {}
+
+/// State of the circuit
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests are passed to inner service
+    Closed,
+    /// Requests fail immediately
+    Open,
+    /// Probe request is in progress
+    HalfOpen,
+}
+
+impl<F> CircuitBreaker<F> {
+    /// Create circuit breaker middleware
+    ///
+    /// Circuit opens after `failures` consecutive failures and stays open
+    /// for `cooldown` period.
+    pub fn new<T: Into<Millis>>(failures: u32, cooldown: T, is_failure: F) -> Self {
+        CircuitBreaker {
+            failures,
+            is_failure,
+            cooldown: cooldown.into(),
+        }
+    }
+}
+
+impl<F> fmt::Debug for CircuitBreaker<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("failures", &self.failures)
+            .field("cooldown", &self.cooldown)
+            .finish()
+    }
+}
+
+impl<S, F: Clone, C> Middleware<S, C> for CircuitBreaker<F> {
+    type Service = CircuitBreakerService<S, F>;
+
+    fn create(&self, service: S, _: C) -> Self::Service {
+        CircuitBreakerService::new(
+            self.failures,
+            self.cooldown,
+            self.is_failure.clone(),
+            service,
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum State {
+    /// Number of consecutive failures
+    Closed(u32),
+    /// Circuit is open until specified time
+    Open(Instant),
+    HalfOpen,
+}
+
+/// Stops calling failing service for a while.
+pub struct CircuitBreakerService<S, F> {
+    service: S,
+    failures: u32,
+    cooldown: Duration,
+    is_failure: F,
+    state: Cell<State>,
+}
+
+impl<S, F> CircuitBreakerService<S, F> {
+    /// Create circuit breaker service
+    pub fn new<T: Into<Millis>>(
+        failures: u32,
+        cooldown: T,
+        is_failure: F,
+        service: S,
+    ) -> Self {
+        CircuitBreakerService {
+            service,
+            failures,
+            is_failure,
+            cooldown: cooldown.into().into(),
+            state: Cell::new(State::Closed(0)),
+        }
+    }
+
+    /// Current state of the circuit
+    pub fn state(&self) -> CircuitState {
+        match self.state.get() {
+            State::Closed(_) => CircuitState::Closed,
+            State::Open(until) if now() >= until => CircuitState::HalfOpen,
+            State::Open(_) => CircuitState::Open,
+            State::HalfOpen => CircuitState::HalfOpen,
+        }
+    }
+
+    fn on_result(&self, probe: bool, failure: bool) {
+        let state = match self.state.get() {
+            // call is started before circuit is opened
+            State::Open(_) | State::HalfOpen if !probe => return,
+            _ if !failure => State::Closed(0),
+            State::Closed(n) if n + 1 < self.failures => State::Closed(n + 1),
+            // consecutive failures limit is reached or probe is failed
+            _ => {
+                log::debug!("Circuit breaker is open");
+                State::Open(now() + self.cooldown)
+            }
+        };
+        self.state.set(state);
+    }
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for CircuitBreakerService<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CircuitBreakerService")
+            .field("service", &self.service)
+            .field("failures", &self.failures)
+            .field("cooldown", &self.cooldown)
+            .field("state", &self.state.get())
+            .finish()
+    }
+}
+
+impl<S, F, R> Service<R> for CircuitBreakerService<S, F>
+where
+    S: Service<R>,
+    F: Fn(&S::Error) -> bool,
+{
+    type Response = S::Response;
+    type Error = CircuitBreakerError<S::Error>;
+
+    async fn call(
+        &self,
+        request: R,
+        ctx: ServiceCtx<'_, Self>,
+    ) -> Result<Self::Response, Self::Error> {
+        if self.failures == 0 {
+            return ctx
+                .call(&self.service, request)
+                .await
+                .map_err(CircuitBreakerError::Service);
+        }
+
+        let probe = match self.state.get() {
+            State::Closed(_) => false,
+            State::Open(until) if now() >= until => {
+                self.state.set(State::HalfOpen);
+                true
+            }
+            State::Open(_) | State::HalfOpen => return Err(CircuitBreakerError::Open),
+        };
+
+        // allow next probe if probe call is cancelled
+        let guard = probe.then(|| ProbeGuard(&self.state));
+        let result = ctx.call(&self.service, request).await;
+        std::mem::forget(guard);
+
+        match result {
+            Ok(res) => {
+                self.on_result(probe, false);
+                Ok(res)
+            }
+            Err(err) => {
+                self.on_result(probe, (self.is_failure)(&err));
+                Err(CircuitBreakerError::Service(err))
+            }
+        }
+    }
+
+    ntex_service::forward_poll!(service, CircuitBreakerError::Service);
+    ntex_service::forward_ready!(service, CircuitBreakerError::Service);
+    ntex_service::forward_shutdown!(service);
+}
+
+struct ProbeGuard<'a>(&'a Cell<State>);
+
+impl Drop for ProbeGuard<'_> {
+    fn drop(&mut self) {
+        if let State::HalfOpen = self.0.get() {
+            self.0.set(State::Open(now()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use ntex_service::{Pipeline, apply, fn_factory, fn_service};
+
+    use super::*;
+    use crate::future::lazy;
+    use crate::time::{Millis, sleep};
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    enum SrvError {
+        Failure,
+        BadRequest,
+    }
+
+    impl fmt::Display for SrvError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "SrvError")
+        }
+    }
+
+    #[ntex::test]
+    async fn test_circuit_breaker() {
+        let result = Rc::new(Cell::new(Ok(())));
+        let calls = Rc::new(Cell::new(0));
+        let srv = Pipeline::new(CircuitBreakerService::new(
+            2,
+            Millis(50),
+            |err: &SrvError| *err == SrvError::Failure,
+            fn_service({
+                let (result, calls) = (result.clone(), calls.clone());
+                move |()| {
+                    calls.set(calls.get() + 1);
+                    let res = result.get();
+                    async move { res }
+                }
+            }),
+        ));
+        assert_eq!(srv.call(()).await, Ok(()));
+        assert_eq!(srv.get_ref().state(), CircuitState::Closed);
+
+        // not a failure
+        result.set(Err(SrvError::BadRequest));
+        for _ in 0..3 {
+            assert_eq!(
+                srv.call(()).await,
+                Err(CircuitBreakerError::Service(SrvError::BadRequest))
+            );
+        }
+        assert_eq!(srv.get_ref().state(), CircuitState::Closed);
+
+        // consecutive failures
+        result.set(Err(SrvError::Failure));
+        assert!(srv.call(()).await.is_err());
+        result.set(Ok(()));
+        assert_eq!(srv.call(()).await, Ok(()));
+        result.set(Err(SrvError::Failure));
+        assert!(srv.call(()).await.is_err());
+        assert_eq!(srv.get_ref().state(), CircuitState::Closed);
+        assert!(srv.call(()).await.is_err());
+        assert_eq!(srv.get_ref().state(), CircuitState::Open);
+
+        // fail fast
+        let count = calls.get();
+        result.set(Ok(()));
+        assert_eq!(srv.call(()).await, Err(CircuitBreakerError::Open));
+        assert_eq!(calls.get(), count);
+
+        // failed probe
+        sleep(Millis(100)).await;
+        assert_eq!(srv.get_ref().state(), CircuitState::HalfOpen);
+        result.set(Err(SrvError::Failure));
+        assert!(matches!(
+            srv.call(()).await,
+            Err(CircuitBreakerError::Service(SrvError::Failure))
+        ));
+        assert_eq!(srv.get_ref().state(), CircuitState::Open);
+        assert_eq!(srv.call(()).await, Err(CircuitBreakerError::Open));
+
+        // successful probe
+        sleep(Millis(100)).await;
+        result.set(Ok(()));
+        assert_eq!(srv.call(()).await, Ok(()));
+        assert_eq!(srv.get_ref().state(), CircuitState::Closed);
+        assert_eq!(calls.get(), count + 2);
+    }
+
+    #[ntex::test]
+    async fn test_probe_in_progress() {
+        let srv = Pipeline::new(CircuitBreakerService::new(
+            1,
+            Millis(10),
+            |_: &()| true,
+            fn_service(|delay: u32| async move {
+                sleep(Millis(delay)).await;
+                Err::<(), _>(())
+            }),
+        ));
+        assert_eq!(srv.call(0).await, Err(CircuitBreakerError::Service(())));
+        assert_eq!(srv.get_ref().state(), CircuitState::Open);
+        sleep(Millis(50)).await;
+
+        // concurrent calls fail while probe is in progress
+        let srv2 = srv.clone();
+        let probe = crate::spawn(async move { srv2.call(100).await });
+        sleep(Millis(20)).await;
+        assert_eq!(srv.get_ref().state(), CircuitState::HalfOpen);
+        assert_eq!(srv.call(0).await, Err(CircuitBreakerError::Open));
+        assert!(probe.await.unwrap().is_err());
+
+        // cancelled probe allows next probe
+        sleep(Millis(50)).await;
+        let mut fut = std::pin::pin!(srv.call(100));
+        let _ = lazy(|cx| fut.as_mut().poll(cx)).await;
+        drop(fut);
+        assert_eq!(srv.get_ref().state(), CircuitState::HalfOpen);
+        assert_eq!(srv.call(0).await, Err(CircuitBreakerError::Service(())));
+    }
+
+    #[ntex::test]
+    async fn test_middleware() {
+        let factory = apply(
+            CircuitBreaker::new(0, Millis(10), |_: &()| true),
+            fn_factory(|| async {
+                Ok::<_, ()>(fn_service(|()| async { Err::<(), _>(()) }))
+            }),
+        );
+        let srv = factory.pipeline(&()).await.unwrap();
+        for _ in 0..3 {
+            assert_eq!(srv.call(()).await, Err(CircuitBreakerError::Service(())));
+        }
+
+        let cb = CircuitBreaker::new(1, Millis(10), |_: &()| true);
+        assert!(format!("{cb:?}").contains("CircuitBreaker"));
+        let srv = CircuitBreakerService::new(1, Millis(10), |_: &()| true, "srv");
+        assert!(format!("{srv:?}").contains("CircuitBreakerService"));
+    }
+
+    #[test]
+    fn test_error() {
+        let err1 = CircuitBreakerError::<SrvError>::Open;
+        assert!(format!("{err1:?}").contains("Open"));
+        assert!(format!("{err1}").contains("Circuit breaker is open"));
+
+        let err2: CircuitBreakerError<_> = SrvError::Failure.into();
+        assert!(format!("{err2:?}").contains("Service"));
+        assert!(format!("{err2}").contains("SrvError"));
+    }
+}
@@ -1,4 +1,4 @@
-use std::{any::Any, any::TypeId, fmt};
+use std::{any::Any, any::TypeId, collections::hash_map::Entry, fmt};
 
 #[derive(Default)]
 /// A type map of request extensions.
@@ -25,6 +25,18 @@ impl Extensions {
             .and_then(|item| item.downcast::<T>().map(|boxed| *boxed).ok())
     }
 
+    /// Insert a type into this `Extensions` if it is not present.
+    ///
+    /// Existing value is not replaced. Returns `true` if value is inserted.
+    pub fn insert_if_absent<T: 'static>(&mut self, val: T) -> bool {
+        if let Entry::Vacant(entry) = self.map.entry(TypeId::of::<T>()) {
+            entry.insert(Box::new(val));
+            true
+        } else {
+            false
+        }
+    }
+
     /// Check if container contains entry
     pub fn contains<T: 'static>(&self) -> bool {
         self.map.contains_key(&TypeId::of::<T>())
@@ -53,6 +65,19 @@ impl Extensions {
             .and_then(|boxed| boxed.downcast().ok().map(|boxed| *boxed))
     }
 
+    /// Iterate over type ids of stored extensions
+    pub fn type_ids(&self) -> impl Iterator<Item = TypeId> + '_ {
+        self.map.keys().copied()
+    }
+
+    /// Retain only extensions with type ids specified by the predicate
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(TypeId) -> bool,
+    {
+        self.map.retain(|id, _| f(*id));
+    }
+
     /// Add all items from other `Extensions`
     pub fn extend(&mut self, other: Extensions) {
         self.map.extend(other.map);
@@ -192,6 +217,30 @@ fn test_extensions() {
     assert_eq!(extensions.get(), Some(&MyType(10)));
 }
 
+#[test]
+fn test_insert_if_absent() {
+    let mut map = Extensions::new();
+
+    assert!(map.insert_if_absent(1i32));
+    assert!(!map.insert_if_absent(2i32));
+    assert!(map.insert_if_absent(0u8));
+    assert_eq!(map.get::<u8>(), Some(&0));
+    assert_eq!(map.get::<i32>(), Some(&1));
+}
+
+#[test]
+fn test_retain() {
+    let mut map = Extensions::new();
+    map.insert(1i32);
+    let ids: Vec<_> = map.type_ids().collect();
+    assert_eq!(ids, vec![TypeId::of::<i32>()]);
+
+    map.insert(0u8);
+    map.retain(|id| ids.contains(&id));
+    assert_eq!(map.get::<i32>(), Some(&1));
+    assert!(!map.contains::<u8>());
+}
+
 #[test]
 fn test_extend() {
     #[derive(Debug, PartialEq)]
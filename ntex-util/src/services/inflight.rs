@@ -6,7 +6,19 @@ use super::counter::Counter;
 /// `InFlight` - service factory for service that can limit number of in-flight
 /// async requests.
 ///
+/// Service readiness is pending while number of in-flight requests is at the
+/// limit, waiting task is woken up as soon as one of the requests completes.
+/// Timers are not used.
+///
 /// Default number of in-flight requests is 15
+///
+/// ```rust
+/// use ntex_service::{chain_factory, fn_service};
+/// use ntex_util::services::inflight::InFlight;
+///
+/// let factory = chain_factory(fn_service(|req: u32| async move { Ok::<_, ()>(req) }))
+///     .apply(InFlight::new(10));
+/// ```
 #[derive(Copy, Clone, Debug)]
 pub struct InFlight {
     max_inflight: usize,
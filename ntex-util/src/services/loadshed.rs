@@ -0,0 +1,177 @@
+//! Service that rejects requests if inner service is not ready.
+//!
+//! Instead of waiting for readiness of inner service, `LoadShed` fails
+//! immediately with `LoadShedError::Overloaded` error.
+use std::{fmt, future::poll_fn, pin::pin, task::Poll};
+
+use ntex_service::{Middleware, Service, ServiceCtx};
+
+/// Rejects requests if inner service is not ready.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct LoadShed;
+
+/// Load shed error
+#[derive(Debug, PartialEq, Eq)]
+pub enum LoadShedError<E> {
+    /// Service error
+    Service(E),
+    /// Service is not ready
+    Overloaded,
+}
+
+impl<E> From<E> for LoadShedError<E> {
+    fn from(err: E) -> Self {
+        LoadShedError::Service(err)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for LoadShedError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadShedError::Service(e) => e.fmt(f),
+            LoadShedError::Overloaded => write!(f, "Service is overloaded"),
+        }
+    }
+}
+
+impl<E: fmt::Display + fmt::Debug> std::error::Error for LoadShedError<E> {}
+
+impl<S, C> Middleware<S, C> for LoadShed {
+    type Service = LoadShedService<S>;
+
+    fn create(&self, service: S, _: C) -> Self::Service {
+        LoadShedService { service }
+    }
+}
+
+/// Rejects requests if inner service is not ready.
+///
+/// Readiness of inner service is checked once, if inner service is
+/// not ready `ready()` returns `LoadShedError::Overloaded` error.
+#[derive(Debug, Clone)]
+pub struct LoadShedService<S> {
+    service: S,
+}
+
+impl<S> LoadShedService<S> {
+    pub fn new<R>(service: S) -> Self
+    where
+        S: Service<R>,
+    {
+        LoadShedService { service }
+    }
+}
+
+impl<S, R> Service<R> for LoadShedService<S>
+where
+    S: Service<R>,
+{
+    type Response = S::Response;
+    type Error = LoadShedError<S::Error>;
+
+    async fn ready(&self, ctx: ServiceCtx<'_, Self>) -> Result<(), Self::Error> {
+        let mut fut = pin!(ctx.ready(&self.service));
+
+        match poll_fn(|cx| Poll::Ready(fut.as_mut().poll(cx))).await {
+            Poll::Ready(res) => res.map_err(LoadShedError::Service),
+            Poll::Pending => Err(LoadShedError::Overloaded),
+        }
+    }
+
+    async fn call(
+        &self,
+        request: R,
+        ctx: ServiceCtx<'_, Self>,
+    ) -> Result<Self::Response, Self::Error> {
+        ctx.call_nowait(&self.service, request)
+            .await
+            .map_err(LoadShedError::Service)
+    }
+
+    ntex_service::forward_poll!(service, LoadShedError::Service);
+    ntex_service::forward_shutdown!(service);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use ntex_service::{Pipeline, apply, fn_factory};
+
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct SrvError;
+
+    impl fmt::Display for SrvError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "SrvError")
+        }
+    }
+
+    struct Srv(Rc<Cell<bool>>);
+
+    impl Service<()> for Srv {
+        type Response = ();
+        type Error = SrvError;
+
+        async fn ready(&self, _: ServiceCtx<'_, Self>) -> Result<(), SrvError> {
+            poll_fn(|_| {
+                if self.0.get() {
+                    Poll::Ready(Ok(()))
+                } else {
+                    Poll::Pending
+                }
+            })
+            .await
+        }
+
+        async fn call(&self, _r: (), _: ServiceCtx<'_, Self>) -> Result<(), SrvError> {
+            Ok(())
+        }
+    }
+
+    #[ntex::test]
+    async fn test_load_shed() {
+        let ready = Rc::new(Cell::new(true));
+        let srv = Pipeline::new(LoadShedService::new(Srv(ready.clone())));
+        assert_eq!(srv.ready().await, Ok(()));
+        assert_eq!(srv.call(()).await, Ok(()));
+
+        ready.set(false);
+        assert_eq!(srv.ready().await, Err(LoadShedError::Overloaded));
+        assert_eq!(srv.call(()).await, Err(LoadShedError::Overloaded));
+
+        ready.set(true);
+        assert_eq!(srv.call(()).await, Ok(()));
+    }
+
+    #[ntex::test]
+    async fn test_load_shed_middleware() {
+        let ready = Rc::new(Cell::new(false));
+        let ready2 = ready.clone();
+        let srv = apply(
+            LoadShed,
+            fn_factory(move || {
+                let ready = ready2.clone();
+                async move { Ok::<_, ()>(Srv(ready)) }
+            }),
+        );
+        let srv = srv.pipeline(&()).await.unwrap();
+        assert_eq!(srv.call(()).await, Err(LoadShedError::Overloaded));
+
+        ready.set(true);
+        assert_eq!(srv.call(()).await, Ok(()));
+    }
+
+    #[test]
+    fn test_error() {
+        let err1 = LoadShedError::<SrvError>::Overloaded;
+        assert!(format!("{err1:?}").contains("Overloaded"));
+        assert!(format!("{err1}").contains("Service is overloaded"));
+
+        let err2: LoadShedError<_> = SrvError.into();
+        assert!(format!("{err2:?}").contains("Service"));
+        assert!(format!("{err2}").contains("SrvError"));
+    }
+}
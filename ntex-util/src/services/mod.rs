@@ -1,8 +1,10 @@
 pub mod buffer;
+pub mod circuit_breaker;
 pub mod either;
 mod extensions;
 pub mod inflight;
 pub mod keepalive;
+pub mod loadshed;
 pub mod onerequest;
 pub mod retry;
 pub mod timeout;
@@ -1,7 +1,7 @@
 //! Service that applies a timeout to requests.
 //!
 //! If the response does not complete within the specified timeout, the response
-//! will be aborted.
+//! will be aborted. `ReadyTimeout` applies a timeout to service readiness.
 use std::{fmt, marker};
 
 use ntex_service::{Middleware, Service, ServiceCtx};
@@ -145,9 +145,94 @@ where
     ntex_service::forward_shutdown!(service);
 }
 
+/// Applies a timeout to service readiness.
+///
+/// If service does not become ready within the specified timeout,
+/// `TimeoutError::Timeout` error is returned from `ready()`. Timer is
+/// restarted for each readiness check.
+///
+/// Timeout is disabled if timeout is set to 0
+#[derive(Copy, Clone, Debug)]
+pub struct ReadyTimeout {
+    timeout: Millis,
+}
+
+impl ReadyTimeout {
+    pub fn new<T: Into<Millis>>(timeout: T) -> Self {
+        ReadyTimeout {
+            timeout: timeout.into(),
+        }
+    }
+}
+
+impl<S, C> Middleware<S, C> for ReadyTimeout {
+    type Service = ReadyTimeoutService<S>;
+
+    fn create(&self, service: S, _: C) -> Self::Service {
+        ReadyTimeoutService {
+            service,
+            timeout: self.timeout,
+        }
+    }
+}
+
+/// Applies a timeout to service readiness.
+#[derive(Debug, Clone)]
+pub struct ReadyTimeoutService<S> {
+    service: S,
+    timeout: Millis,
+}
+
+impl<S> ReadyTimeoutService<S> {
+    pub fn new<T, R>(timeout: T, service: S) -> Self
+    where
+        T: Into<Millis>,
+        S: Service<R>,
+    {
+        ReadyTimeoutService {
+            service,
+            timeout: timeout.into(),
+        }
+    }
+}
+
+impl<S, R> Service<R> for ReadyTimeoutService<S>
+where
+    S: Service<R>,
+{
+    type Response = S::Response;
+    type Error = TimeoutError<S::Error>;
+
+    async fn ready(&self, ctx: ServiceCtx<'_, Self>) -> Result<(), Self::Error> {
+        if self.timeout.is_zero() {
+            ctx.ready(&self.service)
+                .await
+                .map_err(TimeoutError::Service)
+        } else {
+            match select(sleep(self.timeout), ctx.ready(&self.service)).await {
+                Either::Left(()) => Err(TimeoutError::Timeout),
+                Either::Right(res) => res.map_err(TimeoutError::Service),
+            }
+        }
+    }
+
+    async fn call(
+        &self,
+        request: R,
+        ctx: ServiceCtx<'_, Self>,
+    ) -> Result<Self::Response, Self::Error> {
+        ctx.call_nowait(&self.service, request)
+            .await
+            .map_err(TimeoutError::Service)
+    }
+
+    ntex_service::forward_poll!(service, TimeoutError::Service);
+    ntex_service::forward_shutdown!(service);
+}
+
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
+    use std::{cell::Cell, rc::Rc, time::Duration};
 
     use ntex_service::{Pipeline, apply, fn_factory};
 
@@ -224,6 +309,73 @@ mod tests {
         assert_eq!(res, TimeoutError::Timeout);
     }
 
+    struct NeverReady;
+
+    impl Service<()> for NeverReady {
+        type Response = ();
+        type Error = SrvError;
+
+        async fn ready(&self, _: ServiceCtx<'_, Self>) -> Result<(), SrvError> {
+            std::future::pending().await
+        }
+
+        async fn call(&self, _r: (), _: ServiceCtx<'_, Self>) -> Result<(), SrvError> {
+            Ok(())
+        }
+    }
+
+    struct CountReady(Rc<Cell<usize>>);
+
+    impl Service<()> for CountReady {
+        type Response = ();
+        type Error = SrvError;
+
+        async fn ready(&self, _: ServiceCtx<'_, Self>) -> Result<(), SrvError> {
+            self.0.set(self.0.get() + 1);
+            Ok(())
+        }
+
+        async fn call(&self, _r: (), _: ServiceCtx<'_, Self>) -> Result<(), SrvError> {
+            Ok(())
+        }
+    }
+
+    #[ntex::test]
+    async fn test_ready_timeout() {
+        let srv = Pipeline::new(ReadyTimeoutService::new(
+            Duration::from_millis(50),
+            NeverReady,
+        ));
+        assert_eq!(srv.ready().await, Err(TimeoutError::Timeout));
+        assert_eq!(srv.call(()).await, Err(TimeoutError::Timeout));
+
+        let srv = Pipeline::new(ReadyTimeoutService::new(
+            Duration::from_millis(50),
+            SleepService(Duration::from_millis(100)),
+        ));
+        assert_eq!(srv.ready().await, Ok(()));
+        assert_eq!(srv.call(()).await, Ok(()));
+
+        // readiness is checked once per call
+        let cnt = Rc::new(Cell::new(0));
+        let srv = Pipeline::new(ReadyTimeoutService::new(
+            Duration::from_millis(50),
+            CountReady(cnt.clone()),
+        ));
+        assert_eq!(srv.call(()).await, Ok(()));
+        assert_eq!(cnt.get(), 1);
+    }
+
+    #[ntex::test]
+    async fn test_ready_timeout_middleware() {
+        let timeout = apply(
+            ReadyTimeout::new(Duration::from_millis(50)),
+            fn_factory(|| async { Ok::<_, ()>(NeverReady) }),
+        );
+        let srv = timeout.pipeline(&()).await.unwrap();
+        assert_eq!(srv.call(()).await, Err(TimeoutError::Timeout));
+    }
+
     #[test]
     fn test_error() {
         let err1 = TimeoutError::<SrvError>::Timeout;
@@ -23,6 +23,7 @@ pub struct ClientBuilder<M = Identity> {
     max_redirects: usize,
     config: ClientConfigInner,
     connector: Connector,
+    connect_timeout: Option<Millis>,
 }
 
 impl Default for ClientBuilder<Identity> {
@@ -41,6 +42,7 @@ impl ClientBuilder<Identity> {
             max_redirects: 10,
             config: ClientConfigInner::default(),
             connector: Connector::default(),
+            connect_timeout: None,
         }
     }
 }
@@ -63,6 +65,17 @@ impl<M> ClientBuilder<M> {
         self
     }
 
+    #[must_use]
+    /// Set connect timeout.
+    ///
+    /// Connect timeout is the max time for establishing connection, it is
+    /// independent from response timeout. This is same as `Connector::timeout()`,
+    /// and overrides connector's value.
+    pub fn connect_timeout<T: Into<Millis>>(mut self, timeout: T) -> Self {
+        self.connect_timeout = Some(timeout.into());
+        self
+    }
+
     #[must_use]
     /// Disable response timeout.
     pub fn disable_timeout(mut self) -> Self {
@@ -194,6 +207,7 @@ impl<M> ClientBuilder<M> {
             max_redirects: self.max_redirects,
             config: self.config,
             connector: self.connector,
+            connect_timeout: self.connect_timeout,
         }
     }
 
@@ -209,18 +223,23 @@ impl<M> ClientBuilder<M> {
         self.config.cfg = cfg.clone();
         let config = ClientConfig::new(self.config);
 
-        let svc = self
-            .connector
+        let connector = if let Some(timeout) = self.connect_timeout {
+            self.connector.timeout(timeout)
+        } else {
+            self.connector
+        };
+        let svc = connector
             .create(cfg)
             .await
             .map_err(|_| ClientBuilderError::ConnectorFailed)?;
 
+        let connector = svc.clone();
         let svc = boxed::service(
             self.middleware
                 .create(Sender::new(svc, config.clone()), config.clone()),
         );
 
-        Ok(Client::with_service(svc.into(), config))
+        Ok(Client::with_service(svc.into(), connector, config))
     }
 }
 
@@ -238,6 +257,9 @@ mod tests {
         assert!(!builder.allow_redirects);
         assert!(!builder.config.default_headers);
         assert_eq!(builder.max_redirects, 10);
+
+        let builder = ClientBuilder::new().connect_timeout(Millis(100));
+        assert_eq!(builder.connect_timeout, Some(Millis(100)));
     }
 
     #[crate::rt_test]
@@ -1,10 +1,14 @@
 use std::{error::Error, task::Context, time::Duration};
 
 use crate::connect::{Connect as TcpConnect, Connector as TcpConnector};
-use crate::service::{Service, ServiceCtx, ServiceFactory, apply_fn_factory, boxed};
-use crate::{SharedCfg, http::Uri, io::IoBoxed, time::Seconds, util::join};
+use crate::service::{
+    Pipeline, Service, ServiceCtx, ServiceFactory, apply_fn, apply_fn_factory, boxed,
+};
+use crate::time::{Millis, Seconds, timeout_checked};
+use crate::{SharedCfg, http::Uri, io::IoBoxed, util::join};
 
-use super::{Connect, Connection, error::ConnectError, pool::ConnectionPool};
+use super::pool::{ConnectionPool, PoolStats};
+use super::{Connect, Connection, error::ConnectError};
 
 #[cfg(feature = "openssl")]
 use tls_openssl::ssl::SslConnector as OpensslConnector;
@@ -14,6 +18,7 @@ use tls_rustls::ClientConfig;
 
 type BoxedConnector =
     boxed::BoxServiceFactory<SharedCfg, Connect, IoBoxed, ConnectError, Box<dyn Error>>;
+type BoxedConnectorService = boxed::BoxService<Connect, IoBoxed, ConnectError>;
 
 #[derive(Debug)]
 /// Manages http client network connectivity.
@@ -25,9 +30,11 @@ type BoxedConnector =
 /// use ntex::client::Connector;
 ///
 /// let connector = Connector::default()
+///      .timeout(1_000)
 ///      .keep_alive(5_000);
 /// ```
 pub struct Connector {
+    timeout: Millis,
     conn_lifetime: Duration,
     conn_keep_alive: Duration,
     limit: usize,
@@ -53,6 +60,7 @@ impl Connector {
                 .map_init_err(|e| Box::new(e) as Box<dyn Error>),
             ),
             secure_svc: None,
+            timeout: Millis::ZERO,
             conn_lifetime: Duration::from_secs(75),
             conn_keep_alive: Duration::from_secs(15),
             limit: 8,
@@ -110,6 +118,23 @@ impl Connector {
         self.secure_connector(TlsConnector::new(connector))
     }
 
+    #[must_use]
+    /// Set connect timeout.
+    ///
+    /// Connect timeout is the max time for establishing connection, it
+    /// includes dns resolution and tls handshake. Connect timeout is
+    /// independent from client's response timeout.
+    ///
+    /// `IoConfig::set_connect_timeout()` limits dns resolution and tcp
+    /// connect only, while this timeout also limits tls handshake and
+    /// applies to custom connectors.
+    ///
+    /// By default connect timeout is disabled.
+    pub fn timeout<T: Into<Millis>>(mut self, timeout: T) -> Self {
+        self.timeout = timeout.into();
+        self
+    }
+
     #[must_use]
     /// Set total number of simultaneous connections per type of scheme.
     ///
@@ -193,7 +218,7 @@ impl ServiceFactory<Connect, SharedCfg> for Connector {
     async fn create(&self, cfg: SharedCfg) -> Result<Self::Service, Self::InitError> {
         let ssl_pool = if let Some(ref svc) = self.secure_svc {
             Some(ConnectionPool::new(
-                svc.create(cfg.clone()).await?.into(),
+                with_timeout(svc.create(cfg.clone()).await?, self.timeout),
                 self.conn_lifetime,
                 self.conn_keep_alive,
                 self.limit,
@@ -203,7 +228,7 @@ impl ServiceFactory<Connect, SharedCfg> for Connector {
             None
         };
         let tcp_pool = ConnectionPool::new(
-            self.svc.create(cfg.clone()).await?.into(),
+            with_timeout(self.svc.create(cfg.clone()).await?, self.timeout),
             self.conn_lifetime,
             self.conn_keep_alive,
             self.limit,
@@ -213,6 +238,23 @@ impl ServiceFactory<Connect, SharedCfg> for Connector {
     }
 }
 
+fn with_timeout(
+    svc: BoxedConnectorService,
+    timeout: Millis,
+) -> Pipeline<BoxedConnectorService> {
+    if timeout.is_zero() {
+        svc.into()
+    } else {
+        boxed::service(apply_fn(svc, async move |msg: Connect, svc| {
+            timeout_checked(timeout, svc.call(msg))
+                .await
+                .map_err(|()| ConnectError::Timeout)
+                .and_then(|res| res)
+        }))
+        .into()
+    }
+}
+
 /// Manages http client network connectivity.
 #[derive(Clone, Debug)]
 pub struct ConnectorService {
@@ -220,6 +262,17 @@ pub struct ConnectorService {
     ssl_pool: Option<ConnectionPool>,
 }
 
+impl ConnectorService {
+    /// Get connection pools statistics
+    pub fn pool_stats(&self) -> PoolStats {
+        let mut stats = self.tcp_pool.stats();
+        if let Some(ref ssl_pool) = self.ssl_pool {
+            stats.merge(ssl_pool.stats());
+        }
+        stats
+    }
+}
+
 impl Service<Connect> for ConnectorService {
     type Response = Connection;
     type Error = ConnectError;
@@ -271,8 +324,11 @@ impl Service<Connect> for ConnectorService {
 
 #[cfg(test)]
 mod tests {
+    use std::io;
+
     use super::*;
-    use crate::{service::Pipeline, util::lazy};
+    use crate::service::{fn_factory_with_config, fn_service};
+    use crate::{io::Io, time::sleep, util::lazy};
 
     #[crate::rt_test]
     async fn test_readiness() {
@@ -286,4 +342,31 @@ mod tests {
         assert!(lazy(|cx| conn.poll_ready(cx).is_ready()).await);
         assert!(lazy(|cx| conn.poll_shutdown(cx).is_ready()).await);
     }
+
+    #[crate::rt_test]
+    async fn test_connect_timeout() {
+        // custom connector ignores `IoConfig` connect timeout,
+        // so only connector's timeout could interrupt it
+        let conn = Pipeline::new(
+            Connector::default()
+                .connector(fn_factory_with_config(async |_: SharedCfg| {
+                    Ok::<_, io::Error>(fn_service(async |_: TcpConnect<Uri>| {
+                        sleep(Millis(5_000)).await;
+                        Err::<Io, _>(crate::connect::ConnectError::Unresolved)
+                    }))
+                }))
+                .timeout(Millis(50))
+                .create(SharedCfg::default())
+                .await
+                .unwrap(),
+        );
+
+        let res = conn
+            .call(Connect {
+                uri: Uri::from_static("http://localhost:8080"),
+                addr: None,
+            })
+            .await;
+        assert!(matches!(res, Err(ConnectError::Timeout)));
+    }
 }
@@ -34,6 +34,7 @@ pub use self::builder::ClientBuilder;
 pub use self::cfg::ClientConfig;
 pub use self::connection::Connection;
 pub use self::connector::{Connector, ConnectorService};
+pub use self::pool::{HostPoolStats, PoolStats};
 pub use self::request::ClientRequest;
 pub use self::response::{ClientResponse, JsonBody, MessageBody};
 pub use self::service::{ServiceRequest, ServiceResponse};
@@ -71,6 +72,7 @@ type BoxedSender = boxed::BoxService<ServiceRequest, ServiceResponse, error::Cli
 #[derive(Debug, Clone)]
 pub struct Client {
     svc: Pipeline<BoxedSender>,
+    connector: ConnectorService,
     config: ClientConfig,
 }
 
@@ -88,8 +90,21 @@ impl Client {
         ClientBuilder::new()
     }
 
-    pub(crate) fn with_service(svc: Pipeline<BoxedSender>, config: ClientConfig) -> Self {
-        Client { svc, config }
+    pub(crate) fn with_service(
+        svc: Pipeline<BoxedSender>,
+        connector: ConnectorService,
+        config: ClientConfig,
+    ) -> Self {
+        Client {
+            svc,
+            connector,
+            config,
+        }
+    }
+
+    /// Get connection pool statistics.
+    pub fn pool_stats(&self) -> PoolStats {
+        self.connector.pool_stats()
     }
 
     /// Returns when the client is ready to process requests.
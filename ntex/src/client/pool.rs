@@ -30,6 +30,44 @@ impl From<Authority> for Key {
 type Waiter = pool::Sender<Result<Connection, ConnectError>>;
 type WaiterReceiver = pool::Receiver<Result<Connection, ConnectError>>;
 
+#[derive(Clone, Debug, Default)]
+/// Connection pool statistics
+pub struct PoolStats {
+    /// Number of idle connections
+    pub idle: usize,
+    /// Number of acquired connections
+    pub active: usize,
+    /// Number of connections that are being established
+    pub connecting: usize,
+    /// Number of requests that wait for available connection
+    pub waiting: usize,
+    /// Per-host statistics
+    pub hosts: HashMap<Authority, HostPoolStats>,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// Per-host connection pool statistics
+pub struct HostPoolStats {
+    /// Number of idle connections
+    pub idle: usize,
+    /// Number of acquired connections
+    pub active: usize,
+}
+
+impl PoolStats {
+    pub(super) fn merge(&mut self, other: PoolStats) {
+        self.idle += other.idle;
+        self.active += other.active;
+        self.connecting += other.connecting;
+        self.waiting += other.waiting;
+        for (host, stats) in other.hosts {
+            let entry = self.hosts.entry(host).or_default();
+            entry.idle += stats.idle;
+            entry.active += stats.active;
+        }
+    }
+}
+
 enum Acquire {
     Acquired(ConnectionType, Instant),
     Available,
@@ -61,6 +99,7 @@ pub(super) struct Inner {
     conn_keep_alive: Duration,
     limit: usize,
     acquired: usize,
+    active: HashMap<Key, usize>,
     available: HashMap<Key, VecDeque<AvailableConnection>>,
     connecting: HashSet<Key>,
     waker: inplace::Inplace<()>,
@@ -85,6 +124,7 @@ impl ConnectionPool {
             limit,
             stopped: false,
             acquired: 0,
+            active: HashMap::default(),
             available: HashMap::default(),
             connecting: HashSet::default(),
             waker: inplace::channel(),
@@ -111,6 +151,36 @@ impl ConnectionPool {
     }
 }
 
+impl ConnectionPool {
+    /// Get connection pool statistics
+    pub(super) fn stats(&self) -> PoolStats {
+        let inner = self.0.inner.borrow();
+        let mut stats = PoolStats {
+            active: inner.acquired,
+            connecting: inner.connecting.len(),
+            waiting: self
+                .0
+                .waiters
+                .borrow()
+                .waiters
+                .values()
+                .map(VecDeque::len)
+                .sum(),
+            ..Default::default()
+        };
+        for (key, conns) in &inner.available {
+            if !conns.is_empty() {
+                stats.idle += conns.len();
+                stats.hosts.entry(key.authority.clone()).or_default().idle += conns.len();
+            }
+        }
+        for (key, active) in &inner.active {
+            stats.hosts.entry(key.authority.clone()).or_default().active += *active;
+        }
+        stats
+    }
+}
+
 impl Drop for ConnectionPool {
     fn drop(&mut self) {
         if Rc::strong_count(&self.0) == 1 {
@@ -334,6 +404,21 @@ impl Inner {
         }
     }
 
+    fn inc_active(&mut self, key: &Key) {
+        self.acquired += 1;
+        *self.active.entry(key.clone()).or_default() += 1;
+    }
+
+    fn dec_active(&mut self, key: &Key) {
+        self.acquired -= 1;
+        if let Some(active) = self.active.get_mut(key) {
+            *active -= 1;
+            if *active == 0 {
+                self.active.remove(key);
+            }
+        }
+    }
+
     fn check_availibility(&mut self) {
         let mut waiters = self.waiters.borrow_mut();
         waiters.cleanup();
@@ -582,7 +667,7 @@ pub(super) struct Acquired(Key, Option<Rc<RefCell<Inner>>>);
 
 impl Acquired {
     fn new(key: Key, inner: Rc<RefCell<Inner>>) -> Self {
-        inner.borrow_mut().acquired += 1;
+        inner.borrow_mut().inc_active(&key);
         Acquired(key, Some(inner))
     }
 
@@ -594,7 +679,7 @@ impl Acquired {
         if let Some(inner) = self.1.take() {
             let (io, created, _) = conn.into_inner();
             let mut inner = inner.borrow_mut();
-            inner.acquired -= 1;
+            inner.dec_active(&self.0);
             if close {
                 log::trace!(
                     "{:?}: Releasing and closing connection for {:?}",
@@ -634,7 +719,7 @@ impl Drop for Acquired {
     fn drop(&mut self) {
         if let Some(inner) = self.1.take() {
             let mut inner = inner.borrow_mut();
-            inner.acquired -= 1;
+            inner.dec_active(&self.0);
             inner.check_availibility();
         }
     }
@@ -698,6 +783,15 @@ mod tests {
         assert!(lazy(|cx| fut.as_mut().poll(cx)).await.is_pending());
         assert_eq!(pool.get_ref().0.waiters.borrow().waiters.len(), 1);
 
+        let stats = pool.get_ref().stats();
+        assert_eq!(stats.active, 1);
+        assert_eq!(stats.idle, 0);
+        assert_eq!(stats.waiting, 1);
+        assert_eq!(
+            stats.hosts[&Authority::from_static("localhost")],
+            HostPoolStats { idle: 0, active: 1 }
+        );
+
         // release connection and push it to next waiter
         conn.release(false);
         assert_eq!(pool.get_ref().0.inner.borrow().acquired, 0);
@@ -718,6 +812,7 @@ mod tests {
         // release and close
         conn.release(true);
         assert_eq!(pool.get_ref().0.inner.borrow().acquired, 0);
+        assert!(pool.get_ref().0.inner.borrow().active.is_empty());
         assert!(pool.get_ref().0.inner.borrow().connecting.is_empty());
 
         let conn = fut.await.unwrap();
@@ -8,7 +8,7 @@ use serde::Serialize;
 use crate::http::body::Body;
 use crate::http::error::HttpError;
 use crate::http::header::{self, HeaderMap, HeaderName, HeaderValue};
-use crate::http::{ConnectionType, Method, Uri, Version};
+use crate::http::{ConnectionType, Method, TraceContext, Uri, Version};
 use crate::{Pipeline, time::Millis, util::Bytes, util::Stream};
 
 use super::error::{ClientError, InvalidUrl};
@@ -213,6 +213,27 @@ impl ClientRequest {
         self
     }
 
+    #[must_use]
+    /// Propagate distributed tracing context.
+    ///
+    /// Sets `traceparent` and `tracestate` headers, existing headers get replaced.
+    ///
+    /// ```rust
+    /// use ntex::{client::Client, http::TraceContext, web::HttpRequest};
+    ///
+    /// async fn index(req: HttpRequest) {
+    ///     let ctx = req.trace_context().unwrap_or_default();
+    ///     let request = Client::new()
+    ///         .await
+    ///         .get("http://www.rust-lang.org")
+    ///         .trace_context(&ctx.child());
+    /// }
+    /// ```
+    pub fn trace_context(mut self, ctx: &TraceContext) -> Self {
+        ctx.inject(&mut self.request.head.headers);
+        self
+    }
+
     #[inline]
     #[must_use]
     /// Set connection type of the message.
@@ -415,6 +436,10 @@ impl ClientRequest {
     }
 
     /// Set an streaming body and generate `ClientRequest`.
+    ///
+    /// Body size is not known upfront, so `content-length` header is not set.
+    /// Http/1.1 requests use chunked transfer encoding, http/2 requests
+    /// send body as a sequence of data frames.
     pub async fn send_stream<T, E>(
         mut self,
         stream: T,
@@ -486,7 +511,11 @@ impl ClientRequest {
                 .headers
                 .contains_key(&header::ACCEPT_ENCODING)
         {
+            #[cfg(not(feature = "zstd"))]
             const COMPRESSION: HeaderValue = HeaderValue::from_static("gzip, deflate");
+            #[cfg(feature = "zstd")]
+            const COMPRESSION: HeaderValue =
+                HeaderValue::from_static("zstd, gzip, deflate");
             self.request
                 .head
                 .headers
@@ -559,6 +588,21 @@ mod tests {
         let _ = req.send_body("").await;
     }
 
+    #[crate::rt_test]
+    async fn test_trace_context() {
+        let ctx = TraceContext::new().sampled(true);
+        let req = Client::new()
+            .await
+            .get("/")
+            .header("tracestate", "congo=t61rcWkgMzE")
+            .trace_context(&ctx);
+        assert_eq!(
+            req.headers().get("traceparent").unwrap(),
+            &ctx.traceparent()
+        );
+        assert!(!req.headers().contains_key("tracestate"));
+    }
+
     #[crate::rt_test]
     async fn test_client_header() {
         let req = Client::builder()
@@ -0,0 +1,179 @@
+//! Response body with write backpressure
+//!
+//! Response body is fed from the `BodySender`. Dispatcher reads next chunk
+//! from the body only if peer's transport is ready to accept more data,
+//! `BodySender::send()` waits until body buffer has free space. Slow peer
+//! suspends producer instead of buffering unbounded data.
+//!
+//! ```rust,no_run
+//! use ntex::http::{body::Body, body_channel};
+//! use ntex::{util::Bytes, web};
+//!
+//! async fn report() -> web::HttpResponse {
+//!     let (tx, body) = body_channel::channel::<std::io::Error>(64 * 1024);
+//!
+//!     ntex::rt::spawn(async move {
+//!         for idx in 0..1_000_000 {
+//!             if tx.send(Bytes::from(format!("row {idx}\n"))).await.is_err() {
+//!                 // peer is gone
+//!                 return;
+//!             }
+//!         }
+//!         tx.close();
+//!     });
+//!
+//!     web::HttpResponse::Ok().body(Body::from(body))
+//! }
+//! ```
+use std::{error::Error, fmt, rc::Rc, task::Context, task::Poll};
+
+use crate::channel::bstream;
+use crate::http::body::{Body, BodySize, MessageBody};
+use crate::util::Bytes;
+
+/// Create response body channel.
+///
+/// `max_buffer_size` is the size of body buffer, sender waits
+/// if buffer is full.
+pub fn channel<E>(max_buffer_size: usize) -> (BodySender<E>, ChannelBody<E>) {
+    let (tx, rx) = bstream::channel();
+    rx.max_buffer_size(max_buffer_size);
+    (BodySender { tx }, ChannelBody { rx })
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("Response body is dropped")]
+/// Response body is dropped, peer is disconnected or response is not sent
+pub struct BodyDropped;
+
+/// Sender side of the response body channel
+pub struct BodySender<E> {
+    tx: bstream::Sender<E>,
+}
+
+impl<E> BodySender<E> {
+    /// Wait until body is ready to accept more data.
+    pub async fn ready(&self) -> Result<(), BodyDropped> {
+        match self.tx.ready().await {
+            bstream::Status::Ready => Ok(()),
+            bstream::Status::Eof | bstream::Status::Dropped => Err(BodyDropped),
+        }
+    }
+
+    /// Check if body is ready to accept more data.
+    pub fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), BodyDropped>> {
+        self.tx.poll_ready(cx).map(|status| match status {
+            bstream::Status::Ready => Ok(()),
+            bstream::Status::Eof | bstream::Status::Dropped => Err(BodyDropped),
+        })
+    }
+
+    /// Wait until body is ready and send chunk.
+    pub async fn send(&self, chunk: Bytes) -> Result<(), BodyDropped> {
+        self.ready().await?;
+        self.tx.feed_data(chunk);
+        Ok(())
+    }
+
+    /// Check if body is dropped.
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+
+    /// Complete response body.
+    pub fn close(self) {
+        self.tx.feed_eof();
+    }
+
+    /// Terminate response body with error.
+    pub fn error(self, err: E) {
+        self.tx.set_error(err);
+    }
+}
+
+impl<E> fmt::Debug for BodySender<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BodySender")
+            .field("closed", &self.tx.is_closed())
+            .finish()
+    }
+}
+
+/// Response body that is fed from `BodySender`
+pub struct ChannelBody<E> {
+    rx: bstream::Receiver<E>,
+}
+
+impl<E> fmt::Debug for ChannelBody<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChannelBody")
+            .field("eof", &self.rx.is_eof())
+            .finish()
+    }
+}
+
+impl<E: Error + 'static> MessageBody for ChannelBody<E> {
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    fn poll_next_chunk(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Rc<dyn Error>>>> {
+        self.rx.poll_read(cx).map(|item| {
+            item.map(|res| {
+                res.map_err(|e| {
+                    let e: Rc<dyn Error> = Rc::new(e);
+                    e
+                })
+            })
+        })
+    }
+}
+
+impl<E: Error + 'static> From<ChannelBody<E>> for Body {
+    fn from(body: ChannelBody<E>) -> Body {
+        Body::from_message(body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::poll_fn, io};
+
+    use super::*;
+
+    #[crate::rt_test]
+    async fn test_backpressure() {
+        let (tx, mut body) = channel::<io::Error>(8);
+        assert_eq!(body.size(), BodySize::Stream);
+
+        tx.send(Bytes::from_static(b"0123456789")).await.unwrap();
+        // buffer is full
+        assert!(poll_fn(|cx| Poll::Ready(tx.poll_ready(cx).is_pending())).await);
+
+        let chunk = poll_fn(|cx| body.poll_next_chunk(cx)).await;
+        assert_eq!(chunk.unwrap().unwrap(), &b"0123456789"[..]);
+        assert_eq!(tx.ready().await, Ok(()));
+
+        tx.send(Bytes::from_static(b"data")).await.unwrap();
+        tx.close();
+        let chunk = poll_fn(|cx| body.poll_next_chunk(cx)).await;
+        assert_eq!(chunk.unwrap().unwrap(), &b"data"[..]);
+        assert!(poll_fn(|cx| body.poll_next_chunk(cx)).await.is_none());
+    }
+
+    #[crate::rt_test]
+    async fn test_dropped() {
+        let (tx, body) = channel::<io::Error>(1024);
+        drop(body);
+        assert!(tx.is_closed());
+        assert_eq!(tx.send(Bytes::from_static(b"data")).await, Err(BodyDropped));
+
+        let (tx, mut body) = channel::<io::Error>(1024);
+        tx.error(io::Error::other("err"));
+        let chunk = poll_fn(|cx| body.poll_next_chunk(cx)).await;
+        assert!(chunk.unwrap().is_err());
+    }
+}
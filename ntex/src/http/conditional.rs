@@ -0,0 +1,143 @@
+//! Conditional requests support ([RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#name-conditional-requests))
+//!
+//! ```rust
+//! use std::time::SystemTime;
+//!
+//! use ntex::http::conditional;
+//! use ntex::web::{self, HttpRequest, HttpResponse};
+//!
+//! async fn handler(req: HttpRequest) -> HttpResponse {
+//!     let mtime = SystemTime::UNIX_EPOCH;
+//!     conditional::last_modified(req.head(), mtime, || "data")
+//! }
+//! ```
+use std::time::SystemTime;
+
+use httpdate::HttpDate;
+
+use crate::http::body::Body;
+use crate::http::header;
+use crate::http::{Method, RequestHead, Response, StatusCode};
+
+/// Parse HTTP-date value.
+///
+/// All formats defined by RFC 9110 are supported, preferred IMF-fixdate
+/// and obsolete RFC 850 and asctime formats. Returns `None` if value
+/// is malformed.
+pub fn parse_http_date(value: &str) -> Option<SystemTime> {
+    httpdate::parse_http_date(value.trim()).ok()
+}
+
+/// Check if content modified at `mtime` is not modified since
+/// date of request's `If-Modified-Since` header.
+///
+/// Precondition is evaluated only for `GET` and `HEAD` requests and is
+/// ignored if request contains `If-None-Match` header. Modification time is
+/// truncated to whole seconds, HTTP-date does not have better resolution.
+pub fn is_not_modified(req: &RequestHead, mtime: SystemTime) -> bool {
+    if !matches!(req.method, Method::GET | Method::HEAD)
+        || req.headers.contains_key(header::IF_NONE_MATCH)
+    {
+        return false;
+    }
+
+    req.headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|val| val.to_str().ok())
+        .and_then(parse_http_date)
+        .is_some_and(|since| SystemTime::from(HttpDate::from(mtime)) <= since)
+}
+
+/// Create response for content modified at `mtime`.
+///
+/// Response is *304 Not Modified* without body if request's
+/// `If-Modified-Since` date is equal or newer than `mtime`, otherwise
+/// `body` is called and *200 OK* response is created. Both responses
+/// contain `Last-Modified` header.
+pub fn last_modified<F, B>(req: &RequestHead, mtime: SystemTime, body: F) -> Response
+where
+    F: FnOnce() -> B,
+    Body: From<B>,
+{
+    if is_not_modified(req, mtime) {
+        Response::build(StatusCode::NOT_MODIFIED)
+            .header(header::LAST_MODIFIED, HttpDate::from(mtime).to_string())
+            .finish()
+    } else {
+        Response::build(StatusCode::OK)
+            .header(header::LAST_MODIFIED, HttpDate::from(mtime).to_string())
+            .body(body())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::http::test::TestRequest;
+
+    #[test]
+    fn test_parse_http_date() {
+        let expected = SystemTime::UNIX_EPOCH + Duration::from_secs(784_111_777);
+        for val in [
+            "Sun, 06 Nov 1994 08:49:37 GMT",
+            " Sun, 06 Nov 1994 08:49:37 GMT ",
+            "Sunday, 06-Nov-94 08:49:37 GMT",
+            "Sun Nov  6 08:49:37 1994",
+        ] {
+            assert_eq!(parse_http_date(val), Some(expected), "{val}");
+        }
+        assert_eq!(parse_http_date(""), None);
+        assert_eq!(parse_http_date("06 Nov 1994"), None);
+    }
+
+    #[test]
+    fn test_last_modified() {
+        let mtime = SystemTime::UNIX_EPOCH + Duration::from_millis(784_111_777_500);
+
+        let req = TestRequest::default().finish();
+        let resp = last_modified(req.head(), mtime, || "data");
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::LAST_MODIFIED).unwrap(),
+            "Sun, 06 Nov 1994 08:49:37 GMT"
+        );
+        assert!(matches!(resp.body().as_ref(), Some(Body::Bytes(b)) if b == &b"data"[..]));
+
+        // same second and newer dates
+        for val in [
+            "Sun, 06 Nov 1994 08:49:37 GMT",
+            "Sunday, 06-Nov-94 08:49:38 GMT",
+            "Mon Nov  7 08:49:37 1994",
+        ] {
+            let req = TestRequest::default()
+                .header(header::IF_MODIFIED_SINCE, val)
+                .finish();
+            let resp = last_modified(req.head(), mtime, || "data");
+            assert_eq!(resp.status(), StatusCode::NOT_MODIFIED, "{val}");
+            assert!(resp.headers().contains_key(header::LAST_MODIFIED));
+        }
+
+        // older date, malformed date
+        for val in ["Sun, 06 Nov 1994 08:49:36 GMT", "yesterday"] {
+            let req = TestRequest::default()
+                .header(header::IF_MODIFIED_SINCE, val)
+                .finish();
+            let resp = last_modified(req.head(), mtime, || "data");
+            assert_eq!(resp.status(), StatusCode::OK, "{val}");
+        }
+
+        // ignored for non GET requests and with If-None-Match
+        let req = TestRequest::default()
+            .method(Method::POST)
+            .header(header::IF_MODIFIED_SINCE, "Sun, 06 Nov 1994 08:49:37 GMT")
+            .finish();
+        assert!(!is_not_modified(req.head(), mtime));
+        let req = TestRequest::default()
+            .header(header::IF_MODIFIED_SINCE, "Sun, 06 Nov 1994 08:49:37 GMT")
+            .header(header::IF_NONE_MATCH, "\"tag\"")
+            .finish();
+        assert!(!is_not_modified(req.head(), mtime));
+    }
+}
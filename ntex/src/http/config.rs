@@ -1,8 +1,10 @@
-use std::{cell::Cell, time};
+use std::{cell::Cell, fmt, sync::Arc, time};
 
-use crate::service::cfg::{Cfg, CfgContext, Configuration};
+use crate::http::{RequestHead, ResponseHead};
+use crate::io::{Io, IoConfig, cfg::FrameReadRate};
+use crate::service::cfg::{Cfg, CfgContext, Configuration, SharedCfg};
 use crate::time::{Millis, Seconds, sleep};
-use crate::{io::cfg::FrameReadRate, service::Pipeline, util::BytePages, util::BytesMut};
+use crate::{service::Pipeline, util::BytePages, util::BytesMut};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 /// Server keep-alive setting
@@ -39,17 +41,65 @@ impl From<Option<usize>> for KeepAlive {
 
 #[derive(Debug)]
 /// Http service configuration
+///
+/// Read and write buffer sizes of http connections are inherited from
+/// [`IoConfig`](crate::io::IoConfig) and could be overridden with
+/// `set_read_buf_size()` and `set_write_buf_size()`. Larger buffers reduce
+/// number of reallocations for big request and response bodies, smaller
+/// buffers reduce memory usage for many small requests.
+///
+/// ```rust
+/// use ntex::{SharedCfg, http::HttpServiceConfig};
+///
+/// let cfg = SharedCfg::new("HTTP").add(
+///     HttpServiceConfig::new()
+///         .set_max_buf_size(256 * 1024)
+///         // read buffer, 64kb high and 4kb low watermarks
+///         .set_read_buf_size(64 * 1024, 4 * 1024)
+///         // write buffer, 64kb high and 4kb low watermarks
+///         .set_write_buf_size(64 * 1024, 4 * 1024),
+/// );
+/// ```
 pub struct HttpServiceConfig {
     pub(super) keep_alive: Seconds,
     pub(super) ka_enabled: bool,
     pub(super) max_headers: usize,
+    pub(super) max_headers_size: usize,
     pub(super) max_buf_size: usize,
+    pub(super) read_buf_size: Option<(usize, usize)>,
+    pub(super) write_buf_size: Option<(usize, usize)>,
     pub(super) headers_read_rate: Option<FrameReadRate>,
     pub(super) payload_read_rate: Option<FrameReadRate>,
+    pub(super) h2c: bool,
+    pub(super) pipelining: bool,
+    pub(super) raw_headers: bool,
+    pub(super) keep_upgrade_payload: bool,
+    pub(super) keepalive_fn: Option<KeepAliveFn>,
+    pub(super) drain_timeout: Seconds,
+    pub(super) drain_retry_after: Seconds,
+    pub(super) payload_drain_limit: usize,
 
     config: CfgContext,
 }
 
+/// Per-request keep-alive timeout callback
+#[derive(Clone)]
+pub(super) struct KeepAliveFn(
+    Arc<dyn Fn(&RequestHead, &ResponseHead) -> Option<Seconds> + Send + Sync>,
+);
+
+impl KeepAliveFn {
+    pub(super) fn call(&self, req: &RequestHead, res: &ResponseHead) -> Option<Seconds> {
+        (self.0)(req, res)
+    }
+}
+
+impl fmt::Debug for KeepAliveFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeepAliveFn").finish()
+    }
+}
+
 impl Default for HttpServiceConfig {
     fn default() -> Self {
         HttpServiceConfig::new()
@@ -92,8 +142,19 @@ impl HttpServiceConfig {
                 max_timeout: client_timeout + Seconds(15),
             }),
             max_headers: 96,
+            max_headers_size: 64 * 1024,
             max_buf_size: 64 * 1024,
+            read_buf_size: None,
+            write_buf_size: None,
             payload_read_rate: None,
+            h2c: false,
+            pipelining: true,
+            raw_headers: false,
+            keep_upgrade_payload: false,
+            keepalive_fn: None,
+            drain_timeout: Seconds::ZERO,
+            drain_retry_after: Seconds::ZERO,
+            payload_drain_limit: 0,
             config: CfgContext::default(),
         }
     }
@@ -113,6 +174,20 @@ impl HttpServiceConfig {
         self
     }
 
+    #[must_use]
+    /// Set the maximum total size of headers.
+    ///
+    /// Size is calculated as sum of header names and values lengths, limit
+    /// is checked independently of the message head buffer size. If server
+    /// receives larger headers, it closes connection with
+    /// `DecodeError::TooLarge` error.
+    ///
+    /// Default is 64kb
+    pub fn set_max_headers_size(mut self, val: usize) -> Self {
+        self.max_headers_size = val;
+        self
+    }
+
     #[must_use]
     /// Set the maximum buffer size for parsing http message.
     ///
@@ -122,6 +197,60 @@ impl HttpServiceConfig {
         self
     }
 
+    #[must_use]
+    /// Set read buffer high and low watermarks of http connections.
+    ///
+    /// By default read buffer parameters of `IoConfig` are used.
+    pub fn set_read_buf_size(
+        mut self,
+        high_watermark: usize,
+        low_watermark: usize,
+    ) -> Self {
+        self.read_buf_size = Some((high_watermark, low_watermark));
+        self
+    }
+
+    #[must_use]
+    /// Set write buffer high and low watermarks of http connections.
+    ///
+    /// By default write buffer parameters of `IoConfig` are used.
+    pub fn set_write_buf_size(
+        mut self,
+        high_watermark: usize,
+        low_watermark: usize,
+    ) -> Self {
+        self.write_buf_size = Some((high_watermark, low_watermark));
+        self
+    }
+
+    /// Io configuration with overridden buffer sizes
+    fn io_config(&self) -> Option<SharedCfg> {
+        if self.read_buf_size.is_none() && self.write_buf_size.is_none() {
+            return None;
+        }
+
+        let io = self.config.shared().get::<IoConfig>();
+        let (read_high, read_low) = self
+            .read_buf_size
+            .unwrap_or((io.read_buf().high, io.read_buf().low));
+        let (write_high, write_low) = self
+            .write_buf_size
+            .unwrap_or((io.write_buf().high, io.write_buf().low));
+
+        let mut cfg = IoConfig::new()
+            .set_connect_timeout(io.connect_timeout())
+            .set_keepalive_timeout(io.keepalive_timeout())
+            .set_disconnect_timeout(io.disconnect_timeout())
+            .set_read_buf(read_high, read_low, IO_BUF_CACHE_SIZE)
+            .set_write_buf(write_high, write_low, IO_BUF_CACHE_SIZE)
+            .set_write_page_size(io.write_page_size())
+            .set_write_buf_threshold(io.write_buf_threshold());
+        if let Some(rate) = io.frame_read_rate() {
+            cfg = cfg.set_frame_read_rate(rate.timeout, rate.max_timeout, rate.rate);
+        }
+        Some(SharedCfg::new(self.config.tag()).add(cfg).into())
+    }
+
     #[must_use]
     /// Set server keep-alive setting.
     ///
@@ -151,6 +280,26 @@ impl HttpServiceConfig {
         self
     }
 
+    #[must_use]
+    /// Set per-request keep-alive timeout callback.
+    ///
+    /// Callback is called for each http/1 request after response is
+    /// sent. Returned value is used as keep-alive timeout for the next
+    /// request on the same connection, `None` means default timeout.
+    /// Callback does not enable keep-alive if it is disabled.
+    ///
+    /// Request head passed to the callback is a copy of the received
+    /// request head. It contains uri, method, version, headers and
+    /// connection io, so `RequestHead::peer_addr()` is available.
+    /// Request extensions and changes made by the service are not visible.
+    pub fn set_keepalive_fn<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&RequestHead, &ResponseHead) -> Option<Seconds> + Send + Sync + 'static,
+    {
+        self.keepalive_fn = Some(KeepAliveFn(Arc::new(f)));
+        self
+    }
+
     #[must_use]
     /// Set request headers read timeout.
     ///
@@ -176,6 +325,18 @@ impl HttpServiceConfig {
         self
     }
 
+    #[must_use]
+    /// Set request headers read deadline.
+    ///
+    /// Client must send complete request head within `timeout`, the timer
+    /// is not extended while client keeps sending data. Otherwise, connection
+    /// is closed with the 408 (Request Time-out) error.
+    ///
+    /// To disable timeout set value to 0. Overrides headers read rate parameters.
+    pub fn set_headers_timeout(self, timeout: Seconds) -> Self {
+        self.set_headers_read_rate(timeout, timeout, 0)
+    }
+
     #[must_use]
     /// Set read rate parameters for request headers.
     ///
@@ -227,6 +388,110 @@ impl HttpServiceConfig {
         }
         self
     }
+
+    #[must_use]
+    /// Set request payload read deadline.
+    ///
+    /// Timer starts when first chunk of the payload is received. Client must
+    /// send complete payload within `timeout`, the timer is not extended while
+    /// client keeps sending data. Otherwise, payload stream fails and connection
+    /// is closed.
+    ///
+    /// To disable timeout set value to 0. Overrides payload read rate parameters.
+    pub fn set_payload_timeout(self, timeout: Seconds) -> Self {
+        self.set_payload_read_rate(timeout, timeout, 0)
+    }
+
+    #[must_use]
+    /// Enable cleartext http/2 with prior knowledge.
+    ///
+    /// If enabled, plaintext connections that start with http/2 connection
+    /// preface are handled by http/2 dispatcher. Upgrade from http/1.1
+    /// via `Upgrade: h2c` header (deprecated by RFC 9113) is not supported,
+    /// such requests are served over http/1.1 without `101` response.
+    ///
+    /// By default h2c is disabled.
+    pub fn set_h2c(mut self, enabled: bool) -> Self {
+        self.h2c = enabled;
+        self
+    }
+
+    #[must_use]
+    /// Enable http/1 request pipelining.
+    ///
+    /// If disabled, dispatcher does not read next request until response
+    /// for current request is fully flushed to the peer.
+    ///
+    /// By default pipelining is enabled.
+    pub fn set_pipelining(mut self, enabled: bool) -> Self {
+        self.pipelining = enabled;
+        self
+    }
+
+    #[must_use]
+    /// Preserve raw http/1 request headers and request target.
+    ///
+    /// If enabled, original header names and values are stored in received
+    /// order and are available via `RequestHead::raw_headers()`. This is useful
+    /// for proxies that need to forward headers with original casing.
+    /// Request target from request line is available via
+    /// `RequestHead::raw_target()`.
+    ///
+    /// By default raw headers are not preserved.
+    pub fn set_raw_headers(mut self, enabled: bool) -> Self {
+        self.raw_headers = enabled;
+        self
+    }
+
+    #[must_use]
+    /// Keep request payload on the connection for http/1 upgrade requests.
+    ///
+    /// If enabled, dispatcher does not read payload of upgrade requests,
+    /// bytes sent by the client immediately after upgrade request stay in
+    /// connection's read buffer and are available to the upgraded io.
+    /// Request payload of upgrade requests is empty.
+    ///
+    /// By default upgrade request payload is read by dispatcher.
+    pub fn set_keep_upgrade_payload(mut self, enabled: bool) -> Self {
+        self.keep_upgrade_payload = enabled;
+        self
+    }
+
+    #[must_use]
+    /// Set drain timeout for in-flight http/1 requests during shutdown.
+    ///
+    /// If service is shutting down and request handler does not complete
+    /// within `timeout`, handler is dropped and client receives
+    /// *503 Service Unavailable* response with `Retry-After` header and
+    /// the connection is closed. `Retry-After` header is omitted if
+    /// `retry_after` is zero. Timeout should be smaller than server's
+    /// shutdown timeout, otherwise connection is closed without response.
+    ///
+    /// To disable drain timeout set value to 0.
+    ///
+    /// By default drain timeout is disabled.
+    pub fn set_drain_timeout(mut self, timeout: Seconds, retry_after: Seconds) -> Self {
+        self.drain_timeout = timeout;
+        self.drain_retry_after = retry_after;
+        self
+    }
+
+    #[must_use]
+    /// Set limit for draining of unconsumed http/1 request payload.
+    ///
+    /// If request handler drops request payload without reading it,
+    /// dispatcher reads and discards up to `limit` bytes of the payload
+    /// and keeps connection open for the next request. Connection is closed
+    /// if payload is larger than `limit`. Size of chunked payload is not known
+    /// in advance, such payloads are not drained.
+    ///
+    /// To disable payload draining set value to 0.
+    ///
+    /// By default payload draining is disabled.
+    pub fn set_payload_drain_limit(mut self, limit: usize) -> Self {
+        self.payload_drain_limit = limit;
+        self
+    }
 }
 
 bitflags::bitflags! {
@@ -239,6 +504,8 @@ bitflags::bitflags! {
 
 pub(super) struct DispatcherConfig<S, C> {
     flags: Cell<Flags>,
+    shutdown_at: Cell<Option<time::Instant>>,
+    io_config: Option<SharedCfg>,
     pub(super) idx: Cell<usize>,
     pub(super) config: Cfg<HttpServiceConfig>,
     pub(super) service: Pipeline<S>,
@@ -252,10 +519,19 @@ impl<S, C> DispatcherConfig<S, C> {
             service: service.into(),
             control: control.into(),
             flags: Cell::new(Flags::empty()),
+            shutdown_at: Cell::new(None),
+            io_config: config.io_config(),
             config,
         }
     }
 
+    /// Apply http specific io configuration
+    pub(super) fn configure_io<F>(&self, io: &Io<F>) {
+        if let Some(ref cfg) = self.io_config {
+            io.set_config(cfg.clone());
+        }
+    }
+
     /// Get connection id
     pub(super) fn next_id(&self) -> usize {
         let id = self.idx.get();
@@ -273,6 +549,11 @@ impl<S, C> DispatcherConfig<S, C> {
         self.config.ka_enabled
     }
 
+    /// Per-request keep-alive timeout callback
+    pub(super) fn keep_alive_fn(&self) -> Option<&KeepAliveFn> {
+        self.config.keepalive_fn.as_ref()
+    }
+
     pub(super) fn headers_read_rate(&self) -> Option<&FrameReadRate> {
         self.config.headers_read_rate.as_ref()
     }
@@ -281,6 +562,44 @@ impl<S, C> DispatcherConfig<S, C> {
         self.config.payload_read_rate.as_ref()
     }
 
+    /// Return state of cleartext http/2 support
+    pub(super) fn h2c(&self) -> bool {
+        self.config.h2c
+    }
+
+    /// Return state of http/1 request pipelining
+    pub(super) fn pipelining(&self) -> bool {
+        self.config.pipelining
+    }
+
+    /// Keep payload of upgrade requests on the connection
+    pub(super) fn keep_upgrade_payload(&self) -> bool {
+        self.config.keep_upgrade_payload
+    }
+
+    /// Max size of unconsumed request payload to drain
+    pub(super) fn payload_drain_limit(&self) -> usize {
+        self.config.payload_drain_limit
+    }
+
+    /// Drain timeout and `Retry-After` value for in-flight requests
+    pub(super) fn drain_timeout(&self) -> Option<(Seconds, Seconds)> {
+        if self.config.drain_timeout.is_zero() {
+            None
+        } else {
+            Some((self.config.drain_timeout, self.config.drain_retry_after))
+        }
+    }
+
+    /// Time left until drain timeout is elapsed, counts from service shutdown
+    pub(super) fn drain_remains(&self) -> Option<Millis> {
+        let (timeout, _) = self.drain_timeout()?;
+        let elapsed = crate::time::now().saturating_duration_since(self.shutdown_at.get()?);
+        Some(Millis::from(
+            time::Duration::from(timeout).saturating_sub(elapsed),
+        ))
+    }
+
     /// Service is shutting down
     pub(super) fn is_shutdown(&self) -> bool {
         self.flags.get().contains(Flags::SHUTDOWN)
@@ -292,9 +611,13 @@ impl<S, C> DispatcherConfig<S, C> {
         let mut flags = self.flags.get();
         flags.insert(Flags::SHUTDOWN);
         self.flags.set(flags);
+        if self.shutdown_at.get().is_none() {
+            self.shutdown_at.set(Some(crate::time::now()));
+        }
     }
 }
 
+const IO_BUF_CACHE_SIZE: usize = 128;
 const DATE_VALUE_LENGTH_HDR: usize = 39;
 const DATE_VALUE_DEFAULT: [u8; DATE_VALUE_LENGTH_HDR] =
     *b"date: 00000000000000000000000000000\r\n\r\n";
@@ -34,6 +34,14 @@ where
             ContentEncoding::Gzip => Some(ContentDecoder::Gzip(Box::new(GzDecoder::new(
                 Writer::new(),
             )))),
+            #[cfg(feature = "brotli")]
+            ContentEncoding::Br => Some(ContentDecoder::Br(Box::new(
+                brotli::DecompressorWriter::new(Writer::new(), 8192),
+            ))),
+            #[cfg(feature = "zstd")]
+            ContentEncoding::Zstd => zstd::stream::write::Decoder::new(Writer::new())
+                .ok()
+                .map(|decoder| ContentDecoder::Zstd(Box::new(decoder))),
             _ => None,
         };
         Decoder {
@@ -60,6 +68,19 @@ where
 
         Self::new(stream, encoding)
     }
+
+    #[must_use]
+    /// Set max size of decoded payload.
+    ///
+    /// Decoded data is checked during decoding, so the size of a single
+    /// decoded chunk never exceeds the limit. If the limit is reached,
+    /// `PayloadError::Overflow` is returned. By default size is not limited.
+    pub fn limit(mut self, limit: usize) -> Self {
+        if let Some(ref mut inner) = self.inner {
+            inner.writer().limit = limit;
+        }
+        self
+    }
 }
 
 impl<S> Stream for Decoder<S>
@@ -74,14 +95,20 @@ where
     ) -> Poll<Option<Self::Item>> {
         loop {
             if let Some(ref mut fut) = self.fut {
-                let (chunk, decoder) = match Pin::new(fut).poll(cx) {
-                    Poll::Ready(Ok(Ok(item))) => item,
-                    Poll::Ready(Ok(Err(e))) => return Poll::Ready(Some(Err(e.into()))),
-                    Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e.into()))),
+                let result = match Pin::new(fut).poll(cx) {
+                    Poll::Ready(Ok(result)) => result.map_err(payload_error),
+                    Poll::Ready(Err(e)) => Err(e.into()),
                     Poll::Pending => return Poll::Pending,
                 };
-                self.inner = Some(decoder);
                 self.fut.take();
+                let (chunk, decoder) = match result {
+                    Ok(item) => item,
+                    Err(e) => {
+                        self.eof = true;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                };
+                self.inner = Some(decoder);
                 if let Some(chunk) = chunk {
                     return Poll::Ready(Some(Ok(chunk)));
                 }
@@ -96,10 +123,17 @@ where
                 Poll::Ready(Some(Ok(chunk))) => {
                     if let Some(mut decoder) = self.inner.take() {
                         if chunk.len() < INPLACE {
-                            let chunk = decoder.feed_data(&chunk)?;
-                            self.inner = Some(decoder);
-                            if let Some(chunk) = chunk {
-                                return Poll::Ready(Some(Ok(chunk)));
+                            match decoder.feed_data(&chunk) {
+                                Ok(chunk) => {
+                                    self.inner = Some(decoder);
+                                    if let Some(chunk) = chunk {
+                                        return Poll::Ready(Some(Ok(chunk)));
+                                    }
+                                }
+                                Err(e) => {
+                                    self.eof = true;
+                                    return Poll::Ready(Some(Err(payload_error(e))));
+                                }
                             }
                         } else {
                             self.fut = Some(spawn_blocking(move || {
@@ -117,7 +151,7 @@ where
                         match decoder.feed_eof() {
                             Ok(Some(res)) => Poll::Ready(Some(Ok(res))),
                             Ok(None) => Poll::Ready(None),
-                            Err(err) => Poll::Ready(Some(Err(err.into()))),
+                            Err(err) => Poll::Ready(Some(Err(payload_error(err)))),
                         }
                     } else {
                         Poll::Ready(None)
@@ -133,46 +167,67 @@ where
 enum ContentDecoder {
     Deflate(Box<ZlibDecoder<Writer>>),
     Gzip(Box<GzDecoder<Writer>>),
+    #[cfg(feature = "brotli")]
+    Br(Box<brotli::DecompressorWriter<Writer>>),
+    #[cfg(feature = "zstd")]
+    Zstd(Box<zstd::stream::write::Decoder<'static, Writer>>),
 }
 
 impl ContentDecoder {
-    fn feed_eof(&mut self) -> io::Result<Option<Bytes>> {
+    fn writer(&mut self) -> &mut Writer {
         match self {
-            ContentDecoder::Gzip(decoder) => match decoder.try_finish() {
-                Ok(()) => {
-                    let b = decoder.get_mut().take();
-                    if b.is_empty() { Ok(None) } else { Ok(Some(b)) }
-                }
-                Err(e) => Err(e),
-            },
-            ContentDecoder::Deflate(decoder) => match decoder.try_finish() {
-                Ok(()) => {
-                    let b = decoder.get_mut().take();
-                    if b.is_empty() { Ok(None) } else { Ok(Some(b)) }
-                }
-                Err(e) => Err(e),
-            },
+            ContentDecoder::Deflate(decoder) => decoder.get_mut(),
+            ContentDecoder::Gzip(decoder) => decoder.get_mut(),
+            #[cfg(feature = "brotli")]
+            ContentDecoder::Br(decoder) => decoder.get_mut(),
+            #[cfg(feature = "zstd")]
+            ContentDecoder::Zstd(decoder) => decoder.get_mut(),
         }
     }
 
+    fn feed_eof(&mut self) -> io::Result<Option<Bytes>> {
+        let res = match self {
+            ContentDecoder::Gzip(decoder) => decoder.try_finish(),
+            ContentDecoder::Deflate(decoder) => decoder.try_finish(),
+            #[cfg(feature = "brotli")]
+            ContentDecoder::Br(decoder) => decoder.close(),
+            #[cfg(feature = "zstd")]
+            ContentDecoder::Zstd(decoder) => decoder.flush(),
+        };
+        res.map(|()| self.take())
+    }
+
     fn feed_data(&mut self, data: &Bytes) -> io::Result<Option<Bytes>> {
-        match self {
-            ContentDecoder::Gzip(decoder) => match decoder.write_all(data) {
-                Ok(()) => {
-                    decoder.flush()?;
-                    let b = decoder.get_mut().take();
-                    if b.is_empty() { Ok(None) } else { Ok(Some(b)) }
-                }
-                Err(e) => Err(e),
-            },
-            ContentDecoder::Deflate(decoder) => match decoder.write_all(data) {
-                Ok(()) => {
-                    decoder.flush()?;
-                    let b = decoder.get_mut().take();
-                    if b.is_empty() { Ok(None) } else { Ok(Some(b)) }
-                }
-                Err(e) => Err(e),
-            },
-        }
+        let res = match self {
+            ContentDecoder::Gzip(decoder) => {
+                decoder.write_all(data).and_then(|()| decoder.flush())
+            }
+            ContentDecoder::Deflate(decoder) => {
+                decoder.write_all(data).and_then(|()| decoder.flush())
+            }
+            #[cfg(feature = "brotli")]
+            ContentDecoder::Br(decoder) => {
+                decoder.write_all(data).and_then(|()| decoder.flush())
+            }
+            #[cfg(feature = "zstd")]
+            ContentDecoder::Zstd(decoder) => {
+                decoder.write_all(data).and_then(|()| decoder.flush())
+            }
+        };
+        res.map(|()| self.take())
+    }
+
+    fn take(&mut self) -> Option<Bytes> {
+        let b = self.writer().take();
+        if b.is_empty() { None } else { Some(b) }
+    }
+}
+
+/// Writer reports reached size limit with `FileTooLarge` error
+fn payload_error(err: io::Error) -> PayloadError {
+    if err.kind() == io::ErrorKind::FileTooLarge {
+        PayloadError::Overflow
+    } else {
+        err.into()
     }
 }
@@ -28,10 +28,11 @@ impl<B: MessageBody> Encoder<B> {
         head: &mut ResponseHead,
         body: ResponseBody<B>,
     ) -> ResponseBody<B> {
-        let can_encode = ContentEncoder::can_encode(encoding)
+        let can_encode = can_encode(encoding)
             && !(head.headers().contains_key(&CONTENT_ENCODING)
                 || head.status == StatusCode::SWITCHING_PROTOCOLS
                 || head.status == StatusCode::NO_CONTENT
+                || head.is_unbuffered()
                 || encoding == ContentEncoding::Identity
                 || encoding == ContentEncoding::Auto);
 
@@ -186,16 +187,24 @@ fn update_head(encoding: ContentEncoding, head: &mut ResponseHead) {
     );
 }
 
+/// Check if content encoding is supported by encoder
+pub(crate) fn can_encode(encoding: ContentEncoding) -> bool {
+    match encoding {
+        ContentEncoding::Deflate | ContentEncoding::Gzip => true,
+        #[cfg(feature = "zstd")]
+        ContentEncoding::Zstd => true,
+        _ => false,
+    }
+}
+
 enum ContentEncoder {
     Deflate(ZlibEncoder<Writer>),
     Gzip(GzEncoder<Writer>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::stream::write::Encoder<'static, Writer>),
 }
 
 impl ContentEncoder {
-    fn can_encode(encoding: ContentEncoding) -> bool {
-        matches!(encoding, ContentEncoding::Deflate | ContentEncoding::Gzip)
-    }
-
     fn encoder(encoding: ContentEncoding) -> Option<Self> {
         match encoding {
             ContentEncoding::Deflate => Some(ContentEncoder::Deflate(ZlibEncoder::new(
@@ -206,6 +215,10 @@ impl ContentEncoder {
                 Writer::new(),
                 flate2::Compression::fast(),
             ))),
+            #[cfg(feature = "zstd")]
+            ContentEncoding::Zstd => zstd::stream::write::Encoder::new(Writer::new(), 1)
+                .ok()
+                .map(ContentEncoder::Zstd),
             _ => None,
         }
     }
@@ -214,6 +227,8 @@ impl ContentEncoder {
         match *self {
             ContentEncoder::Deflate(ref mut encoder) => encoder.get_mut().take(),
             ContentEncoder::Gzip(ref mut encoder) => encoder.get_mut().take(),
+            #[cfg(feature = "zstd")]
+            ContentEncoder::Zstd(ref mut encoder) => encoder.get_mut().take(),
         }
     }
 
@@ -227,6 +242,11 @@ impl ContentEncoder {
                 Ok(writer) => Ok(writer.buf.freeze()),
                 Err(err) => Err(err),
             },
+            #[cfg(feature = "zstd")]
+            ContentEncoder::Zstd(encoder) => match encoder.finish() {
+                Ok(writer) => Ok(writer.buf.freeze()),
+                Err(err) => Err(err),
+            },
         }
     }
 
@@ -238,6 +258,10 @@ impl ContentEncoder {
             ContentEncoder::Deflate(ref mut encoder) => encoder
                 .write_all(data)
                 .inspect_err(|err| log::trace!("Failed to encode to deflate: {err}")),
+            #[cfg(feature = "zstd")]
+            ContentEncoder::Zstd(ref mut encoder) => encoder
+                .write_all(data)
+                .inspect_err(|err| log::trace!("Failed to encode to zstd: {err}")),
         }
     }
 }
@@ -247,6 +271,8 @@ impl fmt::Debug for ContentEncoder {
         match self {
             ContentEncoder::Deflate(_) => write!(f, "ContentEncoder::Deflate"),
             ContentEncoder::Gzip(_) => write!(f, "ContentEncoder::Gzip"),
+            #[cfg(feature = "zstd")]
+            ContentEncoder::Zstd(_) => write!(f, "ContentEncoder::Zstd"),
         }
     }
 }
@@ -8,25 +8,36 @@ mod encoder;
 
 pub use self::decoder::Decoder;
 pub use self::encoder::Encoder;
+pub(crate) use self::encoder::can_encode;
 
 struct Writer {
     buf: BytesMut,
+    limit: usize,
 }
 
 impl Writer {
     fn new() -> Writer {
         Writer {
             buf: BytesMut::with_capacity(8192),
+            limit: usize::MAX,
         }
     }
 
     fn take(&mut self) -> Bytes {
-        self.buf.take()
+        let buf = self.buf.take();
+        self.limit -= buf.len();
+        buf
     }
 }
 
 impl io::Write for Writer {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.len() > self.limit - self.buf.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::FileTooLarge,
+                "Payload reached size limit",
+            ));
+        }
         self.buf.extend_from_slice(buf);
         Ok(buf.len())
     }
@@ -18,17 +18,21 @@ pub trait ResponseError: error::Error {
     ///
     /// Internal server error is generated by default.
     fn error_response(&self) -> Response {
-        let mut resp = Response::new(StatusCode::INTERNAL_SERVER_ERROR);
-        let mut buf = BytesMut::new();
-        let _ = write!(&mut buf, "{self}");
-        resp.headers_mut().insert(
-            header::CONTENT_TYPE,
-            header::HeaderValue::from_static("text/plain; charset=utf-8"),
-        );
-        resp.set_body(Body::from(buf))
+        text_response(StatusCode::INTERNAL_SERVER_ERROR, self)
     }
 }
 
+fn text_response<T: fmt::Display + ?Sized>(status: StatusCode, err: &T) -> Response {
+    let mut resp = Response::new(status);
+    let mut buf = BytesMut::new();
+    let _ = write!(&mut buf, "{err}");
+    resp.headers_mut().insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("text/plain; charset=utf-8"),
+    );
+    resp.set_body(Body::from(buf))
+}
+
 impl<T: ResponseError> ResponseError for &T {
     fn error_response(&self) -> Response {
         (*self).error_response()
@@ -57,6 +61,32 @@ impl ResponseError for io::Error {}
 /// `InternalServerError` for `JsonError`
 impl ResponseError for serde_json::error::Error {}
 
+#[cfg(feature = "bad-request-errors")]
+macro_rules! bad_request {
+    ($($ty:ty),*) => {
+        $(
+            #[doc = concat!("Return `BadRequest` for `", stringify!($ty), "`")]
+            impl ResponseError for $ty {
+                fn error_response(&self) -> Response {
+                    text_response(StatusCode::BAD_REQUEST, self)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "bad-request-errors")]
+bad_request!(
+    std::num::ParseIntError,
+    std::num::ParseFloatError,
+    std::str::ParseBoolError,
+    std::char::ParseCharError,
+    std::str::Utf8Error,
+    FromUtf8Error,
+    std::net::AddrParseError,
+    serde::de::value::Error
+);
+
 /// A set of errors that can occur during HTTP streams encoding
 #[derive(thiserror::Error, Debug)]
 pub enum EncodeError {
@@ -324,6 +354,21 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
     }
 
+    #[cfg(feature = "bad-request-errors")]
+    #[test]
+    fn test_bad_request_errors() {
+        let resp: Response = "a".parse::<u32>().unwrap_err().into();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let resp: Response = "a".parse::<std::net::IpAddr>().unwrap_err().into();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let resp: Response = String::from_utf8(vec![0xff]).unwrap_err().into();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/plain; charset=utf-8"
+        );
+    }
+
     #[test]
     fn test_payload_error() {
         let err: PayloadError = io::Error::other("DecodeError").into();
@@ -7,7 +7,7 @@ use crate::http::body::BodySize;
 use crate::http::config::{DateService, HttpServiceConfig};
 use crate::http::error::{DecodeError, EncodeError};
 use crate::http::message::ConnectionType;
-use crate::http::{Method, Version, request::Request, response::Response};
+use crate::http::{Method, SentBytes, Version, request::Request, response::Response};
 use crate::{Cfg, util::BytePages, util::BytesMut};
 
 use super::{Message, decoder, decoder::PayloadType, encoder};
@@ -31,6 +31,7 @@ pub struct Codec {
     // encoder part
     flags: Cell<Flags>,
     encoder: encoder::MessageEncoder<Response<()>>,
+    sent: Cell<Option<SentBytes>>,
 }
 
 impl Clone for Codec {
@@ -42,6 +43,7 @@ impl Clone for Codec {
             ctype: self.ctype.clone(),
             flags: self.flags.clone(),
             encoder: self.encoder.clone(),
+            sent: Cell::new(None),
         }
     }
 }
@@ -78,6 +80,7 @@ impl Codec {
             version: Cell::new(Version::HTTP_11),
             ctype: Cell::new(ConnectionType::KeepAlive),
             encoder: encoder::MessageEncoder::default(),
+            sent: Cell::new(None),
         }
     }
 
@@ -151,10 +154,21 @@ impl Encoder for Codec {
 
     fn encodev(&self, item: Self::Item, dst: &mut BytePages) -> Result<(), Self::Error> {
         match item {
-            Message::Item((mut res, length)) => {
+            Message::Item((mut res, mut length)) => {
                 // set response version
                 res.head_mut().version = self.version.get();
 
+                // chunked encoding for payload of known size
+                if res.head().is_force_chunking()
+                    && res.head().version >= Version::HTTP_11
+                    && matches!(length, BodySize::Sized(_))
+                {
+                    length = BodySize::Stream;
+                }
+
+                // response body bytes counter
+                self.sent.set(res.extensions().get::<SentBytes>().cloned());
+
                 // connection status
                 if let Some(ct) = res.head().ctype()
                     && ct != ConnectionType::KeepAlive
@@ -175,10 +189,25 @@ impl Encoder for Codec {
                 )?;
             }
             Message::Chunk(Some(bytes)) => {
-                self.encoder.encode_chunk(bytes, dst)?;
+                if let Some(sent) = self.sent.take() {
+                    let len = dst.len();
+                    let result = self.encoder.encode_chunk(bytes, dst);
+                    sent.add(dst.len() - len);
+                    self.sent.set(Some(sent));
+                    result?;
+                } else {
+                    self.encoder.encode_chunk(bytes, dst)?;
+                }
             }
             Message::Chunk(None) => {
-                self.encoder.encode_eof(dst)?;
+                if let Some(sent) = self.sent.take() {
+                    let len = dst.len();
+                    let result = self.encoder.encode_eof(dst);
+                    sent.add(dst.len() - len);
+                    result?;
+                } else {
+                    self.encoder.encode_eof(dst)?;
+                }
             }
         }
         Ok(())
@@ -237,4 +266,72 @@ mod tests {
         assert!(codec.upgrade());
         assert!(!codec.keepalive());
     }
+
+    #[test]
+    fn test_sent_bytes() {
+        let cfg: SharedCfg = SharedCfg::new("DBG").add(HttpServiceConfig::new()).into();
+        let codec = Codec::new(0, cfg.get());
+
+        let sent = SentBytes::new();
+        let res = Response::Ok().finish().into_parts().0;
+        res.extensions_mut().insert(sent.clone());
+
+        let mut buf = BytePages::default();
+        codec
+            .encodev(Message::Item((res, BodySize::Stream)), &mut buf)
+            .unwrap();
+        let head = buf.len();
+        codec
+            .encodev(Message::Chunk(Some(Bytes::from_static(b"data"))), &mut buf)
+            .unwrap();
+        codec.encodev(Message::Chunk(None), &mut buf).unwrap();
+
+        // "4\r\ndata\r\n0\r\n\r\n"
+        assert_eq!(sent.get(), 14);
+        assert_eq!(buf.len() - head, 14);
+    }
+
+    #[test]
+    fn test_force_chunking() {
+        let cfg: SharedCfg = SharedCfg::new("DBG").add(HttpServiceConfig::new()).into();
+        let codec = Codec::new(0, cfg.get());
+        let mut buf = BytesMut::from("GET /test HTTP/1.1\r\n\r\n");
+        let _item = codec.decode(&mut buf).unwrap().unwrap();
+
+        let res = Response::Ok()
+            .force_chunking()
+            .header("content-length", "4")
+            .finish()
+            .into_parts()
+            .0;
+        let mut buf = BytePages::default();
+        codec
+            .encodev(Message::Item((res, BodySize::Sized(4))), &mut buf)
+            .unwrap();
+        codec
+            .encodev(Message::Chunk(Some(Bytes::from_static(b"data"))), &mut buf)
+            .unwrap();
+        codec.encodev(Message::Chunk(None), &mut buf).unwrap();
+
+        let data = buf.freeze();
+        let s = String::from_utf8_lossy(&data);
+        assert!(s.contains("transfer-encoding: chunked\r\n"), "{s}");
+        assert!(!s.contains("content-length"), "{s}");
+        assert!(s.ends_with("\r\n\r\n4\r\ndata\r\n0\r\n\r\n"), "{s}");
+
+        // http/1.0 response keeps content-length
+        let codec = Codec::new(0, cfg.get());
+        let mut buf = BytesMut::from("GET /test HTTP/1.0\r\n\r\n");
+        let _item = codec.decode(&mut buf).unwrap().unwrap();
+
+        let res = Response::Ok().force_chunking().finish().into_parts().0;
+        let mut buf = BytePages::default();
+        codec
+            .encodev(Message::Item((res, BodySize::Sized(4))), &mut buf)
+            .unwrap();
+        let data = buf.freeze();
+        let s = String::from_utf8_lossy(&data);
+        assert!(s.contains("content-length: 4\r\n"), "{s}");
+        assert!(!s.contains("transfer-encoding"), "{s}");
+    }
 }
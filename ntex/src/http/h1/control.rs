@@ -545,6 +545,10 @@ impl Expect {
 
     #[inline]
     /// Ack expect request
+    ///
+    /// `100 Continue` response is sent when publish service starts waiting,
+    /// for example for request payload. If service responds immediately,
+    /// client does not send payload and connection is closed.
     pub fn ack<F>(self) -> ControlAck<F> {
         ControlAck {
             result: ControlResult::Continue(self.0),
@@ -56,7 +56,8 @@ impl<T: MessageType> Decoder for MessageDecoder<T> {
     fn decode(&self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
         let mut buf = BUF.with(|buf| buf.take().unwrap());
         buf.set_max_headers(self.cfg.max_headers);
-        let result = T::decode(src, &mut buf, self.cfg.max_headers, self.cfg.max_buf_size);
+        buf.raw = self.cfg.raw_headers;
+        let result = T::decode(src, &mut buf, &self.cfg);
         BUF.with(move |v| v.set(Some(buf)));
         result
     }
@@ -97,8 +98,7 @@ pub(crate) trait MessageType: fmt::Debug + Sized {
     fn decode(
         src: &mut BytesMut,
         buf: &mut HeadersBuf,
-        max_headers: usize,
-        max_buf_size: usize,
+        cfg: &HttpServiceConfig,
     ) -> Result<Option<(Self, PayloadType)>, DecodeError>;
 
     fn set_headers(
@@ -233,31 +233,34 @@ impl MessageType for Request {
     fn decode(
         src: &mut BytesMut,
         buf: &mut HeadersBuf,
-        max_headers: usize,
-        max_buf_size: usize,
+        cfg: &HttpServiceConfig,
     ) -> Result<Option<(Self, PayloadType)>, DecodeError> {
-        let (len, method, uri, ver, headers) = {
+        let (len, method, uri, target, ver, headers) = {
             let mut req = httparse::Request::new(&mut []);
 
             match req.parse_with_uninit_headers(src, buf.headers())? {
                 httparse::Status::Complete(len) => {
                     let method = Method::from_bytes(req.method.unwrap().as_bytes())
                         .map_err(|_| DecodeError::Method)?;
-                    let uri = Uri::try_from(req.path.unwrap())?;
+                    let path = req.path.unwrap();
+                    let uri = Uri::try_from(path)?;
+                    let start = path.as_ptr() as usize - src.as_ptr() as usize;
+                    let target = start..start + path.len();
                     let version = if req.version.unwrap() == 1 {
                         Version::HTTP_11
                     } else {
                         Version::HTTP_10
                     };
                     let headers = req.headers.len();
-                    if headers >= max_headers {
+                    if headers >= cfg.max_headers {
                         log::trace!("Max headers reached ({headers}), closing ");
                         return Err(DecodeError::MaxHeaders);
                     }
-                    (len, method, uri, version, headers)
+                    check_headers_size(req.headers, cfg.max_headers_size)?;
+                    (len, method, uri, target, version, headers)
                 }
                 httparse::Status::Partial => {
-                    return if src.len() >= max_buf_size {
+                    return if src.len() >= cfg.max_buf_size {
                         log::trace!("MAX_BUFFER_SIZE unprocessed data reached, closing");
                         Err(DecodeError::TooLarge(src.len()))
                     } else {
@@ -266,7 +269,8 @@ impl MessageType for Request {
                 }
             }
         };
-        buf.record(headers, &src.split_to(len));
+        let bytes = src.split_to(len);
+        buf.record(headers, &bytes);
 
         let mut msg = Request::new();
 
@@ -309,6 +313,10 @@ impl MessageType for Request {
         head.uri = uri;
         head.method = method;
         head.version = ver;
+        if buf.raw {
+            head.raw_headers.append(&mut buf.raw_headers);
+            head.raw_target = Some(bytes.slice(target));
+        }
 
         Ok(Some((msg, decoder)))
     }
@@ -330,8 +338,7 @@ impl MessageType for ResponseHead {
     fn decode(
         src: &mut BytesMut,
         buf: &mut HeadersBuf,
-        max_headers: usize,
-        max_buf_size: usize,
+        cfg: &HttpServiceConfig,
     ) -> Result<Option<(Self, PayloadType)>, DecodeError> {
         let (len, ver, status, headers) = {
             let mut res = httparse::Response::new(&mut []);
@@ -349,15 +356,16 @@ impl MessageType for ResponseHead {
                     let status = StatusCode::from_u16(res.code.unwrap())
                         .map_err(|_| DecodeError::Status)?;
                     let headers = res.headers.len();
-                    if headers >= max_headers {
+                    if headers >= cfg.max_headers {
                         log::trace!("Max headers reached ({headers}), closing ");
                         return Err(DecodeError::MaxHeaders);
                     }
+                    check_headers_size(res.headers, cfg.max_headers_size)?;
 
                     (len, version, status, headers)
                 }
                 httparse::Status::Partial => {
-                    return if src.len() >= max_buf_size {
+                    return if src.len() >= cfg.max_buf_size {
                         log::error!("MAX_BUFFER_SIZE unprocessed data reached, closing");
                         Err(DecodeError::TooLarge(src.len()))
                     } else {
@@ -400,6 +408,22 @@ impl MessageType for ResponseHead {
     }
 }
 
+fn check_headers_size(
+    headers: &[httparse::Header<'_>],
+    max_size: usize,
+) -> Result<(), DecodeError> {
+    let size = headers
+        .iter()
+        .map(|h| h.name.len() + h.value.len())
+        .sum::<usize>();
+    if size > max_size {
+        log::trace!("Max headers size reached ({size}), closing");
+        Err(DecodeError::TooLarge(size))
+    } else {
+        Ok(())
+    }
+}
+
 const S_KEEP_ALIVE: &str = "keep-alive";
 const S_CLOSE: &str = "close";
 const S_UPGRADE: &str = "upgrade";
@@ -455,16 +479,20 @@ fn connection_type(val: &str) -> Option<ConnectionType> {
 
 pub(crate) struct HeadersBuf {
     size: usize,
+    raw: bool,
     headers: Vec<httparse::Header<'static>>,
     indices: VecDeque<(HeaderName, HeaderValue)>,
+    raw_headers: Vec<(Bytes, Bytes)>,
 }
 
 impl HeadersBuf {
     fn new() -> Self {
         Self {
             size: 0,
+            raw: false,
             headers: Vec::new(),
             indices: VecDeque::new(),
+            raw_headers: Vec::new(),
         }
     }
 
@@ -493,6 +521,7 @@ impl HeadersBuf {
 
     fn record(&mut self, len: usize, bytes: &Bytes) {
         self.indices.clear();
+        self.raw_headers.clear();
 
         let bytes_ptr = bytes.as_ptr() as usize;
         self.headers[..len].iter().for_each(|header| {
@@ -507,6 +536,12 @@ impl HeadersBuf {
                 HeaderValue::from_shared_unchecked(bytes.slice(value_start..value_end))
             };
 
+            if self.raw {
+                self.raw_headers.push((
+                    bytes.slice(name_start..name_end),
+                    bytes.slice(value_start..value_end),
+                ));
+            }
             self.indices.push_back((name, value));
         });
     }
@@ -550,6 +585,16 @@ impl PayloadDecoder {
             kind: Cell::new(Kind::Eof),
         }
     }
+
+    /// Check if rest of the payload could fit into `limit` bytes
+    ///
+    /// Size of chunked payload is not known in advance, it never fits.
+    pub(super) fn fits(&self, limit: u64) -> bool {
+        match self.kind.get() {
+            Kind::Length(remaining) => remaining <= limit,
+            Kind::Chunked(..) | Kind::Eof => false,
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -809,6 +854,7 @@ impl ChunkedState {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::SharedCfg;
     use crate::http::HttpMessage;
     use crate::http::header::SET_COOKIE;
 
@@ -872,6 +918,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_raw_headers() {
+        let cfg: SharedCfg = SharedCfg::new("DBG")
+            .add(HttpServiceConfig::new().set_raw_headers(true))
+            .into();
+        let reader = MessageDecoder::<Request>::new(cfg.get());
+
+        let mut buf = BytesMut::from(
+            "GET /test HTTP/1.1\r\nX-Test: 1\r\nHost: localhost\r\nx-test: 2\r\n\r\n",
+        );
+        let (req, _) = reader.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(
+            req.head().raw_headers(),
+            &[
+                (Bytes::from_static(b"X-Test"), Bytes::from_static(b"1")),
+                (
+                    Bytes::from_static(b"Host"),
+                    Bytes::from_static(b"localhost")
+                ),
+                (Bytes::from_static(b"x-test"), Bytes::from_static(b"2")),
+            ]
+        );
+        assert_eq!(req.headers().get_all("x-test").count(), 2);
+        assert_eq!(req.head().raw_target(), Some(&Bytes::from_static(b"/test")));
+
+        let mut buf = BytesMut::from("GET /a%2Fb?x=1 HTTP/1.1\r\n\r\n");
+        let (req, _) = reader.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(
+            req.head().raw_target(),
+            Some(&Bytes::from_static(b"/a%2Fb?x=1"))
+        );
+
+        // disabled by default
+        let mut buf = BytesMut::from("GET /test HTTP/1.1\r\nX-Test: 1\r\n\r\n");
+        let req = parse_ready!(&mut buf);
+        assert!(req.head().raw_headers().is_empty());
+        assert!(req.head().raw_target().is_none());
+    }
+
     #[test]
     fn test_connection_type() {
         for s in &["Close", "Close\r\n", "close,", "close "] {
@@ -1203,6 +1288,67 @@ mod tests {
         assert!(req.upgrade());
     }
 
+    #[test]
+    fn test_parse_headers_limits() {
+        let cfg: SharedCfg = SharedCfg::new("DBG")
+            .add(
+                HttpServiceConfig::new()
+                    .set_max_headers(3)
+                    .set_max_headers_size(16),
+            )
+            .into();
+        let reader = MessageDecoder::<Request>::new(cfg.get());
+
+        let mut buf =
+            BytesMut::from("GET /test HTTP/1.1\r\nX-Test: 1\r\nHost: local\r\n\r\n");
+        assert!(reader.decode(&mut buf).unwrap().is_some());
+
+        let mut buf =
+            BytesMut::from("GET /test HTTP/1.1\r\nX-Test: 1\r\nHost: localhost\r\n\r\n");
+        assert!(matches!(
+            reader.decode(&mut buf),
+            Err(DecodeError::TooLarge(20))
+        ));
+
+        let mut buf = BytesMut::from("GET /test HTTP/1.1\r\nA: 1\r\nB: 2\r\nC: 3\r\n\r\n");
+        assert!(matches!(
+            reader.decode(&mut buf),
+            Err(DecodeError::MaxHeaders)
+        ));
+
+        let reader = MessageDecoder::<ResponseHead>::new(cfg.get());
+        let mut buf =
+            BytesMut::from("HTTP/1.1 200 OK\r\nX-Test: 1\r\nServer: localhost\r\n\r\n");
+        assert!(matches!(
+            reader.decode(&mut buf),
+            Err(DecodeError::TooLarge(22))
+        ));
+    }
+
+    #[test]
+    fn test_parse_absolute_form() {
+        let mut buf =
+            BytesMut::from("GET http://example.com:8080/test?q=1 HTTP/1.1\r\n\r\n");
+        let req = parse_ready!(&mut buf);
+
+        assert_eq!(req.uri().scheme_str(), Some("http"));
+        assert_eq!(req.uri().authority().unwrap(), "example.com:8080");
+        assert_eq!(req.path(), "/test");
+        assert_eq!(req.uri().query(), Some("q=1"));
+    }
+
+    #[test]
+    fn test_parse_authority_form() {
+        let mut buf = BytesMut::from("CONNECT example.com:443 HTTP/1.1\r\n\r\n");
+        let req = parse_ready!(&mut buf);
+
+        assert_eq!(*req.method(), Method::CONNECT);
+        assert_eq!(req.uri().scheme_str(), None);
+        assert_eq!(req.uri().authority().unwrap(), "example.com:443");
+        assert_eq!(req.path(), "");
+        assert!(req.upgrade());
+    }
+
     #[test]
     fn test_request_chunked() {
         let mut buf = BytesMut::from(
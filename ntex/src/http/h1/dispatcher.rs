@@ -4,12 +4,15 @@ use std::{error, future, io, marker, mem, pin::Pin, rc::Rc};
 
 use crate::io::{Decoded, Filter, Io, IoStatusUpdate, RecvError};
 use crate::service::{PipelineCall, Service};
-use crate::{channel::bstream, time::Seconds, util::Either};
+use crate::time::{Seconds, Sleep, sleep};
+use crate::{channel::bstream, util::Either};
 
-use crate::http::body::{BodySize, MessageBody, ResponseBody};
+use crate::http::body::{Body, BodySize, MessageBody, ResponseBody};
 use crate::http::error::{PayloadError, ResponseError};
+use crate::http::header::{HeaderValue, RETRY_AFTER};
 use crate::http::message::CurrentIo;
-use crate::http::{self, config::DispatcherConfig, request::Request, response::Response};
+use crate::http::{self, RequestHead, StatusCode, config::DispatcherConfig};
+use crate::http::{request::Request, response::Response};
 
 use super::control::{Control, ControlAck, ControlResult, ServiceDisconnectReason};
 use super::decoder::{PayloadDecoder, PayloadItem, PayloadType};
@@ -20,6 +23,8 @@ bitflags::bitflags! {
     pub struct Flags: u8 {
         /// Disconnect
         const DISCONNECT_SENT      = 0b0000_0001;
+        /// `100 Continue` is not sent yet
+        const SEND_CONTINUE        = 0b0000_0010;
         /// Keep-alive is enabled
         const READ_KA_TIMEOUT      = 0b0001_0000;
         /// Read headers timer is enabled
@@ -59,6 +64,7 @@ where
     ReadPayload,
     SendPayload {
         body: ResponseBody<B>,
+        unbuffered: bool,
     },
     Stop,
 }
@@ -70,9 +76,13 @@ struct DispatcherInner<F, C, S, B> {
     codec: Codec,
     config: Rc<DispatcherConfig<S, C>>,
     payload: Option<(PayloadDecoder, bstream::Sender<PayloadError>)>,
+    drained: usize,
     read_remains: u32,
     read_consumed: u32,
     read_max_timeout: Seconds,
+    ka_timeout: Seconds,
+    ka_head: Option<RequestHead>,
+    drain: Option<Sleep>,
     _t: marker::PhantomData<(S, B)>,
 }
 
@@ -106,13 +116,17 @@ where
             inner: DispatcherInner {
                 flags,
                 codec,
+                ka_timeout: config.keep_alive(),
+                ka_head: None,
                 config,
                 io: Rc::new(io),
                 payload: None,
+                drained: 0,
                 read_remains: 0,
                 read_consumed: 0,
                 read_max_timeout: max_timeout,
                 disconnect: None,
+                drain: None,
                 _t: marker::PhantomData,
             },
         }
@@ -144,7 +158,19 @@ where
                         inner.send_response(res, body)
                     }
                     Poll::Ready(Err(err)) => inner.ctl_error(err),
+                    // drain timeout is elapsed during shutdown,
+                    // drop publish future and respond with 503
+                    Poll::Pending if inner.poll_drain_timeout(cx).is_ready() => {
+                        inner.drain_response()
+                    }
                     Poll::Pending => {
+                        if inner.flags.contains(Flags::SEND_CONTINUE)
+                            && let Err(err) = inner.send_continue()
+                        {
+                            *this.st = inner.ctl_peer_gone(Some(err));
+                            continue;
+                        }
+
                         // state changed because of error.
                         // spawn current publish future to runtime
                         // so it could complete error handling
@@ -168,15 +194,17 @@ where
                             inner.send_response(res, body.into())
                         }
                         ControlResult::Continue(req) => {
-                            let result =
-                                inner.io.encode_slice(b"HTTP/1.1 100 Continue\r\n\r\n");
-                            if let Err(err) = result {
-                                *this.st = inner.ctl_peer_gone(Some(err));
-                                continue;
-                            }
                             if req.upgrade() {
+                                if let Err(err) = inner.send_continue() {
+                                    *this.st = inner.ctl_peer_gone(Some(err));
+                                    continue;
+                                }
                                 inner.ctl_upgrade(req)
                             } else {
+                                // `100 Continue` is sent when service starts
+                                // waiting, service could respond before that
+                                // without reading request payload
+                                inner.flags.insert(Flags::SEND_CONTINUE);
                                 inner.publish(req)
                             }
                         }
@@ -223,13 +251,17 @@ where
                     let result = inner.poll_request_payload(cx);
                     if let Some(st) = inner.check_disconnect() {
                         st
+                    } else if let Some(st) = ready!(result) {
+                        st
+                    } else if inner.payload.is_some() {
+                        State::ReadPayload
                     } else {
-                        ready!(result).unwrap_or(State::ReadRequest)
+                        State::ReadRequest
                     }
                 }
                 // send response body
-                State::SendPayload { body } => {
-                    ready!(inner.poll_send_payload(cx, body))
+                State::SendPayload { body, unbuffered } => {
+                    ready!(inner.poll_send_payload(cx, body, *unbuffered))
                 }
                 // shutdown io
                 State::Stop => {
@@ -259,6 +291,14 @@ where
             return Poll::Ready(self.ctl_svc_disconnect(ServiceDisconnectReason::Shutdown));
         }
 
+        // pipelining is disabled, previous response must be flushed
+        if !self.config.pipelining()
+            && let Err(err) = ready!(self.io.poll_flush(cx, true))
+        {
+            log::trace!("{}: Peer is gone with {:?}", self.io.tag(), err);
+            return Poll::Ready(self.ctl_peer_gone(Some(err)));
+        }
+
         log::trace!("{}: Trying to read http message", self.io.tag());
 
         let result = match self.io.poll_recv_decode(&self.codec, cx) {
@@ -286,13 +326,38 @@ where
                 );
                 req.head_mut().io = CurrentIo::Ref(self.io.get_ref());
 
+                // keep copy of request head for keep-alive callback,
+                // request is owned by the service, extensions are not copied
+                if self.config.keep_alive_fn().is_some() {
+                    let head = req.head();
+                    self.ka_head = Some(RequestHead {
+                        id: head.id,
+                        uri: head.uri.clone(),
+                        method: head.method.clone(),
+                        version: head.version,
+                        headers: head.headers.clone(),
+                        io: CurrentIo::Ref(self.io.get_ref()),
+                        flags: head.flags,
+                        ..Default::default()
+                    });
+                }
+
                 // configure request payload
                 match pl {
                     PayloadType::None => (),
+                    PayloadType::Stream(_)
+                        if req.upgrade() && self.config.keep_upgrade_payload() =>
+                    {
+                        log::trace!(
+                            "{}: Keep upgrade request payload on the connection",
+                            self.io.tag()
+                        );
+                    }
                     PayloadType::Payload(decoder) | PayloadType::Stream(decoder) => {
                         let (ps, pl) = bstream::channel();
                         req.replace_payload(http::Payload::H1(pl));
                         self.payload = Some((decoder, ps));
+                        self.drained = 0;
                     }
                 }
                 self.control(Control::request(req))
@@ -346,11 +411,37 @@ where
         // close connection if payload stream is dropped and not consumed
         if let Some((_pl, snd)) = &self.payload
             && snd.is_closed()
+            && !self.can_drain_payload()
         {
             msg.head_mut()
                 .set_connection_type(http::ConnectionType::Close);
         }
 
+        // `100 Continue` is not sent, client does not send payload
+        if self.flags.contains(Flags::SEND_CONTINUE) {
+            self.flags.remove(Flags::SEND_CONTINUE);
+            if self.payload.is_some() {
+                log::trace!(
+                    "{}: Respond before `100 Continue`, drop payload",
+                    self.io.tag()
+                );
+                self.drop_payload();
+                msg.head_mut()
+                    .set_connection_type(http::ConnectionType::Close);
+            }
+        }
+
+        // keep-alive timeout for the next request
+        if let Some(f) = self.config.keep_alive_fn() {
+            self.ka_timeout = self
+                .ka_head
+                .take()
+                .and_then(|head| f.call(&head, msg.head()))
+                .unwrap_or_else(|| self.config.keep_alive());
+        }
+
+        let unbuffered = msg.head().is_unbuffered();
+
         // we don't need to process responses if socket is disconnected
         // but we still want to handle requests with app service
         // so we skip response processing for dropped connection
@@ -377,7 +468,7 @@ where
                             State::ReadRequest
                         }
                     }
-                    _ => State::SendPayload { body },
+                    _ => State::SendPayload { body, unbuffered },
                 },
                 Err(err) => self.ctl_proto_err(err.into()),
             }
@@ -388,6 +479,7 @@ where
         &mut self,
         cx: &mut Context<'_>,
         body: &mut ResponseBody<B>,
+        unbuffered: bool,
     ) -> Poll<State<F, C, S, B>> {
         if self.io.is_closed() {
             return Poll::Ready(self.ctl_peer_gone(None));
@@ -397,7 +489,8 @@ where
             self.disconnect = Some(ServiceDisconnectReason::PayloadDropped);
         }
         loop {
-            let _ = ready!(self.io.poll_flush(cx, false));
+            // unbuffered payload, flush each chunk before reading next one
+            let _ = ready!(self.io.poll_flush(cx, unbuffered));
             let item = ready!(body.poll_next_chunk(cx));
 
             let st = match item {
@@ -486,101 +579,128 @@ where
             return Poll::Ready(Ok(()));
         }
 
-        match self.payload.as_ref().unwrap().1.poll_ready(cx) {
-            Poll::Ready(bstream::Status::Ready) => {
-                // read request payload
-                let mut updated = false;
-                loop {
-                    let recv_result = io
-                        .map(|io| {
-                            io.poll_recv_decode(&self.payload.as_ref().unwrap().0, cx)
-                        })
-                        .unwrap_or_else(|| {
-                            self.io
-                                .poll_recv_decode(&self.payload.as_ref().unwrap().0, cx)
-                        });
-
-                    let res = match recv_result {
-                        Ok(decoded) => {
-                            self.update_payload_timer(&decoded);
-                            if let Some(item) = decoded.item {
-                                updated = true;
-                                Ok(item)
-                            } else {
-                                break;
-                            }
-                        }
-                        Err(err) => Err(err),
-                    };
-
-                    match res {
-                        Ok(PayloadItem::Chunk(chunk)) => {
-                            self.payload.as_mut().unwrap().1.feed_data(chunk);
-                        }
-                        Ok(PayloadItem::Eof) => {
-                            self.flags.remove(Flags::READ_PL_TIMEOUT);
-                            self.payload.as_mut().unwrap().1.feed_eof();
-                            self.payload = None;
-                            break;
-                        }
-                        Err(err) => {
-                            let err = match err {
-                                RecvError::WriteBackpressure => {
-                                    let flush_result = io
-                                        .map(|io| io.poll_flush(cx, false))
-                                        .unwrap_or_else(|| self.io.poll_flush(cx, false));
-
-                                    if flush_result
-                                        .map_err(|e| Either::Right(Some(e)))?
-                                        .is_pending()
-                                    {
-                                        break;
-                                    }
-                                    continue;
-                                }
-                                RecvError::KeepAlive => {
-                                    if let Err(err) = self.handle_timeout() {
-                                        Either::Left(err)
-                                    } else {
-                                        continue;
-                                    }
-                                }
-                                RecvError::PeerGone(err) => {
-                                    self.set_payload_error(PayloadError::EncodingCorrupted);
-                                    Either::Right(err)
-                                }
-                                RecvError::Decoder(e) => {
-                                    self.set_payload_error(PayloadError::EncodingCorrupted);
-                                    Either::Left(ProtocolError::Decode(e))
-                                }
-                            };
-                            return Poll::Ready(Err(err));
-                        }
-                    }
-                }
-                if updated {
-                    Poll::Ready(Ok(()))
-                } else {
-                    Poll::Pending
-                }
-            }
+        let drain = match self.payload.as_ref().unwrap().1.poll_ready(cx) {
+            Poll::Ready(bstream::Status::Ready) => false,
             Poll::Pending => {
                 // stop payload timer
                 if self.flags.contains(Flags::READ_PL_TIMEOUT) {
                     self.flags.remove(Flags::READ_PL_TIMEOUT);
                     self.io.stop_timer();
                 }
-                Poll::Pending
+                return Poll::Pending;
             }
+            // service call is not interested in payload,
+            // discard payload if it is small enough
             Poll::Ready(bstream::Status::Dropped | bstream::Status::Eof) => {
-                // service call is not interested in payload
-                // wait until future completes and then close
-                // connection
-                self.payload = None;
-                self.disconnect = Some(ServiceDisconnectReason::PayloadDropped);
-                Poll::Pending
+                if !self.can_drain_payload() {
+                    self.drop_payload();
+                    return Poll::Pending;
+                }
+                true
+            }
+        };
+
+        // read request payload
+        let mut updated = false;
+        loop {
+            let recv_result = io
+                .map(|io| io.poll_recv_decode(&self.payload.as_ref().unwrap().0, cx))
+                .unwrap_or_else(|| {
+                    self.io
+                        .poll_recv_decode(&self.payload.as_ref().unwrap().0, cx)
+                });
+
+            let res = match recv_result {
+                Ok(decoded) => {
+                    self.update_payload_timer(&decoded);
+                    if let Some(item) = decoded.item {
+                        updated = true;
+                        Ok(item)
+                    } else {
+                        break;
+                    }
+                }
+                Err(err) => Err(err),
+            };
+
+            match res {
+                Ok(PayloadItem::Chunk(chunk)) => {
+                    if drain {
+                        self.drained += chunk.len();
+                        if !self.can_drain_payload() {
+                            self.drop_payload();
+                            return Poll::Pending;
+                        }
+                    } else {
+                        self.payload.as_mut().unwrap().1.feed_data(chunk);
+                    }
+                }
+                Ok(PayloadItem::Eof) => {
+                    self.flags.remove(Flags::READ_PL_TIMEOUT);
+                    if !drain {
+                        self.payload.as_mut().unwrap().1.feed_eof();
+                    }
+                    self.payload = None;
+                    break;
+                }
+                Err(err) => {
+                    let err = match err {
+                        RecvError::WriteBackpressure => {
+                            let flush_result = io
+                                .map(|io| io.poll_flush(cx, false))
+                                .unwrap_or_else(|| self.io.poll_flush(cx, false));
+
+                            if flush_result
+                                .map_err(|e| Either::Right(Some(e)))?
+                                .is_pending()
+                            {
+                                break;
+                            }
+                            continue;
+                        }
+                        RecvError::KeepAlive => {
+                            if let Err(err) = self.handle_timeout() {
+                                Either::Left(err)
+                            } else {
+                                continue;
+                            }
+                        }
+                        RecvError::PeerGone(err) => {
+                            self.set_payload_error(PayloadError::EncodingCorrupted);
+                            Either::Right(err)
+                        }
+                        RecvError::Decoder(e) => {
+                            self.set_payload_error(PayloadError::EncodingCorrupted);
+                            Either::Left(ProtocolError::Decode(e))
+                        }
+                    };
+                    return Poll::Ready(Err(err));
+                }
             }
         }
+        if updated {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Check if dropped payload could be drained
+    fn can_drain_payload(&self) -> bool {
+        let limit = self.config.payload_drain_limit();
+        limit != 0
+            && self.drained <= limit
+            && self
+                .payload
+                .as_ref()
+                .is_some_and(|(decoder, _)| decoder.fits((limit - self.drained) as u64))
+    }
+
+    /// Service call is not interested in payload, wait until
+    /// future completes and then close connection
+    fn drop_payload(&mut self) {
+        self.payload = None;
+        self.disconnect = Some(ServiceDisconnectReason::PayloadDropped);
     }
 
     fn handle_timeout(&mut self) -> Result<(), ProtocolError> {
@@ -680,10 +800,10 @@ where
                     log::debug!(
                         "{}: Start keep-alive timer {:?}",
                         self.io.tag(),
-                        self.config.keep_alive()
+                        self.ka_timeout
                     );
                     self.flags.insert(Flags::READ_KA_TIMEOUT);
-                    self.io.start_timer(self.config.keep_alive());
+                    self.io.start_timer(self.ka_timeout);
                 }
             } else {
                 self.io.close();
@@ -710,6 +830,46 @@ where
         }
     }
 
+    /// Check drain timeout of in-flight request during service shutdown
+    ///
+    /// Timeout counts from the moment service started shutting down.
+    fn poll_drain_timeout(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        match self.config.drain_remains() {
+            None => Poll::Pending,
+            Some(remains) if remains.is_zero() => Poll::Ready(()),
+            Some(remains) => self
+                .drain
+                .get_or_insert_with(|| sleep(remains))
+                .poll_elapsed(cx),
+        }
+    }
+
+    fn drain_response(&mut self) -> State<F, C, S, B> {
+        log::trace!(
+            "{}: Drain timeout, in-flight request is dropped",
+            self.io.tag()
+        );
+        self.set_payload_error(PayloadError::Incomplete(None));
+        self.payload = None;
+        self.disconnect = Some(ServiceDisconnectReason::Shutdown);
+
+        let mut res = Response::new(StatusCode::SERVICE_UNAVAILABLE).drop_body();
+        res.head_mut()
+            .set_connection_type(http::ConnectionType::Close);
+        if let Some((_, retry_after)) = self.config.drain_timeout()
+            && !retry_after.is_zero()
+        {
+            res.headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(retry_after.0));
+        }
+        self.send_response(res, Body::Empty.into())
+    }
+
+    fn send_continue(&mut self) -> io::Result<()> {
+        self.flags.remove(Flags::SEND_CONTINUE);
+        self.io.encode_slice(b"HTTP/1.1 100 Continue\r\n\r\n")
+    }
+
     fn publish(&self, req: Request) -> State<F, C, S, B> {
         State::CallPublish {
             fut: self.config.service.call_nowait(req),
@@ -804,7 +964,7 @@ mod tests {
     use crate::client::ClientCodec;
     use crate::http::config::HttpServiceConfig;
     use crate::http::h1::{DefaultControlService, control::Reason};
-    use crate::http::{ResponseHead, StatusCode, body};
+    use crate::http::{ResponseHead, StatusCode, body, header};
     use crate::io::{self as nio, Base};
     use crate::service::{IntoService, cfg::SharedCfg, fn_service};
     use crate::util::{Bytes, BytesMut, lazy, stream_recv};
@@ -917,6 +1077,82 @@ mod tests {
         assert!(data.get());
     }
 
+    #[crate::rt_test]
+    async fn test_drain_timeout() {
+        let (client, server) = IoTest::create();
+        client.remote_buffer_cap(1024);
+        client.write("GET /test HTTP/1.1\r\n\r\n");
+
+        let config: SharedCfg = SharedCfg::new("DBG")
+            .add(HttpServiceConfig::new().set_drain_timeout(Seconds(1), Seconds(5)))
+            .into();
+        let cfg = Rc::new(DispatcherConfig::new(
+            config.get(),
+            fn_service(|_| async {
+                sleep(Millis(10_000)).await;
+                Ok::<_, io::Error>(Response::Ok().finish())
+            }),
+            DefaultControlService,
+        ));
+        let mut h1 =
+            Dispatcher::<_, _, _, _>::new(0, nio::Io::new(server, config), cfg.clone());
+        sleep(Millis(50)).await;
+        assert!(lazy(|cx| Pin::new(&mut h1).poll(cx)).await.is_pending());
+
+        // in-flight request is not completed within drain timeout,
+        // timeout counts from shutdown, not from next dispatcher poll
+        cfg.shutdown();
+        sleep(Millis(600)).await;
+        assert!(lazy(|cx| Pin::new(&mut h1).poll(cx)).await.is_pending());
+        client.local_buffer(|buf| assert!(buf.is_empty()));
+
+        sleep(Millis(500)).await;
+        let _ = lazy(|cx| Pin::new(&mut h1).poll(cx)).await;
+        sleep(Millis(50)).await;
+        client.local_buffer(|buf| {
+            let s = String::from_utf8_lossy(buf);
+            assert!(s.starts_with("HTTP/1.1 503 Service Unavailable\r\n"), "{s}");
+            assert!(s.contains("retry-after: 5\r\n"), "{s}");
+            assert!(s.contains("connection: close\r\n"), "{s}");
+        });
+        assert!(poll_fn(|cx| Pin::new(&mut h1).poll(cx)).await.is_ok());
+        assert!(h1.inner.io.is_closed());
+    }
+
+    #[crate::rt_test]
+    async fn test_pipelining_disabled() {
+        let (client, server) = IoTest::create();
+        // response could not be flushed
+        client.remote_buffer_cap(0);
+        client.write("GET /test1 HTTP/1.1\r\n\r\nGET /test2 HTTP/1.1\r\n\r\n");
+
+        let num = Rc::new(Cell::new(0));
+        let num2 = num.clone();
+        let config: SharedCfg = SharedCfg::new("DBG")
+            .add(HttpServiceConfig::new().set_pipelining(false))
+            .into();
+        let cfg = Rc::new(DispatcherConfig::new(
+            config.get(),
+            fn_service(move |_| {
+                num2.set(num2.get() + 1);
+                async { Ok::<_, io::Error>(Response::Ok().finish()) }
+            }),
+            DefaultControlService,
+        ));
+        let mut h1 = Dispatcher::<_, _, _, _>::new(0, nio::Io::new(server, config), cfg);
+        sleep(Millis(50)).await;
+        assert!(lazy(|cx| Pin::new(&mut h1).poll(cx)).await.is_pending());
+
+        // second request is not read until first response is flushed
+        assert_eq!(num.get(), 1);
+        assert_eq!(client.remote_buffer(|buf| buf.len()), 0);
+
+        client.remote_buffer_cap(4096);
+        sleep(Millis(50)).await;
+        assert!(lazy(|cx| Pin::new(&mut h1).poll(cx)).await.is_pending());
+        assert_eq!(num.get(), 2);
+    }
+
     #[crate::rt_test]
     async fn test_req_parse_err() {
         let (client, server) = IoTest::create();
@@ -1354,4 +1590,59 @@ mod tests {
             b"HTTP/1.1 200 OK\r\ncontent-length: 4\r\nconnection: close\r\n"
         );
     }
+
+    #[crate::rt_test]
+    async fn test_drain_unconsumed_payload() {
+        fn start() -> IoTest {
+            let (client, server) = IoTest::create();
+            client.remote_buffer_cap(4096);
+
+            let config: SharedCfg = SharedCfg::new("DBG")
+                .add(HttpServiceConfig::new().set_payload_drain_limit(16))
+                .into();
+            crate::rt::spawn(Dispatcher::<Base, _, _, _>::new(
+                0,
+                nio::Io::new(server, config.clone()),
+                Rc::new(DispatcherConfig::new(
+                    config.get(),
+                    fn_service(|_| async {
+                        Ok::<_, io::Error>(Response::Ok().body("TEST"))
+                    }),
+                    DefaultControlService,
+                )),
+            ));
+            client
+        }
+        let mut decoder = ClientCodec::new(true, SharedCfg::default().get());
+
+        // small payloads are drained
+        let client = start();
+        client.write("POST /test1 HTTP/1.1\r\ncontent-length: 10\r\n\r\n01234");
+        let mut buf = BytesMut::from(&client.read().await.unwrap()[..]);
+        let head = load(&mut decoder, &mut buf);
+        assert!(head.status.is_success());
+        assert!(!head.headers.contains_key(header::CONNECTION));
+        client.write("56789");
+
+        // payload is larger than limit
+        client.write("POST /test2 HTTP/1.1\r\ncontent-length: 17\r\n\r\n");
+        let mut buf = BytesMut::from(&client.read().await.unwrap()[..]);
+        let head = load(&mut decoder, &mut buf);
+        assert!(head.status.is_success());
+        assert_eq!(head.headers.get(header::CONNECTION).unwrap(), "close");
+        sleep(Millis(50)).await;
+        assert!(client.is_server_dropped());
+
+        // size of chunked payload is unknown, it is not drained
+        let client = start();
+        client.write(
+            "POST /test3 HTTP/1.1\r\ntransfer-encoding: chunked\r\n\r\n5\r\n01234\r\n",
+        );
+        let mut buf = BytesMut::from(&client.read().await.unwrap()[..]);
+        let head = load(&mut decoder, &mut buf);
+        assert!(head.status.is_success());
+        assert_eq!(head.headers.get(header::CONNECTION).unwrap(), "close");
+        sleep(Millis(50)).await;
+        assert!(client.is_server_dropped());
+    }
 }
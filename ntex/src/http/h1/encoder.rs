@@ -164,7 +164,15 @@ impl MessageType for Response<()> {
 
     fn encode_status(&self, dst: &mut BytePages) {
         let head = self.head();
-        let reason = head.reason().as_bytes();
+        let mut reason = head.reason().as_bytes();
+        if !is_valid_reason(reason) {
+            log::warn!("Invalid reason phrase {:?}, use default", head.reason());
+            reason = head
+                .status
+                .canonical_reason()
+                .unwrap_or_default()
+                .as_bytes();
+        }
 
         // status line
         write_status_line(head.version, head.status.as_u16(), dst);
@@ -530,6 +538,13 @@ pub(crate) fn convert_usize<B: BufMut>(mut n: u64, bytes: &mut B, eol: bool) {
     }
 }
 
+/// Check if reason phrase could be written to status line
+fn is_valid_reason(reason: &[u8]) -> bool {
+    reason
+        .iter()
+        .all(|&b| b == b'\t' || b == b' ' || (b >= 0x21 && b != 0x7f))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -576,6 +591,31 @@ mod tests {
         assert!(data.contains("date: date\r\n"));
     }
 
+    #[test]
+    fn test_reason() {
+        let mut bytes = BytePages::default();
+        let res = Response::build(StatusCode::UNPROCESSABLE_ENTITY)
+            .reason("Unprocessable Content")
+            .finish()
+            .drop_body();
+        res.encode_status(&mut bytes);
+        assert_eq!(
+            bytes.take().unwrap().as_ref(),
+            b"HTTP/1.1 422 Unprocessable Content"
+        );
+
+        let res = Response::build(StatusCode::OK)
+            .reason("OK\r\nx-injected: 1")
+            .finish()
+            .drop_body();
+        res.encode_status(&mut bytes);
+        assert_eq!(bytes.take().unwrap().as_ref(), b"HTTP/1.1 200 OK");
+
+        assert!(is_valid_reason(b"Not\tFound \xe2\x9c\x93"));
+        assert!(!is_valid_reason(b"Not\0Found"));
+        assert!(!is_valid_reason(b"Not\x7fFound"));
+    }
+
     #[test]
     fn test_write_content_length() {
         let mut b = BytePages::default();
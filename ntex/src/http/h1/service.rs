@@ -274,6 +274,7 @@ where
     }
 
     async fn call(&self, io: Io<F>, _: ServiceCtx<'_, Self>) -> Result<(), Self::Error> {
+        self.config.configure_io(&io);
         let id = self.config.next_id();
         let inflight = {
             let mut inflight = self.inflight.borrow_mut();
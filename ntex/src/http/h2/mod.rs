@@ -1,4 +1,38 @@
 //! HTTP/2 implementation
+//!
+//! Http/2 protocol settings are configured with `Http2ServiceConfig`, it must be
+//! added to the server's shared configuration. For example, limit of concurrent
+//! streams per connection is advertised in initial `SETTINGS` frame and streams
+//! above the limit are refused.
+//!
+//! Server push is not supported. Server never sends `PUSH_PROMISE` frames
+//! and never advertises `SETTINGS_ENABLE_PUSH=1`, so value of client's
+//! `SETTINGS_ENABLE_PUSH` setting does not affect responses and handlers
+//! could not initiate pushes. `Http2ServiceConfig` does not provide push
+//! settings. Use `Link` response header with `rel=preload` to hint clients
+//! about related resources.
+//!
+//! ```rust,no_run
+//! use ntex::http::{HttpService, Response, h2::Http2ServiceConfig};
+//! use ntex::SharedCfg;
+//!
+//! #[ntex::main]
+//! async fn main() -> std::io::Result<()> {
+//!     ntex::server::build()
+//!         .bind("h2", "127.0.0.1:8080", async |_| {
+//!             HttpService::new(|_| async {
+//!                 Ok::<_, std::io::Error>(Response::Ok().finish())
+//!             })
+//!         })?
+//!         .config(
+//!             "h2",
+//!             SharedCfg::new("H2")
+//!                 .add(Http2ServiceConfig::new().set_max_concurrent_streams(64)),
+//!         )
+//!         .run()
+//!         .await
+//! }
+//! ```
 mod default;
 pub(super) mod payload;
 mod service;
@@ -111,7 +111,9 @@ mod rustls {
         C::Error: StdError,
         C::InitError: fmt::Debug,
     {
-        /// Create openssl based service
+        /// Create rustls based service
+        ///
+        /// If `config.alpn_protocols` is empty, `h2` protocol is advertised.
         pub fn rustls(
             self,
             mut config: ServerConfig,
@@ -122,8 +124,9 @@ mod rustls {
             Error = SslError<DispatchError>,
             InitError = (),
         > {
-            let protos = vec!["h2".to_string().into()];
-            config.alpn_protocols = protos;
+            if config.alpn_protocols.is_empty() {
+                config.alpn_protocols = vec![b"h2".to_vec()];
+            }
 
             TlsAcceptor::from(config)
                 .map_err(|e| SslError::Ssl(Box::new(e)))
@@ -277,6 +280,7 @@ where
             )))
         })?;
 
+        self.config.configure_io(&io);
         let id = self.config.next_id();
         let inflight = {
             let mut inflight = self.inflight.borrow_mut();
@@ -88,6 +88,9 @@ pub trait HttpMessage: Sized {
 
     #[cfg(feature = "cookie")]
     /// Load request cookies.
+    ///
+    /// Cookies from all `Cookie` headers are parsed once and cached
+    /// in message extensions.
     fn cookies(&self) -> Result<Ref<'_, Vec<Cookie<'static>>>, coo_kie::ParseError> {
         if self.message_extensions().get::<Cookies>().is_none() {
             let mut cookies = Vec::new();
@@ -109,6 +112,8 @@ pub trait HttpMessage: Sized {
 
     #[cfg(feature = "cookie")]
     /// Return request cookie.
+    ///
+    /// Use `Cookie::value_trimmed()` to get value without surrounding quotes.
     fn cookie(&self, name: &str) -> Option<Cookie<'static>> {
         if let Ok(cookies) = self.cookies() {
             for cookie in cookies.iter() {
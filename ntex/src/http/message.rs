@@ -1,10 +1,10 @@
-use std::{cell::Ref, cell::RefCell, cell::RefMut, fmt, net, rc::Rc};
+use std::{cell::Ref, cell::RefCell, cell::RefMut, fmt, io, net, rc::Rc};
 
 use bitflags::bitflags;
 
 use crate::http::{Method, StatusCode, Uri, Version, h1::Codec, header::HeaderMap};
 use crate::io::{IoBoxed, IoRef, types};
-use crate::util::Extensions;
+use crate::util::{Bytes, BytesMut, Extensions};
 
 /// Represents various types of connection
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -25,6 +25,8 @@ bitflags! {
         const UPGRADE     = 0b0000_0100;
         const EXPECT      = 0b0000_1000;
         const NO_CHUNKING = 0b0001_0000;
+        const UNBUFFERED  = 0b0010_0000;
+        const FORCE_CHUNKING = 0b0100_0000;
     }
 }
 
@@ -73,6 +75,11 @@ impl CurrentIo {
 #[derive(Debug)]
 pub struct RequestHead {
     pub id: usize,
+    /// Request target
+    ///
+    /// For absolute-form requests (forward proxy) uri contains scheme and
+    /// authority, for authority-form `CONNECT` requests uri contains only
+    /// authority and path is empty.
     pub uri: Uri,
     pub method: Method,
     pub version: Version,
@@ -80,6 +87,8 @@ pub struct RequestHead {
     pub extensions: RefCell<Extensions>,
     pub(crate) io: CurrentIo,
     pub(crate) flags: Flags,
+    pub(crate) raw_headers: Vec<(Bytes, Bytes)>,
+    pub(crate) raw_target: Option<Bytes>,
 }
 
 impl Default for RequestHead {
@@ -93,6 +102,8 @@ impl Default for RequestHead {
             headers: HeaderMap::with_capacity(16),
             flags: Flags::empty(),
             extensions: RefCell::new(Extensions::new()),
+            raw_headers: Vec::new(),
+            raw_target: None,
         }
     }
 }
@@ -103,6 +114,8 @@ impl Head for RequestHead {
         self.flags = Flags::empty();
         self.version = Version::HTTP_11;
         self.headers.clear();
+        self.raw_headers.clear();
+        self.raw_target = None;
         self.extensions.get_mut().clear();
     }
 
@@ -200,6 +213,27 @@ impl RequestHead {
         self.flags.insert(Flags::UPGRADE);
     }
 
+    #[inline]
+    /// Raw request headers with original casing and order.
+    ///
+    /// Raw headers are available only if `HttpServiceConfig::set_raw_headers()`
+    /// is enabled and request is received via http/1, otherwise returns empty
+    /// slice. Changes to `headers` are not reflected in raw headers.
+    pub fn raw_headers(&self) -> &[(Bytes, Bytes)] {
+        &self.raw_headers
+    }
+
+    #[inline]
+    /// Raw request target as received in http/1 request line.
+    ///
+    /// Request target is not normalized, for example for `GET /a%2Fb?x=1 HTTP/1.1`
+    /// request line raw target is `/a%2Fb?x=1`. Raw target is available only if
+    /// `HttpServiceConfig::set_raw_headers()` is enabled and request is received
+    /// via http/1.
+    pub fn raw_target(&self) -> Option<&Bytes> {
+        self.raw_target.as_ref()
+    }
+
     /// Peer socket address
     ///
     /// Peer address is actual socket address, if proxy is used in front of
@@ -213,6 +247,50 @@ impl RequestHead {
         })
     }
 
+    /// Send informational (1xx) response
+    ///
+    /// Informational response is written to the connection immediately,
+    /// before the final response, for example `103 Early Hints` with `Link`
+    /// headers. Multiple informational responses could be sent. Supported only
+    /// for http/1.1 requests, `101 Switching Protocols` status is not allowed.
+    pub fn send_informational(
+        &self,
+        status: StatusCode,
+        headers: &HeaderMap,
+    ) -> io::Result<()> {
+        if !status.is_informational() || status == StatusCode::SWITCHING_PROTOCOLS {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Status code is not allowed for informational response",
+            ));
+        }
+        let io = self
+            .io
+            .as_ref()
+            .filter(|_| self.version == Version::HTTP_11)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "Informational responses are supported only for http/1.1",
+                )
+            })?;
+
+        let mut buf = BytesMut::with_capacity(128);
+        buf.extend_from_slice(b"HTTP/1.1 ");
+        buf.extend_from_slice(status.as_str().as_bytes());
+        buf.extend_from_slice(b" ");
+        buf.extend_from_slice(status.canonical_reason().unwrap_or("").as_bytes());
+        buf.extend_from_slice(b"\r\n");
+        for (name, value) in headers {
+            buf.extend_from_slice(name.as_str().as_bytes());
+            buf.extend_from_slice(b": ");
+            buf.extend_from_slice(value.as_bytes());
+            buf.extend_from_slice(b"\r\n");
+        }
+        buf.extend_from_slice(b"\r\n");
+        io.encode_slice(&buf)
+    }
+
     /// Take io and codec for current request
     ///
     /// This objects are set only for upgrade requests
@@ -348,11 +426,49 @@ impl ResponseHead {
     /// Set no chunking for payload
     pub fn no_chunking(&mut self, val: bool) {
         if val {
+            self.flags.remove(Flags::FORCE_CHUNKING);
             self.flags.insert(Flags::NO_CHUNKING);
         } else {
             self.flags.remove(Flags::NO_CHUNKING);
         }
     }
+
+    #[inline]
+    /// Get forced chunking state
+    pub fn is_force_chunking(&self) -> bool {
+        self.flags.contains(Flags::FORCE_CHUNKING)
+    }
+
+    #[inline]
+    /// Use chunked transfer encoding for payload of known size
+    ///
+    /// Applies to http/1.1 responses only, `Content-Length` header is not sent.
+    pub fn force_chunking(&mut self, val: bool) {
+        if val {
+            self.flags.remove(Flags::NO_CHUNKING);
+            self.flags.insert(Flags::FORCE_CHUNKING);
+        } else {
+            self.flags.remove(Flags::FORCE_CHUNKING);
+        }
+    }
+
+    #[inline]
+    /// Get response body buffering state
+    pub fn is_unbuffered(&self) -> bool {
+        self.flags.contains(Flags::UNBUFFERED)
+    }
+
+    #[inline]
+    /// Flush each chunk of payload immediately
+    ///
+    /// Unbuffered responses are not compressed by `Compress` middleware.
+    pub fn unbuffered(&mut self, val: bool) {
+        if val {
+            self.flags.insert(Flags::UNBUFFERED);
+        } else {
+            self.flags.remove(Flags::UNBUFFERED);
+        }
+    }
 }
 
 impl Default for ResponseHead {
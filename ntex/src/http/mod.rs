@@ -9,11 +9,18 @@ mod message;
 mod payload;
 mod request;
 mod response;
+mod sent;
 mod service;
+mod trace;
 
+pub mod body_channel;
+pub mod conditional;
 pub mod error;
 pub mod h1;
 pub mod h2;
+pub mod proxy;
+pub mod range;
+pub mod sse;
 pub mod test;
 
 pub(crate) use self::message::Message;
@@ -25,7 +32,9 @@ pub use self::message::{ConnectionType, RequestHead, ResponseHead};
 pub use self::payload::{Payload, PayloadStream};
 pub use self::request::Request;
 pub use self::response::{Response, ResponseBuilder};
+pub use self::sent::SentBytes;
 pub use self::service::HttpService;
+pub use self::trace::TraceContext;
 pub use crate::io::types::HttpProtocol;
 
 // re-exports
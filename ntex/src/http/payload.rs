@@ -1,7 +1,7 @@
 use std::{fmt, future::poll_fn, mem, pin::Pin, task::Context, task::Poll};
 
 use crate::http::{error::PayloadError, h1, h2};
-use crate::util::{Bytes, Stream};
+use crate::util::{Bytes, BytesMut, Stream};
 
 /// Type represent boxed payload
 pub type PayloadStream = Pin<Box<dyn Stream<Item = Result<Bytes, PayloadError>>>>;
@@ -82,6 +82,46 @@ impl Payload {
             Payload::Stream(pl) => Pin::new(pl).poll_next(cx),
         }
     }
+
+    /// Read whole payload into a single `Bytes` buffer.
+    ///
+    /// Reading stops as soon as size of received data exceeds `limit`,
+    /// in that case `PayloadError::Overflow` is returned.
+    pub async fn collect_body(&mut self, limit: usize) -> Result<Bytes, PayloadError> {
+        let mut body: Option<Bytes> = None;
+        let mut buf = BytesMut::new();
+
+        while let Some(chunk) = self.recv().await {
+            let chunk = chunk?;
+            let size = body.as_ref().map_or(0, Bytes::len) + buf.len() + chunk.len();
+            if size > limit {
+                return Err(PayloadError::Overflow);
+            }
+            if let Some(first) = body.take() {
+                buf.extend_from_slice(&first);
+            }
+            if buf.is_empty() {
+                body = Some(chunk);
+            } else {
+                buf.extend_from_slice(&chunk);
+            }
+        }
+        Ok(body.unwrap_or_else(|| buf.freeze()))
+    }
+
+    /// Read and discard rest of the payload.
+    ///
+    /// Returns number of discarded bytes. Unconsumed http/1 payload
+    /// prevents connection reuse, use this method if handler does not
+    /// need request body. Size of payload is not limited, see
+    /// `HttpServiceConfig::set_payload_drain_limit()` for bounded draining.
+    pub async fn drain(&mut self) -> Result<usize, PayloadError> {
+        let mut size = 0;
+        while let Some(chunk) = self.recv().await {
+            size += chunk?.len();
+        }
+        Ok(size)
+    }
 }
 
 impl Stream for Payload {
@@ -97,6 +137,53 @@ impl Stream for Payload {
 mod tests {
     use super::*;
 
+    #[crate::rt_test]
+    async fn test_collect_body() {
+        let (tx, rx) = crate::channel::bstream::channel();
+        tx.feed_data(Bytes::from_static(b"line1"));
+        tx.feed_data(Bytes::from_static(b"line2"));
+        tx.feed_eof();
+        let mut pl = Payload::H1(rx);
+        assert_eq!(
+            pl.collect_body(10).await.unwrap(),
+            Bytes::from_static(b"line1line2")
+        );
+
+        let (tx, rx) = crate::channel::bstream::channel();
+        tx.feed_data(Bytes::from_static(b"line1"));
+        tx.feed_data(Bytes::from_static(b"line2"));
+        let mut pl = Payload::H1(rx);
+        assert!(matches!(
+            pl.collect_body(9).await,
+            Err(PayloadError::Overflow)
+        ));
+
+        let mut pl = Payload::None;
+        assert_eq!(pl.collect_body(0).await.unwrap(), Bytes::new());
+    }
+
+    #[crate::rt_test]
+    async fn test_drain() {
+        let (tx, rx) = crate::channel::bstream::channel();
+        tx.feed_data(Bytes::from_static(b"line1"));
+        tx.feed_data(Bytes::from_static(b"line2"));
+        tx.feed_eof();
+        let mut pl = Payload::H1(rx);
+        assert_eq!(pl.drain().await.unwrap(), 10);
+        assert!(pl.recv().await.is_none());
+
+        let (tx, rx) = crate::channel::bstream::channel();
+        tx.feed_data(Bytes::from_static(b"line1"));
+        tx.set_error(PayloadError::Incomplete(None));
+        let mut pl = Payload::H1(rx);
+        assert!(matches!(
+            pl.drain().await,
+            Err(PayloadError::Incomplete(None))
+        ));
+
+        assert_eq!(Payload::None.drain().await.unwrap(), 0);
+    }
+
     #[test]
     fn payload_debug() {
         assert!(format!("{:?}", Payload::None).contains("Payload::None"));
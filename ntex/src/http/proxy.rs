@@ -0,0 +1,262 @@
+//! Proxy helpers ([RFC 7230](https://www.rfc-editor.org/rfc/rfc7230#section-6.1))
+//!
+//! ```rust
+//! use ntex::http::{HeaderMap, header, proxy};
+//!
+//! let mut headers = HeaderMap::new();
+//! headers.insert(header::CONNECTION, "close, x-hop".parse().unwrap());
+//! headers.insert("x-hop".parse().unwrap(), "1".parse().unwrap());
+//! headers.insert(header::CONTENT_TYPE, "text/plain".parse().unwrap());
+//!
+//! proxy::remove_hop_by_hop(&mut headers);
+//! assert!(!headers.contains_key("x-hop"));
+//! assert!(headers.contains_key(header::CONTENT_TYPE));
+//! ```
+use crate::http::HeaderMap;
+use crate::http::header::{self, HeaderName};
+
+/// Element of `Forwarded` header ([RFC 7239](https://www.rfc-editor.org/rfc/rfc7239))
+///
+/// Each proxy appends one element, first element describes connection
+/// of the client to the first proxy.
+///
+/// ```rust
+/// use ntex::http::proxy::Forwarded;
+///
+/// let elements = Forwarded::parse("for=192.0.2.60;proto=https, for=\"[2001:db8::17]\"");
+/// assert_eq!(elements[0].forwarded_for(), Some("192.0.2.60"));
+/// assert_eq!(elements[0].proto(), Some("https"));
+/// assert_eq!(elements[1].forwarded_for(), Some("[2001:db8::17]"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Forwarded {
+    forwarded_for: Option<String>,
+    by: Option<String>,
+    host: Option<String>,
+    proto: Option<String>,
+}
+
+impl Forwarded {
+    /// Parse `Forwarded` header value
+    ///
+    /// Quoted values are unescaped, unknown parameters and malformed pairs
+    /// are ignored.
+    pub fn parse(value: &str) -> Vec<Forwarded> {
+        split_quoted(value, b',')
+            .into_iter()
+            .filter_map(|element| {
+                let mut item = Forwarded::default();
+                for pair in split_quoted(element, b';') {
+                    let Some((name, val)) = pair.split_once('=') else {
+                        continue;
+                    };
+                    let field = match name.trim().to_ascii_lowercase().as_str() {
+                        "for" => &mut item.forwarded_for,
+                        "by" => &mut item.by,
+                        "host" => &mut item.host,
+                        "proto" => &mut item.proto,
+                        _ => continue,
+                    };
+                    if field.is_none() {
+                        *field = Some(unquote(val));
+                    }
+                }
+                if item == Forwarded::default() {
+                    None
+                } else {
+                    Some(item)
+                }
+            })
+            .collect()
+    }
+
+    /// Parse all `Forwarded` headers
+    pub fn from_headers(headers: &HeaderMap) -> Vec<Forwarded> {
+        headers
+            .get_all(header::FORWARDED)
+            .filter_map(|val| val.to_str().ok())
+            .flat_map(Forwarded::parse)
+            .collect()
+    }
+
+    /// Client that initiated the request, `for` parameter
+    pub fn forwarded_for(&self) -> Option<&str> {
+        self.forwarded_for.as_deref()
+    }
+
+    /// Interface where the request came in to the proxy, `by` parameter
+    pub fn by(&self) -> Option<&str> {
+        self.by.as_deref()
+    }
+
+    /// Original `Host` header, `host` parameter
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
+    /// Protocol used to make the request, `proto` parameter
+    pub fn proto(&self) -> Option<&str> {
+        self.proto.as_deref()
+    }
+}
+
+/// Split value by separator, separators in quoted strings are skipped
+fn split_quoted(value: &str, sep: u8) -> Vec<&str> {
+    let mut items = Vec::new();
+    let (mut start, mut quoted, mut escaped) = (0, false, false);
+    for (idx, b) in value.bytes().enumerate() {
+        if escaped {
+            escaped = false;
+        } else if quoted && b == b'\\' {
+            escaped = true;
+        } else if b == b'"' {
+            quoted = !quoted;
+        } else if b == sep && !quoted {
+            items.push(&value[start..idx]);
+            start = idx + 1;
+        }
+    }
+    items.push(&value[start..]);
+    items
+}
+
+/// Remove quotes and unescape quoted-pairs
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    let Some(value) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) else {
+        return value.to_string();
+    };
+
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\\' {
+            result.extend(chars.next());
+        } else {
+            result.push(ch);
+        }
+    }
+    result
+}
+
+/// Remove hop-by-hop headers.
+///
+/// Removes `Connection`, `Keep-Alive`, `Proxy-*`, `TE`, `Trailer`,
+/// `Transfer-Encoding` and `Upgrade` headers, and also all headers
+/// listed in `Connection` header value. Should be used for requests and
+/// responses before forwarding them to the next hop.
+pub fn remove_hop_by_hop(headers: &mut HeaderMap) {
+    // headers nominated by connection header
+    let listed: Vec<HeaderName> = headers
+        .get_all(header::CONNECTION)
+        .filter_map(|val| val.to_str().ok())
+        .flat_map(|val| val.split(','))
+        .filter_map(|name| HeaderName::try_from(name.trim()).ok())
+        .collect();
+    for name in listed {
+        headers.remove(name);
+    }
+
+    for name in [
+        header::CONNECTION,
+        HeaderName::from_static("keep-alive"),
+        header::TE,
+        header::TRAILER,
+        header::TRANSFER_ENCODING,
+        header::UPGRADE,
+    ] {
+        headers.remove(name);
+    }
+
+    // proxy-authenticate, proxy-authorization, proxy-connection, etc
+    let proxy: Vec<HeaderName> = headers
+        .keys()
+        .filter(|name| name.as_str().starts_with("proxy-"))
+        .cloned()
+        .collect();
+    for name in proxy {
+        headers.remove(name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::header::HeaderValue;
+
+    #[test]
+    fn test_forwarded() {
+        let elements = Forwarded::parse("for=192.0.2.60;proto=https");
+        assert_eq!(elements.len(), 1);
+        assert_eq!(elements[0].forwarded_for(), Some("192.0.2.60"));
+        assert_eq!(elements[0].proto(), Some("https"));
+        assert_eq!(elements[0].host(), None);
+        assert_eq!(elements[0].by(), None);
+
+        let elements = Forwarded::parse(
+            "For=\"[2001:db8:cafe::17]:4711\"; by=unknown, \
+             for=_hidden;host=\"a;b,c.org\";Proto=http, for=\"quoted \\\"name\\\"\"",
+        );
+        assert_eq!(elements.len(), 3);
+        assert_eq!(
+            elements[0].forwarded_for(),
+            Some("[2001:db8:cafe::17]:4711")
+        );
+        assert_eq!(elements[0].by(), Some("unknown"));
+        assert_eq!(elements[1].forwarded_for(), Some("_hidden"));
+        assert_eq!(elements[1].host(), Some("a;b,c.org"));
+        assert_eq!(elements[1].proto(), Some("http"));
+        assert_eq!(elements[2].forwarded_for(), Some("quoted \"name\""));
+
+        // malformed and unknown pairs
+        assert!(Forwarded::parse("").is_empty());
+        assert!(Forwarded::parse("for, secret=1, ;").is_empty());
+        assert_eq!(
+            Forwarded::parse("for=a;for=b;x=1")[0].forwarded_for(),
+            Some("a")
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.append(header::FORWARDED, HeaderValue::from_static("for=a"));
+        headers.append(header::FORWARDED, HeaderValue::from_static("for=b, for=c"));
+        let elements = Forwarded::from_headers(&headers);
+        assert_eq!(
+            elements
+                .iter()
+                .map(|e| e.forwarded_for().unwrap())
+                .collect::<Vec<_>>(),
+            ["a", "b", "c"]
+        );
+    }
+
+    #[test]
+    fn test_remove_hop_by_hop() {
+        let mut headers = HeaderMap::new();
+        for (name, value) in [
+            ("connection", "keep-alive, X-Custom-Hop"),
+            ("connection", "x-other-hop"),
+            ("keep-alive", "timeout=5"),
+            ("proxy-authorization", "basic"),
+            ("proxy-connection", "keep-alive"),
+            ("te", "trailers"),
+            ("trailer", "expires"),
+            ("transfer-encoding", "chunked"),
+            ("upgrade", "websocket"),
+            ("x-custom-hop", "1"),
+            ("x-other-hop", "1"),
+            ("content-type", "text/plain"),
+            ("x-forwarded-for", "127.0.0.1"),
+        ] {
+            headers.append(
+                HeaderName::from_static(name),
+                HeaderValue::from_static(value),
+            );
+        }
+
+        remove_hop_by_hop(&mut headers);
+
+        let mut names: Vec<_> = headers.keys().map(HeaderName::as_str).collect();
+        names.sort_unstable();
+        assert_eq!(names, ["content-type", "x-forwarded-for"]);
+    }
+}
@@ -0,0 +1,196 @@
+//! Range requests support ([RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#name-range-requests))
+//!
+//! ```rust
+//! use ntex::http::range;
+//! use ntex::web::{self, HttpRequest, HttpResponse};
+//!
+//! async fn handler(req: HttpRequest) -> HttpResponse {
+//!     let data = vec![0; 1024];
+//!     range::partial(req.head(), data.len() as u64, |r| {
+//!         data[r.start as usize..r.end as usize].to_vec()
+//!     })
+//! }
+//! ```
+use std::ops;
+
+use crate::http::body::Body;
+use crate::http::header::{self, HeaderValue};
+use crate::http::{Method, RequestHead, Response, StatusCode};
+use crate::util::Bytes;
+
+const ACCEPT_RANGES: HeaderValue = HeaderValue::from_static("bytes");
+
+#[derive(thiserror::Error, Copy, Clone, Debug, PartialEq, Eq)]
+#[error("Range not satisfiable")]
+/// Requested range is outside of the content
+pub struct RangeUnsatisfiable;
+
+/// Parse `Range` header value for content of `size` bytes.
+///
+/// Returns byte range with exclusive end. `None` is returned if value is
+/// malformed or contains multiple ranges, in that case header must be ignored
+/// and full content is sent.
+pub fn parse(
+    value: &str,
+    size: u64,
+) -> Result<Option<ops::Range<u64>>, RangeUnsatisfiable> {
+    let value = value.trim();
+    let Some(spec) = value
+        .get(..6)
+        .filter(|unit| unit.eq_ignore_ascii_case("bytes="))
+        .map(|_| &value[6..])
+    else {
+        return Ok(None);
+    };
+    if spec.contains(',') {
+        return Ok(None);
+    }
+    let Some((first, last)) = spec.split_once('-') else {
+        return Ok(None);
+    };
+    let (first, last) = (first.trim(), last.trim());
+
+    if first.is_empty() {
+        // suffix range, last N bytes
+        let Ok(len) = last.parse::<u64>() else {
+            return Ok(None);
+        };
+        if len == 0 || size == 0 {
+            Err(RangeUnsatisfiable)
+        } else {
+            Ok(Some(size.saturating_sub(len)..size))
+        }
+    } else {
+        let Ok(start) = first.parse::<u64>() else {
+            return Ok(None);
+        };
+        let end = if last.is_empty() {
+            size
+        } else {
+            match last.parse::<u64>() {
+                Ok(end) if end >= start => end.saturating_add(1).min(size),
+                _ => return Ok(None),
+            }
+        };
+        if start >= size {
+            Err(RangeUnsatisfiable)
+        } else {
+            Ok(Some(start..end))
+        }
+    }
+}
+
+/// Create response for request that could contain `Range` header.
+///
+/// `body` is called with the range of content that must be sent, full
+/// content range is used if request does not contain valid `Range` header.
+/// Response is *206 Partial Content* with `Content-Range` header for
+/// satisfiable range and *416 Range Not Satisfiable* otherwise.
+/// Only single range requests are supported.
+pub fn partial<F, B>(req: &RequestHead, size: u64, body: F) -> Response
+where
+    F: FnOnce(ops::Range<u64>) -> B,
+    Body: From<B>,
+{
+    let range = if matches!(req.method, Method::GET | Method::HEAD) {
+        req.headers
+            .get(&header::RANGE)
+            .and_then(|val| val.to_str().ok())
+            .map_or(Ok(None), |val| parse(val, size))
+    } else {
+        Ok(None)
+    };
+
+    match range {
+        Ok(Some(range)) => Response::build(StatusCode::PARTIAL_CONTENT)
+            .header(header::ACCEPT_RANGES, ACCEPT_RANGES)
+            .header(
+                header::CONTENT_RANGE,
+                format!("bytes {}-{}/{size}", range.start, range.end - 1),
+            )
+            .body(body(range)),
+        Ok(None) => Response::build(StatusCode::OK)
+            .header(header::ACCEPT_RANGES, ACCEPT_RANGES)
+            .body(body(0..size)),
+        Err(RangeUnsatisfiable) => Response::build(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::ACCEPT_RANGES, ACCEPT_RANGES)
+            .header(header::CONTENT_RANGE, format!("bytes */{size}"))
+            .finish(),
+    }
+}
+
+/// Create response for buffered content, see `partial()`.
+pub fn bytes(req: &RequestHead, data: Bytes) -> Response {
+    partial(req, data.len() as u64, |range| {
+        data.slice(range.start as usize..range.end as usize)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::test::TestRequest;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("bytes=0-499", 1000), Ok(Some(0..500)));
+        assert_eq!(parse("bytes=500-", 1000), Ok(Some(500..1000)));
+        assert_eq!(parse("bytes=-100", 1000), Ok(Some(900..1000)));
+        assert_eq!(parse("bytes=-2000", 1000), Ok(Some(0..1000)));
+        assert_eq!(parse("Bytes= 900 - 2000", 1000), Ok(Some(900..1000)));
+
+        assert_eq!(parse("bytes=1000-", 1000), Err(RangeUnsatisfiable));
+        assert_eq!(parse("bytes=-0", 1000), Err(RangeUnsatisfiable));
+        assert_eq!(parse("bytes=0-", 0), Err(RangeUnsatisfiable));
+
+        for val in [
+            "",
+            "items=0-1",
+            "bytes=",
+            "bytes=a-1",
+            "bytes=5-1",
+            "bytes=0-1,5-6",
+        ] {
+            assert_eq!(parse(val, 1000), Ok(None), "{val}");
+        }
+    }
+
+    #[test]
+    fn test_partial() {
+        let data = Bytes::from_static(b"0123456789");
+
+        let req = TestRequest::default().finish();
+        let resp = bytes(req.head(), data.clone());
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get(header::ACCEPT_RANGES).unwrap(), "bytes");
+        assert!(matches!(resp.body().as_ref(), Some(Body::Bytes(b)) if b == &data));
+
+        let req = TestRequest::default()
+            .header(header::RANGE, "bytes=2-4")
+            .finish();
+        let resp = bytes(req.head(), data.clone());
+        assert_eq!(resp.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes 2-4/10"
+        );
+        assert!(matches!(resp.body().as_ref(), Some(Body::Bytes(b)) if b == &b"234"[..]));
+
+        let req = TestRequest::default()
+            .header(header::RANGE, "bytes=20-")
+            .finish();
+        let resp = bytes(req.head(), data.clone());
+        assert_eq!(resp.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes */10"
+        );
+
+        // range is ignored for non GET requests
+        let req = TestRequest::default()
+            .method(Method::POST)
+            .header(header::RANGE, "bytes=2-4")
+            .finish();
+        assert_eq!(bytes(req.head(), data).status(), StatusCode::OK);
+    }
+}
@@ -96,6 +96,12 @@ impl Request {
         self.head().version
     }
 
+    /// Check if request is sent over http/2 connection.
+    #[inline]
+    pub fn is_h2(&self) -> bool {
+        self.head().version == Version::HTTP_2
+    }
+
     /// The target path of this Request.
     #[inline]
     pub fn path(&self) -> &str {
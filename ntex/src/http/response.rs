@@ -180,6 +180,12 @@ impl<B> Response<B> {
         &self.body
     }
 
+    /// Get mutable reference to body of this response.
+    #[inline]
+    pub fn body_mut(&mut self) -> &mut ResponseBody<B> {
+        &mut self.body
+    }
+
     /// Set a body.
     pub fn set_body<B2>(self, body: B2) -> Response<B2> {
         Response {
@@ -375,6 +381,10 @@ impl ResponseBuilder {
     }
 
     /// Set the custom reason for the response.
+    ///
+    /// Reason phrase is written to http/1 status line as is, phrases with
+    /// control characters are replaced with default phrase of the status code.
+    /// Http/2 does not have reason phrase, custom reason is ignored.
     #[inline]
     pub fn reason(&mut self, reason: &'static str) -> &mut Self {
         if let Some(parts) = parts(&mut self.head, self.err) {
@@ -423,6 +433,33 @@ impl ResponseBuilder {
         self
     }
 
+    /// Always use chunked transfer encoding for HTTP/1.1 responses.
+    ///
+    /// Response body is sent with `Transfer-Encoding: chunked` even if
+    /// body size is known, `Content-Length` header is not sent. Responses
+    /// with empty body are not affected. Use with `unbuffered()` for
+    /// streaming responses, like server-sent events, that pass proxies
+    /// which rely on chunked encoding.
+    #[inline]
+    pub fn force_chunking(&mut self) -> &mut Self {
+        if let Some(parts) = parts(&mut self.head, self.err) {
+            parts.force_chunking(true);
+        }
+        self
+    }
+
+    /// Flush each chunk of response body immediately.
+    ///
+    /// Use for streaming responses, like server-sent events. Unbuffered
+    /// responses are not compressed.
+    #[inline]
+    pub fn unbuffered(&mut self) -> &mut Self {
+        if let Some(parts) = parts(&mut self.head, self.err) {
+            parts.unbuffered(true);
+        }
+        self
+    }
+
     /// Set response content type.
     #[inline]
     pub fn content_type<V>(&mut self, value: V) -> &mut Self
@@ -450,6 +487,9 @@ impl ResponseBuilder {
     #[cfg(feature = "cookie")]
     /// Set a cookie.
     ///
+    /// Every cookie is sent in separate `Set-Cookie` header, cookie with
+    /// the same name replaces previously set one.
+    ///
     /// ```rust
     /// use coo_kie as cookie;
     /// use ntex::http::{Request, Response};
@@ -577,22 +617,30 @@ impl ResponseBuilder {
     /// `ResponseBuilder` can not be used after this call.
     pub fn json<T: Serialize>(&mut self, value: &T) -> Response {
         match serde_json::to_string(value) {
-            Ok(body) => {
-                let contains = if let Some(parts) = parts(&mut self.head, self.err) {
-                    parts.headers.contains_key(header::CONTENT_TYPE)
-                } else {
-                    true
-                };
-                if !contains {
-                    self.header(header::CONTENT_TYPE, "application/json");
-                }
-
-                self.body(Body::from(body))
-            }
+            Ok(body) => self.json_bytes(Bytes::from(body)),
             Err(e) => e.into(),
         }
     }
 
+    #[must_use]
+    /// Set a pre-serialized json body and generate `Response`.
+    ///
+    /// Bytes are used as response body as is, without copying.
+    ///
+    /// `ResponseBuilder` can not be used after this call.
+    pub fn json_bytes(&mut self, body: Bytes) -> Response {
+        let contains = if let Some(parts) = parts(&mut self.head, self.err) {
+            parts.headers.contains_key(header::CONTENT_TYPE)
+        } else {
+            true
+        };
+        if !contains {
+            self.header(header::CONTENT_TYPE, "application/json");
+        }
+
+        self.body(Body::Bytes(body))
+    }
+
     #[inline]
     #[must_use]
     /// Set an empty body and generate `Response`.
@@ -669,6 +717,7 @@ impl<'a> From<&'a ResponseHead> for ResponseBuilder {
             msg.headers.append(k.clone(), v.clone());
         }
         msg.no_chunking(!head.chunked());
+        msg.unbuffered(head.is_unbuffered());
 
         #[cfg(feature = "cookie")]
         {
@@ -796,11 +845,98 @@ impl From<BytesMut> for Response {
     }
 }
 
+/// Convert `http::Response` to a `Response`
+///
+/// Extensions of `http::Response` are stored as `http::Extensions` value
+/// in response's extensions.
+impl<B: Into<Body>> From<http::Response<B>> for Response {
+    fn from(res: http::Response<B>) -> Self {
+        let (parts, body) = res.into_parts();
+        let mut res = Response::with_body(parts.status, body.into());
+        res.head.version = parts.version;
+        res.head.headers = parts.headers.into();
+        if !parts.extensions.is_empty() {
+            res.head.extensions.get_mut().insert(parts.extensions);
+        }
+        res
+    }
+}
+
+/// Convert `Response` to a `http::Response`
+///
+/// Reason phrase is not preserved. Only `http::Extensions` value stored
+/// in response's extensions is converted to `http::Response` extensions.
+impl<B> From<Response<B>> for http::Response<ResponseBody<B>> {
+    fn from(res: Response<B>) -> Self {
+        let (mut head, body) = res.into_parts();
+        let head = head.head_mut();
+        let mut res = http::Response::new(body);
+        *res.status_mut() = head.status;
+        *res.version_mut() = head.version;
+        *res.headers_mut() = std::mem::take(&mut head.headers).into();
+        if let Some(ext) = head.extensions.get_mut().remove::<http::Extensions>() {
+            *res.extensions_mut() = ext;
+        }
+        res
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::http::Version;
     use crate::http::header::{CONTENT_TYPE, COOKIE};
 
+    #[test]
+    fn test_from_http_response() {
+        let res = http::Response::builder()
+            .status(StatusCode::CREATED)
+            .version(Version::HTTP_10)
+            .header("x-test", "1")
+            .header("x-test", "2")
+            .extension(10u32)
+            .body("test")
+            .unwrap();
+
+        let res = Response::from(res);
+        assert_eq!(
+            res.extensions()
+                .get::<http::Extensions>()
+                .unwrap()
+                .get::<u32>(),
+            Some(&10)
+        );
+        assert_eq!(res.status(), StatusCode::CREATED);
+        assert_eq!(res.head().version, Version::HTTP_10);
+        let values: Vec<_> = res.headers().get_all("x-test").collect();
+        assert_eq!(
+            values,
+            [
+                &HeaderValue::from_static("1"),
+                &HeaderValue::from_static("2")
+            ]
+        );
+        assert_eq!(res.body().as_ref(), Some(&Body::from("test")));
+
+        let res = http::Response::from(res);
+        assert_eq!(res.extensions().get::<u32>(), Some(&10));
+    }
+
+    #[test]
+    fn test_into_http_response() {
+        let res = Response::Created()
+            .header("x-test", "1")
+            .header("x-test", "2")
+            .body("test");
+
+        let res = http::Response::from(res);
+        assert_eq!(res.status(), StatusCode::CREATED);
+        assert_eq!(res.version(), Version::HTTP_11);
+        let values: Vec<_> = res.headers().get_all("x-test").iter().collect();
+        assert_eq!(values, ["1", "2"]);
+        assert_eq!(res.body().as_ref(), Some(&Body::from("test")));
+    }
+
     #[test]
     fn test_debug() {
         let resp = Response::Ok()
@@ -855,6 +991,31 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "cookie")]
+    #[test]
+    fn test_multiple_cookies() {
+        use crate::http::header::SET_COOKIE;
+
+        let resp = Response::Ok()
+            .cookie(coo_kie::Cookie::build(("k", "v")).http_only(true))
+            .cookie(
+                coo_kie::Cookie::build(("session", "id"))
+                    .secure(true)
+                    .same_site(coo_kie::SameSite::Strict)
+                    .path("/"),
+            )
+            .cookie(coo_kie::Cookie::new("session", "id2"))
+            .finish();
+
+        let mut val: Vec<_> = resp
+            .headers()
+            .get_all(SET_COOKIE)
+            .map(|v| v.to_str().unwrap().to_owned())
+            .collect();
+        val.sort();
+        assert_eq!(val, vec!["k=v; HttpOnly", "session=id2"]);
+    }
+
     #[cfg(feature = "cookie")]
     #[test]
     fn test_update_response_cookies() {
@@ -929,6 +1090,17 @@ mod tests {
         assert_eq!(resp.get_body_ref(), b"[\"v1\",\"v2\",\"v3\"]");
     }
 
+    #[test]
+    fn test_json_bytes() {
+        let data = Bytes::from_static(b"[\"v1\",\"v2\"]");
+        let resp = Response::build(StatusCode::OK).json_bytes(data.clone());
+        let ct = resp.headers().get(CONTENT_TYPE).unwrap();
+        assert_eq!(ct, HeaderValue::from_static("application/json"));
+        assert!(
+            matches!(resp.body().as_ref(), Some(Body::Bytes(b)) if b.as_ptr() == data.as_ptr())
+        );
+    }
+
     #[test]
     fn test_into_response() {
         let resp: Response = "test".into();
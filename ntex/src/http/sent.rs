@@ -0,0 +1,35 @@
+//! Response body bytes accounting
+use std::{cell::Cell, rc::Rc};
+
+#[derive(Clone, Debug, Default)]
+/// Counter of response body bytes written to the connection
+///
+/// If counter is stored in response extensions, http/1 dispatcher adds
+/// number of encoded body bytes to the counter. Counted bytes include
+/// content encoding and transfer encoding (chunk headers), response head
+/// is not counted. Counter value is final once response body is dropped.
+///
+/// ```rust
+/// use ntex::http::{Response, SentBytes};
+///
+/// let counter = SentBytes::new();
+/// let res = Response::Ok().body("body");
+/// res.extensions_mut().insert(counter.clone());
+/// ```
+pub struct SentBytes(Rc<Cell<u64>>);
+
+impl SentBytes {
+    /// Create new counter
+    pub fn new() -> Self {
+        SentBytes::default()
+    }
+
+    /// Number of sent bytes
+    pub fn get(&self) -> u64 {
+        self.0.get()
+    }
+
+    pub(crate) fn add(&self, size: usize) {
+        self.0.set(self.0.get() + size as u64);
+    }
+}
@@ -1,8 +1,8 @@
-use std::{cell::Cell, cell::RefCell, error, fmt, marker, rc::Rc, task::Context};
+use std::{cell::Cell, cell::RefCell, cmp, error, fmt, marker, rc::Rc, task::Context};
 
 use crate::io::{Filter, Io, IoRef, types};
 use crate::service::{IntoServiceFactory, Service, ServiceCtx, ServiceFactory};
-use crate::{SharedCfg, channel::oneshot, util::HashSet, util::join};
+use crate::{SharedCfg, channel::oneshot, time::timeout, util::HashSet, util::join};
 
 use super::body::MessageBody;
 use super::config::DispatcherConfig;
@@ -156,6 +156,10 @@ mod openssl {
         C2::InitError: fmt::Debug,
     {
         /// Create openssl based service
+        ///
+        /// ALPN protocol is selected by acceptor's alpn select callback.
+        /// Connections are dispatched to http/2 only if `h2` protocol
+        /// is negotiated.
         pub fn openssl(
             self,
             acceptor: ssl::SslAcceptor,
@@ -203,7 +207,12 @@ mod rustls {
         C2::Error: error::Error,
         C2::InitError: fmt::Debug,
     {
-        /// Create openssl based service
+        /// Create rustls based service
+        ///
+        /// If `config.alpn_protocols` is empty, `h2` and `http/1.1` protocols are
+        /// advertised. Otherwise configured list is used as is, for example
+        /// `vec![b"http/1.1".to_vec()]` disables http/2. Connections are
+        /// dispatched to http/2 only if `h2` protocol is negotiated.
         pub fn rustls(
             self,
             mut config: ServerConfig,
@@ -214,8 +223,9 @@ mod rustls {
             Error = SslError<DispatchError>,
             InitError = (),
         > {
-            let protos = vec!["h2".to_string().into(), "http/1.1".to_string().into()];
-            config.alpn_protocols = protos;
+            if config.alpn_protocols.is_empty() {
+                config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+            }
 
             TlsAcceptor::from(config)
                 .map_err(|e| SslError::Ssl(Box::new(e)))
@@ -287,6 +297,45 @@ pub struct HttpServiceHandler<F, S, B, C1, C2> {
     _t: marker::PhantomData<(F, B)>,
 }
 
+/// Http/2 connection preface
+const H2_PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+impl<F, S, B, C1, C2> HttpServiceHandler<F, S, B, C1, C2>
+where
+    F: Filter,
+{
+    /// Check if plaintext connection starts with http/2 connection preface
+    async fn is_h2_preface(&self, io: &Io<F>) -> bool {
+        let fut = async {
+            loop {
+                let result = io.with_read_buf(|buf| {
+                    let len = cmp::min(buf.len(), H2_PREFACE.len());
+                    if buf[..len] != H2_PREFACE[..len] {
+                        Some(false)
+                    } else if len == H2_PREFACE.len() {
+                        Some(true)
+                    } else {
+                        None
+                    }
+                });
+                if let Some(result) = result {
+                    return result;
+                }
+                if !matches!(io.read_ready().await, Ok(Some(()))) {
+                    return false;
+                }
+            }
+        };
+
+        // use headers read timeout for preface
+        if let Some(rate) = self.config.headers_read_rate() {
+            timeout(rate.timeout, fut).await.unwrap_or(false)
+        } else {
+            fut.await
+        }
+    }
+}
+
 impl<F, S, B, C1, C2> Service<Io<F>> for HttpServiceHandler<F, S, B, C1, C2>
 where
     F: Filter,
@@ -362,12 +411,15 @@ where
         io: Io<F>,
         _: ServiceCtx<'_, Self>,
     ) -> Result<Self::Response, Self::Error> {
+        self.config.configure_io(&io);
         let id = self.config.next_id();
         let ioref = io.get_ref();
 
-        let result = if io.query::<types::HttpProtocol>().get()
+        let is_h2 = io.query::<types::HttpProtocol>().get()
             == Some(types::HttpProtocol::Http2)
-        {
+            || (self.config.h2c() && self.is_h2_preface(&io).await);
+
+        let result = if is_h2 {
             let control = self
                 .h2_control
                 .create(self.cfg.clone())
@@ -0,0 +1,297 @@
+//! Server-sent events support
+//!
+//! ```rust,no_run
+//! use ntex::http::sse::{Event, Sse};
+//! use ntex::{time::Seconds, util::Stream, web};
+//!
+//! fn events() -> impl Stream<Item = Result<Event, std::io::Error>> + Unpin {
+//!     futures_util::stream::iter([Ok(Event::data("hello").event("greeting"))])
+//! }
+//!
+//! async fn handler() -> impl web::Responder {
+//!     Sse::from_stream(events()).keep_alive(Seconds(15))
+//! }
+//! ```
+use std::{error::Error, fmt, marker, pin::Pin, rc::Rc, task::Context, task::Poll};
+
+use crate::http::body::{Body, BodySize, MessageBody};
+use crate::http::header::{self, HeaderValue};
+use crate::http::{Response, StatusCode};
+use crate::time::{Millis, Sleep};
+use crate::util::{Bytes, BytesMut, Stream};
+
+const KEEP_ALIVE: Bytes = Bytes::from_static(b":\n\n");
+
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+/// Server-sent event
+pub struct Event {
+    id: Option<String>,
+    event: Option<String>,
+    data: Option<String>,
+    retry: Option<Millis>,
+    comment: Option<String>,
+}
+
+impl Event {
+    /// Create event with `data` field.
+    ///
+    /// Multi-line data is sent as multiple `data` lines.
+    pub fn data<T: Into<String>>(data: T) -> Self {
+        Event {
+            data: Some(data.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Create comment event.
+    ///
+    /// Comments are ignored by clients.
+    pub fn comment<T: Into<String>>(comment: T) -> Self {
+        Event {
+            comment: Some(comment.into()),
+            ..Default::default()
+        }
+    }
+
+    #[must_use]
+    /// Set event type, `event` field.
+    ///
+    /// Line breaks are removed from event type.
+    pub fn event<T: Into<String>>(mut self, event: T) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+
+    #[must_use]
+    /// Set event id, `id` field.
+    ///
+    /// Line breaks are removed from event id.
+    pub fn id<T: Into<String>>(mut self, id: T) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    #[must_use]
+    /// Set client reconnection time, `retry` field.
+    pub fn retry<T: Into<Millis>>(mut self, retry: T) -> Self {
+        self.retry = Some(retry.into());
+        self
+    }
+
+    /// Encode event to the wire format.
+    pub fn encode(&self, dst: &mut BytesMut) {
+        if let Some(ref comment) = self.comment {
+            encode_lines(dst, "", comment);
+        }
+        if let Some(ref event) = self.event {
+            encode_field(dst, "event", event);
+        }
+        if let Some(ref id) = self.id {
+            encode_field(dst, "id", id);
+        }
+        if let Some(retry) = self.retry {
+            encode_field(dst, "retry", &retry.0.to_string());
+        }
+        if let Some(ref data) = self.data {
+            encode_lines(dst, "data", data);
+        }
+        dst.extend_from_slice(b"\n");
+    }
+}
+
+fn encode_field(dst: &mut BytesMut, name: &str, value: &str) {
+    dst.extend_from_slice(name.as_bytes());
+    dst.extend_from_slice(b": ");
+    for part in value.split(['\r', '\n']) {
+        dst.extend_from_slice(part.as_bytes());
+    }
+    dst.extend_from_slice(b"\n");
+}
+
+fn encode_lines(dst: &mut BytesMut, name: &str, value: &str) {
+    for line in value.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        for part in line.split('\r') {
+            dst.extend_from_slice(name.as_bytes());
+            dst.extend_from_slice(b": ");
+            dst.extend_from_slice(part.as_bytes());
+            dst.extend_from_slice(b"\n");
+        }
+    }
+}
+
+/// Server-sent events response body
+///
+/// Body converts stream of events to `text/event-stream` response. Response
+/// is unbuffered, each event is flushed to the peer immediately.
+pub struct Sse<S, E> {
+    stream: S,
+    keep_alive: Option<(Millis, Option<Sleep>)>,
+    _t: marker::PhantomData<E>,
+}
+
+impl<S, E> Sse<S, E>
+where
+    S: Stream<Item = Result<Event, E>> + Unpin,
+    E: Error,
+{
+    /// Create server-sent events body from stream of events.
+    pub fn from_stream(stream: S) -> Self {
+        Sse {
+            stream,
+            keep_alive: None,
+            _t: marker::PhantomData,
+        }
+    }
+
+    #[must_use]
+    /// Send keep-alive comment if stream is idle for specified interval.
+    ///
+    /// By default keep-alive is disabled.
+    pub fn keep_alive<T: Into<Millis>>(mut self, interval: T) -> Self {
+        let interval = interval.into();
+        self.keep_alive = if interval.is_zero() {
+            None
+        } else {
+            Some((interval, None))
+        };
+        self
+    }
+}
+
+impl<S, E> fmt::Debug for Sse<S, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sse")
+            .field("stream", &std::any::type_name::<S>())
+            .field("keep_alive", &self.keep_alive.as_ref().map(|ka| ka.0))
+            .finish()
+    }
+}
+
+impl<S, E> MessageBody for Sse<S, E>
+where
+    S: Stream<Item = Result<Event, E>> + Unpin + 'static,
+    E: Error + 'static,
+{
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    fn poll_next_chunk(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Rc<dyn Error>>>> {
+        match Pin::new(&mut self.stream).poll_next(cx) {
+            Poll::Ready(Some(Ok(event))) => {
+                if let Some((interval, Some(ref sleep))) = self.keep_alive {
+                    sleep.reset(interval);
+                }
+                let mut buf = BytesMut::new();
+                event.encode(&mut buf);
+                Poll::Ready(Some(Ok(buf.freeze())))
+            }
+            Poll::Ready(Some(Err(e))) => {
+                let e: Rc<dyn Error> = Rc::new(e);
+                Poll::Ready(Some(Err(e)))
+            }
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => {
+                if let Some((interval, ref mut sleep)) = self.keep_alive {
+                    let sleep = sleep.get_or_insert_with(|| Sleep::new(interval));
+                    if sleep.poll_elapsed(cx).is_ready() {
+                        sleep.reset(interval);
+                        let _ = sleep.poll_elapsed(cx);
+                        return Poll::Ready(Some(Ok(KEEP_ALIVE)));
+                    }
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<S, E> From<Sse<S, E>> for Response
+where
+    S: Stream<Item = Result<Event, E>> + Unpin + 'static,
+    E: Error + 'static,
+{
+    fn from(sse: Sse<S, E>) -> Response {
+        Response::build(StatusCode::OK)
+            .content_type("text/event-stream")
+            .header(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"))
+            .unbuffered()
+            .body(Body::from_message(sse))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::poll_fn, io};
+
+    use futures_util::stream;
+
+    use super::*;
+    use crate::time::sleep;
+
+    fn encode(ev: &Event) -> BytesMut {
+        let mut buf = BytesMut::new();
+        ev.encode(&mut buf);
+        buf
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode(&Event::data("msg")), "data: msg\n\n");
+        assert_eq!(
+            encode(&Event::data("line1\nline2\r\nline3\rline4")),
+            "data: line1\ndata: line2\ndata: line3\ndata: line4\n\n"
+        );
+        assert_eq!(encode(&Event::data("")), "data: \n\n");
+        assert_eq!(encode(&Event::data("msg\n")), "data: msg\ndata: \n\n");
+        assert_eq!(
+            encode(
+                &Event::data("msg")
+                    .event("update\n")
+                    .id("1")
+                    .retry(Millis(3000))
+            ),
+            "event: update\nid: 1\nretry: 3000\ndata: msg\n\n"
+        );
+        assert_eq!(encode(&Event::comment("ping")), ": ping\n\n");
+    }
+
+    #[crate::rt_test]
+    async fn test_body() {
+        let mut body = Sse::from_stream(stream::iter([
+            Ok(Event::data("first")),
+            Err(io::Error::other("err")),
+        ]));
+        assert_eq!(body.size(), BodySize::Stream);
+
+        let chunk = poll_fn(|cx| body.poll_next_chunk(cx)).await;
+        assert_eq!(chunk.unwrap().unwrap(), "data: first\n\n");
+        let chunk = poll_fn(|cx| body.poll_next_chunk(cx)).await;
+        assert!(chunk.unwrap().is_err());
+        assert!(poll_fn(|cx| body.poll_next_chunk(cx)).await.is_none());
+
+        let resp =
+            Response::from(Sse::from_stream(stream::empty::<Result<Event, io::Error>>()));
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(resp.head().is_unbuffered());
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/event-stream"
+        );
+    }
+
+    #[crate::rt_test]
+    async fn test_keep_alive() {
+        let mut body = Sse::from_stream(stream::pending::<Result<Event, io::Error>>())
+            .keep_alive(Millis(50));
+        assert!(poll_fn(|cx| Poll::Ready(body.poll_next_chunk(cx).is_pending())).await);
+
+        sleep(Millis(150)).await;
+        let chunk = poll_fn(|cx| body.poll_next_chunk(cx)).await;
+        assert_eq!(chunk.unwrap().unwrap(), KEEP_ALIVE);
+    }
+}
@@ -0,0 +1,271 @@
+//! W3C Trace Context support
+use std::fmt;
+
+use nanorand::{Rng, WyRand};
+
+use super::HeaderMap;
+use super::header::{HeaderName, HeaderValue};
+
+/// `traceparent` header name
+const TRACEPARENT: HeaderName = HeaderName::from_static("traceparent");
+
+/// `tracestate` header name
+const TRACESTATE: HeaderName = HeaderName::from_static("tracestate");
+
+const FLAG_SAMPLED: u8 = 0x01;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Distributed tracing context ([W3C Trace Context](https://www.w3.org/TR/trace-context/))
+///
+/// Trace context is propagated with `traceparent` and `tracestate` headers.
+/// Incoming context could be extracted with `HttpRequest::trace_context()`,
+/// outgoing client requests could be linked to the incoming trace with
+/// `ClientRequest::trace_context()`.
+pub struct TraceContext {
+    trace_id: u128,
+    parent_id: u64,
+    flags: u8,
+    state: Option<HeaderValue>,
+}
+
+impl TraceContext {
+    /// Start new trace with random trace id.
+    pub fn new() -> Self {
+        let mut rng = WyRand::new();
+        TraceContext {
+            trace_id: non_zero(|| {
+                (u128::from(rng.generate::<u64>()) << 64)
+                    | u128::from(rng.generate::<u64>())
+            }),
+            parent_id: non_zero(|| rng.generate::<u64>()),
+            flags: 0,
+            state: None,
+        }
+    }
+
+    /// Parse trace context from `traceparent` and `tracestate` headers.
+    ///
+    /// Returns `None` if `traceparent` header is missing or malformed.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let parent = headers.get(&TRACEPARENT)?.to_str().ok()?;
+        let mut ctx = TraceContext::parse(parent)?;
+        ctx.state = headers.get(&TRACESTATE).cloned();
+        Some(ctx)
+    }
+
+    /// Parse `traceparent` header value.
+    ///
+    /// Returns `None` if value is malformed.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let b = value.as_bytes();
+        if b.len() < 55 || b[2] != b'-' || b[35] != b'-' || b[52] != b'-' {
+            return None;
+        }
+
+        let version = hex_u8(&value[0..2])?;
+        match version {
+            // version 255 is invalid
+            0xff => return None,
+            // version 0 has fixed length
+            0 if b.len() != 55 => return None,
+            // future versions could extend header
+            _ if b.len() > 55 && b[55] != b'-' => return None,
+            _ => (),
+        }
+
+        let trace_id = hex_u128(&value[3..35])?;
+        let parent_id = hex_u64(&value[36..52])?;
+        let flags = hex_u8(&value[53..55])?;
+        if trace_id == 0 || parent_id == 0 {
+            return None;
+        }
+
+        Some(TraceContext {
+            trace_id,
+            parent_id,
+            flags,
+            state: None,
+        })
+    }
+
+    /// Trace id
+    pub fn trace_id(&self) -> u128 {
+        self.trace_id
+    }
+
+    /// Parent id, id of the caller span
+    pub fn parent_id(&self) -> u64 {
+        self.parent_id
+    }
+
+    /// Trace flags
+    pub fn flags(&self) -> u8 {
+        self.flags
+    }
+
+    /// Check if caller recorded trace data
+    pub fn is_sampled(&self) -> bool {
+        self.flags & FLAG_SAMPLED != 0
+    }
+
+    /// Vendor specific trace state
+    pub fn state(&self) -> Option<&HeaderValue> {
+        self.state.as_ref()
+    }
+
+    #[must_use]
+    /// Set sampled flag
+    pub fn sampled(mut self, sampled: bool) -> Self {
+        if sampled {
+            self.flags |= FLAG_SAMPLED;
+        } else {
+            self.flags &= !FLAG_SAMPLED;
+        }
+        self
+    }
+
+    #[must_use]
+    /// Create child context with the same trace id and new parent id.
+    pub fn child(&self) -> Self {
+        TraceContext {
+            trace_id: self.trace_id,
+            parent_id: non_zero(|| WyRand::new().generate::<u64>()),
+            flags: self.flags,
+            state: self.state.clone(),
+        }
+    }
+
+    /// `traceparent` header value
+    pub fn traceparent(&self) -> HeaderValue {
+        HeaderValue::try_from(self.to_string()).unwrap()
+    }
+
+    /// Inject `traceparent` and `tracestate` headers
+    pub fn inject(&self, headers: &mut HeaderMap) {
+        headers.insert(TRACEPARENT, self.traceparent());
+        if let Some(ref state) = self.state {
+            headers.insert(TRACESTATE, state.clone());
+        } else {
+            headers.remove(&TRACESTATE);
+        }
+    }
+}
+
+impl Default for TraceContext {
+    fn default() -> Self {
+        TraceContext::new()
+    }
+}
+
+impl fmt::Display for TraceContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "00-{:032x}-{:016x}-{:02x}",
+            self.trace_id, self.parent_id, self.flags
+        )
+    }
+}
+
+fn non_zero<T: Default + PartialEq, F: FnMut() -> T>(mut f: F) -> T {
+    loop {
+        let val = f();
+        if val != T::default() {
+            return val;
+        }
+    }
+}
+
+fn is_lower_hex(s: &str) -> bool {
+    s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+fn hex_u8(s: &str) -> Option<u8> {
+    if is_lower_hex(s) {
+        u8::from_str_radix(s, 16).ok()
+    } else {
+        None
+    }
+}
+
+fn hex_u64(s: &str) -> Option<u64> {
+    if is_lower_hex(s) {
+        u64::from_str_radix(s, 16).ok()
+    } else {
+        None
+    }
+}
+
+fn hex_u128(s: &str) -> Option<u128> {
+    if is_lower_hex(s) {
+        u128::from_str_radix(s, 16).ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARENT: &str = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01";
+
+    #[test]
+    fn test_parse() {
+        let ctx = TraceContext::parse(PARENT).unwrap();
+        assert_eq!(ctx.trace_id(), 0x0af7_6519_16cd_43dd_8448_eb21_1c80_319c);
+        assert_eq!(ctx.parent_id(), 0xb7ad_6b71_6920_3331);
+        assert_eq!(ctx.flags(), 1);
+        assert!(ctx.is_sampled());
+        assert_eq!(ctx.to_string(), PARENT);
+
+        // future version
+        let ctx = TraceContext::parse(
+            "01-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-00-extra",
+        )
+        .unwrap();
+        assert!(!ctx.is_sampled());
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        for val in [
+            "",
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331",
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01-extra",
+            "ff-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+            "00-0AF7651916CD43DD8448EB211C80319C-b7ad6b7169203331-01",
+            "00-00000000000000000000000000000000-b7ad6b7169203331-01",
+            "00-0af7651916cd43dd8448eb211c80319c-0000000000000000-01",
+            "00-0af7651916cd43dd8448eb211c80319c_b7ad6b7169203331-01",
+            "00-0af7651916cd43dd8448eb211c8031zz-b7ad6b7169203331-01",
+        ] {
+            assert!(TraceContext::parse(val).is_none(), "{val}");
+        }
+    }
+
+    #[test]
+    fn test_headers() {
+        let mut headers = HeaderMap::new();
+        assert!(TraceContext::from_headers(&headers).is_none());
+
+        headers.insert(TRACEPARENT, HeaderValue::from_static(PARENT));
+        headers.insert(TRACESTATE, HeaderValue::from_static("congo=t61rcWkgMzE"));
+        let ctx = TraceContext::from_headers(&headers).unwrap();
+        assert_eq!(ctx.state().unwrap(), "congo=t61rcWkgMzE");
+
+        let child = ctx.child();
+        assert_eq!(child.trace_id(), ctx.trace_id());
+        assert_ne!(child.parent_id(), ctx.parent_id());
+
+        let mut headers = HeaderMap::new();
+        child.inject(&mut headers);
+        assert_eq!(TraceContext::from_headers(&headers), Some(child));
+
+        let ctx = TraceContext::new().sampled(true);
+        assert!(ctx.is_sampled());
+        assert_ne!(ctx.trace_id(), 0);
+        assert!(TraceContext::parse(&ctx.to_string()).is_some());
+    }
+}
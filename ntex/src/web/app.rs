@@ -8,14 +8,17 @@ use crate::service::{Identity, Middleware, Service, ServiceCtx, ServiceFactory};
 use crate::service::{IntoServiceFactory, chain_factory, dev::ServiceChainFactory};
 use crate::util::{BoxFuture, Extensions};
 
-use super::app_service::{AppFactory, AppService};
+use super::app_service::{AppFactory, AppService, ChainItem};
 use super::config::ServiceConfig;
+use super::extract::FromRequest;
+use super::handler::Handler;
 use super::request::WebRequest;
 use super::resource::Resource;
 use super::response::WebResponse;
 use super::route::Route;
 use super::service::{AppServiceFactory, ServiceFactoryWrapper, WebServiceFactory};
 use super::stack::WebStack;
+use super::types::Keyed;
 use super::{DefaultError, ErrorRenderer};
 
 type HttpNewService<Err: ErrorRenderer> =
@@ -36,6 +39,8 @@ pub struct App<M, F, Err: ErrorRenderer = DefaultError> {
     state_factories: Vec<FnStateFactory>,
     error_renderer: Err,
     case_insensitive: bool,
+    auto_head: bool,
+    chain: Vec<ChainItem>,
 }
 
 impl Default for App<Identity, Filter<DefaultError>, DefaultError> {
@@ -58,6 +63,8 @@ impl App<Identity, Filter<DefaultError>, DefaultError> {
             extensions: Extensions::new(),
             error_renderer: DefaultError,
             case_insensitive: false,
+            auto_head: false,
+            chain: Vec::new(),
         }
     }
 }
@@ -76,6 +83,8 @@ impl<Err: ErrorRenderer> App<Identity, Filter<Err>, Err> {
             extensions: Extensions::new(),
             error_renderer: err,
             case_insensitive: false,
+            auto_head: false,
+            chain: Vec::new(),
         }
     }
 }
@@ -129,6 +138,32 @@ where
         self
     }
 
+    #[must_use]
+    /// Set application state keyed by tag type `K`.
+    ///
+    /// Keyed state allows to register several values of the same type,
+    /// each value is stored as `Keyed<K, U>` and could be accessed with
+    /// `KeyedState<K, U>` extractor.
+    ///
+    /// ```rust
+    /// use ntex::web::{self, types::KeyedState, App};
+    ///
+    /// struct Primary;
+    /// struct Replica;
+    ///
+    /// async fn index(db: KeyedState<Replica, String>) -> String {
+    ///     db.get_ref().clone()
+    /// }
+    ///
+    /// let app = App::new()
+    ///     .state_keyed::<Primary, _>("primary".to_string())
+    ///     .state_keyed::<Replica, _>("replica".to_string())
+    ///     .service(web::resource("/index.html").to(index));
+    /// ```
+    pub fn state_keyed<K: 'static, U: 'static>(self, state: U) -> Self {
+        self.state(Keyed::<K, U>::new(state))
+    }
+
     #[must_use]
     /// Set application state factory.
     ///
@@ -166,6 +201,58 @@ where
         self
     }
 
+    #[must_use]
+    /// Set per-worker application state factory.
+    ///
+    /// Http server constructs an application instance for each worker thread.
+    /// State factory runs once for each constructed application instance, so
+    /// every worker gets its own non-shared state object. State object does not
+    /// need to be `Send` or `Sync` and could be accessed with `State<T>` extractor.
+    ///
+    /// ```rust
+    /// use std::cell::RefCell;
+    /// use ntex::web::{self, App, HttpResponse};
+    ///
+    /// struct Pool {
+    ///     conns: RefCell<Vec<String>>,
+    /// }
+    ///
+    /// async fn index(pool: web::types::State<Pool>) -> HttpResponse {
+    ///     pool.conns.borrow_mut().push("conn".to_string());
+    ///     HttpResponse::Ok().into()
+    /// }
+    ///
+    /// let app = App::new()
+    ///     .worker_state(async || Ok::<_, ()>(Pool { conns: RefCell::new(Vec::new()) }))
+    ///     .service(web::resource("/index.html").route(web::get().to(index)));
+    /// ```
+    pub fn worker_state<F, D, E>(mut self, state: F) -> Self
+    where
+        F: AsyncFn() -> Result<D, E> + 'static,
+        D: 'static,
+        E: fmt::Debug,
+    {
+        let state = Rc::new(state);
+
+        self.state_factories.push(Box::new(move |mut ext| {
+            let state = state.clone();
+
+            Box::pin(async move {
+                match state().await {
+                    Err(e) => {
+                        log::error!("Cannot construct worker state instance: {e:?}");
+                        Err(())
+                    }
+                    Ok(st) => {
+                        ext.insert(st);
+                        Ok(ext)
+                    }
+                }
+            })
+        }));
+        self
+    }
+
     #[must_use]
     /// Run external configuration as part of the application building
     /// process.
@@ -306,6 +393,32 @@ where
         self
     }
 
+    #[must_use]
+    /// Default handler to be used if no matching resource could be found.
+    ///
+    /// This is a shortcut for `.default_service(web::to(handler))`, handler
+    /// could use any extractors.
+    ///
+    /// ```rust
+    /// use ntex::web::{self, App, HttpRequest, HttpResponse};
+    ///
+    /// fn main() {
+    ///     let app = App::new()
+    ///         .service(web::resource("/index.html").to(|| async { HttpResponse::Ok() }))
+    ///         .default_handler(|req: HttpRequest| async move {
+    ///             HttpResponse::NotFound().body(format!("{} is not found", req.path()))
+    ///         });
+    /// }
+    /// ```
+    pub fn default_handler<F, Args>(self, handler: F) -> Self
+    where
+        F: Handler<Args, Err> + 'static,
+        Args: FromRequest<Err> + 'static,
+        Args::Error: Into<Err::Container>,
+    {
+        self.default_service(Route::new().to(handler))
+    }
+
     #[must_use]
     /// Register an external resource.
     ///
@@ -389,6 +502,9 @@ where
             >,
         U: IntoServiceFactory<S, WebRequest<Err>, SharedCfg>,
     {
+        let mut chain = self.chain;
+        chain.push(ChainItem::Filter(std::any::type_name::<S>()));
+
         App {
             filter: self
                 .filter
@@ -401,6 +517,8 @@ where
             extensions: self.extensions,
             error_renderer: self.error_renderer,
             case_insensitive: self.case_insensitive,
+            auto_head: self.auto_head,
+            chain,
         }
     }
 
@@ -417,6 +535,8 @@ where
     ///
     /// As you register middleware in the App builder, imagine wrapping
     /// layers around an inner App.
+    /// Composed order of middlewares and filters is logged with `debug`
+    /// level on application start.
     ///
     /// ```rust
     /// use ntex::http::header::{CONTENT_TYPE, HeaderValue};
@@ -433,6 +553,9 @@ where
     /// }
     /// ```
     pub fn middleware<U>(self, mw: U) -> App<WebStack<M, U, Err>, T, Err> {
+        let mut chain = self.chain;
+        chain.push(ChainItem::Middleware(std::any::type_name::<U>()));
+
         App {
             middleware: WebStack::new(self.middleware, mw),
             filter: self.filter,
@@ -443,6 +566,8 @@ where
             extensions: self.extensions,
             error_renderer: self.error_renderer,
             case_insensitive: self.case_insensitive,
+            auto_head: self.auto_head,
+            chain,
         }
     }
 
@@ -460,6 +585,32 @@ where
         self.case_insensitive = true;
         self
     }
+
+    #[must_use]
+    /// Handle `HEAD` requests with `GET` routes.
+    ///
+    /// If `HEAD` request does not match any route of the resource, routes
+    /// with `GET` method (`web::get()`, `Route::method()`) are matched and
+    /// response body is dropped. Request method is not changed, handlers
+    /// and middlewares see `HEAD` method, method guards do not match. Route is
+    /// selected once, resource filters and middlewares are called once. Response headers and
+    /// body size are preserved, so `Content-Length` header is the same
+    /// as for `GET` request. Explicitly registered `HEAD` routes take
+    /// precedence.
+    ///
+    /// ```rust
+    /// use ntex::web::{self, App};
+    ///
+    /// fn main() {
+    ///     let app = App::new()
+    ///         .auto_head()
+    ///         .route("/index.html", web::get().to(|| async { "hello" }));
+    /// }
+    /// ```
+    pub fn auto_head(mut self) -> Self {
+        self.auto_head = true;
+        self
+    }
 }
 
 impl<M, F, Err> App<M, F, Err>
@@ -530,6 +681,8 @@ where
             default: self.default,
             extensions: RefCell::new(Some(self.extensions)),
             case_insensitive: self.case_insensitive,
+            auto_head: self.auto_head,
+            chain: self.chain,
         }
     }
 }
@@ -574,7 +727,29 @@ mod tests {
     use crate::http::{Method, StatusCode, header, header::HeaderValue};
     use crate::web::test::{TestRequest, call_service, init_service, read_body};
     use crate::web::{self, HttpRequest, HttpResponse, middleware::DefaultHeaders};
-    use crate::{service::fn_service, util::Ready};
+    use crate::{service::fn_service, util::Bytes, util::Ready};
+
+    #[crate::rt_test]
+    async fn test_default_handler() {
+        let srv = App::new()
+            .service(web::resource("/test").to(|| async { HttpResponse::Ok() }))
+            .default_handler(|req: HttpRequest| async move {
+                HttpResponse::NotFound().body(format!("{} not found", req.path()))
+            })
+            .finish()
+            .pipeline(SharedCfg::default())
+            .await
+            .unwrap();
+
+        let req = TestRequest::with_uri("/test").to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = TestRequest::with_uri("/blah").to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(read_body(resp).await, &b"/blah not found"[..]);
+    }
 
     #[crate::rt_test]
     async fn test_default_resource() {
@@ -653,6 +828,54 @@ mod tests {
         assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
     }
 
+    #[crate::rt_test]
+    async fn test_worker_state() {
+        use std::sync::{Arc, atomic::AtomicUsize, atomic::Ordering};
+
+        let counter = Arc::new(AtomicUsize::new(0));
+        let app = || {
+            let counter = counter.clone();
+            App::new()
+                .worker_state(async move || {
+                    Ok::<_, ()>(Cell::new(counter.fetch_add(1, Ordering::SeqCst)))
+                })
+                .service(web::resource("/").to(
+                    |st: web::types::State<Cell<usize>>| async move {
+                        st.set(st.get() + 10);
+                        HttpResponse::Ok().body(st.get().to_string())
+                    },
+                ))
+        };
+
+        let srv1 = init_service(app()).await;
+        let srv2 = init_service(app()).await;
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+
+        let resp = srv1
+            .call(TestRequest::default().to_request())
+            .await
+            .unwrap();
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"10"));
+        let resp = srv1
+            .call(TestRequest::default().to_request())
+            .await
+            .unwrap();
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"20"));
+        let resp = srv2
+            .call(TestRequest::default().to_request())
+            .await
+            .unwrap();
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"11"));
+
+        let res = App::new()
+            .worker_state(async || Err::<usize, _>("error"))
+            .service(web::resource("/").to(|| async { HttpResponse::Ok() }))
+            .finish()
+            .pipeline(SharedCfg::default())
+            .await;
+        assert!(res.is_err());
+    }
+
     #[crate::rt_test]
     async fn test_extension() {
         let srv = init_service(
@@ -749,11 +972,140 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::OK);
     }
 
+    #[crate::rt_test]
+    async fn test_auto_head() {
+        use crate::http::body::{BodySize, MessageBody};
+
+        let srv =
+            init_service(App::new().route("/test", web::get().to(|| async { "hello" })))
+                .await;
+        let req = TestRequest::with_uri("/test")
+            .method(Method::HEAD)
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        let srv = init_service(
+            App::new()
+                .auto_head()
+                .route("/test", web::get().to(|| async { "hello" }))
+                .service(
+                    web::resource("/head")
+                        .route(web::get().to(|| async { "hello" }))
+                        .route(web::head().to(|| async { HttpResponse::NoContent() })),
+                ),
+        )
+        .await;
+        let req = TestRequest::with_uri("/test")
+            .method(Method::HEAD)
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(*resp.request().method(), Method::HEAD);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            HeaderValue::from_static("text/plain; charset=utf-8")
+        );
+        assert_eq!(resp.response().body().size(), BodySize::Sized(5));
+        assert!(read_body(resp).await.is_empty());
+
+        let req = TestRequest::with_uri("/head")
+            .method(Method::HEAD)
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        let req = TestRequest::with_uri("/test")
+            .method(Method::POST)
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        // resource filter is called once
+        let counter = Rc::new(Cell::new(0));
+        let cnt = counter.clone();
+        let srv = init_service(
+            App::new().auto_head().service(
+                web::resource("/test")
+                    .filter(fn_service(move |req: WebRequest<_>| {
+                        cnt.set(cnt.get() + 1);
+                        Ready::Ok(req)
+                    }))
+                    .route(web::get().to(|| async { "hello" })),
+            ),
+        )
+        .await;
+        let req = TestRequest::with_uri("/test")
+            .method(Method::HEAD)
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(*resp.request().method(), Method::HEAD);
+        assert!(read_body(resp).await.is_empty());
+        assert_eq!(counter.get(), 1);
+
+        // outer middleware sees `HEAD` method, handler keeps request
+        let methods = Rc::new(RefCell::new(Vec::new()));
+        let requests = Rc::new(RefCell::new(Vec::new()));
+        let reqs = requests.clone();
+        let srv = init_service(
+            App::new()
+                .auto_head()
+                .middleware(RecordMethod(methods.clone()))
+                .route(
+                    "/test",
+                    web::get().to(move |req: HttpRequest| {
+                        reqs.borrow_mut().push(req);
+                        async { "hello" }
+                    }),
+                ),
+        )
+        .await;
+        let req = TestRequest::with_uri("/test")
+            .method(Method::HEAD)
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(read_body(resp).await.is_empty());
+        assert_eq!(*methods.borrow(), [Method::HEAD, Method::HEAD]);
+        assert_eq!(*requests.borrow()[0].method(), Method::HEAD);
+    }
+
+    /// Records request method before and after inner service call
+    struct RecordMethod(Rc<RefCell<Vec<Method>>>);
+
+    impl<S> Middleware<S, SharedCfg> for RecordMethod {
+        type Service = RecordMethodService<S>;
+
+        fn create(&self, service: S, _: SharedCfg) -> Self::Service {
+            RecordMethodService(service, self.0.clone())
+        }
+    }
+
+    struct RecordMethodService<S>(S, Rc<RefCell<Vec<Method>>>);
+
+    impl<S, Err> Service<WebRequest<Err>> for RecordMethodService<S>
+    where
+        S: Service<WebRequest<Err>, Response = WebResponse>,
+    {
+        type Response = WebResponse;
+        type Error = S::Error;
+
+        async fn call(
+            &self,
+            req: WebRequest<Err>,
+            ctx: ServiceCtx<'_, Self>,
+        ) -> Result<WebResponse, S::Error> {
+            self.1.borrow_mut().push(req.head().method.clone());
+            let res = ctx.call(&self.0, req).await?;
+            self.1.borrow_mut().push(res.request().method().clone());
+            Ok(res)
+        }
+    }
+
     #[cfg(feature = "url")]
     #[crate::rt_test]
     async fn test_external_resource() {
-        use crate::util::Bytes;
-
         let srv = init_service(
             App::new()
                 .external_resource("youtube", "https://youtube.com/watch/{video_id}")
@@ -1,6 +1,6 @@
 use std::{cell::RefCell, marker, rc::Rc, task::Context};
 
-use crate::http::{Request, Response};
+use crate::http::{Message, Payload, Request, Response, Uri};
 use crate::router::{Path, ResourceDef, Router};
 use crate::service::boxed::{self, BoxService, BoxServiceFactory};
 use crate::service::cfg::SharedCfg;
@@ -9,9 +9,10 @@ use crate::service::{Middleware, Service, ServiceCtx, ServiceFactory, fn_service
 use crate::util::{BoxFuture, Extensions, join};
 
 use super::error::ErrorRenderer;
-use super::guard::Guard;
+use super::guard::{self, Guard};
 use super::httprequest::HttpRequest;
 use super::request::WebRequest;
+use super::resource::AutoHead;
 use super::response::WebResponse;
 use super::rmap::ResourceMap;
 use super::service::{AppServiceFactory, AppState, WebServiceConfig};
@@ -46,6 +47,35 @@ where
     pub(super) default: Option<Rc<HttpNewService<Err>>>,
     pub(super) external: RefCell<Vec<ResourceDef>>,
     pub(super) case_insensitive: bool,
+    pub(super) auto_head: bool,
+    pub(super) chain: Vec<ChainItem>,
+}
+
+/// Registered app middleware or filter
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum ChainItem {
+    Middleware(&'static str),
+    Filter(&'static str),
+}
+
+/// Describe request processing order
+///
+/// Last registered middleware is the outermost one, filters are called
+/// after all middlewares in order of registration.
+fn describe_chain(chain: &[ChainItem]) -> String {
+    let middlewares = chain.iter().rev().filter_map(|item| match item {
+        ChainItem::Middleware(name) => Some(format!("middleware({name})")),
+        ChainItem::Filter(_) => None,
+    });
+    let filters = chain.iter().filter_map(|item| match item {
+        ChainItem::Filter(name) => Some(format!("filter({name})")),
+        ChainItem::Middleware(_) => None,
+    });
+    middlewares
+        .chain(filters)
+        .chain(Some("router".to_string()))
+        .collect::<Vec<_>>()
+        .join(" -> ")
 }
 
 impl<T, F, Err> ServiceFactory<Request, SharedCfg> for AppFactory<T, F, Err>
@@ -68,6 +98,10 @@ where
 
     async fn create(&self, cfg: SharedCfg) -> Result<Self::Service, Self::InitError> {
         let services = std::mem::take(&mut *self.services.borrow_mut());
+        log::debug!(
+            "App request processing order: {}",
+            describe_chain(&self.chain)
+        );
 
         // update resource default service
         let default = self.default.clone().unwrap_or_else(|| {
@@ -95,6 +129,9 @@ where
                 .await
                 .map_err(|()| log::error!("Cannot initialize state factory"))?;
         }
+        if self.auto_head {
+            extensions.insert(AutoHead);
+        }
         let state = AppState::new(extensions, None, cfg.get());
 
         // App config
@@ -108,8 +145,8 @@ where
 
         // resource map
         let mut rmap = ResourceMap::new(ResourceDef::new(""));
-        for mut rdef in external {
-            rmap.add(&mut rdef, None);
+        for rdef in external {
+            rmap.add_external(rdef);
         }
 
         // complete pipeline creation
@@ -125,17 +162,41 @@ where
         let rmap = Rc::new(rmap);
         rmap.finish(&rmap);
 
+        // pre-allocate request objects
+        let config = state.config();
+        let capacity = config
+            .pool_capacity
+            .min(config.pool_size)
+            .saturating_sub(config.pooled_requests());
+        let requests: Vec<_> = (0..capacity)
+            .map(|_| {
+                HttpRequest::new(
+                    Path::new(Uri::default()),
+                    Message::new(),
+                    Payload::None,
+                    rmap.clone(),
+                    state.clone(),
+                )
+            })
+            .collect();
+        drop(requests);
+
         // create http services
+        let mut async_guards = false;
         for (path, factory, guards) in &mut services.iter() {
             let service = factory
                 .create(cfg.clone())
                 .await
                 .map_err(|()| log::error!("Cannot construct app service"))?;
-            router.rdef(path.clone(), service).2 = guards.borrow_mut().take();
+            let pattern = Rc::from(path.pattern());
+            let guards = guards.borrow_mut().take();
+            async_guards |= guards.as_deref().is_some_and(guard::has_async_guards);
+            router.rdef(path.clone(), (service, pattern)).2 = guards;
         }
 
         let routing = AppRouting {
             router: router.finish(),
+            async_guards,
             default: Some(
                 default
                     .create(cfg.clone())
@@ -210,6 +271,14 @@ where
                 self.state.clone(),
             )
         };
+
+        #[cfg(feature = "tracing")]
+        {
+            let span = super::span::request_span(req.head());
+            super::span::instrument(span, ctx.call(&self.service, WebRequest::new(req)))
+                .await
+        }
+        #[cfg(not(feature = "tracing"))]
         ctx.call(&self.service, WebRequest::new(req)).await
     }
 }
@@ -225,7 +294,8 @@ where
 }
 
 struct AppRouting<Err: ErrorRenderer> {
-    router: Router<HttpService<Err>, Guards>,
+    router: Router<(HttpService<Err>, Rc<str>), Guards>,
+    async_guards: bool,
     default: Option<HttpService<Err>>,
 }
 
@@ -238,22 +308,18 @@ impl<Err: ErrorRenderer> Service<WebRequest<Err>> for AppRouting<Err> {
         mut req: WebRequest<Err>,
         ctx: ServiceCtx<'_, Self>,
     ) -> Result<WebResponse, Err::Container> {
-        let res = self.router.recognize_checked(&mut req, |req, guards| {
-            if let Some(guards) = guards {
-                for f in guards {
-                    if !f.check(req.head()) {
-                        return false;
-                    }
-                }
-            }
-            true
-        });
+        let res = guard::recognize(&self.router, &mut req, self.async_guards).await;
 
-        if let Some((srv, _info)) = res {
+        if let Some(((srv, pattern), _info)) = res {
+            #[cfg(feature = "tracing")]
+            super::span::record_route(pattern);
+            req.set_match_pattern(Some(pattern.clone()));
             ctx.call(srv, req).await
         } else if let Some(ref default) = self.default {
+            req.set_match_pattern(None);
             ctx.call(default, req).await
         } else {
+            req.set_match_pattern(None);
             let req = req.into_parts().0;
             Ok(WebResponse::new(Response::NotFound().finish(), req))
         }
@@ -305,6 +371,7 @@ mod tests {
     use std::sync::Arc;
     use std::sync::atomic::{AtomicBool, Ordering};
 
+    use super::{ChainItem, describe_chain};
     use crate::web::test::{TestRequest, init_service};
     use crate::web::{self, App, HttpResponse};
 
@@ -332,4 +399,18 @@ mod tests {
         }
         assert!(data.load(Ordering::Relaxed));
     }
+
+    #[test]
+    fn test_describe_chain() {
+        assert_eq!(describe_chain(&[]), "router");
+        assert_eq!(
+            describe_chain(&[
+                ChainItem::Middleware("A"),
+                ChainItem::Filter("F1"),
+                ChainItem::Middleware("B"),
+                ChainItem::Filter("F2"),
+            ]),
+            "middleware(B) -> middleware(A) -> filter(F1) -> filter(F2) -> router"
+        );
+    }
 }
@@ -16,6 +16,7 @@ pub struct WebAppConfig {
     addr: SocketAddr,
     config: CfgContext,
     pub(super) pool_size: usize,
+    pub(super) pool_capacity: usize,
 }
 
 impl Default for WebAppConfig {
@@ -57,6 +58,7 @@ impl WebAppConfig {
             addr,
             name: name.into(),
             pool_size: 128,
+            pool_capacity: 0,
             config: CfgContext::default(),
         }
     }
@@ -119,6 +121,18 @@ impl WebAppConfig {
         self
     }
 
+    #[must_use]
+    /// Set number of pre-allocated `HttpRequest` objects.
+    ///
+    /// Requests are allocated during application startup, so first burst of
+    /// requests does not allocate. Capacity is limited by pool size.
+    ///
+    /// By default requests are not pre-allocated.
+    pub fn set_pool_capacity(mut self, capacity: usize) -> Self {
+        self.pool_capacity = capacity;
+        self
+    }
+
     /// Get message from the pool.
     pub(crate) fn get_request(&self) -> Option<HttpRequest> {
         CACHE.with(|cache| {
@@ -126,6 +140,11 @@ impl WebAppConfig {
         })
     }
 
+    /// Number of requests in the pool.
+    pub(crate) fn pooled_requests(&self) -> usize {
+        CACHE.with(|cache| cache.with(self.config.id(), |cache| cache.len()))
+    }
+
     /// Get message from the pool.
     pub(crate) fn clear_requests(&self) {
         CACHE.with(|cache| cache.with(self.config.id(), Vec::clear));
@@ -140,6 +159,7 @@ pub(crate) fn put_request(id: usize, pool_size: usize, req: &mut Rc<HttpRequestI
                 && let Some(inner) = Rc::get_mut(req)
             {
                 inner.head.remove_io();
+                inner.match_pattern = None;
                 inner.head.extensions.borrow_mut().clear();
                 cache.push(req.clone());
             }
@@ -258,6 +278,7 @@ impl LocalCache {
 mod tests {
     use super::*;
     use crate::http::{Method, StatusCode};
+    use crate::service::cfg::SharedCfg;
     use crate::util::Bytes;
     use crate::web::test::{TestRequest, call_service, init_service, read_body};
     use crate::web::{self, App, DefaultError, HttpRequest, HttpResponse};
@@ -273,6 +294,29 @@ mod tests {
         assert_eq!(cfg.pool_size, 256);
     }
 
+    #[crate::rt_test]
+    async fn test_pool_capacity() {
+        let cfg: SharedCfg = SharedCfg::new("WEB")
+            .add(WebAppConfig::new().set_pool_size(8).set_pool_capacity(16))
+            .into();
+        let srv = App::new()
+            .service(web::resource("/").to(|| async { HttpResponse::Ok() }))
+            .finish()
+            .pipeline(cfg.clone())
+            .await
+            .unwrap();
+        assert_eq!(cfg.get::<WebAppConfig>().pooled_requests(), 8);
+
+        let req = TestRequest::default().to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        drop(resp);
+        assert_eq!(cfg.get::<WebAppConfig>().pooled_requests(), 8);
+
+        drop(srv);
+        assert_eq!(cfg.get::<WebAppConfig>().pooled_requests(), 0);
+    }
+
     #[crate::rt_test]
     async fn test_configure_state() {
         let cfg = |cfg: &mut ServiceConfig<_>| {
@@ -82,6 +82,44 @@ pub enum StateExtractorError {
     NotConfigured,
 }
 
+/// Error returned by `RequireHeader` extractor if header is missing
+#[derive(Debug, Copy, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Required header {name:?} is missing")]
+pub struct MissingHeaderError {
+    name: &'static str,
+    status: StatusCode,
+}
+
+impl MissingHeaderError {
+    pub(crate) fn new(name: &'static str, status: StatusCode) -> Self {
+        MissingHeaderError { name, status }
+    }
+
+    /// Name of the missing header
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Response status code
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+}
+
+/// Errors which can occur when attempting to work with `ReqData` extractor
+#[derive(Debug, Copy, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ReqDataExtractorError {
+    #[error("Request data is not found in request extensions")]
+    NotFound,
+}
+
+/// Error returned by `ReqLocal` extractor if request-local value is not set
+#[derive(Debug, Copy, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "Request-local value `{0}` is not set, middleware must insert it into request extensions"
+)]
+pub struct ReqLocalError(pub &'static str);
+
 /// Errors which can occur when attempting to generate resource uri.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, thiserror::Error)]
 pub enum UrlGenerationError {
@@ -132,6 +170,7 @@ pub enum UrlencodedError {
 
 /// A set of errors that can occur during parsing json payloads
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum JsonPayloadError {
     /// Payload size is bigger than allowed. (default: 32kB)
     #[error("Json payload size is bigger than allowed")]
@@ -146,6 +185,9 @@ pub enum JsonPayloadError {
         #[source]
         serde_json::error::Error,
     ),
+    /// Custom deserializer error
+    #[error("Json deserialize error: {0}")]
+    Custom(#[source] Box<dyn std::error::Error + Send + Sync>),
     /// Payload error
     #[error("Error that occur during reading payload: {0}")]
     Payload(
@@ -155,6 +197,50 @@ pub enum JsonPayloadError {
     ),
 }
 
+#[cfg(feature = "msgpack")]
+/// A set of errors that can occur during parsing messagepack payloads
+#[derive(Debug, thiserror::Error)]
+pub enum MsgPackPayloadError {
+    /// Payload size is bigger than allowed. (default: 32kB)
+    #[error("MessagePack payload size is bigger than allowed")]
+    Overflow,
+    /// Content type error
+    #[error("Content type error")]
+    ContentType,
+    /// Deserialize error
+    #[error("MessagePack deserialize error: {0}")]
+    Deserialize(
+        #[from]
+        #[source]
+        rmp_serde::decode::Error,
+    ),
+    /// Payload error
+    #[error("Error that occur during reading payload: {0}")]
+    Payload(
+        #[from]
+        #[source]
+        error::PayloadError,
+    ),
+}
+
+/// A set of errors that can occur during extracting `AnyBody` payloads
+#[derive(Debug, thiserror::Error)]
+pub enum AnyBodyError {
+    /// Content type is not supported
+    #[error("Unsupported content type")]
+    UnsupportedMediaType,
+    /// Json payload error
+    #[error(transparent)]
+    Json(#[from] JsonPayloadError),
+    /// Urlencoded payload error
+    #[error(transparent)]
+    Form(#[from] UrlencodedError),
+    #[cfg(feature = "msgpack")]
+    /// Messagepack payload error
+    #[error(transparent)]
+    MsgPack(#[from] MsgPackPayloadError),
+}
+
 /// A set of errors that can occur during parsing request paths
 #[derive(Debug, thiserror::Error)]
 pub enum PathError {
@@ -204,6 +290,17 @@ pub enum PayloadError {
     Decoding,
 }
 
+/// Errors that can occur during connection upgrade
+#[derive(Debug, Copy, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum UpgradeError {
+    /// Request is not an upgrade request or connection is already taken
+    #[error("Connection upgrade is not available")]
+    NoUpgrade,
+    /// Cannot send upgrade response
+    #[error("Cannot send upgrade response")]
+    Send,
+}
+
 /// Helper type that can wrap any error and generate custom response.
 ///
 /// In following example any `io::Error` will be converted into "BAD REQUEST"
@@ -818,6 +915,20 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
     }
 
+    #[cfg(feature = "bad-request-errors")]
+    #[test]
+    fn test_bad_request_errors() {
+        let req = TestRequest::default().to_http_request();
+
+        let err = "a".parse::<i64>().unwrap_err();
+        let resp = WebResponseError::<DefaultError>::error_response(&err, &req);
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let err: crate::web::Error = "a".parse::<bool>().unwrap_err().into();
+        let resp = crate::http::ResponseError::error_response(&err);
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
     #[test]
     fn test_io_error() {
         assert_eq!(
@@ -1,7 +1,6 @@
 //! Web error
-use std::{fmt, io, io::Write, str::Utf8Error};
+use std::{fmt, io, io::Write};
 
-use serde::de::value::Error as DeError;
 use serde_json::error::Error as JsonError;
 use serde_urlencoded::ser::Error as FormError;
 
@@ -97,12 +96,29 @@ where
 /// `InternalServerError` for `StateExtractorError`
 impl WebResponseError<DefaultError> for error::StateExtractorError {}
 
+/// `InternalServerError` for `ReqDataExtractorError`
+impl WebResponseError<DefaultError> for error::ReqDataExtractorError {}
+
+/// `InternalServerError` for `ReqLocalError`
+impl WebResponseError<DefaultError> for error::ReqLocalError {}
+
+/// Response renderer for `MissingHeaderError`
+impl WebResponseError<DefaultError> for error::MissingHeaderError {
+    fn status_code(&self) -> StatusCode {
+        self.status()
+    }
+}
+
 /// `InternalServerError` for `JsonError`
 impl WebResponseError<DefaultError> for JsonError {}
 
 /// `InternalServerError` for `FormError`
 impl WebResponseError<DefaultError> for FormError {}
 
+#[cfg(feature = "msgpack")]
+/// `InternalServerError` for `rmp_serde::encode::Error`
+impl WebResponseError<DefaultError> for rmp_serde::encode::Error {}
+
 #[cfg(feature = "openssl")]
 /// `InternalServerError` for `openssl::ssl::Error`
 impl WebResponseError<DefaultError> for tls_openssl::ssl::Error {}
@@ -114,13 +130,6 @@ impl<T: fmt::Debug + 'static> WebResponseError<DefaultError>
 {
 }
 
-/// Return `BAD_REQUEST` for `de::value::Error`
-impl WebResponseError<DefaultError> for DeError {
-    fn status_code(&self) -> StatusCode {
-        StatusCode::BAD_REQUEST
-    }
-}
-
 /// `InternalServerError` for `Canceled`
 impl WebResponseError<DefaultError> for crate::http::error::Canceled {}
 
@@ -130,13 +139,31 @@ impl<E: fmt::Debug + 'static> WebResponseError<DefaultError>
 {
 }
 
-/// Return `BAD_REQUEST` for `Utf8Error`
-impl WebResponseError<DefaultError> for Utf8Error {
-    fn status_code(&self) -> StatusCode {
-        StatusCode::BAD_REQUEST
-    }
+macro_rules! bad_request {
+    ($($ty:ty),*) => {
+        $(
+            #[doc = concat!("Return `BAD_REQUEST` for `", stringify!($ty), "`")]
+            impl WebResponseError<DefaultError> for $ty {
+                fn status_code(&self) -> StatusCode {
+                    StatusCode::BAD_REQUEST
+                }
+            }
+        )*
+    };
 }
 
+bad_request!(serde::de::value::Error, std::str::Utf8Error);
+
+#[cfg(feature = "bad-request-errors")]
+bad_request!(
+    std::num::ParseIntError,
+    std::num::ParseFloatError,
+    std::str::ParseBoolError,
+    std::char::ParseCharError,
+    std::string::FromUtf8Error,
+    std::net::AddrParseError
+);
+
 /// Return `InternalServerError` for `HttpError`,
 /// Response generation can return `HttpError`, so it is internal error
 impl WebResponseError<DefaultError> for crate::http::error::HttpError {}
@@ -176,6 +203,36 @@ impl WebResponseError<DefaultError> for error::JsonPayloadError {
     }
 }
 
+#[cfg(feature = "msgpack")]
+/// Return `BadRequest` for `MsgPackPayloadError`
+impl WebResponseError<DefaultError> for error::MsgPackPayloadError {
+    fn status_code(&self) -> StatusCode {
+        match *self {
+            error::MsgPackPayloadError::Overflow => StatusCode::PAYLOAD_TOO_LARGE,
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+/// Response renderer for `AnyBodyError`
+impl WebResponseError<DefaultError> for error::AnyBodyError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            error::AnyBodyError::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            error::AnyBodyError::Json(err) => {
+                WebResponseError::<DefaultError>::status_code(err)
+            }
+            error::AnyBodyError::Form(err) => {
+                WebResponseError::<DefaultError>::status_code(err)
+            }
+            #[cfg(feature = "msgpack")]
+            error::AnyBodyError::MsgPack(err) => {
+                WebResponseError::<DefaultError>::status_code(err)
+            }
+        }
+    }
+}
+
 /// Error renderer for `PathError`
 impl WebResponseError<DefaultError> for error::PathError {
     fn status_code(&self) -> StatusCode {
@@ -240,6 +297,16 @@ impl WebResponseError<DefaultError> for client::error::ClientError {
     }
 }
 
+/// Response renderer for `UpgradeError`
+impl WebResponseError<DefaultError> for error::UpgradeError {
+    fn status_code(&self) -> StatusCode {
+        match *self {
+            error::UpgradeError::NoUpgrade => StatusCode::BAD_REQUEST,
+            error::UpgradeError::Send => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
 #[cfg(feature = "ws")]
 /// Error renderer for `ws::HandshakeError`
 impl WebResponseError<DefaultError> for HandshakeError {
@@ -0,0 +1,349 @@
+use std::{io, path::Path, path::PathBuf, rc::Rc};
+
+use percent_encoding::percent_decode_str;
+
+use crate::http::{Method, Response, header};
+use crate::router::ResourceDef;
+use crate::service::{Service, ServiceCtx, ServiceFactory, cfg::SharedCfg};
+use crate::web::error::ErrorRenderer;
+use crate::web::service::{WebServiceConfig, WebServiceFactory};
+use crate::web::{WebRequest, WebResponse};
+
+use super::named::NamedFile;
+
+/// Static files service factory
+///
+/// `Files` service maps url prefix to a directory of the filesystem. Files
+/// are read on a thread pool and streamed to the peer chunk by chunk.
+/// Conditional requests (`ETag`, `Last-Modified`) and single byte ranges
+/// are supported.
+///
+/// Request path segments that start with `.` are rejected, so hidden files
+/// are not served and request path could not leave the directory.
+/// Symbolic links that point outside of the directory are not followed.
+/// Directory listing is not supported, directory is served only if index
+/// file is configured. Responds with *404 Not Found* if file could not be
+/// served and with *405 Method Not Allowed* for methods other than `GET`
+/// and `HEAD`.
+///
+/// ```rust
+/// use ntex::web::{self, App, fs::Files};
+///
+/// fn main() {
+///     let app = App::new()
+///         .service(Files::new("/static", "./public").index_file("index.html"));
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Files {
+    path: String,
+    directory: PathBuf,
+    index: Option<String>,
+}
+
+impl Files {
+    /// Create new `Files` service for the directory
+    ///
+    /// `mount_path` is a url prefix, `dir` is a path to the directory.
+    pub fn new<T: Into<PathBuf>>(mount_path: &str, dir: T) -> Self {
+        Files {
+            path: mount_path.trim_end_matches('/').to_string(),
+            directory: dir.into(),
+            index: None,
+        }
+    }
+
+    #[must_use]
+    /// Set index file name
+    ///
+    /// Index file is served for requests to a directory.
+    pub fn index_file<T: Into<String>>(mut self, index: T) -> Self {
+        self.index = Some(index.into());
+        self
+    }
+}
+
+impl<Err: ErrorRenderer> WebServiceFactory<Err> for Files {
+    fn register(self, config: &mut WebServiceConfig<Err>) {
+        let rdef = if config.is_root() || !self.path.is_empty() {
+            ResourceDef::root_prefix(self.path.as_str())
+        } else {
+            ResourceDef::prefix(self.path.as_str())
+        };
+        config.register_service(rdef, None, self, None);
+    }
+}
+
+impl<Err: ErrorRenderer> ServiceFactory<WebRequest<Err>, SharedCfg> for Files {
+    type Response = WebResponse;
+    type Error = Err::Container;
+    type InitError = ();
+    type Service = FilesService;
+
+    async fn create(&self, _: SharedCfg) -> Result<FilesService, ()> {
+        Ok(FilesService(Rc::new(self.clone())))
+    }
+}
+
+/// Static files service
+#[derive(Clone, Debug)]
+pub struct FilesService(Rc<Files>);
+
+impl<Err: ErrorRenderer> Service<WebRequest<Err>> for FilesService {
+    type Response = WebResponse;
+    type Error = Err::Container;
+
+    async fn call(
+        &self,
+        req: WebRequest<Err>,
+        _: ServiceCtx<'_, Self>,
+    ) -> Result<WebResponse, Err::Container> {
+        if !matches!(*req.method(), Method::GET | Method::HEAD) {
+            return Ok(req.into_response(
+                Response::MethodNotAllowed()
+                    .header(header::ALLOW, "GET, HEAD")
+                    .finish(),
+            ));
+        }
+
+        let Some(path) = relative_path(req.match_info().unprocessed()) else {
+            log::trace!("Invalid static file path: {:?}", req.path());
+            return Ok(req.into_response(Response::NotFound().finish()));
+        };
+
+        let files = self.0.clone();
+        let result = crate::rt::spawn_blocking(move || {
+            open(&files.directory, &path, files.index.as_deref())
+        })
+        .await;
+
+        match result {
+            Ok(Ok(file)) => {
+                let res = file.into_response(req.head());
+                Ok(req.into_response(res))
+            }
+            Ok(Err(err)) => {
+                log::trace!("Cannot open static file {:?}: {err}", req.path());
+                Ok(req.into_response(Response::NotFound().finish()))
+            }
+            Err(err) => {
+                log::error!("Cannot open static file {:?}: {err}", req.path());
+                Ok(req.into_response(Response::InternalServerError().finish()))
+            }
+        }
+    }
+}
+
+/// Convert unprocessed part of request path to relative file path
+///
+/// Returns `None` if path contains hidden or parent directory segments.
+fn relative_path(path: &str) -> Option<PathBuf> {
+    if !(path.is_empty() || path.starts_with('/')) {
+        return None;
+    }
+    let path = percent_decode_str(path).decode_utf8().ok()?;
+
+    let mut buf = PathBuf::new();
+    for segment in path.split('/') {
+        if segment.is_empty() {
+            continue;
+        }
+        if segment.starts_with('.') || segment.contains(['\\', ':', '\0']) {
+            return None;
+        }
+        buf.push(segment);
+    }
+    Some(buf)
+}
+
+/// Open file in the directory
+fn open(dir: &Path, path: &Path, index: Option<&str>) -> io::Result<NamedFile> {
+    let dir = dir.canonicalize()?;
+    let mut path = dir.join(path).canonicalize()?;
+    if !path.starts_with(&dir) {
+        return Err(io::ErrorKind::NotFound.into());
+    }
+
+    if path.is_dir() {
+        let Some(index) = index else {
+            return Err(io::ErrorKind::NotFound.into());
+        };
+        // index file could be a symlink
+        path = path.join(index).canonicalize()?;
+        if !path.starts_with(&dir) {
+            return Err(io::ErrorKind::NotFound.into());
+        }
+    }
+    NamedFile::open(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::http::StatusCode;
+    use crate::util::Bytes;
+    use crate::web::test::{TestRequest, call_service, init_service, read_body};
+    use crate::web::{self, App, fs::TestDir};
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(relative_path(""), Some(PathBuf::new()));
+        assert_eq!(relative_path("/a/./b"), None);
+        assert_eq!(
+            relative_path("/a//b/c%20d.txt"),
+            Some(PathBuf::from("a").join("b").join("c d.txt"))
+        );
+        assert_eq!(relative_path("/../a"), None);
+        assert_eq!(relative_path("/%2e%2e/a"), None);
+        assert_eq!(relative_path("/a/%2E%2E%2Fb"), None);
+        assert_eq!(relative_path("/a\\..\\b"), None);
+        assert_eq!(relative_path("/a%00"), None);
+        assert_eq!(relative_path("a"), None);
+    }
+
+    #[crate::rt_test]
+    async fn test_files() {
+        let dir = TestDir::new("files");
+        dir.write("public/index.html", "<p>index</p>");
+        dir.write("public/docs/data.txt", "0123456789");
+        dir.write("public/.secret", "secret");
+        dir.write("private.txt", "private");
+        let srv = init_service(
+            App::new()
+                .service(
+                    Files::new("/static", dir.0.join("public")).index_file("index.html"),
+                )
+                .service(web::resource("/").to(|| async { "root" })),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/static/docs/data.txt").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(resp.headers().get(header::ACCEPT_RANGES).unwrap(), "bytes");
+        let etag = resp.headers().get(header::ETAG).unwrap().clone();
+        let modified = resp.headers().get(header::LAST_MODIFIED).unwrap().clone();
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"0123456789"));
+
+        // directory index
+        let req = TestRequest::with_uri("/static/").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"<p>index</p>"));
+
+        // conditional requests
+        let req = TestRequest::with_uri("/static/docs/data.txt")
+            .header(header::IF_NONE_MATCH, etag.clone())
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+
+        let req = TestRequest::with_uri("/static/docs/data.txt")
+            .header(header::IF_MODIFIED_SINCE, modified)
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+
+        let req = TestRequest::with_uri("/static/docs/data.txt")
+            .header(header::IF_MATCH, "\"other\"")
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+
+        // ranges
+        let req = TestRequest::with_uri("/static/docs/data.txt")
+            .header(header::RANGE, "bytes=2-4")
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes 2-4/10"
+        );
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"234"));
+
+        let req = TestRequest::with_uri("/static/docs/data.txt")
+            .header(header::RANGE, "bytes=-3")
+            .header(header::IF_RANGE, etag)
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"789"));
+
+        let req = TestRequest::with_uri("/static/docs/data.txt")
+            .header(header::RANGE, "bytes=2-4")
+            .header(header::IF_RANGE, "\"other\"")
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"0123456789"));
+
+        let req = TestRequest::with_uri("/static/docs/data.txt")
+            .header(header::RANGE, "bytes=20-")
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes */10"
+        );
+
+        // head request
+        let req = TestRequest::with_uri("/static/docs/data.txt")
+            .method(Method::HEAD)
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(read_body(resp).await.is_empty());
+
+        let req = TestRequest::with_uri("/static/docs/data.txt")
+            .method(Method::POST)
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        // not found, hidden files and path traversal
+        for uri in [
+            "/static/missing.txt",
+            "/static/docs",
+            "/static/.secret",
+            "/static/../private.txt",
+            "/static/%2e%2e/private.txt",
+            "/static/docs/%2e%2e%2f%2e%2e%2fprivate.txt",
+        ] {
+            let req = TestRequest::with_uri(uri).to_request();
+            let resp = call_service(&srv, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND, "{uri}");
+        }
+
+        let req = TestRequest::with_uri("/").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"root"));
+    }
+
+    #[cfg(unix)]
+    #[crate::rt_test]
+    async fn test_symlink_index() {
+        let dir = TestDir::new("files-symlink");
+        let private = dir.write("private.txt", "private");
+        fs::create_dir_all(dir.0.join("public/sub")).unwrap();
+        std::os::unix::fs::symlink(private, dir.0.join("public/sub/index.html")).unwrap();
+
+        let srv =
+            init_service(App::new().service(
+                Files::new("/static", dir.0.join("public")).index_file("index.html"),
+            ))
+            .await;
+
+        // index symlink points outside of the directory
+        let req = TestRequest::with_uri("/static/sub/").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+}
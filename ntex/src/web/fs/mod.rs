@@ -0,0 +1,38 @@
+//! Static files support
+mod files;
+mod named;
+
+pub use self::files::{Files, FilesService};
+pub use self::named::NamedFile;
+
+#[cfg(test)]
+/// Temporary directory for tests, removed on drop
+struct TestDir(std::path::PathBuf);
+
+#[cfg(test)]
+impl TestDir {
+    fn new(name: &str) -> Self {
+        let id = std::time::SystemTime::now()
+            .duration_since(std::time::SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("ntex-{name}-{id}"));
+        std::fs::create_dir_all(&dir).unwrap();
+        TestDir(dir)
+    }
+
+    /// Write file, parent directories are created
+    fn write(&self, path: &str, content: &str) -> std::path::PathBuf {
+        let path = self.0.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
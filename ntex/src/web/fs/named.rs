@@ -0,0 +1,461 @@
+use std::fs::{File, Metadata};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::task::{Context, Poll, ready};
+use std::{cmp, error::Error, future::Future, pin::Pin, rc::Rc};
+use std::{path::Path, path::PathBuf, time::SystemTime};
+
+use httpdate::HttpDate;
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
+
+use crate::http::body::{Body, BodySize, MessageBody};
+use crate::http::header;
+use crate::http::range::{self, RangeUnsatisfiable};
+use crate::http::{Method, RequestHead, Response, StatusCode, conditional};
+use crate::rt::{BlockingResult, spawn_blocking};
+use crate::util::Bytes;
+use crate::web::{HttpRequest, Responder, error::ErrorRenderer};
+
+/// Size of a chunk read from file
+const CHUNK_SIZE: u64 = 65_536;
+
+/// File responder
+///
+/// Content type is guessed from the file extension. Conditional requests
+/// (`ETag`, `Last-Modified`) and single byte ranges are supported, file is
+/// read on a thread pool and streamed to the peer chunk by chunk.
+///
+/// ```rust
+/// use ntex::web::{self, App, fs::NamedFile};
+///
+/// async fn report() -> std::io::Result<NamedFile> {
+///     Ok(NamedFile::open("report.pdf")?.attachment())
+/// }
+///
+/// fn main() {
+///     let app = App::new().service(web::resource("/report").to(report));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct NamedFile {
+    file: File,
+    md: Metadata,
+    path: PathBuf,
+    content_type: &'static str,
+    disposition: Option<&'static str>,
+}
+
+impl NamedFile {
+    /// Open file in read-only mode
+    ///
+    /// Returns `NotFound` error if path points to a directory.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<NamedFile> {
+        let file = File::open(path.as_ref())?;
+        NamedFile::from_file(file, path)
+    }
+
+    /// Create `NamedFile` from opened file
+    ///
+    /// `path` is used for content type and file name detection.
+    pub fn from_file<P: AsRef<Path>>(file: File, path: P) -> io::Result<NamedFile> {
+        let md = file.metadata()?;
+        if md.is_dir() {
+            return Err(io::ErrorKind::NotFound.into());
+        }
+        let path = path.as_ref().to_path_buf();
+        Ok(NamedFile {
+            file,
+            md,
+            content_type: content_type(&path),
+            path,
+            disposition: None,
+        })
+    }
+
+    /// Path of the file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// File metadata
+    pub fn metadata(&self) -> &Metadata {
+        &self.md
+    }
+
+    /// Content type of the file
+    pub fn content_type(&self) -> &'static str {
+        self.content_type
+    }
+
+    #[must_use]
+    /// Ask peer to save file instead of displaying it
+    ///
+    /// Sets `Content-Disposition: attachment` header with the file name.
+    pub fn attachment(mut self) -> Self {
+        self.disposition = Some("attachment");
+        self
+    }
+
+    #[must_use]
+    /// Ask peer to display file
+    ///
+    /// Sets `Content-Disposition: inline` header with the file name.
+    pub fn inline(mut self) -> Self {
+        self.disposition = Some("inline");
+        self
+    }
+
+    fn modified(&self) -> Option<SystemTime> {
+        self.md.modified().ok()
+    }
+
+    /// Strong entity tag, based on file modification time and size
+    fn etag(&self) -> Option<String> {
+        self.modified()
+            .and_then(|mtime| mtime.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .map(|dur| {
+                format!(
+                    "\"{:x}-{:x}.{:x}\"",
+                    self.md.len(),
+                    dur.as_secs(),
+                    dur.subsec_nanos()
+                )
+            })
+    }
+
+    /// Create response for the request
+    ///
+    /// Conditional request headers and single byte range requests are
+    /// handled, multiple ranges are not supported and full content is sent.
+    pub fn into_response(self, req: &RequestHead) -> Response {
+        let size = self.md.len();
+        let etag = self.etag();
+        let mtime = self.modified();
+
+        let mut res = Response::build(StatusCode::OK);
+        if let Some(ref etag) = etag {
+            res.header(header::ETAG, etag.as_str());
+        }
+        if let Some(mtime) = mtime {
+            res.header(header::LAST_MODIFIED, HttpDate::from(mtime).to_string());
+        }
+
+        // preconditions
+        if !precondition(req, etag.as_deref(), mtime) {
+            return res.status(StatusCode::PRECONDITION_FAILED).finish();
+        }
+        if is_not_modified(req, etag.as_deref(), mtime) {
+            return res.status(StatusCode::NOT_MODIFIED).finish();
+        }
+
+        res.header(header::CONTENT_TYPE, self.content_type)
+            .header(header::ACCEPT_RANGES, "bytes");
+        if let Some(disposition) = self.disposition {
+            res.header(
+                header::CONTENT_DISPOSITION,
+                content_disposition(disposition, &self.path),
+            );
+        }
+
+        // range request
+        let mut offset = 0;
+        let mut length = size;
+        if let Some(hdr) = req.headers.get(header::RANGE)
+            && if_range(req, etag.as_deref(), mtime)
+        {
+            match hdr.to_str().ok().map(|val| range::parse(val, size)) {
+                Some(Ok(Some(range))) => {
+                    offset = range.start;
+                    length = range.end - range.start;
+                    res.status(StatusCode::PARTIAL_CONTENT).header(
+                        header::CONTENT_RANGE,
+                        format!("bytes {}-{}/{size}", range.start, range.end - 1),
+                    );
+                }
+                Some(Err(RangeUnsatisfiable)) => {
+                    return res
+                        .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                        .header(header::CONTENT_RANGE, format!("bytes */{size}"))
+                        .finish();
+                }
+                _ => (),
+            }
+        }
+
+        let file = if req.method == Method::HEAD {
+            None
+        } else {
+            Some(self.file)
+        };
+        res.body(Body::from_message(ChunkedReadFile {
+            size: length,
+            remaining: length,
+            offset,
+            file,
+            fut: None,
+        }))
+    }
+}
+
+impl<Err: ErrorRenderer> Responder<Err> for NamedFile {
+    async fn respond_to(self, req: &HttpRequest) -> Response {
+        self.into_response(req.head())
+    }
+}
+
+/// `Content-Disposition` header value with the file name
+fn content_disposition(disposition: &str, path: &Path) -> String {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return disposition.to_string();
+    };
+    if name.bytes().all(|b| b.is_ascii_graphic() || b == b' ') {
+        let name = name.replace('\\', "\\\\").replace('"', "\\\"");
+        format!("{disposition}; filename=\"{name}\"")
+    } else {
+        let name = utf8_percent_encode(name, NON_ALPHANUMERIC);
+        format!("{disposition}; filename*=UTF-8''{name}")
+    }
+}
+
+/// Evaluate `If-Match` and `If-Unmodified-Since` preconditions
+fn precondition(req: &RequestHead, etag: Option<&str>, mtime: Option<SystemTime>) -> bool {
+    if let Some(val) = req.headers.get(header::IF_MATCH) {
+        let val = val.to_str().unwrap_or_default();
+        val.trim() == "*" || etag.is_some_and(|etag| etag_match(val, etag, true))
+    } else if let Some(since) = req
+        .headers
+        .get(header::IF_UNMODIFIED_SINCE)
+        .and_then(|val| val.to_str().ok())
+        .and_then(conditional::parse_http_date)
+    {
+        mtime.is_some_and(|mtime| SystemTime::from(HttpDate::from(mtime)) <= since)
+    } else {
+        true
+    }
+}
+
+/// Evaluate `If-None-Match` and `If-Modified-Since` preconditions
+fn is_not_modified(
+    req: &RequestHead,
+    etag: Option<&str>,
+    mtime: Option<SystemTime>,
+) -> bool {
+    if let Some(val) = req.headers.get(header::IF_NONE_MATCH) {
+        let val = val.to_str().unwrap_or_default();
+        val.trim() == "*" || etag.is_some_and(|etag| etag_match(val, etag, false))
+    } else {
+        mtime.is_some_and(|mtime| conditional::is_not_modified(req, mtime))
+    }
+}
+
+/// Check if `If-Range` condition allows to use `Range` header
+fn if_range(req: &RequestHead, etag: Option<&str>, mtime: Option<SystemTime>) -> bool {
+    let Some(val) = req.headers.get(header::IF_RANGE) else {
+        return true;
+    };
+    let val = val.to_str().unwrap_or_default().trim();
+    if val.starts_with('"') || val.starts_with("W/") {
+        etag.is_some_and(|etag| etag_match(val, etag, true))
+    } else if let Some(date) = conditional::parse_http_date(val) {
+        mtime.is_some_and(|mtime| SystemTime::from(HttpDate::from(mtime)) == date)
+    } else {
+        false
+    }
+}
+
+/// Compare entity tag with list of entity tags
+fn etag_match(list: &str, etag: &str, strong: bool) -> bool {
+    list.split(',').map(str::trim).any(|tag| {
+        if strong {
+            tag == etag
+        } else {
+            tag.trim_start_matches("W/") == etag.trim_start_matches("W/")
+        }
+    })
+}
+
+/// Content type of the file, guessed from the file extension
+fn content_type(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+
+    match ext.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "txt" => "text/plain; charset=utf-8",
+        "csv" => "text/csv; charset=utf-8",
+        "md" => "text/markdown; charset=utf-8",
+        "xml" => "text/xml; charset=utf-8",
+        "json" | "map" => "application/json",
+        "wasm" => "application/wasm",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "mp3" => "audio/mpeg",
+        "ogg" => "audio/ogg",
+        "wav" => "audio/wav",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Response body, reads file chunks on a thread pool
+struct ChunkedReadFile {
+    size: u64,
+    remaining: u64,
+    offset: u64,
+    file: Option<File>,
+    fut: Option<BlockingResult<io::Result<(File, Bytes)>>>,
+}
+
+impl MessageBody for ChunkedReadFile {
+    fn size(&self) -> BodySize {
+        BodySize::Sized(self.size)
+    }
+
+    fn poll_next_chunk(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Rc<dyn Error>>>> {
+        if let Some(ref mut fut) = self.fut {
+            let result = ready!(Pin::new(fut).poll(cx));
+            self.fut = None;
+
+            return Poll::Ready(Some(match result {
+                Ok(Ok((file, chunk))) => {
+                    self.file = Some(file);
+                    self.offset += chunk.len() as u64;
+                    self.remaining -= chunk.len() as u64;
+                    Ok(chunk)
+                }
+                Ok(Err(err)) => Err(Rc::new(err)),
+                Err(err) => Err(Rc::new(err)),
+            }));
+        }
+
+        if self.remaining == 0 {
+            return Poll::Ready(None);
+        }
+        let Some(mut file) = self.file.take() else {
+            // HEAD request
+            return Poll::Ready(None);
+        };
+
+        let offset = self.offset;
+        let max = cmp::min(self.remaining, CHUNK_SIZE);
+        self.fut = Some(spawn_blocking(move || {
+            file.seek(SeekFrom::Start(offset))?;
+            let mut buf = Vec::with_capacity(max as usize);
+            (&mut file).take(max).read_to_end(&mut buf)?;
+            if buf.is_empty() {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            Ok((file, Bytes::from(buf)))
+        }));
+        self.poll_next_chunk(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::web::test::{TestRequest, call_service, init_service, read_body};
+    use crate::web::{self, App, fs::TestDir};
+
+    #[test]
+    fn test_content_type() {
+        assert_eq!(
+            content_type(Path::new("index.HTML")),
+            "text/html; charset=utf-8"
+        );
+        assert_eq!(content_type(Path::new("a/b.png")), "image/png");
+        assert_eq!(content_type(Path::new("data")), "application/octet-stream");
+    }
+
+    #[test]
+    fn test_content_disposition() {
+        assert_eq!(
+            content_disposition("attachment", Path::new("/tmp/report.pdf")),
+            "attachment; filename=\"report.pdf\""
+        );
+        assert_eq!(
+            content_disposition("inline", Path::new("a \"b\".txt")),
+            "inline; filename=\"a \\\"b\\\".txt\""
+        );
+        assert_eq!(
+            content_disposition("attachment", Path::new("отчёт.pdf")),
+            "attachment; filename*=UTF-8''%D0%BE%D1%82%D1%87%D1%91%D1%82%2Epdf"
+        );
+        assert_eq!(content_disposition("inline", Path::new("/")), "inline");
+    }
+
+    #[test]
+    fn test_etag_match() {
+        assert!(etag_match("\"a\", \"b\"", "\"b\"", true));
+        assert!(!etag_match("W/\"b\"", "\"b\"", true));
+        assert!(etag_match("W/\"b\"", "\"b\"", false));
+        assert!(!etag_match("\"c\"", "\"b\"", false));
+    }
+
+    #[crate::rt_test]
+    async fn test_responder() {
+        let dir = TestDir::new("named");
+        let path = dir.write("data.json", "{\"a\": 1}");
+
+        let file = path.clone();
+        let srv = init_service(
+            App::new()
+                .service(web::resource("/file").to(move || {
+                    let file = file.clone();
+                    async move { Ok::<_, io::Error>(NamedFile::open(file)?.attachment()) }
+                }))
+                .service(
+                    web::resource("/missing")
+                        .to(|| async { NamedFile::open("/missing/file.txt") }),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/file").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+        assert_eq!(
+            resp.headers().get(header::CONTENT_DISPOSITION).unwrap(),
+            "attachment; filename=\"data.json\""
+        );
+        assert!(resp.headers().contains_key(header::ETAG));
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"{\"a\": 1}"));
+
+        let req = TestRequest::with_uri("/file")
+            .header(header::RANGE, "bytes=1-4")
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"\"a\":"));
+
+        let req = TestRequest::with_uri("/missing").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        assert!(NamedFile::open(&dir.0).is_err());
+    }
+}
@@ -10,7 +10,12 @@
 //!
 //! Guards can not modify the request object. But it is possible
 //! to store extra attributes on a request by using the `Extensions` container.
-//! Extensions containers are available via the `RequestHead::extensions()` method.
+//! Extensions containers are available via the `RequestHead::extensions_mut()`
+//! method, stored values could be extracted in a handler with
+//! `types::ReqData<T>` extractor. Values stored by guards of a route,
+//! resource or scope that does not match the request are removed.
+//! Replaced values are not restored, if a guard overwrites existing
+//! extension, new value is kept even if request does not match.
 //!
 //! ```rust
 //! use ntex::http::Method;
@@ -27,19 +32,51 @@
 //! ```
 #![allow(non_snake_case)]
 
-use std::fmt;
+use std::{any::TypeId, fmt, future};
+
+use mime::Mime;
 
 use crate::http::{Method, RequestHead, Uri, header};
+use crate::router::{ResourceId, Router};
+use crate::util::BoxFuture;
+
+use super::request::WebRequest;
 
 /// Trait defines resource guards. Guards are used for route selection.
 ///
 /// Guards can not modify the request object. But it is possible
 /// to store extra attributes on a request by using the `Extensions` container.
-/// Extensions containers are available via the `RequestHead::extensions()` method.
+/// Extensions containers are available via the `RequestHead::extensions_mut()`
+/// method, see `types::ReqData<T>` extractor. Guards should not overwrite
+/// existing extensions, replaced values are not restored for not matched
+/// routes.
 pub trait Guard {
     /// Check if request matches predicate
+    ///
+    /// Async guards can not be checked synchronously and should return `false`.
     fn check(&self, request: &RequestHead) -> bool;
 
+    /// Check if guard requires async check
+    ///
+    /// For async guards router uses `check_async()` instead of `check()`.
+    fn is_async(&self) -> bool {
+        false
+    }
+
+    /// Check if request matches predicate asynchronously
+    fn check_async<'a>(&'a self, request: &'a RequestHead) -> BoxFuture<'a, bool> {
+        Box::pin(future::ready(self.check(request)))
+    }
+
+    /// Check if guard could store values in request extensions
+    ///
+    /// Router removes extensions added by guards of not matched routes,
+    /// guards that never store extensions should return `false`. Values
+    /// replaced by a guard are not restored.
+    fn stores_extensions(&self) -> bool {
+        true
+    }
+
     /// Debug format
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("Guard").finish()
@@ -105,6 +142,174 @@ where
     }
 }
 
+/// Create async guard object for supplied async function.
+///
+/// Async guards are checked after all sync guards of the resource or route
+/// matched.
+///
+/// ```rust
+/// use ntex::http::RequestHead;
+/// use ntex::web::{self, guard, App, HttpResponse};
+///
+/// async fn is_valid(token: &str) -> bool {
+///     // check token in cache
+///     token == "secret"
+/// }
+///
+/// fn main() {
+///     App::new().service(web::resource("/index.html").route(
+///         web::route()
+///             .guard(guard::async_guard(async |req: &RequestHead| {
+///                 if let Some(token) = req.headers().get("x-token") {
+///                     is_valid(token.to_str().unwrap_or_default()).await
+///                 } else {
+///                     false
+///                 }
+///             }))
+///             .to(|| async { HttpResponse::Ok() }))
+///     );
+/// }
+/// ```
+pub fn async_guard<F>(f: F) -> impl Guard
+where
+    F: AsyncFn(&RequestHead) -> bool,
+{
+    AsyncFnGuard(f)
+}
+
+struct AsyncFnGuard<F: AsyncFn(&RequestHead) -> bool>(F);
+
+impl<F> Guard for AsyncFnGuard<F>
+where
+    F: AsyncFn(&RequestHead) -> bool,
+{
+    fn check(&self, _: &RequestHead) -> bool {
+        false
+    }
+
+    fn is_async(&self) -> bool {
+        true
+    }
+
+    fn check_async<'a>(&'a self, head: &'a RequestHead) -> BoxFuture<'a, bool> {
+        Box::pin((self.0)(head))
+    }
+
+    /// Debug format
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AsyncFnGuard")
+            .field(&std::any::type_name::<F>())
+            .finish()
+    }
+}
+
+impl<F> fmt::Debug for AsyncFnGuard<F>
+where
+    F: AsyncFn(&RequestHead) -> bool,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Guard::fmt(self, f)
+    }
+}
+
+/// Check sync guards, async guards are skipped
+///
+/// Extensions stored by guards are removed if request does not match.
+pub(super) fn check_sync_guards(guards: &[Box<dyn Guard>], req: &RequestHead) -> bool {
+    if guards.is_empty() {
+        return true;
+    }
+
+    let stored = stored_extensions(guards, req);
+    if guards.iter().all(|g| g.is_async() || g.check(req)) {
+        true
+    } else {
+        restore_extensions(req, stored);
+        false
+    }
+}
+
+/// Check sync guards first and async guards after
+///
+/// Extensions stored by guards are removed if request does not match.
+pub(super) async fn check_guards(guards: &[Box<dyn Guard>], req: &RequestHead) -> bool {
+    if guards.is_empty() {
+        return true;
+    }
+
+    let stored = stored_extensions(guards, req);
+    if guards.iter().all(|g| g.is_async() || g.check(req))
+        && check_async_guards(guards, req).await
+    {
+        true
+    } else {
+        restore_extensions(req, stored);
+        false
+    }
+}
+
+/// Check async guards, sync guards are skipped
+async fn check_async_guards(guards: &[Box<dyn Guard>], req: &RequestHead) -> bool {
+    for g in guards {
+        if g.is_async() && !g.check_async(req).await {
+            return false;
+        }
+    }
+    true
+}
+
+/// Type ids of extensions stored before guards check
+///
+/// Snapshot is not needed if none of guards stores extensions.
+fn stored_extensions(guards: &[Box<dyn Guard>], req: &RequestHead) -> Option<Vec<TypeId>> {
+    if guards.iter().any(|g| g.stores_extensions()) {
+        Some(req.extensions().type_ids().collect())
+    } else {
+        None
+    }
+}
+
+/// Remove extensions stored by guards of not matched request
+///
+/// Values replaced by guards are not restored.
+fn restore_extensions(req: &RequestHead, stored: Option<Vec<TypeId>>) {
+    if let Some(stored) = stored {
+        req.extensions_mut().retain(|id| stored.contains(&id));
+    }
+}
+
+/// Check if list contains async guards
+pub(super) fn has_async_guards(guards: &[Box<dyn Guard>]) -> bool {
+    guards.iter().any(|g| g.is_async())
+}
+
+/// Find matching resource, check sync guards first and async guards after
+pub(super) async fn recognize<'a, T, Err>(
+    router: &'a Router<T, Vec<Box<dyn Guard>>>,
+    req: &mut WebRequest<Err>,
+    async_guards: bool,
+) -> Option<(&'a T, ResourceId)> {
+    if async_guards {
+        router
+            .recognize_checked_async(
+                req,
+                |_, _| true,
+                async |req, guards| {
+                    if let Some(guards) = guards {
+                        check_guards(guards, req.head()).await
+                    } else {
+                        true
+                    }
+                },
+            )
+            .await
+    } else {
+        router.recognize_checked(req, |req, guards| {
+            guards.is_none_or(|guards| check_sync_guards(guards, req.head()))
+        })
+    }
+}
+
 /// Return guard that matches if any of supplied guards.
 ///
 /// ```rust
@@ -145,6 +350,30 @@ impl Guard for AnyGuard {
         false
     }
 
+    fn is_async(&self) -> bool {
+        has_async_guards(&self.0)
+    }
+
+    fn stores_extensions(&self) -> bool {
+        self.0.iter().any(|g| g.stores_extensions())
+    }
+
+    fn check_async<'a>(&'a self, req: &'a RequestHead) -> BoxFuture<'a, bool> {
+        Box::pin(async move {
+            for p in &self.0 {
+                let matched = if p.is_async() {
+                    p.check_async(req).await
+                } else {
+                    p.check(req)
+                };
+                if matched {
+                    return true;
+                }
+            }
+            false
+        })
+    }
+
     /// Debug format
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "AnyGuard(")?;
@@ -207,6 +436,18 @@ impl Guard for AllGuard {
         true
     }
 
+    fn is_async(&self) -> bool {
+        has_async_guards(&self.0)
+    }
+
+    fn stores_extensions(&self) -> bool {
+        self.0.iter().any(|g| g.stores_extensions())
+    }
+
+    fn check_async<'a>(&'a self, req: &'a RequestHead) -> BoxFuture<'a, bool> {
+        Box::pin(async move { check_guards(&self.0, req).await })
+    }
+
     /// Debug format
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "AllGuard(")?;
@@ -233,7 +474,19 @@ pub struct NotGuard(Box<dyn Guard>);
 
 impl Guard for NotGuard {
     fn check(&self, request: &RequestHead) -> bool {
-        !self.0.check(request)
+        !self.0.is_async() && !self.0.check(request)
+    }
+
+    fn is_async(&self) -> bool {
+        self.0.is_async()
+    }
+
+    fn stores_extensions(&self) -> bool {
+        self.0.stores_extensions()
+    }
+
+    fn check_async<'a>(&'a self, req: &'a RequestHead) -> BoxFuture<'a, bool> {
+        Box::pin(async move { !self.0.check_async(req).await })
     }
 
     /// Debug format
@@ -260,6 +513,10 @@ impl Guard for MethodGuard {
         request.method == self.0
     }
 
+    fn stores_extensions(&self) -> bool {
+        false
+    }
+
     /// Debug format
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Debug::fmt(self, f)
@@ -337,6 +594,67 @@ impl Guard for HeaderGuard {
         false
     }
 
+    fn stores_extensions(&self) -> bool {
+        false
+    }
+
+    /// Debug format
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Return predicate that matches if request's `Content-Type` matches specified mime type.
+///
+/// Type and subtype could be a wildcard, i.e. `application/*`. Mime type parameters
+/// are ignored.
+///
+/// ```rust
+/// use ntex::web::{self, guard, App, HttpResponse};
+///
+/// fn main() {
+///     App::new().service(
+///         web::resource("/import")
+///             .route(
+///                 web::post()
+///                     .guard(guard::ContentType(mime::APPLICATION_JSON))
+///                     .to(|| async { HttpResponse::Ok().body("json") }),
+///             )
+///             .route(
+///                 web::post()
+///                     .guard(guard::ContentType(mime::MULTIPART_FORM_DATA))
+///                     .to(|| async { HttpResponse::Ok().body("multipart") }),
+///             ),
+///     );
+/// }
+/// ```
+pub fn ContentType(mime: Mime) -> ContentTypeGuard {
+    ContentTypeGuard(mime)
+}
+
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct ContentTypeGuard(Mime);
+
+impl Guard for ContentTypeGuard {
+    fn check(&self, req: &RequestHead) -> bool {
+        let Some(ct) = req
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|val| val.to_str().ok())
+            .and_then(|val| val.parse::<Mime>().ok())
+        else {
+            return false;
+        };
+
+        (self.0.type_() == mime::STAR || self.0.type_() == ct.type_())
+            && (self.0.subtype() == mime::STAR || self.0.subtype() == ct.subtype())
+    }
+
+    fn stores_extensions(&self) -> bool {
+        false
+    }
+
     /// Debug format
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Debug::fmt(self, f)
@@ -406,6 +724,10 @@ impl Guard for HostGuard {
         true
     }
 
+    fn stores_extensions(&self) -> bool {
+        false
+    }
+
     /// Debug format
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Debug::fmt(self, f)
@@ -434,6 +756,71 @@ mod tests {
         assert!(format!("{pred:?}").contains("Header"));
     }
 
+    #[test]
+    fn test_content_type() {
+        let req = TestRequest::with_header(
+            header::CONTENT_TYPE,
+            "application/json; charset=utf-8",
+        )
+        .to_http_request();
+
+        assert!(ContentType(mime::APPLICATION_JSON).check(req.head()));
+        assert!(ContentType("application/*".parse().unwrap()).check(req.head()));
+        assert!(ContentType(mime::STAR_STAR).check(req.head()));
+        assert!(!ContentType(mime::MULTIPART_FORM_DATA).check(req.head()));
+        assert!(!ContentType(mime::TEXT_STAR).check(req.head()));
+
+        let req = TestRequest::with_header(
+            header::CONTENT_TYPE,
+            "multipart/form-data; boundary=abc",
+        )
+        .to_http_request();
+        assert!(ContentType(mime::MULTIPART_FORM_DATA).check(req.head()));
+        assert!(!ContentType(mime::APPLICATION_JSON).check(req.head()));
+
+        let req = TestRequest::default().to_http_request();
+        assert!(!ContentType(mime::STAR_STAR).check(req.head()));
+        assert!(format!("{:?}", ContentType(mime::STAR_STAR)).contains("ContentType"));
+    }
+
+    #[crate::rt_test]
+    async fn test_content_type_routes() {
+        use crate::util::Bytes;
+        use crate::web::test::{call_service, init_service, read_body};
+        use crate::web::{self, App, HttpResponse};
+
+        let srv = init_service(
+            App::new().service(
+                web::resource("/import")
+                    .route(
+                        web::post()
+                            .guard(ContentType(mime::APPLICATION_JSON))
+                            .to(|| async { HttpResponse::Ok().body("json") }),
+                    )
+                    .route(
+                        web::post()
+                            .guard(ContentType(mime::MULTIPART_FORM_DATA))
+                            .to(|| async { HttpResponse::Ok().body("multipart") }),
+                    ),
+            ),
+        )
+        .await;
+
+        let req = TestRequest::post()
+            .uri("/import")
+            .header(header::CONTENT_TYPE, "application/json")
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"json"));
+
+        let req = TestRequest::post()
+            .uri("/import")
+            .header(header::CONTENT_TYPE, "multipart/form-data; boundary=abc")
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"multipart"));
+    }
+
     #[test]
     fn test_host() {
         let req = TestRequest::default()
@@ -593,6 +980,22 @@ mod tests {
         assert!(format!("{:?}", Any(Get())).contains("AnyGuard"));
     }
 
+    #[test]
+    fn test_stores_extensions() {
+        assert!(!Get().stores_extensions());
+        assert!(!Header("x-test", "1").stores_extensions());
+        assert!(!Host("localhost").stores_extensions());
+        assert!(!Not(Get()).stores_extensions());
+        assert!(!All(Get()).and(Header("x-test", "1")).stores_extensions());
+        assert!(Any(Get()).or(fn_guard(|_| true)).stores_extensions());
+        assert!(async_guard(async |_: &RequestHead| true).stores_extensions());
+
+        // snapshot is not taken if guards do not store extensions
+        let guards: Vec<Box<dyn Guard>> = vec![Box::new(Get())];
+        let req = TestRequest::default().to_http_request();
+        assert!(stored_extensions(&guards, req.head()).is_none());
+    }
+
     #[test]
     fn test_fn_guard() {
         let req =
@@ -606,4 +1009,104 @@ mod tests {
         let g = |req: &RequestHead| req.headers().contains_key("content-type");
         assert!(g.check(req.head()));
     }
+
+    #[crate::rt_test]
+    async fn test_async_guard() {
+        use crate::time::{Millis, sleep};
+        use crate::util::Bytes;
+        use crate::web::test::{call_service, init_service, read_body};
+        use crate::web::{self, App, HttpResponse};
+
+        async fn token(req: &RequestHead) -> bool {
+            sleep(Millis(1)).await;
+            req.headers().get("x-token").is_some_and(|v| v == "secret")
+        }
+
+        let g = async_guard(token);
+        assert!(g.is_async());
+        // async guards never match synchronously
+        let req = TestRequest::default()
+            .header("x-token", "secret")
+            .to_http_request();
+        assert!(!g.check(req.head()));
+        assert!(!Not(async_guard(token)).check(req.head()));
+        assert!(!All(Get()).and(async_guard(token)).check(req.head()));
+        assert!(format!("{:?}", AsyncFnGuard(token)).contains("AsyncFnGuard"));
+        assert!(Any(Get()).or(async_guard(token)).is_async());
+        assert!(!All(Get()).and(Post()).is_async());
+
+        let srv = init_service(
+            App::new()
+                .service(
+                    web::resource("/resource")
+                        .guard(async_guard(token))
+                        .to(|| async { HttpResponse::Ok().body("resource") }),
+                )
+                .service(
+                    web::resource("/resource")
+                        .to(|| async { HttpResponse::Ok().body("fallback") }),
+                )
+                .service(
+                    web::resource("/not").route(
+                        web::get()
+                            .guard(Not(async_guard(token)))
+                            .to(|| async { HttpResponse::Ok().body("not") }),
+                    ),
+                )
+                .service(
+                    web::scope("/scope").guard(Not(async_guard(token))).service(
+                        web::resource("/route")
+                            .route(
+                                web::get()
+                                    .guard(async_guard(token))
+                                    .to(|| async { HttpResponse::Ok().body("route") }),
+                            )
+                            .route(
+                                web::get()
+                                    .guard(Any(async_guard(token)).or(Header("x-ok", "1")))
+                                    .to(|| async { HttpResponse::Ok().body("any") }),
+                            ),
+                    ),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/resource")
+            .header("x-token", "secret")
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"resource"));
+
+        let req = TestRequest::with_uri("/resource").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"fallback"));
+
+        // route level Not guard
+        let req = TestRequest::with_uri("/not").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"not"));
+
+        let req = TestRequest::with_uri("/not")
+            .header("x-token", "secret")
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), crate::http::StatusCode::METHOD_NOT_ALLOWED);
+
+        // route guards, scope guard does not allow token
+        let req = TestRequest::with_uri("/scope/route").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), crate::http::StatusCode::METHOD_NOT_ALLOWED);
+
+        let req = TestRequest::with_uri("/scope/route")
+            .header("x-ok", "1")
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"any"));
+
+        let req = TestRequest::with_uri("/scope/route")
+            .header("x-token", "secret")
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), crate::http::StatusCode::NOT_FOUND);
+    }
 }
@@ -1,14 +1,15 @@
 use std::{cell::Ref, cell::RefMut, fmt, net, rc::Rc};
 
 use crate::http::{
-    HeaderMap, HttpMessage, Message, Method, Payload, RequestHead, Uri, Version,
+    HeaderMap, HttpMessage, Message, Method, Payload, RequestHead, TraceContext, Uri,
+    Version,
 };
 use crate::{io::IoRef, io::types, router::Path, util::Extensions};
 
 use super::config::WebAppConfig;
 use super::error::ErrorRenderer;
 use super::extract::FromRequest;
-use super::info::ConnectionInfo;
+use super::info::{ConnectionInfo, ProxyHeaders};
 use super::rmap::ResourceMap;
 use super::service::AppState;
 
@@ -21,6 +22,7 @@ pub(crate) struct HttpRequestInner {
     pub(crate) path: Path<Uri>,
     pub(crate) payload: Payload,
     pub(crate) app_state: AppState,
+    pub(crate) match_pattern: Option<Rc<str>>,
     rmap: Rc<ResourceMap>,
 }
 
@@ -39,6 +41,7 @@ impl HttpRequest {
             payload,
             app_state,
             rmap,
+            match_pattern: None,
         }))
     }
 }
@@ -58,6 +61,11 @@ impl HttpRequest {
     }
 
     /// Request's uri.
+    ///
+    /// Uri contains full request target, for absolute-form requests it includes
+    /// scheme and authority. Authority-form `CONNECT` requests have empty path,
+    /// such requests are routed to resources with empty path pattern,
+    /// `web::resource("")`.
     #[inline]
     pub fn uri(&self) -> &Uri {
         &self.head().uri
@@ -75,6 +83,12 @@ impl HttpRequest {
         self.head().version
     }
 
+    /// Check if request is sent over http/2 connection.
+    #[inline]
+    pub fn is_h2(&self) -> bool {
+        self.head().version == Version::HTTP_2
+    }
+
     #[inline]
     /// Returns request's headers.
     pub fn headers(&self) -> &HeaderMap {
@@ -127,6 +141,15 @@ impl HttpRequest {
         &mut Rc::get_mut(&mut self.0).unwrap().path
     }
 
+    /// Path pattern of the matched resource, i.e. `/users/{id}`.
+    ///
+    /// Pattern includes prefixes of all matched scopes. Returns `None`
+    /// if request is handled by default service.
+    #[inline]
+    pub fn match_pattern(&self) -> Option<&str> {
+        self.0.match_pattern.as_deref()
+    }
+
     /// Request extensions
     #[inline]
     pub fn extensions(&self) -> Ref<'_, Extensions> {
@@ -191,11 +214,27 @@ impl HttpRequest {
 
     /// Get *`ConnectionInfo`* for the current request.
     ///
+    /// Proxy headers are trusted according to `ProxyHeaders` application state,
+    /// if state is not set all proxy headers are trusted.
+    ///
     /// This method panics if request's extensions container is already
     /// borrowed.
     #[inline]
     pub fn connection_info(&self) -> Ref<'_, ConnectionInfo> {
-        ConnectionInfo::get(self.head(), self.app_config())
+        let trusted = self
+            .app_state::<ProxyHeaders>()
+            .copied()
+            .unwrap_or_default();
+        ConnectionInfo::get_with(self.head(), self.app_config(), trusted)
+    }
+
+    /// Get distributed tracing context from `traceparent` and `tracestate` headers.
+    ///
+    /// Returns `None` if `traceparent` header is missing or malformed, use
+    /// `TraceContext::new()` to start new trace in that case.
+    #[inline]
+    pub fn trace_context(&self) -> Option<TraceContext> {
+        TraceContext::from_headers(self.headers())
     }
 
     /// App config
@@ -304,7 +343,7 @@ mod tests {
     use crate::http::{StatusCode, header};
     use crate::router::ResourceDef;
 
-    use crate::web::test::{TestRequest, call_service, init_service};
+    use crate::web::test::{TestRequest, call_service, init_service, read_body};
     use crate::web::{self, App, HttpResponse};
 
     #[test]
@@ -351,6 +390,14 @@ mod tests {
 
         let cookie = req.cookie("cookie-unknown");
         assert!(cookie.is_none());
+
+        let req = TestRequest::default()
+            .header(header::COOKIE, "session=\"abc\"; theme=dark")
+            .to_http_request();
+        let cookie = req.cookie("session").unwrap();
+        assert_eq!(cookie.value(), "\"abc\"");
+        assert_eq!(cookie.value_trimmed(), "abc");
+        assert_eq!(req.cookie("theme").unwrap().value(), "dark");
     }
 
     #[test]
@@ -359,6 +406,42 @@ mod tests {
         assert_eq!(req.query_string(), "id=test");
     }
 
+    #[test]
+    fn test_version() {
+        let req = TestRequest::default().to_http_request();
+        assert_eq!(req.version(), Version::HTTP_11);
+        assert!(!req.is_h2());
+
+        let req = TestRequest::default()
+            .version(Version::HTTP_2)
+            .to_http_request();
+        assert!(req.is_h2());
+    }
+
+    #[test]
+    fn test_trace_context() {
+        let req = TestRequest::default().to_http_request();
+        assert!(req.trace_context().is_none());
+
+        let req = TestRequest::default()
+            .header(
+                "traceparent",
+                "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+            )
+            .header("tracestate", "congo=t61rcWkgMzE")
+            .to_http_request();
+        let ctx = req.trace_context().unwrap();
+        assert_eq!(ctx.trace_id(), 0x0af7_6519_16cd_43dd_8448_eb21_1c80_319c);
+        assert_eq!(ctx.parent_id(), 0xb7ad_6b71_6920_3331);
+        assert!(ctx.is_sampled());
+        assert_eq!(ctx.state().unwrap(), "congo=t61rcWkgMzE");
+
+        let req = TestRequest::default()
+            .header("traceparent", "00-0af7651916cd43dd8448eb211c80319c-xx-01")
+            .to_http_request();
+        assert!(req.trace_context().is_none());
+    }
+
     #[cfg(feature = "url")]
     #[test]
     fn test_url_for() {
@@ -463,6 +546,34 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
     }
 
+    #[crate::rt_test]
+    async fn test_request_target_forms() {
+        let srv = init_service(
+            App::new()
+                .service(web::resource("/test").to(|req: HttpRequest| async move {
+                    HttpResponse::Ok().body(req.uri().to_string())
+                }))
+                .service(web::resource("").route(web::method(Method::CONNECT).to(
+                    |req: HttpRequest| async move {
+                        HttpResponse::Ok().body(req.uri().authority().unwrap().to_string())
+                    },
+                ))),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("http://example.com/test?q=1").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(read_body(resp).await, &b"http://example.com/test?q=1"[..]);
+
+        let req = TestRequest::with_uri("example.com:443")
+            .method(Method::CONNECT)
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(read_body(resp).await, &b"example.com:443"[..]);
+    }
+
     #[crate::rt_test]
     async fn test_extensions_dropped() {
         struct Tracker {
@@ -1,13 +1,101 @@
 use std::{borrow::ToOwned, cell::Ref};
 
 use super::config::WebAppConfig;
-use crate::http::{RequestHead, header, header::HeaderName, uri};
+use super::{ErrorRenderer, FromRequest, HttpRequest};
+use crate::http::proxy::Forwarded;
+use crate::http::{Payload, RequestHead, header, header::HeaderName, uri};
 
 const X_FORWARDED_FOR: &[u8] = b"x-forwarded-for";
 const X_FORWARDED_HOST: &[u8] = b"x-forwarded-host";
 const X_FORWARDED_PROTO: &[u8] = b"x-forwarded-proto";
 
+/// Proxy headers trusted by *`ConnectionInfo`*
+///
+/// Proxy headers could be spoofed by the client, if application is not
+/// deployed behind reverse proxy that overrides them, headers must not be
+/// trusted. Register with `App::state()` to override default behavior.
+///
+/// By default all proxy headers are trusted.
+///
+/// ```rust
+/// use ntex::web::{self, App, types::ConnectionInfo, types::ProxyHeaders};
+///
+/// async fn index(info: ConnectionInfo) -> String {
+///     format!("{:?}", info.realip_remote_addr())
+/// }
+///
+/// let app = App::new()
+///     .state(ProxyHeaders::none().x_forwarded_for(true))
+///     .route("/", web::get().to(index));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ProxyHeaders {
+    forwarded: bool,
+    x_forwarded_for: bool,
+    x_forwarded_host: bool,
+    x_forwarded_proto: bool,
+}
+
+impl Default for ProxyHeaders {
+    fn default() -> Self {
+        ProxyHeaders::all()
+    }
+}
+
+impl ProxyHeaders {
+    /// Trust all proxy headers
+    pub const fn all() -> Self {
+        ProxyHeaders {
+            forwarded: true,
+            x_forwarded_for: true,
+            x_forwarded_host: true,
+            x_forwarded_proto: true,
+        }
+    }
+
+    /// Do not trust any proxy header
+    pub const fn none() -> Self {
+        ProxyHeaders {
+            forwarded: false,
+            x_forwarded_for: false,
+            x_forwarded_host: false,
+            x_forwarded_proto: false,
+        }
+    }
+
+    #[must_use]
+    /// Trust `Forwarded` header
+    pub const fn forwarded(mut self, val: bool) -> Self {
+        self.forwarded = val;
+        self
+    }
+
+    #[must_use]
+    /// Trust `X-Forwarded-For` header
+    pub const fn x_forwarded_for(mut self, val: bool) -> Self {
+        self.x_forwarded_for = val;
+        self
+    }
+
+    #[must_use]
+    /// Trust `X-Forwarded-Host` header
+    pub const fn x_forwarded_host(mut self, val: bool) -> Self {
+        self.x_forwarded_host = val;
+        self
+    }
+
+    #[must_use]
+    /// Trust `X-Forwarded-Proto` header
+    pub const fn x_forwarded_proto(mut self, val: bool) -> Self {
+        self.x_forwarded_proto = val;
+        self
+    }
+}
+
 /// `HttpRequest` connection information
+///
+/// *`ConnectionInfo`* could be used as an extractor, proxy headers
+/// are trusted according to application's `ProxyHeaders` state.
 #[derive(Debug, Clone, Default)]
 pub struct ConnectionInfo {
     scheme: String,
@@ -18,51 +106,50 @@ pub struct ConnectionInfo {
 
 impl ConnectionInfo {
     /// Create *`ConnectionInfo`* instance for a request.
+    ///
+    /// All proxy headers are trusted. Use `HttpRequest::connection_info()`
+    /// for connection info according to application's `ProxyHeaders` state.
     pub fn get<'a>(req: &'a RequestHead, cfg: &'a WebAppConfig) -> Ref<'a, Self> {
-        if !req.extensions().contains::<ConnectionInfo>() {
-            req.extensions_mut().insert(ConnectionInfo::new(req, cfg));
-        }
-        Ref::map(req.extensions(), |e| e.get().unwrap())
+        ConnectionInfo::get_with(req, cfg, ProxyHeaders::all())
     }
 
-    fn new(req: &RequestHead, cfg: &WebAppConfig) -> ConnectionInfo {
-        let mut host = None;
-        let mut scheme = None;
-        let mut remote = None;
-        let mut peer = None;
+    /// Create *`ConnectionInfo`* instance for a request with trusted proxy headers.
+    ///
+    /// Connection info is cached in request extensions, cached value is
+    /// re-created if it was created with different trusted proxy headers.
+    pub fn get_with<'a>(
+        req: &'a RequestHead,
+        cfg: &'a WebAppConfig,
+        trusted: ProxyHeaders,
+    ) -> Ref<'a, Self> {
+        let cached = req
+            .extensions()
+            .get::<CachedInfo>()
+            .is_some_and(|cached| cached.trusted == trusted);
+        if !cached {
+            let info = ConnectionInfo::new(req, cfg, trusted);
+            req.extensions_mut().insert(CachedInfo { trusted, info });
+        }
+        Ref::map(req.extensions(), |e| &e.get::<CachedInfo>().unwrap().info)
+    }
 
+    fn new(req: &RequestHead, cfg: &WebAppConfig, trusted: ProxyHeaders) -> ConnectionInfo {
         // load forwarded header
-        for hdr in req.headers.get_all(&header::FORWARDED) {
-            if let Ok(val) = hdr.to_str() {
-                for pair in val.split(';') {
-                    for el in pair.split(',') {
-                        let mut items = el.trim().splitn(2, '=');
-                        if let Some(name) = items.next()
-                            && let Some(val) = items.next()
-                        {
-                            match &name.to_lowercase() as &str {
-                                "for" if remote.is_none() => {
-                                    remote = Some(val.trim());
-                                }
-                                "proto" if scheme.is_none() => {
-                                    scheme = Some(val.trim());
-                                }
-                                "host" if host.is_none() => {
-                                    host = Some(val.trim());
-                                }
-                                _ => (),
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        let forwarded = if trusted.forwarded {
+            Forwarded::from_headers(&req.headers)
+        } else {
+            Vec::new()
+        };
+        let mut host = forwarded.iter().find_map(Forwarded::host);
+        let mut scheme = forwarded.iter().find_map(Forwarded::proto);
+        let mut remote = forwarded.iter().find_map(Forwarded::forwarded_for);
 
         // scheme
         if scheme.is_none() {
-            if let Some(h) = req
-                .headers
-                .get(HeaderName::from_lowercase(X_FORWARDED_PROTO).unwrap())
+            if trusted.x_forwarded_proto
+                && let Some(h) = req
+                    .headers
+                    .get(HeaderName::from_lowercase(X_FORWARDED_PROTO).unwrap())
                 && let Ok(h) = h.to_str()
             {
                 scheme = h.split(',').next().map(str::trim);
@@ -77,9 +164,10 @@ impl ConnectionInfo {
 
         // host
         if host.is_none() {
-            if let Some(h) = req
-                .headers
-                .get(HeaderName::from_lowercase(X_FORWARDED_HOST).unwrap())
+            if trusted.x_forwarded_host
+                && let Some(h) = req
+                    .headers
+                    .get(HeaderName::from_lowercase(X_FORWARDED_HOST).unwrap())
                 && let Ok(h) = h.to_str()
             {
                 host = h.split(',').next().map(str::trim);
@@ -98,20 +186,19 @@ impl ConnectionInfo {
         }
 
         // remote addr
-        if remote.is_none() {
-            if let Some(h) = req
+        if remote.is_none()
+            && trusted.x_forwarded_for
+            && let Some(h) = req
                 .headers
                 .get(HeaderName::from_lowercase(X_FORWARDED_FOR).unwrap())
-                && let Ok(h) = h.to_str()
-            {
-                remote = h.split(',').next().map(str::trim);
-            }
-            if remote.is_none() {
-                // get peeraddr from socketaddr
-                peer = req.peer_addr().map(|addr| format!("{addr}"));
-            }
+            && let Ok(h) = h.to_str()
+        {
+            remote = h.split(',').next().map(str::trim);
         }
 
+        // get peeraddr from socketaddr
+        let peer = req.peer_addr().map(|addr| format!("{addr}"));
+
         ConnectionInfo {
             peer,
             scheme: scheme.unwrap_or("http").to_owned(),
@@ -168,12 +255,48 @@ impl ConnectionInfo {
             None
         }
     }
+
+    /// Real address of the client initiated HTTP request.
+    ///
+    /// The addr is resolved through trusted proxy headers and falls
+    /// back to peer address of opened socket. Check `ProxyHeaders` for
+    /// configuring trusted headers.
+    #[inline]
+    pub fn realip_remote_addr(&self) -> Option<&str> {
+        self.remote()
+    }
+
+    /// Peer address of opened socket.
+    #[inline]
+    pub fn peer_addr(&self) -> Option<&str> {
+        self.peer.as_deref()
+    }
+}
+
+/// Connection info cached in request extensions
+struct CachedInfo {
+    trusted: ProxyHeaders,
+    info: ConnectionInfo,
+}
+
+impl<Err: ErrorRenderer> FromRequest<Err> for ConnectionInfo {
+    type Error = Err::Container;
+
+    #[inline]
+    async fn from_request(req: &HttpRequest, _: &mut Payload) -> Result<Self, Self::Error> {
+        Ok(req.connection_info().clone())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::web::test::TestRequest;
+    use crate::util::Bytes;
+    use crate::web::middleware::Logger;
+    use crate::web::test::{
+        TestRequest, call_service, from_request, init_service, read_body,
+    };
+    use crate::web::{self, App};
 
     #[test]
     fn test_forwarded() {
@@ -221,5 +344,94 @@ mod tests {
             .to_http_request();
         let info = req.connection_info();
         assert_eq!(info.scheme(), "https");
+
+        let req = TestRequest::default()
+            .header(header::FORWARDED, "for=\"[2001:db8:cafe::17]:4711\"")
+            .to_http_request();
+        let info = req.connection_info();
+        assert_eq!(info.remote(), Some("[2001:db8:cafe::17]:4711"));
+
+        let req = TestRequest::default()
+            .header(
+                header::FORWARDED,
+                "for=\"192.0.2.60\";proto=https, for=10.0.0.1;host=\"example.com\"",
+            )
+            .header(X_FORWARDED_PROTO, "http")
+            .to_http_request();
+        let info = req.connection_info();
+        assert_eq!(info.remote(), Some("192.0.2.60"));
+        assert_eq!(info.scheme(), "https");
+        assert_eq!(info.host(), "example.com");
+    }
+
+    #[crate::rt_test]
+    async fn test_trusted_headers() {
+        let peer = "127.0.0.1:8081".parse().unwrap();
+
+        let (req, mut pl) = TestRequest::default()
+            .header(X_FORWARDED_FOR, "192.0.2.60")
+            .header(X_FORWARDED_PROTO, "https")
+            .peer_addr(peer)
+            .to_http_parts();
+        let info = from_request::<ConnectionInfo>(&req, &mut pl).await.unwrap();
+        assert_eq!(info.realip_remote_addr(), Some("192.0.2.60"));
+        assert_eq!(info.peer_addr(), Some("127.0.0.1:8081"));
+        assert_eq!(info.scheme(), "https");
+
+        let (req, mut pl) = TestRequest::default()
+            .header(header::FORWARDED, "for=192.0.2.43")
+            .header(X_FORWARDED_FOR, "192.0.2.60")
+            .header(X_FORWARDED_PROTO, "https")
+            .peer_addr(peer)
+            .state(ProxyHeaders::none())
+            .to_http_parts();
+        let info = from_request::<ConnectionInfo>(&req, &mut pl).await.unwrap();
+        assert_eq!(info.realip_remote_addr(), Some("127.0.0.1:8081"));
+        assert_eq!(info.scheme(), "http");
+
+        let (req, mut pl) = TestRequest::default()
+            .header(header::FORWARDED, "for=192.0.2.43")
+            .header(X_FORWARDED_FOR, "192.0.2.60")
+            .peer_addr(peer)
+            .state(ProxyHeaders::none().x_forwarded_for(true))
+            .to_http_parts();
+        let info = from_request::<ConnectionInfo>(&req, &mut pl).await.unwrap();
+        assert_eq!(info.realip_remote_addr(), Some("192.0.2.60"));
+
+        // cached info created with other trusted headers is not used
+        let (req, mut pl) = TestRequest::default()
+            .header(X_FORWARDED_FOR, "192.0.2.60")
+            .peer_addr(peer)
+            .state(ProxyHeaders::none())
+            .to_http_parts();
+        assert_eq!(
+            ConnectionInfo::get(req.head(), req.app_config()).remote(),
+            Some("192.0.2.60")
+        );
+        let info = from_request::<ConnectionInfo>(&req, &mut pl).await.unwrap();
+        assert_eq!(info.realip_remote_addr(), Some("127.0.0.1:8081"));
+    }
+
+    #[crate::rt_test]
+    async fn test_trusted_headers_logger() {
+        let srv = init_service(
+            App::new()
+                .state(ProxyHeaders::none())
+                .middleware(Logger::new("%a"))
+                .route(
+                    "/",
+                    web::get().to(|info: ConnectionInfo| async move {
+                        info.realip_remote_addr().unwrap_or_default().to_string()
+                    }),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::default()
+            .header(X_FORWARDED_FOR, "192.0.2.60")
+            .peer_addr("127.0.0.1:8081".parse().unwrap())
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"127.0.0.1:8081"));
     }
 }
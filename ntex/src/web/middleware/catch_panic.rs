@@ -0,0 +1,183 @@
+//! Middleware for handler panic recovery
+use std::{future::poll_fn, panic, pin::pin, task::Poll};
+
+use crate::http::StatusCode;
+use crate::service::{Middleware, Service, ServiceCtx, cfg::SharedCfg};
+use crate::web::error::{ErrorRenderer, InternalError};
+use crate::web::{WebRequest, WebResponse};
+
+/// `Middleware` for handler panic recovery.
+///
+/// If inner service panics, panic is caught and *500 Internal Server Error*
+/// response is returned, connection stays alive and worker continues
+/// to serve requests. Status code could be changed with `CatchPanic::status()`.
+///
+/// Panic is caught only during inner service call. Middleware does not
+/// make shared state unwind safe, state that is modified by the panicking
+/// handler (`RefCell`, `Cell`, etc) could be left inconsistent, so recovery
+/// is opt-in.
+///
+/// ```rust
+/// use ntex::web::{self, middleware, App, HttpResponse};
+///
+/// fn main() {
+///     let app = App::new()
+///         .middleware(middleware::CatchPanic::new())
+///         .service(
+///             web::resource("/test")
+///                 .route(web::get().to(|| async { HttpResponse::Ok() }))
+///         );
+/// }
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct CatchPanic {
+    status: StatusCode,
+}
+
+impl Default for CatchPanic {
+    fn default() -> Self {
+        CatchPanic::new()
+    }
+}
+
+impl CatchPanic {
+    /// Construct `CatchPanic` middleware.
+    pub fn new() -> Self {
+        CatchPanic {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    #[must_use]
+    /// Set response status for panicked requests.
+    ///
+    /// Default is *500 Internal Server Error*.
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+}
+
+impl<S> Middleware<S, SharedCfg> for CatchPanic {
+    type Service = CatchPanicMiddleware<S>;
+
+    fn create(&self, service: S, _: SharedCfg) -> Self::Service {
+        CatchPanicMiddleware {
+            service,
+            status: self.status,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CatchPanicMiddleware<S> {
+    service: S,
+    status: StatusCode,
+}
+
+impl<S, Err> Service<WebRequest<Err>> for CatchPanicMiddleware<S>
+where
+    S: Service<WebRequest<Err>, Response = WebResponse>,
+    S::Error: From<InternalError<&'static str, Err>>,
+    Err: ErrorRenderer,
+{
+    type Response = WebResponse;
+    type Error = S::Error;
+
+    crate::forward_poll!(service);
+    crate::forward_ready!(service);
+    crate::forward_shutdown!(service);
+
+    async fn call(
+        &self,
+        req: WebRequest<Err>,
+        ctx: ServiceCtx<'_, Self>,
+    ) -> Result<Self::Response, Self::Error> {
+        let mut fut = pin!(ctx.call(&self.service, req));
+
+        let result = poll_fn(|cx| {
+            match panic::catch_unwind(panic::AssertUnwindSafe(|| fut.as_mut().poll(cx))) {
+                Ok(poll) => poll.map(Ok),
+                Err(_) => Poll::Ready(Err(())),
+            }
+        })
+        .await;
+
+        match result {
+            Ok(res) => res,
+            Err(()) => {
+                log::error!("Request handler panicked");
+                Err(InternalError::new("Request handler panicked", self.status).into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::error::ResponseError;
+    use crate::time::{Millis, sleep};
+    use crate::web::test::{TestRequest, call_service, init_service};
+    use crate::web::{self, App, HttpResponse};
+
+    async fn index() -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    async fn panics() -> HttpResponse {
+        panic!("handler panic")
+    }
+
+    async fn panics_later() -> HttpResponse {
+        sleep(Millis(10)).await;
+        panic::panic_any(42_usize)
+    }
+
+    #[crate::rt_test]
+    async fn test_catch_panic() {
+        let srv = init_service(
+            App::new()
+                .middleware(CatchPanic::new())
+                .route("/", web::get().to(index))
+                .route("/panic", web::get().to(panics))
+                .route("/panic-later", web::get().to(panics_later)),
+        )
+        .await;
+
+        // panic on first poll and on later poll, with non-string payload
+        for path in ["/panic", "/panic-later"] {
+            let err = srv
+                .call(TestRequest::with_uri(path).to_request())
+                .await
+                .unwrap_err();
+            assert_eq!(err.to_string(), "Request handler panicked");
+            assert_eq!(
+                err.error_response().status(),
+                StatusCode::INTERNAL_SERVER_ERROR
+            );
+
+            // app is still usable
+            let resp = call_service(&srv, TestRequest::default().to_request()).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+        }
+    }
+
+    #[crate::rt_test]
+    async fn test_status() {
+        let srv = init_service(
+            App::new()
+                .middleware(CatchPanic::new().status(StatusCode::SERVICE_UNAVAILABLE))
+                .route("/", web::get().to(panics)),
+        )
+        .await;
+        let err = srv
+            .call(TestRequest::default().to_request())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.error_response().status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+}
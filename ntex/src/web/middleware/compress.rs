@@ -1,7 +1,8 @@
 //! `Middleware` for compressing response body.
 use std::{cmp, str::FromStr};
 
-use crate::http::encoding::Encoder;
+use crate::http::body::{BodySize, MessageBody};
+use crate::http::encoding::{Encoder, can_encode};
 use crate::http::header::{ACCEPT_ENCODING, ContentEncoding};
 use crate::service::{Middleware, Service, ServiceCtx, cfg::SharedCfg};
 use crate::web::{BodyEncoding, ErrorRenderer, WebRequest, WebResponse};
@@ -11,6 +12,11 @@ use crate::web::{BodyEncoding, ErrorRenderer, WebRequest, WebResponse};
 ///
 /// Use `BodyEncoding` trait for overriding response compression.
 /// To disable compression set encoding to `ContentEncoding::Identity` value.
+/// Responses smaller than `min_size` are sent uncompressed.
+///
+/// Supported encodings are `gzip` and `deflate`, `zstd` encoding requires
+/// `zstd` feature. Encodings without encoder support are skipped during
+/// `Accept-Encoding` negotiation.
 ///
 /// ```rust
 /// use ntex::web::{self, middleware, App, HttpResponse};
@@ -27,12 +33,37 @@ use crate::web::{BodyEncoding, ErrorRenderer, WebRequest, WebResponse};
 /// ```
 pub struct Compress {
     enc: ContentEncoding,
+    min_size: u64,
+    streams: bool,
 }
 
 impl Compress {
     /// Create new `Compress` middleware with default encoding.
     pub fn new(encoding: ContentEncoding) -> Self {
-        Compress { enc: encoding }
+        Compress {
+            enc: encoding,
+            min_size: 0,
+            streams: true,
+        }
+    }
+
+    #[must_use]
+    /// Set minimum body size for compression.
+    ///
+    /// Bodies with known size below threshold are sent uncompressed.
+    /// By default all bodies are compressed.
+    pub fn min_size(mut self, size: u64) -> Self {
+        self.min_size = size;
+        self
+    }
+
+    #[must_use]
+    /// Compress streaming bodies of unknown size.
+    ///
+    /// By default streaming bodies are compressed.
+    pub fn compress_streams(mut self, enabled: bool) -> Self {
+        self.streams = enabled;
+        self
     }
 }
 
@@ -49,6 +80,8 @@ impl<S> Middleware<S, SharedCfg> for Compress {
         CompressMiddleware {
             service,
             encoding: self.enc,
+            min_size: self.min_size,
+            streams: self.streams,
         }
     }
 }
@@ -57,6 +90,8 @@ impl<S> Middleware<S, SharedCfg> for Compress {
 pub struct CompressMiddleware<S> {
     service: S,
     encoding: ContentEncoding,
+    min_size: u64,
+    streams: bool,
 }
 
 impl<S, E> Service<WebRequest<E>> for CompressMiddleware<S>
@@ -92,7 +127,11 @@ where
         let enc = if let Some(enc) = resp.response().get_encoding() {
             enc
         } else {
-            encoding
+            match resp.response().body().size() {
+                BodySize::Sized(size) if size < self.min_size => ContentEncoding::Identity,
+                BodySize::Stream if !self.streams => ContentEncoding::Identity,
+                _ => encoding,
+            }
         };
 
         Ok(resp.map_body(move |head, body| Encoder::response(enc, head, body)))
@@ -156,7 +195,10 @@ impl AcceptEncoding {
 
         for enc in encodings.into_iter().flatten() {
             if encoding == ContentEncoding::Auto {
-                return enc.encoding;
+                // skip encodings without encoder support
+                if can_encode(enc.encoding) || enc.encoding == ContentEncoding::Identity {
+                    return enc.encoding;
+                }
             } else if encoding == enc.encoding {
                 return encoding;
             }
@@ -168,9 +210,92 @@ impl AcceptEncoding {
 #[cfg(test)]
 #[allow(clippy::float_cmp)]
 mod tests {
-    use std::cmp::Ordering;
+    use std::{cmp::Ordering, io};
 
     use super::*;
+    use crate::http::header::CONTENT_ENCODING;
+    use crate::service::{IntoService, Pipeline};
+    use crate::util::Bytes;
+    use crate::web::test::TestRequest;
+    use crate::web::{DefaultError, Error, HttpResponse};
+
+    async fn call(mw: Compress, body: &'static str, stream: bool) -> WebResponse {
+        let srv = move |req: WebRequest<DefaultError>| async move {
+            let resp = if stream {
+                HttpResponse::Ok().streaming(futures_util::stream::iter([
+                    Ok::<_, io::Error>(Bytes::from_static(body.as_bytes())),
+                ]))
+            } else {
+                HttpResponse::Ok().body(body)
+            };
+            Ok::<_, Error>(req.into_response(resp))
+        };
+        let mw = Pipeline::new(mw.create(srv.into_service(), SharedCfg::default()));
+        let req = TestRequest::default()
+            .header(ACCEPT_ENCODING, "gzip")
+            .to_srv_request();
+        mw.call(req).await.unwrap()
+    }
+
+    #[crate::rt_test]
+    async fn test_min_size() {
+        let data = "data";
+        let mw = Compress::new(ContentEncoding::Gzip);
+        let resp = call(mw.clone(), data, false).await;
+        assert_eq!(resp.headers().get(CONTENT_ENCODING).unwrap(), "gzip");
+
+        let mw = mw.min_size(1024);
+        let resp = call(mw.clone(), data, false).await;
+        assert!(!resp.headers().contains_key(CONTENT_ENCODING));
+        let resp = call(mw.clone(), data, true).await;
+        assert_eq!(resp.headers().get(CONTENT_ENCODING).unwrap(), "gzip");
+
+        let resp = call(mw.compress_streams(false), data, true).await;
+        assert!(!resp.headers().contains_key(CONTENT_ENCODING));
+    }
+
+    #[test]
+    fn test_parse_supported() {
+        // br is not supported
+        assert_eq!(
+            AcceptEncoding::parse("br, gzip;q=0.9", ContentEncoding::Auto),
+            ContentEncoding::Gzip
+        );
+        #[cfg(feature = "zstd")]
+        assert_eq!(
+            AcceptEncoding::parse("gzip, deflate, br, zstd", ContentEncoding::Auto),
+            ContentEncoding::Zstd
+        );
+        #[cfg(not(feature = "zstd"))]
+        assert_eq!(
+            AcceptEncoding::parse("gzip, deflate, br, zstd", ContentEncoding::Auto),
+            ContentEncoding::Gzip
+        );
+    }
+
+    #[cfg(feature = "zstd")]
+    #[crate::rt_test]
+    async fn test_zstd() {
+        let data = "data".repeat(1024);
+        let srv = move |req: WebRequest<DefaultError>| {
+            let data = data.clone();
+            async move { Ok::<_, Error>(req.into_response(HttpResponse::Ok().body(data))) }
+        };
+        let mw = Pipeline::new(
+            Compress::default().create(srv.into_service(), SharedCfg::default()),
+        );
+        let req = TestRequest::default()
+            .header(ACCEPT_ENCODING, "gzip, deflate, br, zstd")
+            .to_srv_request();
+        let resp = mw.call(req).await.unwrap();
+        assert_eq!(resp.headers().get(CONTENT_ENCODING).unwrap(), "zstd");
+
+        let body = crate::web::test::read_body(resp).await;
+        assert_eq!(
+            zstd::decode_all(&body[..]).unwrap(),
+            "data".repeat(1024).as_bytes()
+        );
+    }
 
     #[test]
     fn test_accepting_encodings_equal() {
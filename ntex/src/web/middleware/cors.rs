@@ -0,0 +1,596 @@
+//! Middleware for cross-origin resource sharing
+use std::{fmt, rc::Rc};
+
+use crate::http::error::HttpError;
+use crate::http::header::{self, HeaderMap, HeaderName, HeaderValue};
+use crate::http::{Method, RequestHead};
+use crate::service::{Middleware, Service, ServiceCtx, cfg::SharedCfg};
+use crate::time::Seconds;
+use crate::web::{HttpRequest, HttpResponse, WebRequest, WebResponse};
+
+/// `Middleware` for cross-origin resource sharing (CORS).
+///
+/// Middleware answers preflight `OPTIONS` requests with *204 No Content*
+/// response and adds CORS headers to responses for actual requests. Preflight
+/// requests with not allowed origin, method or headers are rejected with
+/// *403 Forbidden* response. Responses for actual requests from not allowed
+/// origins do not contain CORS headers, so browser blocks them. Requests
+/// without `Origin` header are passed to the service as is.
+///
+/// By default any origin is allowed, and `GET`, `HEAD` and `POST` methods
+/// and any request headers are allowed.
+///
+/// ```rust
+/// use ntex::time::Seconds;
+/// use ntex::web::{self, middleware, App, HttpResponse};
+///
+/// fn main() {
+///     let app = App::new()
+///         .middleware(
+///             middleware::Cors::new()
+///                 .allowed_origin("https://example.com")
+///                 .allowed_origin("https://*.example.com")
+///                 .allowed_methods(["GET", "POST", "DELETE"])
+///                 .allowed_headers(["content-type", "authorization"])
+///                 .supports_credentials()
+///                 .max_age(Seconds(3600)),
+///         )
+///         .service(
+///             web::resource("/test")
+///                 .route(web::get().to(|| async { HttpResponse::Ok() }))
+///         );
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Cors {
+    inner: Rc<Inner>,
+}
+
+struct Inner {
+    origins: Origins,
+    methods: Vec<Method>,
+    headers: Option<Vec<HeaderName>>,
+    expose_headers: Vec<HeaderName>,
+    credentials: bool,
+    max_age: Option<Seconds>,
+}
+
+enum Origins {
+    Any,
+    List(Vec<Origin>),
+    Fn(Box<dyn Fn(&str, &HttpRequest) -> bool>),
+}
+
+#[derive(Debug)]
+enum Origin {
+    Exact(String),
+    Wildcard(String, String),
+}
+
+impl Default for Cors {
+    fn default() -> Self {
+        Cors {
+            inner: Rc::new(Inner {
+                origins: Origins::Any,
+                methods: vec![Method::GET, Method::HEAD, Method::POST],
+                headers: None,
+                expose_headers: Vec::new(),
+                credentials: false,
+                max_age: None,
+            }),
+        }
+    }
+}
+
+impl Cors {
+    #[must_use]
+    /// Construct `Cors` middleware.
+    pub fn new() -> Cors {
+        Cors::default()
+    }
+
+    fn inner(&mut self) -> &mut Inner {
+        Rc::get_mut(&mut self.inner).expect("Multiple copies exist")
+    }
+
+    #[must_use]
+    /// Add allowed origin.
+    ///
+    /// Origin could contain single `*` wildcard, for example
+    /// `https://*.example.com` matches any subdomain of `example.com`.
+    /// Origins are matched case-insensitively. `*` origin allows any origin.
+    /// Overrides origin predicate.
+    pub fn allowed_origin(mut self, origin: &str) -> Self {
+        let inner = self.inner();
+        if origin == "*" {
+            inner.origins = Origins::Any;
+        } else {
+            let origin = match origin.split_once('*') {
+                Some((prefix, suffix)) => Origin::Wildcard(
+                    prefix.to_ascii_lowercase(),
+                    suffix.to_ascii_lowercase(),
+                ),
+                None => Origin::Exact(origin.to_string()),
+            };
+            match inner.origins {
+                Origins::List(ref mut list) => list.push(origin),
+                _ => inner.origins = Origins::List(vec![origin]),
+            }
+        }
+        self
+    }
+
+    #[must_use]
+    /// Set origin predicate.
+    ///
+    /// Predicate receives `Origin` header value and request, origin is
+    /// allowed if predicate returns `true`. Overrides allowed origins.
+    pub fn allowed_origin_fn<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&str, &HttpRequest) -> bool + 'static,
+    {
+        self.inner().origins = Origins::Fn(Box::new(f));
+        self
+    }
+
+    #[must_use]
+    /// Set allowed methods.
+    ///
+    /// By default `GET`, `HEAD` and `POST` methods are allowed.
+    pub fn allowed_methods<I, M>(mut self, methods: I) -> Self
+    where
+        I: IntoIterator<Item = M>,
+        Method: TryFrom<M>,
+    {
+        self.inner().methods = methods
+            .into_iter()
+            .map(|m| Method::try_from(m).unwrap_or_else(|_| panic!("Cannot create method")))
+            .collect();
+        self
+    }
+
+    #[must_use]
+    /// Set allowed request headers.
+    ///
+    /// By default any request headers are allowed.
+    pub fn allowed_headers<I, H>(mut self, headers: I) -> Self
+    where
+        I: IntoIterator<Item = H>,
+        HeaderName: TryFrom<H>,
+        <HeaderName as TryFrom<H>>::Error: Into<HttpError>,
+    {
+        self.inner().headers = Some(header_names(headers));
+        self
+    }
+
+    #[must_use]
+    /// Set response headers that are exposed to the browser.
+    pub fn expose_headers<I, H>(mut self, headers: I) -> Self
+    where
+        I: IntoIterator<Item = H>,
+        HeaderName: TryFrom<H>,
+        <HeaderName as TryFrom<H>>::Error: Into<HttpError>,
+    {
+        self.inner().expose_headers = header_names(headers);
+        self
+    }
+
+    #[must_use]
+    /// Allow requests with credentials.
+    ///
+    /// Credentials require explicit origins, allowed origins must be set
+    /// with `Cors::allowed_origin()` or `Cors::allowed_origin_fn()`.
+    ///
+    /// # Panics
+    ///
+    /// Middleware panics on creation if any origin is allowed.
+    pub fn supports_credentials(mut self) -> Self {
+        self.inner().credentials = true;
+        self
+    }
+
+    #[must_use]
+    /// Set how long preflight response could be cached.
+    pub fn max_age(mut self, max_age: Seconds) -> Self {
+        self.inner().max_age = Some(max_age);
+        self
+    }
+}
+
+fn header_names<I, H>(headers: I) -> Vec<HeaderName>
+where
+    I: IntoIterator<Item = H>,
+    HeaderName: TryFrom<H>,
+    <HeaderName as TryFrom<H>>::Error: Into<HttpError>,
+{
+    headers
+        .into_iter()
+        .map(|h| {
+            HeaderName::try_from(h).unwrap_or_else(|_| panic!("Cannot create header name"))
+        })
+        .collect()
+}
+
+fn join<T: AsRef<str>>(items: &[T]) -> HeaderValue {
+    let value = items
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<Vec<_>>()
+        .join(", ");
+    HeaderValue::try_from(value).unwrap()
+}
+
+impl Inner {
+    /// Get `Access-Control-Allow-Origin` value for the request
+    fn allow_origin(&self, origin: &HeaderValue, req: &HttpRequest) -> Option<HeaderValue> {
+        let allowed = match self.origins {
+            Origins::Any => return Some(HeaderValue::from_static("*")),
+            Origins::List(ref list) => {
+                let origin = origin.to_str().ok()?;
+                list.iter().any(|item| match item {
+                    Origin::Exact(o) => o.eq_ignore_ascii_case(origin),
+                    Origin::Wildcard(prefix, suffix) => {
+                        let origin = origin.as_bytes();
+                        origin.len() > prefix.len() + suffix.len()
+                            && origin[..prefix.len()]
+                                .eq_ignore_ascii_case(prefix.as_bytes())
+                            && origin[origin.len() - suffix.len()..]
+                                .eq_ignore_ascii_case(suffix.as_bytes())
+                    }
+                })
+            }
+            Origins::Fn(ref f) => f(origin.to_str().ok()?, req),
+        };
+        allowed.then(|| origin.clone())
+    }
+
+    /// Check preflight request, returns allowed headers
+    fn check_preflight(&self, head: &RequestHead) -> Option<HeaderValue> {
+        let method = head
+            .headers
+            .get(&header::ACCESS_CONTROL_REQUEST_METHOD)
+            .and_then(|val| Method::from_bytes(val.as_bytes()).ok())?;
+        if !self.methods.contains(&method) {
+            return None;
+        }
+
+        let requested = head.headers.get(&header::ACCESS_CONTROL_REQUEST_HEADERS);
+        match self.headers {
+            None => Some(requested.cloned().unwrap_or(HeaderValue::from_static(""))),
+            Some(ref allowed) => {
+                if let Some(requested) = requested {
+                    for name in requested.to_str().ok()?.split(',') {
+                        let name = name.trim();
+                        if !name.is_empty()
+                            && !allowed
+                                .iter()
+                                .any(|h| h.as_str().eq_ignore_ascii_case(name))
+                        {
+                            return None;
+                        }
+                    }
+                }
+                Some(join(allowed))
+            }
+        }
+    }
+
+    fn set_vary(&self, headers: &mut HeaderMap, preflight: bool) {
+        if preflight {
+            headers.append(
+                header::VARY,
+                HeaderValue::from_static(
+                    "Origin, Access-Control-Request-Method, Access-Control-Request-Headers",
+                ),
+            );
+        } else if !matches!(self.origins, Origins::Any) {
+            headers.append(header::VARY, HeaderValue::from_static("Origin"));
+        }
+    }
+}
+
+impl fmt::Debug for Inner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cors")
+            .field("methods", &self.methods)
+            .field("headers", &self.headers)
+            .field("expose_headers", &self.expose_headers)
+            .field("credentials", &self.credentials)
+            .field("max_age", &self.max_age)
+            .finish()
+    }
+}
+
+impl<S> Middleware<S, SharedCfg> for Cors {
+    type Service = CorsMiddleware<S>;
+
+    fn create(&self, service: S, _: SharedCfg) -> Self::Service {
+        assert!(
+            !(self.inner.credentials && matches!(self.inner.origins, Origins::Any)),
+            "Cors: credentials are not supported with any origin, \
+             use `Cors::allowed_origin()` or `Cors::allowed_origin_fn()`"
+        );
+        CorsMiddleware {
+            service,
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CorsMiddleware<S> {
+    service: S,
+    inner: Rc<Inner>,
+}
+
+impl<S, E> Service<WebRequest<E>> for CorsMiddleware<S>
+where
+    S: Service<WebRequest<E>, Response = WebResponse>,
+{
+    type Response = WebResponse;
+    type Error = S::Error;
+
+    crate::forward_poll!(service);
+    crate::forward_ready!(service);
+    crate::forward_shutdown!(service);
+
+    async fn call(
+        &self,
+        req: WebRequest<E>,
+        ctx: ServiceCtx<'_, Self>,
+    ) -> Result<Self::Response, Self::Error> {
+        let Some(origin) = req.headers().get(&header::ORIGIN).cloned() else {
+            return ctx.call(&self.service, req).await;
+        };
+        let allow_origin = self.inner.allow_origin(&origin, req.http_request());
+
+        // preflight request
+        if *req.method() == Method::OPTIONS
+            && req
+                .headers()
+                .contains_key(&header::ACCESS_CONTROL_REQUEST_METHOD)
+        {
+            let allow_headers = self.inner.check_preflight(req.head());
+            let (Some(allow_origin), Some(allow_headers)) = (allow_origin, allow_headers)
+            else {
+                log::debug!("CORS preflight request is not allowed, origin: {origin:?}");
+                return Ok(req.into_response(HttpResponse::Forbidden().finish()));
+            };
+
+            let mut res = HttpResponse::NoContent();
+            res.header(header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin)
+                .header(
+                    header::ACCESS_CONTROL_ALLOW_METHODS,
+                    join(&self.inner.methods),
+                );
+            if !allow_headers.is_empty() {
+                res.header(header::ACCESS_CONTROL_ALLOW_HEADERS, allow_headers);
+            }
+            if self.inner.credentials {
+                res.header(
+                    header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                    HeaderValue::from_static("true"),
+                );
+            }
+            if let Some(max_age) = self.inner.max_age {
+                res.header(
+                    header::ACCESS_CONTROL_MAX_AGE,
+                    HeaderValue::from(max_age.seconds()),
+                );
+            }
+            let mut res = res.finish();
+            self.inner.set_vary(res.headers_mut(), true);
+            return Ok(req.into_response(res));
+        }
+
+        let mut res = ctx.call(&self.service, req).await?;
+        if let Some(allow_origin) = allow_origin {
+            let headers = res.headers_mut();
+            headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+            if self.inner.credentials {
+                headers.insert(
+                    header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                    HeaderValue::from_static("true"),
+                );
+            }
+            if !self.inner.expose_headers.is_empty() {
+                headers.insert(
+                    header::ACCESS_CONTROL_EXPOSE_HEADERS,
+                    join(&self.inner.expose_headers),
+                );
+            }
+        }
+        self.inner.set_vary(res.headers_mut(), false);
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::StatusCode;
+    use crate::service::Pipeline;
+    use crate::web::test::{TestRequest, ok_service};
+
+    fn preflight(origin: &str, method: &str) -> TestRequest {
+        TestRequest::default()
+            .method(Method::OPTIONS)
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, method)
+    }
+
+    #[crate::rt_test]
+    async fn test_any_origin() {
+        let mw = Pipeline::new(Cors::new().create(ok_service(), SharedCfg::default()));
+
+        // not cors request
+        let req = TestRequest::default().to_srv_request();
+        let resp = mw.call(req).await.unwrap();
+        assert!(
+            !resp
+                .headers()
+                .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+        );
+
+        let req = TestRequest::with_header(header::ORIGIN, "https://example.com")
+            .to_srv_request();
+        let resp = mw.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers()
+                .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+                .unwrap(),
+            "*"
+        );
+        assert!(!resp.headers().contains_key(header::VARY));
+
+        let req = preflight("https://example.com", "POST")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "x-custom")
+            .to_srv_request();
+        let resp = mw.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            resp.headers()
+                .get(header::ACCESS_CONTROL_ALLOW_METHODS)
+                .unwrap(),
+            "GET, HEAD, POST"
+        );
+        assert_eq!(
+            resp.headers()
+                .get(header::ACCESS_CONTROL_ALLOW_HEADERS)
+                .unwrap(),
+            "x-custom"
+        );
+        assert!(!resp.headers().contains_key(header::ACCESS_CONTROL_MAX_AGE));
+
+        let req = preflight("https://example.com", "DELETE").to_srv_request();
+        let resp = mw.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[crate::rt_test]
+    async fn test_origins() {
+        let mw = Pipeline::new(
+            Cors::new()
+                .allowed_origin("https://example.com")
+                .allowed_origin("https://*.example.org")
+                .allowed_methods([Method::GET, Method::PUT])
+                .allowed_headers(["content-type"])
+                .expose_headers(["x-total"])
+                .supports_credentials()
+                .max_age(Seconds(600))
+                .create(ok_service(), SharedCfg::default()),
+        );
+
+        for origin in [
+            "https://example.com",
+            "https://api.example.org",
+            "https://API.Example.ORG",
+        ] {
+            let req = TestRequest::with_header(header::ORIGIN, origin).to_srv_request();
+            let resp = mw.call(req).await.unwrap();
+            let headers = resp.headers();
+            assert_eq!(
+                headers.get(header::ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+                origin
+            );
+            assert_eq!(
+                headers
+                    .get(header::ACCESS_CONTROL_ALLOW_CREDENTIALS)
+                    .unwrap(),
+                "true"
+            );
+            assert_eq!(
+                headers.get(header::ACCESS_CONTROL_EXPOSE_HEADERS).unwrap(),
+                "x-total"
+            );
+            assert_eq!(headers.get(header::VARY).unwrap(), "Origin");
+        }
+
+        for origin in [
+            "https://other.com",
+            "https://example.org",
+            "http://a.example.org",
+        ] {
+            let req = TestRequest::with_header(header::ORIGIN, origin).to_srv_request();
+            let resp = mw.call(req).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert!(
+                !resp
+                    .headers()
+                    .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            );
+
+            let req = preflight(origin, "GET").to_srv_request();
+            let resp = mw.call(req).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        let req = preflight("https://example.com", "PUT")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "Content-Type")
+            .to_srv_request();
+        let resp = mw.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        let headers = resp.headers();
+        assert_eq!(
+            headers.get(header::ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "https://example.com"
+        );
+        assert_eq!(
+            headers.get(header::ACCESS_CONTROL_ALLOW_METHODS).unwrap(),
+            "GET, PUT"
+        );
+        assert_eq!(
+            headers.get(header::ACCESS_CONTROL_ALLOW_HEADERS).unwrap(),
+            "content-type"
+        );
+        assert_eq!(headers.get(header::ACCESS_CONTROL_MAX_AGE).unwrap(), "600");
+
+        let req = preflight("https://example.com", "PUT")
+            .header(
+                header::ACCESS_CONTROL_REQUEST_HEADERS,
+                "content-type, x-other",
+            )
+            .to_srv_request();
+        let resp = mw.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[crate::rt_test]
+    async fn test_origin_fn() {
+        let mw = Pipeline::new(
+            Cors::new()
+                .allowed_origin_fn(|origin, _| origin.ends_with(".local"))
+                .create(ok_service(), SharedCfg::default()),
+        );
+
+        let req =
+            TestRequest::with_header(header::ORIGIN, "http://app.local").to_srv_request();
+        let resp = mw.call(req).await.unwrap();
+        assert_eq!(
+            resp.headers()
+                .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+                .unwrap(),
+            "http://app.local"
+        );
+
+        let req = preflight("http://app.remote", "GET").to_srv_request();
+        let resp = mw.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[crate::rt_test]
+    #[should_panic(expected = "credentials are not supported with any origin")]
+    async fn test_credentials_any_origin() {
+        let _ = Cors::new()
+            .supports_credentials()
+            .allowed_origin("*")
+            .create(ok_service(), SharedCfg::default());
+    }
+
+    #[crate::rt_test]
+    #[should_panic(expected = "Cannot create header name")]
+    async fn test_invalid_header_name() {
+        let _ = Cors::new().allowed_headers(["no existing header name"]);
+    }
+}
@@ -0,0 +1,269 @@
+//! `Middleware` for decompressing request body.
+use crate::http::encoding::Decoder;
+use crate::http::header::{CONTENT_ENCODING, CONTENT_LENGTH, ContentEncoding, HeaderValue};
+use crate::http::{Payload, Response};
+use crate::service::{Middleware, Service, ServiceCtx, cfg::SharedCfg};
+use crate::web::{WebRequest, WebResponse};
+
+#[derive(Debug, Clone)]
+/// `Middleware` for decompressing request body.
+///
+/// Request payload encoded with `gzip`, `deflate`, `br` (requires `brotli`
+/// feature) or `zstd` (requires `zstd` feature) content encoding gets decoded
+/// before it reaches extractors.
+/// `Content-Encoding` and `Content-Length` headers are removed from decoded
+/// requests.
+///
+/// Size of decoded payload is limited, if decoded payload exceeds limit
+/// `PayloadError::Overflow` get returned from payload stream. Limit is checked
+/// during decoding, decoder never buffers more than limit. Default limit is 2Mb.
+///
+/// Requests with unsupported content encoding are passed through untouched,
+/// use `Decompress::reject_unsupported()` to respond with
+/// *415 Unsupported Media Type* instead.
+///
+/// ```rust
+/// use ntex::web::{self, middleware, App, HttpResponse};
+///
+/// fn main() {
+///     let app = App::new()
+///         .middleware(middleware::Decompress::default().limit(65_536))
+///         .service(
+///             web::resource("/test")
+///                 .route(web::post().to(|body: String| async move { body }))
+///         );
+/// }
+/// ```
+pub struct Decompress {
+    limit: usize,
+    reject: bool,
+}
+
+impl Decompress {
+    /// Create new `Decompress` middleware.
+    pub fn new() -> Self {
+        Decompress {
+            limit: 2_097_152,
+            reject: false,
+        }
+    }
+
+    #[must_use]
+    /// Change max size of decoded payload.
+    ///
+    /// By default max size is 2Mb.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    #[must_use]
+    /// Respond with *415 Unsupported Media Type* for unsupported content encodings.
+    pub fn reject_unsupported(mut self) -> Self {
+        self.reject = true;
+        self
+    }
+}
+
+impl Default for Decompress {
+    fn default() -> Self {
+        Decompress::new()
+    }
+}
+
+impl<S> Middleware<S, SharedCfg> for Decompress {
+    type Service = DecompressMiddleware<S>;
+
+    fn create(&self, service: S, _: SharedCfg) -> Self::Service {
+        DecompressMiddleware {
+            service,
+            limit: self.limit,
+            reject: self.reject,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DecompressMiddleware<S> {
+    service: S,
+    limit: usize,
+    reject: bool,
+}
+
+impl<S, E> Service<WebRequest<E>> for DecompressMiddleware<S>
+where
+    S: Service<WebRequest<E>, Response = WebResponse>,
+{
+    type Response = WebResponse;
+    type Error = S::Error;
+
+    crate::forward_poll!(service);
+    crate::forward_ready!(service);
+    crate::forward_shutdown!(service);
+
+    async fn call(
+        &self,
+        mut req: WebRequest<E>,
+        ctx: ServiceCtx<'_, Self>,
+    ) -> Result<WebResponse, S::Error> {
+        if let Some(val) = req.headers().get(&CONTENT_ENCODING) {
+            match encoding(val) {
+                Some(ContentEncoding::Identity) => (),
+                Some(enc) => {
+                    let payload = req.take_payload();
+                    req.set_payload(Payload::from_stream(
+                        Decoder::new(payload, enc).limit(self.limit),
+                    ));
+                    req.headers_mut().remove(&CONTENT_ENCODING);
+                    req.headers_mut().remove(&CONTENT_LENGTH);
+                }
+                None => {
+                    if self.reject {
+                        return Ok(req.into_response(Response::UnsupportedMediaType()));
+                    }
+                }
+            }
+        }
+        ctx.call(&self.service, req).await
+    }
+}
+
+/// Get supported content encoding from header value
+fn encoding(val: &HeaderValue) -> Option<ContentEncoding> {
+    let val = val.to_str().ok()?;
+    match ContentEncoding::from(val) {
+        enc @ (ContentEncoding::Gzip | ContentEncoding::Deflate) => Some(enc),
+        #[cfg(feature = "brotli")]
+        enc @ ContentEncoding::Br => Some(enc),
+        #[cfg(feature = "zstd")]
+        enc @ ContentEncoding::Zstd => Some(enc),
+        _ if val.trim().eq_ignore_ascii_case("identity") => Some(ContentEncoding::Identity),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{Compression, write::GzEncoder};
+
+    use super::*;
+    use crate::http::{StatusCode, header};
+    use crate::util::{Bytes, BytesMut};
+    use crate::web::test::{TestRequest, call_service, init_service, read_body};
+    use crate::web::{self, App, HttpResponse};
+
+    fn gzip(data: &[u8]) -> Bytes {
+        let mut e = GzEncoder::new(Vec::new(), Compression::default());
+        e.write_all(data).unwrap();
+        Bytes::from(e.finish().unwrap())
+    }
+
+    async fn index(
+        mut body: web::types::Payload,
+    ) -> Result<HttpResponse, crate::http::error::PayloadError> {
+        let mut bytes = BytesMut::new();
+        while let Some(item) = body.recv().await {
+            bytes.extend_from_slice(&item?);
+        }
+        Ok(HttpResponse::Ok().body(bytes.freeze()))
+    }
+
+    #[crate::rt_test]
+    async fn test_gzip() {
+        let srv = init_service(
+            App::new()
+                .middleware(Decompress::default())
+                .route("/", web::post().to(|body: Bytes| async move { body })),
+        )
+        .await;
+
+        let data = "hello world".repeat(1024);
+        let req = TestRequest::post()
+            .header(header::CONTENT_ENCODING, "gzip")
+            .set_payload(gzip(data.as_bytes()))
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(read_body(resp).await, Bytes::from(data));
+    }
+
+    #[cfg(feature = "brotli")]
+    #[crate::rt_test]
+    async fn test_brotli() {
+        let srv = init_service(
+            App::new()
+                .middleware(Decompress::default())
+                .route("/", web::post().to(|body: Bytes| async move { body })),
+        )
+        .await;
+
+        let data = "hello world".repeat(1024);
+        let mut e = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+        e.write_all(data.as_bytes()).unwrap();
+        let req = TestRequest::post()
+            .header(header::CONTENT_ENCODING, "br")
+            .set_payload(Bytes::from(e.into_inner()))
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(read_body(resp).await, Bytes::from(data));
+    }
+
+    #[crate::rt_test]
+    async fn test_limit() {
+        let srv = init_service(
+            App::new()
+                .middleware(Decompress::default().limit(1024))
+                .route("/", web::post().to(index)),
+        )
+        .await;
+
+        let data = "a".repeat(1024);
+        let req = TestRequest::post()
+            .header(header::CONTENT_ENCODING, "gzip")
+            .set_payload(gzip(data.as_bytes()))
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let data = "a".repeat(64 * 1024);
+        let req = TestRequest::post()
+            .header(header::CONTENT_ENCODING, "gzip")
+            .set_payload(gzip(data.as_bytes()))
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[crate::rt_test]
+    async fn test_unsupported() {
+        let srv = init_service(
+            App::new()
+                .middleware(Decompress::default())
+                .route("/", web::post().to(index)),
+        )
+        .await;
+        let req = TestRequest::post()
+            .header(header::CONTENT_ENCODING, "compress")
+            .set_payload(Bytes::from_static(b"data"))
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"data"));
+
+        let srv = init_service(
+            App::new()
+                .middleware(Decompress::default().reject_unsupported())
+                .route("/", web::post().to(index)),
+        )
+        .await;
+        let req = TestRequest::post()
+            .header(header::CONTENT_ENCODING, "compress")
+            .set_payload(Bytes::from_static(b"data"))
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+}
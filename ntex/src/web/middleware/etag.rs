@@ -0,0 +1,254 @@
+//! Middleware for response `ETag` generation
+use std::hash::{BuildHasher, BuildHasherDefault, Hasher};
+use std::rc::Rc;
+
+use crate::http::body::{Body, ResponseBody};
+use crate::http::header::{CONTENT_LENGTH, CONTENT_TYPE, ETAG, HeaderValue, IF_NONE_MATCH};
+use crate::http::{Method, StatusCode};
+use crate::service::{Middleware, Service, ServiceCtx, cfg::SharedCfg};
+use crate::web::{WebRequest, WebResponse};
+
+/// `Middleware` for response `ETag` generation and conditional requests.
+///
+/// Middleware hashes buffered bodies of successful `GET` and `HEAD`
+/// responses and sets `ETag` header. If request's `If-None-Match` header
+/// matches generated tag, *304 Not Modified* response without body is sent.
+/// Streaming responses and responses that already contain `ETag` header
+/// are not modified.
+///
+/// By default 64-bit FNV-1a hash is used, so tags stay the same across
+/// restarts and releases. Custom hasher could be set with
+/// `ETag::with_hasher()`. Hasher must produce stable values.
+///
+/// ```rust
+/// use ntex::web::{self, middleware, App, HttpResponse};
+///
+/// fn main() {
+///     let app = App::new()
+///         .middleware(middleware::ETag::default())
+///         .service(
+///             web::resource("/test")
+///                 .route(web::get().to(|| async { HttpResponse::Ok().body("data") }))
+///         );
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ETag<H = BuildHasherDefault<FnvHasher>> {
+    hasher: Rc<H>,
+}
+
+impl Default for ETag {
+    fn default() -> Self {
+        ETag::new()
+    }
+}
+
+impl ETag {
+    /// Create new `ETag` middleware.
+    pub fn new() -> Self {
+        ETag::with_hasher(BuildHasherDefault::default())
+    }
+}
+
+impl<H: BuildHasher> ETag<H> {
+    /// Create new `ETag` middleware with custom hasher.
+    pub fn with_hasher(hasher: H) -> Self {
+        ETag {
+            hasher: Rc::new(hasher),
+        }
+    }
+}
+
+impl<S, H> Middleware<S, SharedCfg> for ETag<H> {
+    type Service = ETagMiddleware<S, H>;
+
+    fn create(&self, service: S, _: SharedCfg) -> Self::Service {
+        ETagMiddleware {
+            service,
+            hasher: self.hasher.clone(),
+        }
+    }
+}
+
+/// 64-bit FNV-1a hasher.
+///
+/// Unlike std `DefaultHasher`, produced values are stable.
+#[derive(Copy, Clone, Debug)]
+pub struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        FnvHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[derive(Debug)]
+pub struct ETagMiddleware<S, H> {
+    service: S,
+    hasher: Rc<H>,
+}
+
+impl<S, H, E> Service<WebRequest<E>> for ETagMiddleware<S, H>
+where
+    S: Service<WebRequest<E>, Response = WebResponse>,
+    H: BuildHasher,
+{
+    type Response = WebResponse;
+    type Error = S::Error;
+
+    crate::forward_poll!(service);
+    crate::forward_ready!(service);
+    crate::forward_shutdown!(service);
+
+    async fn call(
+        &self,
+        req: WebRequest<E>,
+        ctx: ServiceCtx<'_, Self>,
+    ) -> Result<Self::Response, Self::Error> {
+        let mut res = ctx.call(&self.service, req).await?;
+
+        if !matches!(*res.request().method(), Method::GET | Method::HEAD)
+            || res.status() != StatusCode::OK
+            || res.headers().contains_key(&ETAG)
+        {
+            return Ok(res);
+        }
+
+        // only buffered bodies
+        let tag = match res.response().body() {
+            ResponseBody::Body(Body::Bytes(bytes))
+            | ResponseBody::Other(Body::Bytes(bytes)) => {
+                let mut hasher = self.hasher.build_hasher();
+                hasher.write(bytes);
+                format!("\"{:x}-{:016x}\"", bytes.len(), hasher.finish())
+            }
+            _ => return Ok(res),
+        };
+
+        let not_modified = res
+            .request()
+            .headers()
+            .get_all(&IF_NONE_MATCH)
+            .filter_map(|val| val.to_str().ok())
+            .flat_map(|val| val.split(','))
+            .map(str::trim)
+            .any(|val| val == "*" || val.strip_prefix("W/").unwrap_or(val) == tag);
+
+        res.headers_mut()
+            .insert(ETAG, HeaderValue::try_from(tag).unwrap());
+
+        if not_modified {
+            let _ = res.take_body();
+            *res.response_mut().status_mut() = StatusCode::NOT_MODIFIED;
+            res.headers_mut().remove(&CONTENT_TYPE);
+            res.headers_mut().remove(&CONTENT_LENGTH);
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+    use crate::service::{IntoService, Pipeline};
+    use crate::util::Bytes;
+    use crate::web::test::{TestRequest, ok_service};
+    use crate::web::{DefaultError, Error, HttpResponse};
+
+    #[crate::rt_test]
+    async fn test_etag() {
+        let srv = |req: WebRequest<DefaultError>| async move {
+            Ok::<_, Error>(req.into_response(HttpResponse::Ok().body("data")))
+        };
+        let mw =
+            Pipeline::new(ETag::new().create(srv.into_service(), SharedCfg::default()));
+
+        let req = TestRequest::default().to_srv_request();
+        let resp = mw.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let tag = resp.headers().get(ETAG).unwrap().clone();
+        assert_eq!(tag, "\"4-855b556730a34a05\"");
+
+        // same body, same tag
+        let req = TestRequest::default().to_srv_request();
+        let resp = mw.call(req).await.unwrap();
+        assert_eq!(resp.headers().get(ETAG).unwrap(), &tag);
+
+        let req = TestRequest::default()
+            .header(IF_NONE_MATCH, tag.clone())
+            .to_srv_request();
+        let mut resp = mw.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(resp.headers().get(ETAG).unwrap(), &tag);
+        assert!(matches!(resp.take_body(), ResponseBody::Other(Body::None)));
+
+        let req = TestRequest::default()
+            .header(
+                IF_NONE_MATCH,
+                format!("\"other\", W/{}", tag.to_str().unwrap()),
+            )
+            .to_srv_request();
+        let resp = mw.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+
+        let req = TestRequest::default()
+            .header(IF_NONE_MATCH, "\"other\"")
+            .to_srv_request();
+        let resp = mw.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // only GET and HEAD
+        let req = TestRequest::default()
+            .method(Method::POST)
+            .header(IF_NONE_MATCH, tag)
+            .to_srv_request();
+        let resp = mw.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(!resp.headers().contains_key(ETAG));
+    }
+
+    #[test]
+    fn test_fnv_hasher() {
+        let mut hasher = FnvHasher::default();
+        assert_eq!(hasher.finish(), 0xcbf2_9ce4_8422_2325);
+        hasher.write(b"data");
+        assert_eq!(hasher.finish(), 0x855b_5567_30a3_4a05);
+    }
+
+    #[crate::rt_test]
+    async fn test_etag_skip() {
+        // empty body
+        let mw = Pipeline::new(ETag::new().create(ok_service(), SharedCfg::default()));
+        let req = TestRequest::default().to_srv_request();
+        let resp = mw.call(req).await.unwrap();
+        assert!(!resp.headers().contains_key(ETAG));
+
+        // streaming body
+        let srv = |req: WebRequest<DefaultError>| async move {
+            Ok::<_, Error>(req.into_response(HttpResponse::Ok().streaming(
+                futures_util::stream::iter([Ok::<_, io::Error>(Bytes::from_static(
+                    b"data",
+                ))]),
+            )))
+        };
+        let mw =
+            Pipeline::new(ETag::new().create(srv.into_service(), SharedCfg::default()));
+        let req = TestRequest::default().to_srv_request();
+        let resp = mw.call(req).await.unwrap();
+        assert!(!resp.headers().contains_key(ETAG));
+    }
+}
@@ -6,10 +6,10 @@ use std::{env, error::Error, fmt, fmt::Display, rc::Rc, time};
 use regex::Regex;
 
 use crate::http::body::{Body, BodySize, MessageBody, ResponseBody};
-use crate::http::header::HeaderName;
+use crate::http::{SentBytes, header::HeaderName};
 use crate::service::{Middleware, Service, ServiceCtx, cfg::SharedCfg};
-use crate::util::{Bytes, HashSet};
-use crate::web::{HttpResponse, WebRequest, WebResponse};
+use crate::util::{Bytes, HashMap, HashSet};
+use crate::web::{HttpRequest, HttpResponse, WebRequest, WebResponse};
 
 /// `Middleware` for logging request and response info to the terminal.
 ///
@@ -51,6 +51,9 @@ use crate::web::{HttpResponse, WebRequest, WebResponse};
 ///
 /// `%b`  Size of response in bytes, including HTTP headers
 ///
+/// `%B`  Size of response body in bytes as written to the connection, after
+/// compression and transfer encoding (http/1 only, see `http::SentBytes`)
+///
 /// `%T` Time taken to serve the request, in seconds with floating fraction in
 /// .06f format
 ///
@@ -64,15 +67,21 @@ use crate::web::{HttpResponse, WebRequest, WebResponse};
 ///
 /// `%{FOO}e`  os.environ[`FOO`]
 ///
+/// `%{FOO}x`  value of custom token `FOO`, see `Logger::custom()`
+///
 #[derive(Debug)]
 pub struct Logger {
     inner: Rc<Inner>,
 }
 
-#[derive(Debug)]
+type CustomFn = Rc<dyn Fn(&HttpRequest, &HttpResponse) -> String>;
+
+#[derive(derive_more::Debug)]
 struct Inner {
     format: Format,
     exclude: HashSet<String>,
+    #[debug(skip)]
+    custom: HashMap<String, CustomFn>,
 }
 
 impl Logger {
@@ -82,6 +91,7 @@ impl Logger {
             inner: Rc::new(Inner {
                 format: Format::new(format),
                 exclude: HashSet::default(),
+                custom: HashMap::default(),
             }),
         }
     }
@@ -95,6 +105,38 @@ impl Logger {
             .insert(path.into());
         self
     }
+
+    #[must_use]
+    /// Register custom format token.
+    ///
+    /// Token value is computed by the function once response is ready and
+    /// could be used in the format string as `%{name}x`. Unregistered custom
+    /// tokens are logged as `-`.
+    ///
+    /// ```rust
+    /// use ntex::web::{App, middleware::Logger};
+    ///
+    /// #[derive(Clone)]
+    /// struct Tenant(String);
+    ///
+    /// let app = App::new().middleware(
+    ///     Logger::new("%{tenant}x %r %s").custom("tenant", |req, _| {
+    ///         req.extensions()
+    ///             .get::<Tenant>()
+    ///             .map_or_else(|| "-".to_string(), |t| t.0.clone())
+    ///     }),
+    /// );
+    /// ```
+    pub fn custom<F>(mut self, name: &str, f: F) -> Self
+    where
+        F: Fn(&HttpRequest, &HttpResponse) -> String + 'static,
+    {
+        Rc::get_mut(&mut self.inner)
+            .unwrap()
+            .custom
+            .insert(name.to_string(), Rc::new(f));
+        self
+    }
 }
 
 impl Default for Logger {
@@ -108,6 +150,7 @@ impl Default for Logger {
             inner: Rc::new(Inner {
                 format: Format::default(),
                 exclude: HashSet::default(),
+                custom: HashMap::default(),
             }),
         }
     }
@@ -160,6 +203,7 @@ where
             let res = ctx.call(&self.service, req).await?;
             for unit in &mut format.0 {
                 unit.render_response(res.response());
+                unit.render_custom(&self.inner.custom, &res);
             }
 
             Ok(res.map_body(move |_, body| {
@@ -233,7 +277,7 @@ impl Format {
     /// Returns `None` if the format string syntax is incorrect.
     fn new(s: &str) -> Format {
         log::trace!("Access log format: {s}");
-        let fmt = Regex::new(r"%(\{([A-Za-z0-9\-_]+)\}([ioe])|[atPrUsbTD]?)").unwrap();
+        let fmt = Regex::new(r"%(\{([A-Za-z0-9\-_]+)\}([ioex])|[atPrUsbBTD]?)").unwrap();
 
         let mut idx = 0;
         let mut results = Vec::new();
@@ -254,6 +298,7 @@ impl Format {
                         HeaderName::try_from(key.as_str()).unwrap(),
                     ),
                     "e" => FormatText::EnvironHeader(key.as_str().to_owned()),
+                    "x" => FormatText::Custom(key.as_str().to_owned()),
                     _ => unreachable!(),
                 });
             } else {
@@ -265,6 +310,7 @@ impl Format {
                     "r" => FormatText::RequestLine,
                     "s" => FormatText::ResponseStatus,
                     "b" => FormatText::ResponseSize,
+                    "B" => FormatText::ResponseSentSize,
                     "U" => FormatText::UrlPath,
                     "T" => FormatText::Time,
                     "D" => FormatText::TimeMillis,
@@ -291,6 +337,8 @@ enum FormatText {
     RequestTime,
     ResponseStatus,
     ResponseSize,
+    ResponseSentSize,
+    SentBytes(SentBytes),
     Time,
     TimeMillis,
     RemoteAddr,
@@ -298,6 +346,7 @@ enum FormatText {
     RequestHeader(HeaderName),
     ResponseHeader(HeaderName),
     EnvironHeader(String),
+    Custom(String),
 }
 
 impl FormatText {
@@ -311,6 +360,7 @@ impl FormatText {
             FormatText::Str(ref string) => fmt.write_str(string),
             FormatText::Percent => "%".fmt(fmt),
             FormatText::ResponseSize => size.fmt(fmt),
+            FormatText::SentBytes(ref sent) => sent.get().fmt(fmt),
             FormatText::Time => {
                 let rt = entry_time.elapsed().unwrap();
                 let rt = rt.as_secs_f64();
@@ -345,10 +395,30 @@ impl FormatText {
                 };
                 *self = FormatText::Str(s.to_string());
             }
+            FormatText::ResponseSentSize => {
+                let sent = res.extensions().get::<SentBytes>().cloned();
+                let sent = sent.unwrap_or_else(|| {
+                    let sent = SentBytes::new();
+                    res.extensions_mut().insert(sent.clone());
+                    sent
+                });
+                *self = FormatText::SentBytes(sent);
+            }
             _ => (),
         }
     }
 
+    fn render_custom(&mut self, custom: &HashMap<String, CustomFn>, res: &WebResponse) {
+        if let FormatText::Custom(ref name) = *self {
+            let s = if let Some(f) = custom.get(name) {
+                f(res.request(), res.response())
+            } else {
+                "-".to_string()
+            };
+            *self = FormatText::Str(s);
+        }
+    }
+
     fn render_request<E>(&mut self, now: time::SystemTime, req: &WebRequest<E>) {
         match *self {
             FormatText::RequestLine => {
@@ -455,6 +525,44 @@ mod tests {
         assert_eq!(body, Bytes::from_static(b"TEST"));
     }
 
+    #[crate::rt_test]
+    async fn test_custom_token() {
+        #[derive(Clone)]
+        struct Tenant(&'static str);
+
+        let logger =
+            Logger::new("%{tenant}x %{unknown}x %s").custom("tenant", |req, res| {
+                format!(
+                    "{}:{}",
+                    req.extensions().get::<Tenant>().unwrap().0,
+                    res.headers().get("X-Test").unwrap().to_str().unwrap()
+                )
+            });
+        let mut format = logger.inner.format.clone();
+
+        let req = TestRequest::default().to_srv_request();
+        req.extensions_mut().insert(Tenant("acme"));
+        let res = req.into_response(
+            HttpResponse::build(StatusCode::OK)
+                .header("X-Test", "ttt")
+                .finish(),
+        );
+        for unit in &mut format.0 {
+            unit.render_response(res.response());
+            unit.render_custom(&logger.inner.custom, &res);
+        }
+
+        let now = time::SystemTime::now();
+        let render = |fmt: &mut fmt::Formatter<'_>| {
+            for unit in &format.0 {
+                unit.render(fmt, 1024, now)?;
+            }
+            Ok(())
+        };
+        let s = format!("{}", FormatDisplay(&render));
+        assert_eq!(s, "acme:ttt - 200");
+    }
+
     #[crate::rt_test]
     async fn test_request_line() {
         let mut format = Format::new("%r");
@@ -485,6 +593,26 @@ mod tests {
         assert_eq!(s, "GET /test/route/yeah?q=test HTTP/1.1");
     }
 
+    #[crate::rt_test]
+    async fn test_sent_size() {
+        let mut format = Format::new("%b %B");
+        let resp = HttpResponse::build(StatusCode::OK).finish();
+        for unit in &mut format.0 {
+            unit.render_response(&resp);
+        }
+        resp.extensions().get::<SentBytes>().unwrap().add(512);
+
+        let now = time::SystemTime::now();
+        let render = |fmt: &mut fmt::Formatter<'_>| {
+            for unit in &format.0 {
+                unit.render(fmt, 1024, now)?;
+            }
+            Ok(())
+        };
+        let s = format!("{}", FormatDisplay(&render));
+        assert_eq!(s, "1024 512");
+    }
+
     #[crate::rt_test]
     async fn test_url_path() {
         let mut format = Format::new("%T %U");
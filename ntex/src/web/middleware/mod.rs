@@ -5,8 +5,34 @@ mod compress;
 #[cfg(feature = "compress")]
 pub use self::compress::Compress;
 
+#[cfg(feature = "compress")]
+mod decompress;
+#[cfg(feature = "compress")]
+pub use self::decompress::Decompress;
+
 mod logger;
 pub use self::logger::Logger;
 
 mod defaultheaders;
 pub use self::defaultheaders::DefaultHeaders;
+
+mod etag;
+pub use self::etag::{ETag, FnvHasher};
+
+mod timeout;
+pub use self::timeout::Timeout;
+
+mod catch_panic;
+pub use self::catch_panic::CatchPanic;
+
+mod normalize;
+pub use self::normalize::{NormalizePath, TrailingSlash};
+
+mod ratelimit;
+pub use self::ratelimit::{MemoryStore, RateLimit, RateLimitStore};
+
+mod cors;
+pub use self::cors::Cors;
+
+mod response_time;
+pub use self::response_time::{ResponseTime, TimeUnit};
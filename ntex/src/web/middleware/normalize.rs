@@ -0,0 +1,230 @@
+//! Middleware for request path normalization
+use crate::http::header::{HeaderValue, LOCATION};
+use crate::http::uri::{PathAndQuery, Uri};
+use crate::service::{Middleware, Service, ServiceCtx, cfg::SharedCfg};
+use crate::web::{HttpResponse, WebRequest, WebResponse};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+/// Trailing slash handling for `NormalizePath` middleware
+pub enum TrailingSlash {
+    /// Remove trailing slash, root path stays `/`
+    #[default]
+    Trim,
+    /// Add trailing slash
+    Always,
+    /// Keep trailing slash as is, only merge duplicate slashes
+    MergeOnly,
+}
+
+/// `Middleware` for request path normalization.
+///
+/// Middleware merges duplicate slashes and handles trailing slash according
+/// to `TrailingSlash` setting, so `/a//b/` becomes `/a/b`. Path is normalized
+/// before router recognizes the route. By default request path is rewritten,
+/// with `NormalizePath::redirect()` client is redirected with
+/// *301 Moved Permanently* to the normalized path instead.
+///
+/// ```rust
+/// use ntex::web::{self, middleware, App, HttpResponse};
+///
+/// fn main() {
+///     let app = App::new()
+///         .middleware(middleware::NormalizePath::new().redirect(true))
+///         .service(
+///             web::resource("/a/b")
+///                 .route(web::get().to(|| async { HttpResponse::Ok() }))
+///         );
+/// }
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NormalizePath {
+    trailing_slash: TrailingSlash,
+    redirect: bool,
+}
+
+impl NormalizePath {
+    /// Construct `NormalizePath` middleware.
+    pub fn new() -> Self {
+        NormalizePath::default()
+    }
+
+    #[must_use]
+    /// Set trailing slash handling.
+    ///
+    /// Default is `TrailingSlash::Trim`.
+    pub fn trailing_slash(mut self, trailing_slash: TrailingSlash) -> Self {
+        self.trailing_slash = trailing_slash;
+        self
+    }
+
+    #[must_use]
+    /// Redirect to normalized path instead of rewriting request path.
+    ///
+    /// By default redirect is disabled.
+    pub fn redirect(mut self, redirect: bool) -> Self {
+        self.redirect = redirect;
+        self
+    }
+}
+
+impl<S> Middleware<S, SharedCfg> for NormalizePath {
+    type Service = NormalizePathMiddleware<S>;
+
+    fn create(&self, service: S, _: SharedCfg) -> Self::Service {
+        NormalizePathMiddleware {
+            service,
+            trailing_slash: self.trailing_slash,
+            redirect: self.redirect,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct NormalizePathMiddleware<S> {
+    service: S,
+    trailing_slash: TrailingSlash,
+    redirect: bool,
+}
+
+impl<S, Err> Service<WebRequest<Err>> for NormalizePathMiddleware<S>
+where
+    S: Service<WebRequest<Err>, Response = WebResponse>,
+{
+    type Response = WebResponse;
+    type Error = S::Error;
+
+    crate::forward_poll!(service);
+    crate::forward_ready!(service);
+    crate::forward_shutdown!(service);
+
+    async fn call(
+        &self,
+        mut req: WebRequest<Err>,
+        ctx: ServiceCtx<'_, Self>,
+    ) -> Result<Self::Response, Self::Error> {
+        if let Some(path) = normalize(req.path(), self.trailing_slash) {
+            let path_and_query = if let Some(query) = req.uri().query() {
+                format!("{path}?{query}")
+            } else {
+                path
+            };
+
+            if self.redirect {
+                if let Ok(location) = HeaderValue::try_from(path_and_query) {
+                    let res = HttpResponse::MovedPermanently()
+                        .header(LOCATION, location)
+                        .finish();
+                    return Ok(req.into_response(res));
+                }
+            } else {
+                let mut parts = req.uri().clone().into_parts();
+                parts.path_and_query = PathAndQuery::try_from(path_and_query).ok();
+                if let Ok(uri) = Uri::from_parts(parts) {
+                    req.match_info_mut().set(uri.clone());
+                    req.head_mut().uri = uri;
+                }
+            }
+        }
+        ctx.call(&self.service, req).await
+    }
+}
+
+/// Normalize path, returns `None` if path is already normalized
+fn normalize(path: &str, trailing_slash: TrailingSlash) -> Option<String> {
+    let mut result = String::with_capacity(path.len() + 1);
+    for ch in path.chars() {
+        if ch != '/' || !result.ends_with('/') {
+            result.push(ch);
+        }
+    }
+
+    match trailing_slash {
+        TrailingSlash::Trim => {
+            if result.len() > 1 && result.ends_with('/') {
+                result.pop();
+            }
+        }
+        TrailingSlash::Always => {
+            if !result.ends_with('/') {
+                result.push('/');
+            }
+        }
+        TrailingSlash::MergeOnly => (),
+    }
+
+    if result.is_empty() {
+        result.push('/');
+    }
+
+    if result == path { None } else { Some(result) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::StatusCode;
+    use crate::service::{IntoService, Pipeline};
+    use crate::web::test::TestRequest;
+    use crate::web::{DefaultError, Error};
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("/a//b/", TrailingSlash::Trim).unwrap(), "/a/b");
+        assert_eq!(normalize("//", TrailingSlash::Trim).unwrap(), "/");
+        assert!(normalize("/", TrailingSlash::Trim).is_none());
+        assert!(normalize("/a/b", TrailingSlash::Trim).is_none());
+        assert_eq!(normalize("/a//b", TrailingSlash::Always).unwrap(), "/a/b/");
+        assert!(normalize("/a/b/", TrailingSlash::Always).is_none());
+        assert_eq!(
+            normalize("/a///b/", TrailingSlash::MergeOnly).unwrap(),
+            "/a/b/"
+        );
+        assert!(normalize("/a/b", TrailingSlash::MergeOnly).is_none());
+    }
+
+    #[crate::rt_test]
+    async fn test_rewrite() {
+        let srv = |req: WebRequest<DefaultError>| async move {
+            assert_eq!(req.path(), "/a/b");
+            assert_eq!(req.match_info().path(), "/a/b");
+            assert_eq!(req.query_string(), "q=1");
+            Ok::<_, Error>(req.into_response(HttpResponse::Ok().finish()))
+        };
+        let mw = Pipeline::new(
+            NormalizePath::new().create(srv.into_service(), SharedCfg::default()),
+        );
+
+        for uri in ["/a//b/?q=1", "/a///b?q=1", "/a/b?q=1"] {
+            let resp = mw
+                .call(TestRequest::with_uri(uri).to_srv_request())
+                .await
+                .unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+        }
+    }
+
+    #[crate::rt_test]
+    async fn test_redirect() {
+        let srv = |req: WebRequest<DefaultError>| async move {
+            Ok::<_, Error>(req.into_response(HttpResponse::Ok().finish()))
+        };
+        let mw = Pipeline::new(
+            NormalizePath::new()
+                .redirect(true)
+                .create(srv.into_service(), SharedCfg::default()),
+        );
+
+        let resp = mw
+            .call(TestRequest::with_uri("/a//b/?q=1").to_srv_request())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::MOVED_PERMANENTLY);
+        assert_eq!(resp.headers().get(LOCATION).unwrap(), "/a/b?q=1");
+
+        let resp = mw
+            .call(TestRequest::with_uri("/a/b").to_srv_request())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,332 @@
+//! Middleware for request rate limiting
+use std::{cell::RefCell, collections::HashMap, fmt, rc::Rc, time::Instant};
+
+use crate::http::header::{HeaderValue, RETRY_AFTER};
+use crate::service::{Middleware, Service, ServiceCtx, cfg::SharedCfg};
+use crate::time::Seconds;
+use crate::web::{HttpRequest, HttpResponse, WebRequest, WebResponse};
+
+/// Rate limit state storage
+///
+/// Store tracks number of requests for each key.
+#[allow(async_fn_in_trait)]
+pub trait RateLimitStore {
+    /// Acquire permit for the key
+    ///
+    /// Returns `Err` with delay after which request could be retried,
+    /// if limit is exceeded.
+    async fn acquire(&self, key: &str) -> Result<(), Seconds>;
+}
+
+#[derive(Debug)]
+/// In-memory token bucket rate limit store
+///
+/// Every key gets a bucket of `burst` tokens, bucket is refilled with
+/// `requests` tokens every `period`. Store is local to the worker thread.
+pub struct MemoryStore {
+    burst: f64,
+    rate: f64,
+    max_keys: usize,
+    buckets: RefCell<HashMap<String, Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl MemoryStore {
+    /// Create store that allows `requests` per `period` for each key
+    ///
+    /// # Panics
+    ///
+    /// Panics if `requests` or `period` is zero.
+    pub fn new(requests: u32, period: Seconds) -> Self {
+        assert!(requests > 0, "Number of requests must be greater than zero");
+        assert!(!period.is_zero(), "Period must be greater than zero");
+
+        MemoryStore {
+            burst: f64::from(requests),
+            rate: f64::from(requests) / f64::from(period.0),
+            max_keys: 10_000,
+            buckets: RefCell::new(HashMap::new()),
+        }
+    }
+
+    #[must_use]
+    /// Set max number of requests that could be sent at once
+    ///
+    /// By default burst is equal to number of requests per period.
+    pub fn burst(mut self, burst: u32) -> Self {
+        self.burst = f64::from(burst.max(1));
+        self
+    }
+
+    #[must_use]
+    /// Set max number of tracked keys
+    ///
+    /// If number of keys reaches the limit, full buckets are removed,
+    /// and if it is not enough least recently used bucket is removed.
+    /// By default limit is 10 000 keys.
+    pub fn max_keys(mut self, max: usize) -> Self {
+        self.max_keys = max.max(1);
+        self
+    }
+
+    fn refill(&self, bucket: &mut Bucket, now: Instant) {
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+        bucket.updated = now;
+    }
+}
+
+impl RateLimitStore for MemoryStore {
+    async fn acquire(&self, key: &str) -> Result<(), Seconds> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.borrow_mut();
+
+        if buckets.len() >= self.max_keys && !buckets.contains_key(key) {
+            buckets.retain(|_, bucket| {
+                let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+                bucket.tokens + elapsed * self.rate < self.burst
+            });
+
+            // evict least recently used buckets
+            while buckets.len() >= self.max_keys {
+                let oldest = buckets
+                    .iter()
+                    .min_by_key(|(_, bucket)| bucket.updated)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    buckets.remove(&oldest);
+                }
+            }
+        }
+
+        let bucket = buckets.entry(key.to_string()).or_insert_with(|| Bucket {
+            tokens: self.burst,
+            updated: now,
+        });
+        self.refill(bucket, now);
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            #[allow(clippy::cast_sign_loss)]
+            let delay = ((1.0 - bucket.tokens) / self.rate).ceil() as usize;
+            Err(Seconds::checked_new(delay))
+        }
+    }
+}
+
+/// `Middleware` for request rate limiting.
+///
+/// Middleware computes key for each request and acquires permit from the store,
+/// if limit is exceeded *429 Too Many Requests* response with `Retry-After`
+/// header is returned. Requests without key are not limited. By default key
+/// is ip address of the peer, proxy headers are not used. Use custom key
+/// extractor if application is deployed behind reverse proxy.
+///
+/// ```rust
+/// use ntex::time::Seconds;
+/// use ntex::web::{self, middleware, App, HttpResponse};
+///
+/// fn main() {
+///     let app = App::new()
+///         .middleware(middleware::RateLimit::new(
+///             middleware::MemoryStore::new(100, Seconds(60)),
+///         ))
+///         .service(
+///             web::resource("/test")
+///                 .route(web::get().to(|| async { HttpResponse::Ok() }))
+///         );
+/// }
+/// ```
+pub struct RateLimit<St> {
+    store: Rc<St>,
+    key: Rc<dyn Fn(&HttpRequest) -> Option<String>>,
+}
+
+impl<St: RateLimitStore> RateLimit<St> {
+    /// Construct `RateLimit` middleware.
+    pub fn new(store: St) -> Self {
+        RateLimit {
+            store: Rc::new(store),
+            key: Rc::new(|req| req.peer_addr().map(|addr| addr.ip().to_string())),
+        }
+    }
+
+    #[must_use]
+    /// Set request key extractor.
+    pub fn key<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&HttpRequest) -> Option<String> + 'static,
+    {
+        self.key = Rc::new(f);
+        self
+    }
+}
+
+impl<St> Clone for RateLimit<St> {
+    fn clone(&self) -> Self {
+        RateLimit {
+            store: self.store.clone(),
+            key: self.key.clone(),
+        }
+    }
+}
+
+impl<St: fmt::Debug> fmt::Debug for RateLimit<St> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimit")
+            .field("store", &self.store)
+            .finish()
+    }
+}
+
+impl<S, St> Middleware<S, SharedCfg> for RateLimit<St> {
+    type Service = RateLimitMiddleware<S, St>;
+
+    fn create(&self, service: S, _: SharedCfg) -> Self::Service {
+        RateLimitMiddleware {
+            service,
+            store: self.store.clone(),
+            key: self.key.clone(),
+        }
+    }
+}
+
+pub struct RateLimitMiddleware<S, St> {
+    service: S,
+    store: Rc<St>,
+    key: Rc<dyn Fn(&HttpRequest) -> Option<String>>,
+}
+
+impl<S: fmt::Debug, St: fmt::Debug> fmt::Debug for RateLimitMiddleware<S, St> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimitMiddleware")
+            .field("service", &self.service)
+            .field("store", &self.store)
+            .finish()
+    }
+}
+
+impl<S, St, Err> Service<WebRequest<Err>> for RateLimitMiddleware<S, St>
+where
+    S: Service<WebRequest<Err>, Response = WebResponse>,
+    St: RateLimitStore,
+{
+    type Response = WebResponse;
+    type Error = S::Error;
+
+    crate::forward_poll!(service);
+    crate::forward_ready!(service);
+    crate::forward_shutdown!(service);
+
+    async fn call(
+        &self,
+        req: WebRequest<Err>,
+        ctx: ServiceCtx<'_, Self>,
+    ) -> Result<Self::Response, Self::Error> {
+        if let Some(key) = (self.key)(req.http_request())
+            && let Err(delay) = self.store.acquire(&key).await
+        {
+            let res = HttpResponse::TooManyRequests()
+                .header(RETRY_AFTER, HeaderValue::from(delay.seconds()))
+                .finish();
+            return Ok(req.into_response(res));
+        }
+        ctx.call(&self.service, req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::StatusCode;
+    use crate::service::Pipeline;
+    use crate::web::test::{TestRequest, ok_service};
+
+    #[crate::rt_test]
+    async fn test_memory_store() {
+        let store = MemoryStore::new(2, Seconds(10));
+        assert!(store.acquire("a").await.is_ok());
+        assert!(store.acquire("a").await.is_ok());
+        assert_eq!(store.acquire("a").await, Err(Seconds(5)));
+        assert!(store.acquire("b").await.is_ok());
+
+        let store = MemoryStore::new(1, Seconds(1)).max_keys(1);
+        assert!(store.acquire("a").await.is_ok());
+        assert!(store.acquire("b").await.is_ok());
+        assert_eq!(store.buckets.borrow().len(), 1);
+        assert!(store.buckets.borrow().contains_key("b"));
+
+        let store = MemoryStore::new(1, Seconds(60)).max_keys(2);
+        assert!(store.acquire("a").await.is_ok());
+        assert!(store.acquire("b").await.is_ok());
+        assert!(store.acquire("c").await.is_ok());
+        assert_eq!(store.buckets.borrow().len(), 2);
+        assert!(!store.buckets.borrow().contains_key("a"));
+    }
+
+    #[crate::rt_test]
+    async fn test_rate_limit() {
+        let mw = Pipeline::new(
+            RateLimit::new(MemoryStore::new(2, Seconds(60)))
+                .create(ok_service(), SharedCfg::default()),
+        );
+
+        for _ in 0..2 {
+            let req = TestRequest::default()
+                .peer_addr("127.0.0.1:8080".parse().unwrap())
+                .to_srv_request();
+            assert_eq!(mw.call(req).await.unwrap().status(), StatusCode::OK);
+        }
+        let req = TestRequest::default()
+            .peer_addr("127.0.0.1:8080".parse().unwrap())
+            .to_srv_request();
+        let resp = mw.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(resp.headers().get(RETRY_AFTER).unwrap(), "30");
+
+        // new connection or proxy headers do not change the key
+        let req = TestRequest::default()
+            .peer_addr("127.0.0.1:8081".parse().unwrap())
+            .header("x-forwarded-for", "192.0.2.60")
+            .to_srv_request();
+        assert_eq!(
+            mw.call(req).await.unwrap().status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+
+        // other key
+        let req = TestRequest::default()
+            .peer_addr("127.0.0.2:8080".parse().unwrap())
+            .to_srv_request();
+        assert_eq!(mw.call(req).await.unwrap().status(), StatusCode::OK);
+
+        // custom key
+        let mw = Pipeline::new(
+            RateLimit::new(MemoryStore::new(1, Seconds(60)))
+                .key(|req| req.headers().get("x-api-key").map(|v| format!("{v:?}")))
+                .create(ok_service(), SharedCfg::default()),
+        );
+        let req = TestRequest::with_header("x-api-key", "1").to_srv_request();
+        assert_eq!(mw.call(req).await.unwrap().status(), StatusCode::OK);
+        let req = TestRequest::with_header("x-api-key", "1").to_srv_request();
+        assert_eq!(
+            mw.call(req).await.unwrap().status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        let req = TestRequest::with_header("x-api-key", "2").to_srv_request();
+        assert_eq!(mw.call(req).await.unwrap().status(), StatusCode::OK);
+
+        // requests without key are not limited
+        for _ in 0..3 {
+            let req = TestRequest::default().to_srv_request();
+            assert_eq!(mw.call(req).await.unwrap().status(), StatusCode::OK);
+        }
+    }
+}
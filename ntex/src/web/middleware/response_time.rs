@@ -0,0 +1,205 @@
+//! Middleware for response time header
+use std::{rc::Rc, time::Duration, time::Instant};
+
+use crate::http::error::HttpError;
+use crate::http::header::{HeaderName, HeaderValue};
+use crate::service::{Middleware, Service, ServiceCtx, cfg::SharedCfg};
+use crate::web::{WebRequest, WebResponse};
+
+/// Unit of the response time header value
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TimeUnit {
+    /// Seconds with microsecond precision, `0.001234s`
+    Seconds,
+    /// Milliseconds with microsecond precision, `1.234ms`
+    #[default]
+    Millis,
+    /// Whole microseconds, `1234us`
+    Micros,
+}
+
+impl TimeUnit {
+    fn format(self, elapsed: Duration) -> String {
+        match self {
+            TimeUnit::Seconds => format!("{:.6}s", elapsed.as_secs_f64()),
+            TimeUnit::Millis => format!("{:.3}ms", elapsed.as_secs_f64() * 1000.0),
+            TimeUnit::Micros => format!("{}us", elapsed.as_micros()),
+        }
+    }
+}
+
+/// `Middleware` for adding request processing time header to responses.
+///
+/// Time is measured from the start of the request processing until
+/// the service returns response, right before response headers are sent.
+/// Response body streaming time is not included. By default `X-Response-Time`
+/// header with value in milliseconds is added.
+///
+/// ```rust
+/// use ntex::web::{self, middleware, App, HttpResponse};
+///
+/// fn main() {
+///     let app = App::new()
+///         .middleware(
+///             middleware::ResponseTime::new()
+///                 .header("x-elapsed")
+///                 .unit(middleware::TimeUnit::Micros),
+///         )
+///         .service(
+///             web::resource("/test")
+///                 .route(web::get().to(|| async { HttpResponse::Ok() }))
+///         );
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ResponseTime {
+    inner: Rc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    name: HeaderName,
+    unit: TimeUnit,
+}
+
+impl Default for ResponseTime {
+    fn default() -> Self {
+        ResponseTime {
+            inner: Rc::new(Inner {
+                name: HeaderName::from_static("x-response-time"),
+                unit: TimeUnit::default(),
+            }),
+        }
+    }
+}
+
+impl ResponseTime {
+    #[must_use]
+    /// Construct `ResponseTime` middleware.
+    pub fn new() -> ResponseTime {
+        ResponseTime::default()
+    }
+
+    #[must_use]
+    /// Set header name.
+    ///
+    /// By default `X-Response-Time` header is used.
+    pub fn header<K>(mut self, name: K) -> Self
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<HttpError>,
+    {
+        let Ok(name) = HeaderName::try_from(name) else {
+            panic!("Cannot create header name")
+        };
+        Rc::get_mut(&mut self.inner)
+            .expect("Multiple copies exist")
+            .name = name;
+        self
+    }
+
+    #[must_use]
+    /// Set unit of the header value.
+    ///
+    /// By default time is reported in milliseconds.
+    pub fn unit(mut self, unit: TimeUnit) -> Self {
+        Rc::get_mut(&mut self.inner)
+            .expect("Multiple copies exist")
+            .unit = unit;
+        self
+    }
+}
+
+impl<S> Middleware<S, SharedCfg> for ResponseTime {
+    type Service = ResponseTimeMiddleware<S>;
+
+    fn create(&self, service: S, _: SharedCfg) -> Self::Service {
+        ResponseTimeMiddleware {
+            service,
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ResponseTimeMiddleware<S> {
+    service: S,
+    inner: Rc<Inner>,
+}
+
+impl<S, E> Service<WebRequest<E>> for ResponseTimeMiddleware<S>
+where
+    S: Service<WebRequest<E>, Response = WebResponse>,
+{
+    type Response = WebResponse;
+    type Error = S::Error;
+
+    crate::forward_poll!(service);
+    crate::forward_ready!(service);
+    crate::forward_shutdown!(service);
+
+    async fn call(
+        &self,
+        req: WebRequest<E>,
+        ctx: ServiceCtx<'_, Self>,
+    ) -> Result<Self::Response, Self::Error> {
+        let start = Instant::now();
+        let mut res = ctx.call(&self.service, req).await?;
+
+        let value = self.inner.unit.format(start.elapsed());
+        if let Ok(value) = HeaderValue::try_from(value) {
+            res.headers_mut().insert(self.inner.name.clone(), value);
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::{IntoService, Pipeline};
+    use crate::time::{Millis, sleep};
+    use crate::web::test::{TestRequest, ok_service};
+    use crate::web::{DefaultError, Error, HttpResponse};
+
+    #[test]
+    fn test_format() {
+        let elapsed = Duration::from_micros(1234);
+        assert_eq!(TimeUnit::Seconds.format(elapsed), "0.001234s");
+        assert_eq!(TimeUnit::Millis.format(elapsed), "1.234ms");
+        assert_eq!(TimeUnit::Micros.format(elapsed), "1234us");
+    }
+
+    #[crate::rt_test]
+    async fn test_response_time() {
+        let mw =
+            Pipeline::new(ResponseTime::new().create(ok_service(), SharedCfg::default()));
+        let req = TestRequest::default().to_srv_request();
+        let resp = mw.call(req).await.unwrap();
+        let value = resp.headers().get("x-response-time").unwrap();
+        assert!(value.to_str().unwrap().ends_with("ms"));
+
+        let srv = |req: WebRequest<DefaultError>| async move {
+            sleep(Millis(50)).await;
+            Ok::<_, Error>(req.into_response(HttpResponse::Ok().finish()))
+        };
+        let mw = Pipeline::new(
+            ResponseTime::new()
+                .header("x-elapsed")
+                .unit(TimeUnit::Micros)
+                .create(srv.into_service(), SharedCfg::default()),
+        );
+        let req = TestRequest::default().to_srv_request();
+        let resp = mw.call(req).await.unwrap();
+        assert!(!resp.headers().contains_key("x-response-time"));
+        let value = resp.headers().get("x-elapsed").unwrap().to_str().unwrap();
+        let micros: u64 = value.strip_suffix("us").unwrap().parse().unwrap();
+        assert!(micros >= 50_000);
+    }
+
+    #[crate::rt_test]
+    #[should_panic(expected = "Cannot create header name")]
+    async fn test_invalid_header_name() {
+        let _ = ResponseTime::new().header("no existing header name");
+    }
+}
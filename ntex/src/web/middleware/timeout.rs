@@ -0,0 +1,170 @@
+//! Middleware for request processing timeout
+use crate::http::StatusCode;
+use crate::service::{Middleware, Service, ServiceCtx, cfg::SharedCfg};
+use crate::time::{Millis, sleep};
+use crate::util::{Either, select};
+use crate::web::error::{ErrorRenderer, InternalError};
+use crate::web::{WebRequest, WebResponse};
+
+/// `Middleware` for request processing timeout.
+///
+/// If inner service does not complete within the configured duration,
+/// its future is dropped and *504 Gateway Timeout* error is returned.
+/// Status code could be changed with `Timeout::status()`.
+///
+/// Middleware could be applied to app, scope or resource, so different
+/// routes could use different timeouts.
+///
+/// ```rust
+/// use ntex::time::Seconds;
+/// use ntex::web::{self, middleware, App, HttpResponse};
+///
+/// fn main() {
+///     let app = App::new()
+///         .middleware(middleware::Timeout::new(Seconds(30)))
+///         .service(
+///             web::resource("/test")
+///                 .route(web::get().to(|| async { HttpResponse::Ok() }))
+///         );
+/// }
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Timeout {
+    timeout: Millis,
+    status: StatusCode,
+}
+
+impl Timeout {
+    /// Construct `Timeout` middleware.
+    pub fn new<T: Into<Millis>>(timeout: T) -> Self {
+        Timeout {
+            timeout: timeout.into(),
+            status: StatusCode::GATEWAY_TIMEOUT,
+        }
+    }
+
+    #[must_use]
+    /// Set response status for timed out requests.
+    ///
+    /// Default is *504 Gateway Timeout*.
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+}
+
+impl<S> Middleware<S, SharedCfg> for Timeout {
+    type Service = TimeoutMiddleware<S>;
+
+    fn create(&self, service: S, _: SharedCfg) -> Self::Service {
+        TimeoutMiddleware {
+            service,
+            timeout: self.timeout,
+            status: self.status,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TimeoutMiddleware<S> {
+    service: S,
+    timeout: Millis,
+    status: StatusCode,
+}
+
+impl<S, Err> Service<WebRequest<Err>> for TimeoutMiddleware<S>
+where
+    S: Service<WebRequest<Err>, Response = WebResponse>,
+    S::Error: From<InternalError<&'static str, Err>>,
+    Err: ErrorRenderer,
+{
+    type Response = WebResponse;
+    type Error = S::Error;
+
+    crate::forward_poll!(service);
+    crate::forward_ready!(service);
+    crate::forward_shutdown!(service);
+
+    async fn call(
+        &self,
+        req: WebRequest<Err>,
+        ctx: ServiceCtx<'_, Self>,
+    ) -> Result<Self::Response, Self::Error> {
+        if self.timeout.is_zero() {
+            return ctx.call(&self.service, req).await;
+        }
+
+        match select(sleep(self.timeout), ctx.call(&self.service, req)).await {
+            Either::Left(()) => {
+                Err(InternalError::new("Request processing timeout", self.status).into())
+            }
+            Either::Right(res) => res,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+    use crate::http::error::ResponseError;
+    use crate::service::{IntoService, Pipeline};
+    use crate::web::test::{TestRequest, ok_service};
+    use crate::web::{DefaultError, Error, HttpResponse};
+
+    struct Guard(Rc<Cell<bool>>);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    #[crate::rt_test]
+    async fn test_timeout() {
+        let mw = Pipeline::new(
+            Timeout::new(Millis(50)).create(ok_service(), SharedCfg::default()),
+        );
+        let resp = mw
+            .call(TestRequest::default().to_srv_request())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let dropped = Rc::new(Cell::new(false));
+        let dropped2 = dropped.clone();
+        let srv = move |req: WebRequest<DefaultError>| {
+            let guard = Guard(dropped2.clone());
+            async move {
+                sleep(Millis(500)).await;
+                drop(guard);
+                Ok::<_, Error>(req.into_response(HttpResponse::Ok().finish()))
+            }
+        };
+        let mw = Pipeline::new(
+            Timeout::new(Millis(50))
+                .create(srv.clone().into_service(), SharedCfg::default()),
+        );
+        let err = mw
+            .call(TestRequest::default().to_srv_request())
+            .await
+            .unwrap_err();
+        assert_eq!(err.error_response().status(), StatusCode::GATEWAY_TIMEOUT);
+        assert!(dropped.get());
+
+        let mw = Pipeline::new(
+            Timeout::new(Millis(50))
+                .status(StatusCode::SERVICE_UNAVAILABLE)
+                .create(srv.into_service(), SharedCfg::default()),
+        );
+        let err = mw
+            .call(TestRequest::default().to_srv_request())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.error_response().status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+}
@@ -49,7 +49,7 @@
 //! * Streaming and pipelining
 //! * Keep-alive and slow requests handling
 //! * `WebSockets` server/client
-//! * Transparent content compression/decompression (br, gzip, deflate)
+//! * Transparent content compression/decompression (br, gzip, deflate, zstd)
 //! * Configurable request routing
 //! * SSL support with OpenSSL or `rustls`
 //! * Middlewares
@@ -61,17 +61,20 @@
 //! * `compress` - enables content encoding compression support
 //! * `openssl` - enables ssl support via `openssl` crate
 //! * `rustls` - enables ssl support via `rustls` crate
+//! * `tracing` - handles each request within `tracing` span
 mod app;
 mod app_service;
 mod config;
 pub mod error;
 mod error_default;
 mod extract;
+pub mod fs;
 pub mod guard;
 mod handler;
 mod httprequest;
 mod info;
 pub mod middleware;
+mod negotiate;
 mod request;
 mod resource;
 mod responder;
@@ -81,9 +84,12 @@ mod route;
 mod scope;
 mod server;
 mod service;
+#[cfg(feature = "tracing")]
+mod span;
 pub mod stack;
 pub mod test;
 pub mod types;
+pub mod upgrade;
 mod util;
 
 #[cfg(feature = "ws")]
@@ -112,6 +118,7 @@ pub use self::error::{
 pub use self::extract::FromRequest;
 pub use self::handler::Handler;
 pub use self::httprequest::HttpRequest;
+pub use self::negotiate::{Negotiate, Negotiated};
 pub use self::request::WebRequest;
 pub use self::resource::Resource;
 pub use self::responder::Responder;
@@ -129,7 +136,7 @@ pub mod dev {
     //! traits by adding a glob import to the top of `ntex::web` heavy modules:
 
     pub use crate::web::app_service::AppService;
-    pub use crate::web::info::ConnectionInfo;
+    pub use crate::web::info::{ConnectionInfo, ProxyHeaders};
     pub use crate::web::rmap::ResourceMap;
     pub use crate::web::route::IntoRoutes;
     pub use crate::web::service::{WebServiceAdapter, WebServiceConfig, WebServiceFactory};
@@ -0,0 +1,364 @@
+//! Accept header content negotiation
+use std::{ops, str::FromStr};
+
+use mime::Mime;
+use serde::Serialize;
+
+#[cfg(feature = "msgpack")]
+use crate::http::StatusCode;
+use crate::http::header::{ACCEPT, HeaderMap, HeaderValue, VARY};
+use crate::http::{Payload, Response};
+use crate::web::error::{ErrorRenderer, JsonError};
+#[cfg(feature = "msgpack")]
+use crate::web::error::{InternalError, WebResponseError};
+use crate::web::types::{Form, Json};
+use crate::web::{FromRequest, HttpRequest, Responder};
+
+/// Content negotiation helper.
+///
+/// Parses media ranges and quality values of the request `Accept` header and
+/// selects the best matching mime type from a list of available types.
+/// The most specific media range determines quality of a type, types with
+/// zero quality are not acceptable. If request does not contain `Accept`
+/// header, any type is acceptable.
+///
+/// ```rust
+/// use ntex::web::{self, App, HttpResponse, Negotiate};
+///
+/// async fn index(accept: Negotiate) -> HttpResponse {
+///     match accept.best(&[mime::TEXT_HTML, mime::TEXT_PLAIN]) {
+///         Some(m) if *m == mime::TEXT_HTML => HttpResponse::Ok().body("<p>html</p>"),
+///         Some(_) => HttpResponse::Ok().body("plain"),
+///         None => HttpResponse::NotAcceptable().finish(),
+///     }
+/// }
+///
+/// fn main() {
+///     let app = App::new().service(web::resource("/index.html").to(index));
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Negotiate(Vec<MediaRange>);
+
+#[derive(Clone, Debug)]
+struct MediaRange {
+    mime: Mime,
+    quality: f32,
+}
+
+impl MediaRange {
+    fn parse(item: &str) -> Option<MediaRange> {
+        let mime = Mime::from_str(item.trim()).ok()?;
+        let quality = mime
+            .get_param("q")
+            .map_or(Some(1.0), |q| f32::from_str(q.as_str()).ok())?
+            .clamp(0.0, 1.0);
+        Some(MediaRange { mime, quality })
+    }
+
+    /// Specificity of the range if it matches mime type
+    fn matches(&self, mime: &Mime) -> Option<u8> {
+        if self.mime.type_() == mime::STAR {
+            Some(0)
+        } else if self.mime.type_() != mime.type_() {
+            None
+        } else if self.mime.subtype() == mime::STAR {
+            Some(1)
+        } else if self.mime.subtype() == mime.subtype() {
+            Some(2)
+        } else {
+            None
+        }
+    }
+}
+
+impl Negotiate {
+    /// Parse `Accept` headers
+    ///
+    /// Invalid media ranges are ignored.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        Negotiate(
+            headers
+                .get_all(ACCEPT)
+                .filter_map(|hdr| hdr.to_str().ok())
+                .flat_map(|val| val.split(','))
+                .filter_map(MediaRange::parse)
+                .collect(),
+        )
+    }
+
+    /// Quality of the mime type
+    ///
+    /// Returns `0.0` if mime type is not acceptable.
+    pub fn quality(&self, mime: &Mime) -> f32 {
+        if self.0.is_empty() {
+            return 1.0;
+        }
+
+        let mut result: Option<(u8, f32)> = None;
+        for range in &self.0 {
+            if let Some(spec) = range.matches(mime)
+                && result.is_none_or(|(s, _)| spec > s)
+            {
+                result = Some((spec, range.quality));
+            }
+        }
+        result.map_or(0.0, |(_, q)| q)
+    }
+
+    /// Select best match from the provided mime types
+    ///
+    /// Returns `None` if none of the types is acceptable. Types with
+    /// equal quality are preferred in the order of the list.
+    pub fn best<'a>(&self, available: &'a [Mime]) -> Option<&'a Mime> {
+        let mut best: Option<(&Mime, f32)> = None;
+        for mime in available {
+            let quality = self.quality(mime);
+            if quality > 0.0 && best.is_none_or(|(_, q)| quality > q) {
+                best = Some((mime, quality));
+            }
+        }
+        best.map(|(mime, _)| mime)
+    }
+}
+
+impl<Err: ErrorRenderer> FromRequest<Err> for Negotiate {
+    type Error = Err::Container;
+
+    #[inline]
+    async fn from_request(req: &HttpRequest, _: &mut Payload) -> Result<Self, Self::Error> {
+        Ok(Negotiate::from_headers(req.headers()))
+    }
+}
+
+/// Negotiated responder
+///
+/// Serializes value to the format selected by the request `Accept` header.
+/// Supported formats are `application/json`, `application/msgpack`
+/// (requires `msgpack` feature) and `application/x-www-form-urlencoded`,
+/// json is used if client accepts any format. If none of the formats is
+/// acceptable *406 Not Acceptable* response is returned.
+///
+/// ```rust
+/// use ntex::web::{self, App, Negotiated};
+///
+/// #[derive(serde::Serialize)]
+/// struct User {
+///     name: String,
+/// }
+///
+/// async fn index() -> Negotiated<User> {
+///     Negotiated(User { name: "ntex".to_string() })
+/// }
+///
+/// fn main() {
+///     let app = App::new().service(web::resource("/user").to(index));
+/// }
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct Negotiated<T>(pub T);
+
+impl<T> Negotiated<T> {
+    /// Deconstruct to an inner value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> ops::Deref for Negotiated<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> Negotiated<T> {
+    fn format(req: &HttpRequest) -> Option<Mime> {
+        let available = [
+            mime::APPLICATION_JSON,
+            #[cfg(feature = "msgpack")]
+            Mime::from_str("application/msgpack").unwrap(),
+            mime::APPLICATION_WWW_FORM_URLENCODED,
+        ];
+        Negotiate::from_headers(req.headers())
+            .best(&available)
+            .cloned()
+    }
+}
+
+impl<T: Serialize, Err: ErrorRenderer> Responder<Err> for Negotiated<T>
+where
+    Err::Container: From<JsonError> + From<serde_urlencoded::ser::Error>,
+{
+    async fn respond_to(self, req: &HttpRequest) -> Response {
+        let mut res = match Self::format(req) {
+            Some(m) if m == mime::APPLICATION_JSON => {
+                Responder::<Err>::respond_to(Json(self.0), req).await
+            }
+            #[cfg(feature = "msgpack")]
+            Some(m) if m.subtype() == "msgpack" => msgpack::<_, Err>(&self.0, req),
+            Some(_) => Responder::<Err>::respond_to(Form(self.0), req).await,
+            None => Response::NotAcceptable().finish(),
+        };
+        res.headers_mut()
+            .append(VARY, HeaderValue::from_static("accept"));
+        res
+    }
+}
+
+/// Serialize value to messagepack, encoding error is an internal server error
+#[cfg(feature = "msgpack")]
+fn msgpack<T: Serialize, Err: ErrorRenderer>(value: &T, req: &HttpRequest) -> Response {
+    match rmp_serde::to_vec_named(value) {
+        Ok(body) => Response::Ok()
+            .content_type("application/msgpack")
+            .body(body),
+        Err(e) => WebResponseError::<Err>::error_response(
+            &InternalError::<_, Err>::new(e, StatusCode::INTERNAL_SERVER_ERROR),
+            req,
+        ),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::http::{StatusCode, header::CONTENT_TYPE};
+    use crate::util::Bytes;
+    use crate::web::test::{TestRequest, call_service, init_service, read_body};
+    use crate::web::{self, App};
+
+    fn negotiate(accept: &str) -> Negotiate {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_str(accept).unwrap());
+        Negotiate::from_headers(&headers)
+    }
+
+    #[test]
+    fn test_quality() {
+        let n = Negotiate::default();
+        assert_eq!(n.quality(&mime::TEXT_HTML), 1.0);
+
+        let n = negotiate("text/*;q=0.3, text/html;q=0.7, text/html;level=1, */*;q=0.5");
+        assert_eq!(n.quality(&mime::TEXT_HTML), 0.7);
+        assert_eq!(n.quality(&mime::TEXT_PLAIN), 0.3);
+        assert_eq!(n.quality(&mime::IMAGE_PNG), 0.5);
+
+        let n = negotiate("application/json, text/plain;q=0");
+        assert_eq!(n.quality(&mime::TEXT_PLAIN), 0.0);
+        assert_eq!(n.quality(&mime::TEXT_HTML), 0.0);
+
+        let n = negotiate("invalid, application/json;q=bad, text/plain;q=2");
+        assert_eq!(n.quality(&mime::APPLICATION_JSON), 0.0);
+        assert_eq!(n.quality(&mime::TEXT_PLAIN), 1.0);
+    }
+
+    #[test]
+    fn test_best() {
+        let available = [mime::APPLICATION_JSON, mime::TEXT_PLAIN];
+
+        let n = Negotiate::default();
+        assert_eq!(n.best(&available), Some(&mime::APPLICATION_JSON));
+        assert_eq!(n.best(&[]), None);
+
+        let n = negotiate("text/plain, application/json;q=0.9");
+        assert_eq!(n.best(&available), Some(&mime::TEXT_PLAIN));
+
+        let n = negotiate("*/*");
+        assert_eq!(n.best(&available), Some(&mime::APPLICATION_JSON));
+
+        let n = negotiate("text/*, application/json");
+        assert_eq!(n.best(&available), Some(&mime::APPLICATION_JSON));
+
+        let n = negotiate("image/png");
+        assert_eq!(n.best(&available), None);
+    }
+
+    #[derive(serde::Serialize)]
+    struct Info {
+        name: &'static str,
+    }
+
+    #[crate::rt_test]
+    async fn test_negotiated() {
+        let srv = init_service(App::new().service(
+            web::resource("/").to(|| async { Negotiated(Info { name: "ntex" }) }),
+        ))
+        .await;
+
+        let req = TestRequest::default().to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+        assert_eq!(resp.headers().get(VARY).unwrap(), "accept");
+        assert_eq!(
+            read_body(resp).await,
+            Bytes::from_static(b"{\"name\":\"ntex\"}")
+        );
+
+        let req = TestRequest::with_header(
+            ACCEPT,
+            "application/x-www-form-urlencoded, application/json;q=0.5",
+        )
+        .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(CONTENT_TYPE).unwrap(),
+            "application/x-www-form-urlencoded"
+        );
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"name=ntex"));
+
+        let req = TestRequest::with_header(ACCEPT, "text/html").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_ACCEPTABLE);
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[crate::rt_test]
+    async fn test_negotiated_msgpack() {
+        let srv = init_service(App::new().service(
+            web::resource("/").to(|| async { Negotiated(Info { name: "ntex" }) }),
+        ))
+        .await;
+
+        let req = TestRequest::with_header(ACCEPT, "application/msgpack").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(CONTENT_TYPE).unwrap(),
+            "application/msgpack"
+        );
+        assert_eq!(
+            read_body(resp).await,
+            Bytes::from(rmp_serde::to_vec_named(&Info { name: "ntex" }).unwrap())
+        );
+
+        let req = TestRequest::with_header(ACCEPT, "application/json").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(
+            resp.headers().get(CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+    }
+
+    #[crate::rt_test]
+    async fn test_negotiate_extractor() {
+        let srv = init_service(App::new().service(web::resource("/").to(
+            |n: Negotiate| async move {
+                n.best(&[mime::TEXT_HTML, mime::TEXT_PLAIN])
+                    .map_or_else(String::new, ToString::to_string)
+            },
+        )))
+        .await;
+
+        let req = TestRequest::with_header(ACCEPT, "text/plain").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"text/plain"));
+    }
+}
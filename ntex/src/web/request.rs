@@ -92,6 +92,14 @@ impl<Err> WebRequest<Err> {
         self.head().io.as_ref()
     }
 
+    /// Reference to the http request
+    ///
+    /// `WebRequest` can not be deconstructed if http request is cloned.
+    #[inline]
+    pub fn http_request(&self) -> &HttpRequest {
+        &self.req
+    }
+
     /// This method returns reference to the request head
     #[inline]
     pub fn head(&self) -> &RequestHead {
@@ -122,6 +130,12 @@ impl<Err> WebRequest<Err> {
         self.head().version
     }
 
+    /// Check if request is sent over http/2 connection.
+    #[inline]
+    pub fn is_h2(&self) -> bool {
+        self.head().version == Version::HTTP_2
+    }
+
     #[inline]
     /// Returns request's headers.
     pub fn headers(&self) -> &HeaderMap {
@@ -163,9 +177,12 @@ impl<Err> WebRequest<Err> {
     }
 
     /// Get `ConnectionInfo` for the current request.
+    ///
+    /// Proxy headers are trusted according to `ProxyHeaders` application state,
+    /// if state is not set all proxy headers are trusted.
     #[inline]
     pub fn connection_info(&self) -> Ref<'_, ConnectionInfo> {
-        ConnectionInfo::get(self.head(), self.app_config())
+        self.req.connection_info()
     }
 
     /// Get a reference to the Path parameters.
@@ -185,6 +202,27 @@ impl<Err> WebRequest<Err> {
         self.req.match_info_mut()
     }
 
+    /// Path pattern of the matched resource, i.e. `/users/{id}`.
+    ///
+    /// Returns `None` if request is handled by default service.
+    #[inline]
+    pub fn match_pattern(&self) -> Option<&str> {
+        self.req.match_pattern()
+    }
+
+    pub(super) fn set_match_pattern(&mut self, pattern: Option<Rc<str>>) {
+        Rc::get_mut(&mut (self.req).0).unwrap().match_pattern = pattern;
+    }
+
+    /// Append pattern to the matched scope's pattern
+    pub(super) fn join_match_pattern(&mut self, pattern: &Rc<str>) {
+        let inner = Rc::get_mut(&mut (self.req).0).unwrap();
+        inner.match_pattern = Some(match inner.match_pattern.take() {
+            Some(prefix) => format!("{prefix}{pattern}").into(),
+            None => pattern.clone(),
+        });
+    }
+
     #[inline]
     /// Get a reference to a `ResourceMap` of current application.
     pub fn resource_map(&self) -> &ResourceMap {
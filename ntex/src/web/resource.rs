@@ -1,12 +1,14 @@
-use std::{cell::RefCell, fmt, rc::Rc};
+use std::{cell::RefCell, error::Error, fmt, rc::Rc, task::Context, task::Poll};
 
-use crate::router::{IntoPattern, ResourceDef};
+use crate::http::body::{Body, BodySize, MessageBody};
+use crate::http::{Method, Response};
+use crate::router::{IntoPattern, Metadata, ResourceDef};
 use crate::service::boxed::{self, BoxService, BoxServiceFactory};
 use crate::service::cfg::SharedCfg;
 use crate::service::dev::{AndThen, ServiceChain, ServiceChainFactory};
 use crate::service::{Identity, IntoServiceFactory, Middleware, Service, ServiceFactory};
 use crate::service::{ServiceCtx, chain, chain_factory};
-use crate::{http::Response, util::Extensions};
+use crate::util::{Bytes, Extensions};
 
 use super::dev::{WebServiceConfig, WebServiceFactory, insert_slash};
 use super::extract::FromRequest;
@@ -52,6 +54,7 @@ pub struct Resource<Err: ErrorRenderer, M = Identity, T = Filter<Err>> {
     filter: ServiceChainFactory<T, WebRequest<Err>, SharedCfg>,
     rdef: Vec<String>,
     name: Option<String>,
+    metadata: Metadata,
     routes: Vec<Route<Err>>,
     state: Option<Extensions>,
     guards: Vec<Box<dyn Guard>>,
@@ -65,6 +68,7 @@ impl<Err: ErrorRenderer> Resource<Err> {
             routes: Vec::new(),
             rdef: path.patterns(),
             name: None,
+            metadata: Metadata::default(),
             state: None,
             middleware: Identity,
             filter: chain_factory(Filter::new()),
@@ -94,6 +98,46 @@ where
         self
     }
 
+    #[must_use]
+    /// Attach metadata to a resource.
+    ///
+    /// Metadata does not affect request handling, it is stored in resource
+    /// definition and is available via `ResourceMap::resources()`. Only one value
+    /// of each type could be stored, previous value of the same type is replaced.
+    ///
+    /// ```rust
+    /// use ntex::web::{self, App, HttpRequest, HttpResponse};
+    ///
+    /// struct RouteDoc {
+    ///     summary: &'static str,
+    ///     tags: Vec<&'static str>,
+    /// }
+    ///
+    /// async fn docs(req: HttpRequest) -> String {
+    ///     let mut docs = String::new();
+    ///     for (path, rdef) in req.resource_map().resources() {
+    ///         if let Some(doc) = rdef.metadata().get::<RouteDoc>() {
+    ///             docs.push_str(&format!("{path}: {} {:?}\n", doc.summary, doc.tags));
+    ///         }
+    ///     }
+    ///     docs
+    /// }
+    ///
+    /// fn main() {
+    ///     let app = App::new()
+    ///         .service(
+    ///             web::resource("/users")
+    ///                 .metadata(RouteDoc { summary: "List users", tags: vec!["users"] })
+    ///                 .route(web::get().to(|| async { HttpResponse::Ok() }))
+    ///         )
+    ///         .service(web::resource("/docs").to(docs));
+    /// }
+    /// ```
+    pub fn metadata<U: Send + Sync + 'static>(mut self, val: U) -> Self {
+        self.metadata.insert(val);
+        self
+    }
+
     #[must_use]
     /// Add match guard to a resource.
     ///
@@ -276,6 +320,7 @@ where
             middleware: self.middleware,
             rdef: self.rdef,
             name: self.name,
+            metadata: self.metadata,
             state: self.state,
             guards: self.guards,
             routes: self.routes,
@@ -295,6 +340,7 @@ where
             filter: self.filter,
             rdef: self.rdef,
             name: self.name,
+            metadata: self.metadata,
             state: self.state,
             guards: self.guards,
             routes: self.routes,
@@ -361,6 +407,7 @@ where
         if let Some(ref name) = self.name {
             rdef.name_mut().clone_from(name);
         }
+        *rdef.metadata_mut() = std::mem::take(&mut self.metadata);
 
         let state = self.state.take().map(|state| {
             AppState::new(
@@ -496,6 +543,32 @@ pub struct ResourceRouter<Err: ErrorRenderer> {
     default: Option<HttpService<Err>>,
 }
 
+impl<Err: ErrorRenderer> ResourceRouter<Err> {
+    /// Select first route that matches request
+    ///
+    /// If `auto_head` is set, routes are matched with `GET` method.
+    async fn select(
+        &self,
+        req: &mut WebRequest<Err>,
+        auto_head: bool,
+    ) -> Option<&RouteService<Err>> {
+        for route in &self.routes {
+            let matched = if route.is_async() {
+                route.check_async(req, auto_head).await
+            } else {
+                route.check(req, auto_head)
+            };
+            if matched {
+                if let Some(ref state) = self.state {
+                    req.set_state_container(state.clone());
+                }
+                return Some(route);
+            }
+        }
+        None
+    }
+}
+
 impl<Err: ErrorRenderer> Service<WebRequest<Err>> for ResourceRouter<Err> {
     type Response = WebResponse;
     type Error = Err::Container;
@@ -505,14 +578,24 @@ impl<Err: ErrorRenderer> Service<WebRequest<Err>> for ResourceRouter<Err> {
         mut req: WebRequest<Err>,
         ctx: ServiceCtx<'_, Self>,
     ) -> Result<Self::Response, Self::Error> {
-        for route in &self.routes {
-            if route.check(&mut req) {
-                if let Some(ref state) = self.state {
-                    req.set_state_container(state.clone());
-                }
-                return ctx.call(route, req).await;
-            }
+        if let Some(route) = self.select(&mut req, false).await {
+            return ctx.call(route, req).await;
+        }
+
+        // route `HEAD` request as `GET`, explicit `HEAD` routes take precedence.
+        // request method is not changed
+        if req.head().method == Method::HEAD
+            && req.app_state::<AutoHead>().is_some()
+            && let Some(route) = self.select(&mut req, true).await
+        {
+            let (mut res, req) = ctx.call(route, req).await?.into_parts();
+
+            // drop body, but keep its size
+            let size = res.body().size();
+            res = res.map_body(|_, _| Body::from_message(HeadBody(size)).into());
+            return Ok(WebResponse::new(res, req));
         }
+
         if let Some(ref default) = self.default {
             ctx.call(default, req).await
         } else {
@@ -524,15 +607,34 @@ impl<Err: ErrorRenderer> Service<WebRequest<Err>> for ResourceRouter<Err> {
     }
 }
 
+/// Marker for apps that route `HEAD` requests to `GET` routes
+pub(super) struct AutoHead;
+
+/// Empty body for `HEAD` responses, reports size of the original body
+struct HeadBody(BodySize);
+
+impl MessageBody for HeadBody {
+    fn size(&self) -> BodySize {
+        self.0
+    }
+
+    fn poll_next_chunk(
+        &mut self,
+        _: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Rc<dyn Error>>>> {
+        Poll::Ready(None)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::http::header::{self, HeaderValue};
     use crate::http::{Method, StatusCode};
     use crate::time::{Millis, sleep};
     use crate::web::middleware::DefaultHeaders;
-    use crate::web::test::{TestRequest, call_service, init_service};
-    use crate::web::{self, App, DefaultError, HttpResponse, guard, request::WebRequest};
-    use crate::{service::fn_service, util::Ready};
+    use crate::web::test::{TestRequest, call_service, init_service, read_body};
+    use crate::web::{self, App, DefaultError, HttpRequest, HttpResponse, guard};
+    use crate::{service::fn_service, util::Bytes, util::Ready, web::request::WebRequest};
 
     #[crate::rt_test]
     async fn test_filter() {
@@ -650,6 +752,72 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
     }
 
+    #[crate::rt_test]
+    async fn test_resource_metadata() {
+        #[derive(Debug, PartialEq)]
+        struct RouteDoc {
+            summary: &'static str,
+            tags: Vec<&'static str>,
+        }
+
+        let srv = init_service(
+            App::new()
+                .service(
+                    web::resource("/users")
+                        .metadata(RouteDoc {
+                            summary: "users",
+                            tags: vec!["users"],
+                        })
+                        .route(web::get().to(|| async { HttpResponse::Ok() })),
+                )
+                .service(
+                    web::scope("/api/").service(
+                        web::resource("")
+                            .metadata(RouteDoc {
+                                summary: "index",
+                                tags: vec![],
+                            })
+                            .to(|| async { HttpResponse::Ok() }),
+                    ),
+                )
+                .service(
+                    web::scope("/v1").service(
+                        web::resource("/items/{id}")
+                            .metadata(RouteDoc {
+                                summary: "item",
+                                tags: vec!["items"],
+                            })
+                            .to(|| async { HttpResponse::Ok() }),
+                    ),
+                )
+                .service(web::resource("/docs").to(|req: HttpRequest| async move {
+                    let docs: Vec<_> = req
+                        .resource_map()
+                        .resources()
+                        .into_iter()
+                        .map(|(path, rdef)| {
+                            let summary = rdef
+                                .metadata()
+                                .get::<RouteDoc>()
+                                .map_or("-", |doc| doc.summary);
+                            format!("{path} {summary}")
+                        })
+                        .collect();
+                    docs.join(",")
+                }))
+                .external_resource("youtube", "https://youtube.com/watch/{video_id}"),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/docs").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            read_body(resp).await,
+            Bytes::from_static(b"/users users,/api/ index,/v1/items/{id} item,/docs -")
+        );
+    }
+
     #[crate::rt_test]
     async fn test_resource_guards() {
         let srv = init_service(
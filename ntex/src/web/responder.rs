@@ -1,9 +1,10 @@
-use std::marker::PhantomData;
+use std::{error::Error, marker::PhantomData};
 
 use crate::http::error::HttpError;
 use crate::http::header::{HeaderMap, HeaderName, HeaderValue};
+use crate::http::sse::{Event, Sse};
 use crate::http::{Response, ResponseBuilder, StatusCode};
-use crate::util::{Bytes, BytesMut, Either};
+use crate::util::{Bytes, BytesMut, Either, Stream};
 
 use super::error::{
     DefaultError, ErrorContainer, ErrorRenderer, InternalError, WebResponseError,
@@ -120,6 +121,18 @@ where
     }
 }
 
+impl<S, E, Err> Responder<Err> for Sse<S, E>
+where
+    S: Stream<Item = Result<Event, E>> + Unpin + 'static,
+    E: Error + 'static,
+    Err: ErrorRenderer,
+{
+    #[inline]
+    async fn respond_to(self, _: &HttpRequest) -> Response {
+        Response::from(self)
+    }
+}
+
 impl<Err: ErrorRenderer> Responder<Err> for &'static str {
     async fn respond_to(self, _: &HttpRequest) -> Response {
         Response::build(StatusCode::OK)
@@ -267,6 +280,9 @@ impl<T: Responder<Err>, Err: ErrorRenderer> Responder<Err> for CustomResponder<T
 
 /// Combines two different responder types into a single type
 ///
+/// Active variant is selected at runtime, no boxing is required. To combine
+/// two different body types use `http::body::EitherBody`.
+///
 /// ```rust
 /// use ntex::{web::HttpResponse, util::Either};
 ///
@@ -339,6 +355,33 @@ pub(crate) mod tests {
         let req = TestRequest::with_uri("/index.html?query=test").to_request();
         let resp = srv.call(req).await.unwrap();
         assert_eq!(resp.status(), StatusCode::OK);
+
+        let srv = init_service(web::App::new().service(web::resource("/index.html").to(
+            |req: HttpRequest| async move {
+                if req.query_string().is_empty() {
+                    Either::Left(web::types::Json(vec![1, 2]))
+                } else {
+                    Either::Right(
+                        HttpResponse::Found()
+                            .header("location", "/index.html")
+                            .finish(),
+                    )
+                }
+            },
+        )))
+        .await;
+
+        let req = TestRequest::with_uri("/index.html").to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(CONTENT_TYPE).unwrap(),
+            HeaderValue::from_static("application/json")
+        );
+
+        let req = TestRequest::with_uri("/index.html?query=test").to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FOUND);
     }
 
     #[crate::rt_test]
@@ -93,6 +93,16 @@ impl WebResponse {
         self.response.headers_mut()
     }
 
+    #[must_use]
+    /// Flush each chunk of response body immediately.
+    ///
+    /// Use for streaming responses, like server-sent events. Unbuffered
+    /// responses are not compressed by `Compress` middleware.
+    pub fn unbuffered(mut self) -> Self {
+        self.response.head_mut().unbuffered(true);
+        self
+    }
+
     #[must_use]
     /// Execute closure and in case of error convert it to response.
     pub fn checked_expr<Err, F, E>(mut self, f: F) -> Self
@@ -111,10 +121,24 @@ impl WebResponse {
 
     #[must_use]
     /// Extract response body.
+    ///
+    /// Body is replaced with an empty one, new body could be set
+    /// with `set_body()`.
     pub fn take_body(&mut self) -> ResponseBody<Body> {
         self.response.take_body()
     }
 
+    /// Set response body.
+    ///
+    /// Existing body is dropped, `Content-Length` is calculated from the
+    /// new body.
+    pub fn set_body<B>(&mut self, body: B)
+    where
+        Body: From<B>,
+    {
+        *self.response.body_mut() = ResponseBody::Body(Body::from(body));
+    }
+
     #[must_use]
     /// Set a new body.
     pub fn map_body<F>(self, f: F) -> WebResponse
@@ -133,6 +157,13 @@ impl WebResponse {
     pub fn into_parts(self) -> (Response<Body>, HttpRequest) {
         (self.response, self.request)
     }
+
+    /// Convert web response to a `http::Response`
+    ///
+    /// Reason phrase and response extensions are not preserved.
+    pub fn into_http_response(self) -> http::Response<Body> {
+        http::Response::from(self.response).map(Body::from)
+    }
 }
 
 impl From<WebResponse> for Response<Body> {
@@ -184,4 +215,39 @@ mod tests {
         });
         assert_eq!(res.response().status(), StatusCode::PAYLOAD_TOO_LARGE);
     }
+
+    #[crate::rt_test]
+    async fn test_take_set_body() {
+        let mut res =
+            TestRequest::default().to_srv_response(HttpResponse::Ok().body("<p>hello</p>"));
+
+        let mut body = res.take_body();
+        assert!(res.response().body().as_ref().is_none());
+        let mut data = Vec::new();
+        while let Some(chunk) = crate::util::stream_recv(&mut body).await {
+            data.extend_from_slice(&chunk.unwrap());
+        }
+        assert_eq!(data, b"<p>hello</p>");
+
+        res.set_body(String::from_utf8(data).unwrap().replace("hello", "world"));
+        let res: http::Response = res.into();
+        assert_eq!(res.get_body_ref(), b"<p>world</p>");
+    }
+
+    #[test]
+    fn test_into_http_response() {
+        let res = TestRequest::default().to_srv_response(
+            HttpResponse::NotFound()
+                .header(http::header::CONTENT_TYPE, "text/plain")
+                .body("not found"),
+        );
+
+        let res = res.into_http_response();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            res.headers().get(http::header::CONTENT_TYPE).unwrap(),
+            "text/plain"
+        );
+        assert_eq!(res.body(), &http::body::Body::from("not found"));
+    }
 }
@@ -36,6 +36,39 @@ impl ResourceMap {
         }
     }
 
+    pub(crate) fn add_external(&mut self, pattern: ResourceDef) {
+        self.named.insert(pattern.name().to_string(), pattern);
+    }
+
+    /// Registered resources
+    ///
+    /// Returns full path pattern and definition of each registered resource,
+    /// including resources of nested scopes. External resources are not included.
+    pub fn resources(&self) -> Vec<(String, &ResourceDef)> {
+        let mut resources = Vec::new();
+        self.collect_resources("", &mut resources);
+        resources
+    }
+
+    fn collect_resources<'a>(
+        &'a self,
+        prefix: &str,
+        resources: &mut Vec<(String, &'a ResourceDef)>,
+    ) {
+        for (rdef, nested) in &self.patterns {
+            let pattern = rdef.pattern();
+            let path = match pattern.strip_prefix('/') {
+                Some(pattern) if prefix.ends_with('/') => format!("{prefix}{pattern}"),
+                _ => format!("{prefix}{pattern}"),
+            };
+            if let Some(nested) = nested {
+                nested.collect_resources(&path, resources);
+            } else {
+                resources.push((path, rdef));
+            }
+        }
+    }
+
     pub(crate) fn finish(&self, current: &Rc<ResourceMap>) {
         for (_, nested) in &self.patterns {
             if let Some(nested) = nested {
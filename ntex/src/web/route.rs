@@ -47,6 +47,7 @@ impl<Err: ErrorRenderer> Route<Err> {
             handler: self.handler.clone(),
             guards: self.guards.clone(),
             methods: self.methods.clone(),
+            async_guards: self.guards.is_async(),
         }
     }
 }
@@ -82,15 +83,40 @@ pub struct RouteService<Err: ErrorRenderer> {
     handler: Rc<dyn HandlerFn<Err>>,
     methods: Vec<Method>,
     guards: Rc<AllGuard>,
+    async_guards: bool,
 }
 
 impl<Err: ErrorRenderer> RouteService<Err> {
-    pub fn check(&self, req: &mut WebRequest<Err>) -> bool {
-        if !self.methods.is_empty() && !self.methods.contains(&req.head().method) {
-            return false;
-        }
+    /// Check if route contains async guards
+    pub(super) fn is_async(&self) -> bool {
+        self.async_guards
+    }
+
+    /// Check if route matches request, async guards are skipped
+    ///
+    /// Extensions stored by route guards are removed if request does not match.
+    /// If `auto_head` is set, route methods are matched with `GET` method.
+    pub(super) fn check(&self, req: &WebRequest<Err>, auto_head: bool) -> bool {
+        self.check_methods(req, auto_head)
+            && guard::check_sync_guards(&self.guards.0, req.head())
+    }
 
-        self.guards.check(req.head())
+    /// Check if route matches request, including async guards
+    ///
+    /// Extensions stored by route guards are removed if request does not match.
+    /// If `auto_head` is set, route methods are matched with `GET` method.
+    pub(super) async fn check_async(&self, req: &WebRequest<Err>, auto_head: bool) -> bool {
+        self.check_methods(req, auto_head)
+            && guard::check_guards(&self.guards.0, req.head()).await
+    }
+
+    fn check_methods(&self, req: &WebRequest<Err>, auto_head: bool) -> bool {
+        if auto_head {
+            // routes without methods are already checked with `HEAD` method
+            self.methods.contains(&Method::GET)
+        } else {
+            self.methods.is_empty() || self.methods.contains(&req.head().method)
+        }
     }
 }
 
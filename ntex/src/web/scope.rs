@@ -12,7 +12,7 @@ use super::app::Filter;
 use super::config::ServiceConfig;
 use super::dev::{WebServiceConfig, WebServiceFactory};
 use super::error::ErrorRenderer;
-use super::guard::Guard;
+use super::guard::{self, Guard};
 use super::request::WebRequest;
 use super::resource::Resource;
 use super::response::WebResponse;
@@ -421,8 +421,8 @@ where
         let mut rmap = ResourceMap::new(ResourceDef::root_prefix(self.rdef.clone()));
 
         // external resources
-        for mut rdef in std::mem::take(&mut self.external) {
-            rmap.add(&mut rdef, None);
+        for rdef in std::mem::take(&mut self.external) {
+            rmap.add_external(rdef);
         }
 
         // complete scope pipeline creation
@@ -437,7 +437,9 @@ where
                     // case for scope prefix ends with '/' and
                     // resource is empty pattern
                     let mut rdef = if slesh && rdef.pattern() == "" {
-                        ResourceDef::new("/")
+                        let mut root = ResourceDef::new("/");
+                        *root.metadata_mut() = rdef.metadata().clone();
+                        root
                     } else {
                         rdef
                     };
@@ -566,9 +568,13 @@ impl<Err: ErrorRenderer> ServiceFactory<WebRequest<Err>, SharedCfg>
         if self.case_insensitive {
             router.case_insensitive();
         }
+        let mut async_guards = false;
         for (path, factory, guards) in &mut self.services.iter() {
             let service = factory.create(cfg.clone()).await?;
-            router.rdef(path.clone(), service).2 = guards.borrow_mut().take();
+            let pattern = Rc::from(path.pattern());
+            let guards = guards.borrow_mut().take();
+            async_guards |= guards.as_deref().is_some_and(guard::has_async_guards);
+            router.rdef(path.clone(), (service, pattern)).2 = guards;
         }
 
         let default = if let Some(ref default) = self.default {
@@ -580,6 +586,7 @@ impl<Err: ErrorRenderer> ServiceFactory<WebRequest<Err>, SharedCfg>
         Ok(ScopeRouter {
             default,
             router: router.finish(),
+            async_guards,
             state: self.state.clone(),
         })
     }
@@ -587,7 +594,8 @@ impl<Err: ErrorRenderer> ServiceFactory<WebRequest<Err>, SharedCfg>
 
 struct ScopeRouter<Err: ErrorRenderer> {
     state: Option<AppState>,
-    router: Router<HttpService<Err>, Vec<Box<dyn Guard>>>,
+    router: Router<(HttpService<Err>, Rc<str>), Vec<Box<dyn Guard>>>,
+    async_guards: bool,
     default: Option<HttpService<Err>>,
 }
 
@@ -600,25 +608,23 @@ impl<Err: ErrorRenderer> Service<WebRequest<Err>> for ScopeRouter<Err> {
         mut req: WebRequest<Err>,
         ctx: ServiceCtx<'_, Self>,
     ) -> Result<Self::Response, Self::Error> {
-        let res = self.router.recognize_checked(&mut req, |req, guards| {
-            if let Some(guards) = guards {
-                for f in guards {
-                    if !f.check(req.head()) {
-                        return false;
-                    }
-                }
-            }
-            true
-        });
+        let res = guard::recognize(&self.router, &mut req, self.async_guards).await;
 
-        if let Some((srv, _info)) = res {
+        if let Some(((srv, pattern), _info)) = res {
             if let Some(ref state) = self.state {
                 req.set_state_container(state.clone());
             }
+            req.join_match_pattern(pattern);
+            #[cfg(feature = "tracing")]
+            if let Some(pattern) = req.match_pattern() {
+                super::span::record_route(pattern);
+            }
             ctx.call(srv, req).await
         } else if let Some(ref default) = self.default {
+            req.set_match_pattern(None);
             ctx.call(default, req).await
         } else {
+            req.set_match_pattern(None);
             let req = req.into_parts().0;
             Ok(WebResponse::new(Response::NotFound().finish(), req))
         }
@@ -1224,6 +1230,40 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::OK);
     }
 
+    #[crate::rt_test]
+    async fn test_match_pattern() {
+        async fn pattern(req: HttpRequest) -> HttpResponse {
+            HttpResponse::Ok().body(req.match_pattern().unwrap_or("none").to_string())
+        }
+
+        let srv = init_service(
+            App::new()
+                .service(web::resource("/users/{id}").to(pattern))
+                .service(
+                    web::scope("/app/{v}")
+                        .service(web::resource("/users/{id}").to(pattern))
+                        .service(
+                            web::scope("/nested").service(web::resource("").to(pattern)),
+                        )
+                        .default_service(web::resource("").to(pattern)),
+                )
+                .default_service(web::resource("").to(pattern)),
+        )
+        .await;
+
+        for (uri, expected) in [
+            ("/users/1", "/users/{id}"),
+            ("/app/v1/users/1", "/app/{v}/users/{id}"),
+            ("/app/v1/nested", "/app/{v}/nested"),
+            ("/app/v1/unknown", "none"),
+            ("/unknown", "none"),
+        ] {
+            let req = TestRequest::with_uri(uri).to_request();
+            let body = read_body(srv.call(req).await.unwrap()).await;
+            assert_eq!(body, expected.as_bytes(), "{uri}");
+        }
+    }
+
     #[cfg(feature = "url")]
     #[crate::rt_test]
     async fn test_url_for_external() {
@@ -369,7 +369,24 @@ where
     #[cfg(unix)]
     /// Start listening for incoming unix domain connections.
     ///
-    /// This method is available with `uds` feature.
+    /// Unix domain listener could be combined with tcp listeners, each
+    /// listener constructs its own application instances with the same
+    /// application factory.
+    ///
+    /// ```rust,no_run
+    /// use ntex::web::{self, App, HttpResponse, HttpServer};
+    ///
+    /// #[ntex::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     HttpServer::new(async || {
+    ///         App::new().service(web::resource("/").to(|| async { HttpResponse::Ok() }))
+    ///     })
+    ///     .bind("0.0.0.0:8080")?
+    ///     .bind_uds("/tmp/ntex.sock")?
+    ///     .run()
+    ///     .await
+    /// }
+    /// ```
     pub fn bind_uds<A>(mut self, addr: A) -> io::Result<Self>
     where
         A: AsRef<std::path::Path>,
@@ -0,0 +1,159 @@
+//! Per-request `tracing` spans
+use std::{future::Future, time::Instant};
+
+use tracing::{Instrument, Span, field};
+
+use crate::http::RequestHead;
+
+use super::response::WebResponse;
+
+/// Header with request id
+const X_REQUEST_ID: &str = "x-request-id";
+
+/// Create span for the request
+///
+/// Request id is taken from `x-request-id` header, new id is generated
+/// if request does not contain it. `route` field is recorded after routing,
+/// `status` and `latency_ms` fields are recorded on completion.
+pub(super) fn request_span(head: &RequestHead) -> Span {
+    let request_id = head
+        .headers
+        .get(X_REQUEST_ID)
+        .and_then(|val| val.to_str().ok())
+        .map_or_else(|| uuid::Uuid::now_v7().to_string(), ToString::to_string);
+
+    tracing::info_span!(
+        "request",
+        method = %head.method,
+        path = head.uri.path(),
+        route = field::Empty,
+        request_id = %request_id,
+        status = field::Empty,
+        latency_ms = field::Empty,
+        error = field::Empty,
+    )
+}
+
+/// Record matched resource pattern for current request span
+pub(super) fn record_route(pattern: &str) {
+    Span::current().record("route", pattern);
+}
+
+/// Run request future within the span
+pub(super) async fn instrument<F, E>(span: Span, fut: F) -> Result<WebResponse, E>
+where
+    F: Future<Output = Result<WebResponse, E>>,
+    E: std::fmt::Display,
+{
+    let start = Instant::now();
+    let result = fut.instrument(span.clone()).await;
+
+    match result {
+        Ok(ref res) => span.record("status", res.status().as_u16()),
+        Err(ref err) => span.record("error", field::display(err)),
+    };
+    span.record("latency_ms", start.elapsed().as_millis() as u64);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::{collections::HashMap, fmt};
+
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Current, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    use crate::web::test::{TestRequest, call_service, init_service};
+    use crate::web::{self, App, HttpResponse};
+
+    type Fields = Arc<Mutex<HashMap<&'static str, String>>>;
+
+    /// Subscriber that records fields of the request span
+    #[derive(Default)]
+    struct Recorder {
+        fields: Fields,
+        meta: Mutex<Option<&'static Metadata<'static>>>,
+        entered: Mutex<usize>,
+    }
+
+    struct Visitor<'a>(&'a Fields);
+
+    impl Visit for Visitor<'_> {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(field.name(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(field.name(), format!("{value:?}"));
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            *self.meta.lock().unwrap() = Some(span.metadata());
+            span.record(&mut Visitor(&self.fields));
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, values: &Record<'_>) {
+            values.record(&mut Visitor(&self.fields));
+        }
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &Event<'_>) {}
+
+        fn enter(&self, _: &Id) {
+            *self.entered.lock().unwrap() += 1;
+        }
+
+        fn exit(&self, _: &Id) {
+            *self.entered.lock().unwrap() -= 1;
+        }
+
+        fn current_span(&self) -> Current {
+            match *self.meta.lock().unwrap() {
+                Some(meta) if *self.entered.lock().unwrap() > 0 => {
+                    Current::new(Id::from_u64(1), meta)
+                }
+                _ => Current::none(),
+            }
+        }
+    }
+
+    #[crate::rt_test]
+    async fn test_request_span() {
+        let recorder = Recorder::default();
+        let fields = recorder.fields.clone();
+        let _guard = tracing::subscriber::set_default(recorder);
+
+        let srv = init_service(App::new().service(web::scope("/api").service(
+            web::resource("/users/{id}").to(|| async { HttpResponse::Created() }),
+        )))
+        .await;
+
+        let req = TestRequest::with_uri("/api/users/1")
+            .header("x-request-id", "req-1")
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status().as_u16(), 201);
+
+        let fields = fields.lock().unwrap();
+        assert_eq!(fields["route"], "/api/users/{id}");
+        assert_eq!(fields["status"], "201");
+        assert_eq!(fields["request_id"], "req-1");
+        assert_eq!(fields["path"], "/api/users/1");
+    }
+}
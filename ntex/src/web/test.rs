@@ -197,6 +197,42 @@ pub async fn read_body(mut res: WebResponse) -> Bytes {
     bytes.freeze()
 }
 
+/// Helper function that returns response body chunks.
+///
+/// Each chunk produced by response body is returned as is, so tests
+/// could check framing of streaming responses.
+///
+/// ```rust
+/// use ntex::web::{self, test, App, HttpResponse};
+/// use ntex::util::Bytes;
+///
+/// #[ntex::test]
+/// async fn test_chunks() {
+///     let app = test::init_service(
+///         App::new().service(web::resource("/").to(|| async {
+///             HttpResponse::Ok().streaming(futures_util::stream::iter([
+///                 Ok::<_, std::io::Error>(Bytes::from_static(b"1")),
+///                 Ok(Bytes::from_static(b"2")),
+///             ]))
+///         }))
+///     ).await;
+///
+///     let resp = test::call_service(&app, test::TestRequest::default().to_request()).await;
+///     let chunks = test::read_body_chunks(resp).await.unwrap();
+///     assert_eq!(chunks, vec![Bytes::from_static(b"1"), Bytes::from_static(b"2")]);
+/// }
+/// ```
+pub async fn read_body_chunks(
+    mut res: WebResponse,
+) -> Result<Vec<Bytes>, Rc<dyn std::error::Error>> {
+    let mut body = res.take_body();
+    let mut chunks = Vec::new();
+    while let Some(item) = stream_recv(&mut body).await {
+        chunks.push(item?);
+    }
+    Ok(chunks)
+}
+
 /// Reads response's body and combines it to a Bytes objects
 pub async fn load_stream<S, E>(mut stream: S) -> Result<Bytes, E>
 where
@@ -1130,6 +1166,39 @@ mod tests {
         assert_eq!(result, Bytes::from_static(b"welcome!"));
     }
 
+    #[crate::rt_test]
+    async fn test_read_body_chunks() {
+        let app = init_service(App::new().service(web::resource("/").to(|| async {
+            HttpResponse::Ok().streaming(futures_util::stream::iter([
+                Ok::<_, std::io::Error>(Bytes::from_static(b"data: 1\n\n")),
+                Ok(Bytes::from_static(b"data: 2\n\n")),
+                Ok(Bytes::from_static(b"data: 3\n\n")),
+            ]))
+        })))
+        .await;
+
+        let resp = call_service(&app, TestRequest::default().to_request()).await;
+        let chunks = read_body_chunks(resp).await.unwrap();
+        assert_eq!(
+            chunks,
+            vec![
+                Bytes::from_static(b"data: 1\n\n"),
+                Bytes::from_static(b"data: 2\n\n"),
+                Bytes::from_static(b"data: 3\n\n"),
+            ]
+        );
+
+        let app = init_service(App::new().service(web::resource("/").to(|| async {
+            HttpResponse::Ok().streaming(futures_util::stream::iter([
+                Ok::<_, std::io::Error>(Bytes::from_static(b"data")),
+                Err(std::io::Error::other("err")),
+            ]))
+        })))
+        .await;
+        let resp = call_service(&app, TestRequest::default().to_request()).await;
+        assert!(read_body_chunks(resp).await.is_err());
+    }
+
     #[derive(Serialize, Deserialize)]
     struct Person {
         id: String,
@@ -0,0 +1,232 @@
+use std::{fmt, ops};
+
+use serde::de::DeserializeOwned;
+
+use crate::http::{HttpMessage, Payload};
+use crate::web::error::{AnyBodyError, ErrorRenderer};
+use crate::web::extract::FromRequest;
+use crate::web::httprequest::HttpRequest;
+
+use super::{Form, Json};
+
+/// Request body extractor, deserializes body according to `Content-Type`.
+///
+/// Json (`application/json` and `+json` types) and urlencoded
+/// (`application/x-www-form-urlencoded`) bodies are supported, and
+/// messagepack bodies if `msgpack` feature is enabled. Body is extracted
+/// with `Json<T>`, `Form<T>` or `MsgPack<T>` extractors, their configs and
+/// payload limits are used. Other content types are rejected with
+/// *415 Unsupported Media Type* response.
+///
+/// ```rust
+/// use ntex::web::{self, App, types::AnyBody};
+///
+/// #[derive(serde::Deserialize)]
+/// struct Info {
+///     username: String,
+/// }
+///
+/// /// accepts json or urlencoded body
+/// async fn index(info: AnyBody<Info>) -> String {
+///     format!("Welcome {}!", info.username)
+/// }
+///
+/// fn main() {
+///     let app = App::new().service(
+///         web::resource("/index.html").route(web::post().to(index))
+///     );
+/// }
+/// ```
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+pub struct AnyBody<T>(pub T);
+
+impl<T> AnyBody<T> {
+    /// Deconstruct to an inner value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> ops::Deref for AnyBody<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> ops::DerefMut for AnyBody<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AnyBody<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AnyBody").field(&self.0).finish()
+    }
+}
+
+impl<T, Err> FromRequest<Err> for AnyBody<T>
+where
+    T: DeserializeOwned + 'static,
+    Err: ErrorRenderer,
+{
+    type Error = AnyBodyError;
+
+    async fn from_request(
+        req: &HttpRequest,
+        payload: &mut Payload,
+    ) -> Result<Self, Self::Error> {
+        let Ok(Some(mime)) = req.mime_type() else {
+            return Err(AnyBodyError::UnsupportedMediaType);
+        };
+
+        if mime.subtype() == mime::JSON || mime.suffix() == Some(mime::JSON) {
+            let body = <Json<T> as FromRequest<Err>>::from_request(req, payload).await?;
+            Ok(AnyBody(body.into_inner()))
+        } else if mime.subtype() == mime::WWW_FORM_URLENCODED {
+            let body = <Form<T> as FromRequest<Err>>::from_request(req, payload).await?;
+            Ok(AnyBody(body.into_inner()))
+        } else {
+            #[cfg(feature = "msgpack")]
+            if matches!(
+                mime.subtype().as_str(),
+                "msgpack" | "x-msgpack" | "vnd.msgpack"
+            ) {
+                let body =
+                    <super::MsgPack<T> as FromRequest<Err>>::from_request(req, payload)
+                        .await?;
+                return Ok(AnyBody(body.into_inner()));
+            }
+
+            log::debug!(
+                "Unsupported request body content type {:?}. Request path: {}",
+                mime,
+                req.path()
+            );
+            Err(AnyBodyError::UnsupportedMediaType)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::http::{StatusCode, header};
+    use crate::util::Bytes;
+    use crate::web::error::{JsonPayloadError, UrlencodedError};
+    use crate::web::test::{TestRequest, call_service, from_request, init_service};
+    use crate::web::types::{FormConfig, JsonConfig};
+    use crate::web::{self, App};
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Info {
+        name: String,
+        counter: i64,
+    }
+
+    #[crate::rt_test]
+    async fn test_content_types() {
+        let (req, mut pl) = TestRequest::default()
+            .header(header::CONTENT_TYPE, "application/json")
+            .set_payload(Bytes::from_static(b"{\"name\":\"test\",\"counter\":1}"))
+            .to_http_parts();
+        let body = from_request::<AnyBody<Info>>(&req, &mut pl).await.unwrap();
+        assert_eq!(body.name, "test");
+        assert_eq!(body.counter, 1);
+
+        let (req, mut pl) = TestRequest::default()
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(header::CONTENT_LENGTH, "19")
+            .set_payload(Bytes::from_static(b"name=test&counter=2"))
+            .to_http_parts();
+        let body = from_request::<AnyBody<Info>>(&req, &mut pl).await.unwrap();
+        assert_eq!(
+            body.into_inner(),
+            Info {
+                name: "test".to_string(),
+                counter: 2
+            }
+        );
+
+        let (req, mut pl) = TestRequest::default()
+            .header(header::CONTENT_TYPE, "text/plain")
+            .set_payload(Bytes::from_static(b"name=test"))
+            .to_http_parts();
+        let err = from_request::<AnyBody<Info>>(&req, &mut pl)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AnyBodyError::UnsupportedMediaType));
+
+        let (req, mut pl) = TestRequest::default()
+            .set_payload(Bytes::from_static(b"name=test"))
+            .to_http_parts();
+        let err = from_request::<AnyBody<Info>>(&req, &mut pl)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AnyBodyError::UnsupportedMediaType));
+    }
+
+    #[crate::rt_test]
+    async fn test_limits() {
+        let (req, mut pl) = TestRequest::default()
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::CONTENT_LENGTH, "27")
+            .set_payload(Bytes::from_static(b"{\"name\":\"test\",\"counter\":1}"))
+            .state(JsonConfig::default().limit(10))
+            .to_http_parts();
+        let err = from_request::<AnyBody<Info>>(&req, &mut pl)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AnyBodyError::Json(JsonPayloadError::Overflow)
+        ));
+
+        let (req, mut pl) = TestRequest::default()
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(header::CONTENT_LENGTH, "19")
+            .set_payload(Bytes::from_static(b"name=test&counter=2"))
+            .state(FormConfig::default().limit(10))
+            .to_http_parts();
+        let err = from_request::<AnyBody<Info>>(&req, &mut pl)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AnyBodyError::Form(UrlencodedError::Overflow { .. })
+        ));
+    }
+
+    #[crate::rt_test]
+    async fn test_responses() {
+        async fn index(body: AnyBody<Info>) -> String {
+            format!("{}", body.counter)
+        }
+
+        let srv = init_service(
+            App::new().service(web::resource("/").route(web::post().to(index))),
+        )
+        .await;
+
+        let req = TestRequest::post()
+            .uri("/")
+            .header(header::CONTENT_TYPE, "text/plain")
+            .set_payload(Bytes::from_static(b"name=test"))
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let req = TestRequest::post()
+            .uri("/")
+            .header(header::CONTENT_TYPE, "application/json")
+            .set_payload(Bytes::from_static(b"{\"name\":1}"))
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert!(format!("{:?}", AnyBody(1)).contains("AnyBody"));
+    }
+}
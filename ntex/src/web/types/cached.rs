@@ -0,0 +1,206 @@
+//! Cached request body extractor
+use std::ops;
+
+use crate::channel::bstream;
+use crate::http::{HeaderMap, Payload, error, header};
+use crate::util::{Bytes, BytesMut};
+use crate::web::error::{ErrorRenderer, PayloadError};
+use crate::web::{FromRequest, HttpRequest, WebRequest};
+
+use super::PayloadConfig;
+
+/// Request body that is buffered and cached in request extensions.
+///
+/// `CachedBody` reads request's payload into memory, stores it in request
+/// extensions and replaces request's payload with the copy of the body. This
+/// allows to read the same body multiple times, for example middleware could
+/// verify request signature and handler could still use `Json` or `Bytes`
+/// extractors. Body is cached as is, content encoding is not decoded.
+///
+/// [**`PayloadConfig`**](struct.PayloadConfig.html) limit is used as max size
+/// of the body.
+///
+/// ## Example
+///
+/// ```rust
+/// use ntex::web::{self, App, WebRequest, DefaultError};
+/// use ntex::web::types::{CachedBody, Json};
+///
+/// #[derive(serde::Deserialize)]
+/// struct Info {
+///     username: String,
+/// }
+///
+/// async fn index(body: CachedBody, info: Json<Info>) -> String {
+///     format!("Welcome {}! Body size: {}", info.username, body.len())
+/// }
+///
+/// fn main() {
+///     let app = App::new()
+///         .filter(ntex::fn_service(|mut req: WebRequest<DefaultError>| async move {
+///             let body = CachedBody::load(&mut req).await?;
+///             // verify signature of the body
+///             Ok::<_, web::Error>(req)
+///         }))
+///         .route("/index.html", web::post().to(index));
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachedBody(pub Bytes);
+
+impl CachedBody {
+    /// Deconstruct to an inner value
+    pub fn into_inner(self) -> Bytes {
+        self.0
+    }
+
+    /// Read and cache request's body.
+    ///
+    /// If body is already cached, cached value is returned.
+    pub async fn load<Err>(req: &mut WebRequest<Err>) -> Result<CachedBody, PayloadError> {
+        if let Some(body) = req.extensions().get::<CachedBody>() {
+            return Ok(body.clone());
+        }
+
+        let limit = req
+            .app_state::<PayloadConfig>()
+            .map_or(PayloadConfig::default().limit, |cfg| cfg.limit);
+        let mut payload = req.take_payload();
+        let result = read(req.headers(), &mut payload, limit).await;
+        req.set_payload(payload);
+
+        let body = CachedBody(result?);
+        req.extensions_mut().insert(body.clone());
+        Ok(body)
+    }
+}
+
+impl ops::Deref for CachedBody {
+    type Target = Bytes;
+
+    fn deref(&self) -> &Bytes {
+        &self.0
+    }
+}
+
+impl<Err: ErrorRenderer> FromRequest<Err> for CachedBody {
+    type Error = PayloadError;
+
+    async fn from_request(
+        req: &HttpRequest,
+        payload: &mut Payload,
+    ) -> Result<CachedBody, Self::Error> {
+        if let Some(body) = req.extensions().get::<CachedBody>() {
+            return Ok(body.clone());
+        }
+
+        let limit = req
+            .app_state::<PayloadConfig>()
+            .map_or(PayloadConfig::default().limit, |cfg| cfg.limit);
+
+        let body = CachedBody(read(req.headers(), payload, limit).await?);
+        req.extensions_mut().insert(body.clone());
+        Ok(body)
+    }
+}
+
+/// Read payload and replace it with the copy of the body
+async fn read(
+    headers: &HeaderMap,
+    payload: &mut Payload,
+    limit: usize,
+) -> Result<Bytes, PayloadError> {
+    if let Some(len) = headers.get(&header::CONTENT_LENGTH) {
+        match len.to_str().ok().and_then(|s| s.parse::<usize>().ok()) {
+            Some(len) if len > limit => {
+                return Err(PayloadError::from(error::PayloadError::Overflow));
+            }
+            Some(_) => (),
+            None => return Err(PayloadError::from(error::PayloadError::UnknownLength)),
+        }
+    }
+
+    let mut body = BytesMut::with_capacity(8192);
+    while let Some(item) = payload.recv().await {
+        let chunk = item?;
+        if body.len() + chunk.len() > limit {
+            return Err(PayloadError::from(error::PayloadError::Overflow));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    let body = body.freeze();
+    *payload = Payload::H1(bstream::empty(Some(body.clone())));
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::StatusCode;
+    use crate::service::fn_service;
+    use crate::web::test::{TestRequest, call_service, from_request, init_service};
+    use crate::web::types::Json;
+    use crate::web::{self, App, DefaultError, HttpResponse};
+
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Info {
+        hello: String,
+    }
+
+    #[crate::rt_test]
+    async fn test_cached_body() {
+        let (req, mut pl) = TestRequest::default()
+            .set_json(&serde_json::json!({"hello": "world"}))
+            .to_http_parts();
+
+        let body = from_request::<CachedBody>(&req, &mut pl).await.unwrap();
+        assert_eq!(body.0, Bytes::from_static(b"{\"hello\":\"world\"}"));
+        assert_eq!(req.extensions().get::<CachedBody>(), Some(&body));
+
+        let info = from_request::<Json<Info>>(&req, &mut pl).await.unwrap();
+        assert_eq!(info.hello, "world");
+
+        let body2 = from_request::<CachedBody>(&req, &mut pl).await.unwrap();
+        assert_eq!(body, body2);
+    }
+
+    #[crate::rt_test]
+    async fn test_cached_body_limit() {
+        let (req, mut pl) = TestRequest::default()
+            .set_payload(Bytes::from_static(b"hello=world"))
+            .state(PayloadConfig::new(5))
+            .to_http_parts();
+        let res = from_request::<CachedBody>(&req, &mut pl).await;
+        assert!(matches!(
+            res,
+            Err(PayloadError::Payload(error::PayloadError::Overflow))
+        ));
+    }
+
+    #[crate::rt_test]
+    async fn test_cached_body_filter() {
+        let srv = init_service(
+            App::new()
+                .filter(fn_service(|mut req: WebRequest<DefaultError>| async move {
+                    let body = CachedBody::load(&mut req).await?;
+                    assert_eq!(body.0, Bytes::from_static(b"{\"hello\":\"world\"}"));
+                    Ok::<_, web::Error>(req)
+                }))
+                .route(
+                    "/",
+                    web::post().to(|body: CachedBody, info: Json<Info>| async move {
+                        assert_eq!(body.0, Bytes::from_static(b"{\"hello\":\"world\"}"));
+                        assert_eq!(info.hello, "world");
+                        HttpResponse::Ok()
+                    }),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::post()
+            .set_json(&serde_json::json!({"hello": "world"}))
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
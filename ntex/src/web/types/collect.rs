@@ -0,0 +1,212 @@
+use std::{fmt, fmt::Write, ops};
+
+use crate::http::{Payload, StatusCode, body::Body, header};
+use crate::util::BytesMut;
+use crate::web::error::{ErrorContainer, ErrorRenderer, WebResponseError};
+use crate::web::{FromRequest, HttpRequest, HttpResponse};
+
+/// Extract multiple values and collect all extraction errors.
+///
+/// Tuple extractor returns error of the first failed extractor, `Collect`
+/// runs all extractors and returns `CollectError` with all failures. Response
+/// for collected errors uses status code of the first error, response body
+/// contains descriptions of all errors, one per line.
+///
+/// ```rust
+/// use ntex::web::{self, types::{Collect, Query}, App};
+///
+/// #[derive(serde::Deserialize)]
+/// struct Page {
+///     page: u32,
+/// }
+///
+/// #[derive(serde::Deserialize)]
+/// struct Limit {
+///     limit: u32,
+/// }
+///
+/// async fn index(params: Collect<(Query<Page>, Query<Limit>)>) -> String {
+///     let (page, limit) = params.into_inner();
+///     format!("page: {}, limit: {}", page.page, limit.limit)
+/// }
+///
+/// fn main() {
+///     let app = App::new().service(
+///         web::resource("/index.html").route(web::get().to(index))
+///     );
+/// }
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct Collect<T>(pub T);
+
+impl<T> Collect<T> {
+    /// Deconstruct to an inner value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> ops::Deref for Collect<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+/// Errors collected by `Collect` extractor
+pub struct CollectError<Err: ErrorRenderer>(Vec<Err::Container>);
+
+impl<Err: ErrorRenderer> CollectError<Err> {
+    /// Collected errors
+    pub fn errors(&self) -> &[Err::Container] {
+        &self.0
+    }
+}
+
+impl<Err: ErrorRenderer> fmt::Debug for CollectError<Err> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CollectError").field(&self.0).finish()
+    }
+}
+
+impl<Err: ErrorRenderer> fmt::Display for CollectError<Err> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, err) in self.0.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{err}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<Err: ErrorRenderer> WebResponseError<Err> for CollectError<Err> {
+    fn error_response(&self, req: &HttpRequest) -> HttpResponse {
+        let status = self.0.first().map_or(StatusCode::BAD_REQUEST, |err| {
+            ErrorContainer::error_response(err, req).status()
+        });
+
+        let mut resp = HttpResponse::new(status);
+        let mut buf = BytesMut::new();
+        let _ = write!(&mut buf, "{self}");
+        resp.headers_mut().insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("text/plain; charset=utf-8"),
+        );
+        resp.set_body(Body::from(buf))
+    }
+}
+
+impl<Err: ErrorRenderer> std::error::Error for CollectError<Err> {}
+
+macro_rules! collect_from_req {
+    ($(#[$meta:meta])* $(($T:ident, $t:ident)),*) => {
+        $(#[$meta])*
+        impl<$($T,)+ Err: ErrorRenderer> FromRequest<Err> for Collect<($($T,)+)>
+        where
+            $($T: FromRequest<Err> + 'static,)+
+            $(<$T as $crate::web::FromRequest<Err>>::Error: Into<Err::Container>),+
+        {
+            type Error = CollectError<Err>;
+
+            async fn from_request(req: &HttpRequest, payload: &mut Payload) -> Result<Self, Self::Error> {
+                let mut errors = Vec::new();
+                $(
+                    let $t = match $T::from_request(req, payload).await {
+                        Ok(item) => Some(item),
+                        Err(e) => {
+                            errors.push(e.into());
+                            None
+                        }
+                    };
+                )+
+
+                if errors.is_empty() {
+                    Ok(Collect(($($t.unwrap(),)+)))
+                } else {
+                    Err(CollectError(errors))
+                }
+            }
+        }
+    }
+}
+
+#[allow(non_snake_case, clippy::wildcard_imports)]
+#[rustfmt::skip]
+mod m {
+    use super::*;
+    use variadics_please::all_tuples;
+
+    all_tuples!(#[doc(fake_variadic)] collect_from_req, 1, 12, T, t);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::Bytes;
+    use crate::web::test::{
+        TestRequest, call_service, from_request, init_service, read_body,
+    };
+    use crate::web::types::Query;
+    use crate::web::{self, App, DefaultError};
+
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Page {
+        page: u32,
+    }
+
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Limit {
+        limit: u32,
+    }
+
+    type Params = Collect<(Query<Page>, Query<Limit>)>;
+
+    #[crate::rt_test]
+    async fn test_collect() {
+        let (req, mut pl) = TestRequest::with_uri("/?page=1&limit=10").to_http_parts();
+        let res = from_request::<Params>(&req, &mut pl).await.unwrap();
+        assert_eq!(res.0.0.page, 1);
+        assert_eq!(res.0.1.limit, 10);
+
+        let (req, mut pl) = TestRequest::with_uri("/").to_http_parts();
+        let err = from_request::<Params>(&req, &mut pl).await.unwrap_err();
+        assert_eq!(err.errors().len(), 2);
+        let resp = WebResponseError::<DefaultError>::error_response(&err, &req);
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[crate::rt_test]
+    async fn test_collect_handler() {
+        let srv = init_service(App::new().service(web::resource("/").to(
+            |params: Params| async move {
+                let (page, limit) = params.into_inner();
+                format!("{} {}", page.page, limit.limit)
+            },
+        )))
+        .await;
+
+        let req = TestRequest::with_uri("/?page=1").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body = read_body(resp).await;
+        assert_eq!(
+            body,
+            Bytes::from_static(b"Query deserialize error: missing field `limit`")
+        );
+
+        let req = TestRequest::with_uri("/").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body = read_body(resp).await;
+        assert_eq!(
+            body,
+            Bytes::from_static(
+                b"Query deserialize error: missing field `page`\n\
+                  Query deserialize error: missing field `limit`"
+            )
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use std::{fmt, marker::PhantomData, ops::Deref};
+
+use crate::http::{Payload, StatusCode, header::HeaderValue};
+use crate::web::error::{ErrorRenderer, MissingHeaderError};
+use crate::web::extract::FromRequest;
+use crate::web::httprequest::HttpRequest;
+
+/// Required header definition for `RequireHeader` extractor.
+pub trait RequiredHeader {
+    /// Header name
+    const NAME: &'static str;
+
+    /// Response status code if header is missing
+    ///
+    /// *400 Bad Request* is used by default.
+    const STATUS: StatusCode = StatusCode::BAD_REQUEST;
+}
+
+/// Required header extractor.
+///
+/// Extracts value of the header defined by `H`. If header is missing
+/// extraction fails with `MissingHeaderError` and response with
+/// `H::STATUS` status code is returned. Extractors are executed in order,
+/// so putting `RequireHeader` first rejects requests before other
+/// extractors consume request payload.
+///
+/// ```rust
+/// use ntex::http::StatusCode;
+/// use ntex::web::{self, App, types::{Json, RequireHeader, RequiredHeader}};
+///
+/// struct IdempotencyKey;
+///
+/// impl RequiredHeader for IdempotencyKey {
+///     const NAME: &'static str = "idempotency-key";
+///     const STATUS: StatusCode = StatusCode::PRECONDITION_REQUIRED;
+/// }
+///
+/// async fn index(
+///     key: RequireHeader<IdempotencyKey>,
+///     body: Json<serde_json::Value>,
+/// ) -> String {
+///     format!("{:?}", key.to_str())
+/// }
+///
+/// fn main() {
+///     let app = App::new().service(
+///         web::resource("/index.html").route(web::post().to(index))
+///     );
+/// }
+/// ```
+pub struct RequireHeader<H>(HeaderValue, PhantomData<H>);
+
+impl<H> RequireHeader<H> {
+    /// Deconstruct to an inner value
+    pub fn into_inner(self) -> HeaderValue {
+        self.0
+    }
+}
+
+impl<H> Deref for RequireHeader<H> {
+    type Target = HeaderValue;
+
+    fn deref(&self) -> &HeaderValue {
+        &self.0
+    }
+}
+
+impl<H> Clone for RequireHeader<H> {
+    fn clone(&self) -> Self {
+        RequireHeader(self.0.clone(), PhantomData)
+    }
+}
+
+impl<H: RequiredHeader> fmt::Debug for RequireHeader<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequireHeader")
+            .field("name", &H::NAME)
+            .field("value", &self.0)
+            .finish()
+    }
+}
+
+impl<H: RequiredHeader, Err: ErrorRenderer> FromRequest<Err> for RequireHeader<H> {
+    type Error = MissingHeaderError;
+
+    #[inline]
+    async fn from_request(req: &HttpRequest, _: &mut Payload) -> Result<Self, Self::Error> {
+        if let Some(value) = req.headers().get(H::NAME) {
+            Ok(RequireHeader(value.clone(), PhantomData))
+        } else {
+            log::debug!(
+                "Required header {:?} is missing. Request path: {:?}",
+                H::NAME,
+                req.path()
+            );
+            Err(MissingHeaderError::new(H::NAME, H::STATUS))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::Bytes;
+    use crate::web::test::{TestRequest, call_service, init_service, read_body};
+    use crate::web::{self, App, types::Json};
+
+    struct IdempotencyKey;
+
+    impl RequiredHeader for IdempotencyKey {
+        const NAME: &'static str = "idempotency-key";
+    }
+
+    struct ApiKey;
+
+    impl RequiredHeader for ApiKey {
+        const NAME: &'static str = "x-api-key";
+        const STATUS: StatusCode = StatusCode::UNAUTHORIZED;
+    }
+
+    #[crate::rt_test]
+    async fn test_require_header() {
+        let srv = init_service(
+            App::new()
+                .service(web::resource("/").route(web::post().to(
+                    |key: RequireHeader<IdempotencyKey>, _: Json<u32>| async move {
+                        key.to_str().unwrap().to_string()
+                    },
+                )))
+                .service(
+                    web::resource("/api")
+                        .to(|key: RequireHeader<ApiKey>| async move { format!("{key:?}") }),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::post()
+            .header("idempotency-key", "abc")
+            .header("content-type", "application/json")
+            .set_payload(Bytes::from_static(b"1"))
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"abc"));
+
+        // missing header is checked before payload
+        let req = TestRequest::post().to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            read_body(resp).await,
+            Bytes::from_static(b"Required header \"idempotency-key\" is missing")
+        );
+
+        let req = TestRequest::with_uri("/api").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = TestRequest::with_uri("/api")
+            .header("x-api-key", "1")
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(
+            std::str::from_utf8(&read_body(resp).await)
+                .unwrap()
+                .contains("x-api-key")
+        );
+    }
+}
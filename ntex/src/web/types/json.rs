@@ -1,5 +1,7 @@
 //! Json extractor/responder
-use std::{fmt, future::Future, ops, pin::Pin, sync::Arc, task::Context, task::Poll};
+use std::any::{Any, TypeId};
+use std::{collections::HashMap, error::Error, fmt, future::Future, ops, pin::Pin};
+use std::{sync::Arc, task::Context, task::Poll};
 
 use serde::{Serialize, de::DeserializeOwned};
 
@@ -165,11 +167,16 @@ where
         payload: &mut Payload,
     ) -> Result<Self, Self::Error> {
         let req2 = req.clone();
-        let (limit, ctype) = req
-            .app_state::<JsonConfig>()
-            .map_or((32768, None), |c| (c.limit, c.content_type.as_ref()));
-
-        match JsonBody::new(req, payload, ctype).limit(limit).await {
+        let cfg = req.app_state::<JsonConfig>();
+        let (limit, ctype) =
+            cfg.map_or((32768, None), |c| (c.limit, c.content_type.as_ref()));
+        let deserializer = cfg.and_then(JsonConfig::get_deserializer::<T>);
+
+        match JsonBody::new(req, payload, ctype)
+            .limit(limit)
+            .deserializer(deserializer)
+            .await
+        {
             Err(e) => {
                 log::debug!(
                     "Failed to deserialize Json from payload. \
@@ -209,6 +216,8 @@ where
 ///                    .content_type(|mime| {  // <- accept text/plain content type
 ///                        mime.type_() == mime::TEXT && mime.subtype() == mime::PLAIN
 ///                    })
+///                    // <- custom deserializer for `Info` type
+///                    .deserializer(|body: &[u8]| serde_json::from_slice::<Info>(body))
 ///             )
 ///             .route(web::post().to(index))
 ///     );
@@ -218,8 +227,11 @@ where
 pub struct JsonConfig {
     limit: usize,
     content_type: Option<Arc<dyn Fn(mime::Mime) -> bool + Send + Sync>>,
+    deserializers: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
 }
 
+type Deserializer<T> = Arc<dyn Fn(&[u8]) -> Result<T, JsonPayloadError> + Send + Sync>;
+
 impl JsonConfig {
     #[must_use]
     /// Change max size of payload.
@@ -239,6 +251,31 @@ impl JsonConfig {
         self.content_type = Some(Arc::new(predicate));
         self
     }
+
+    #[must_use]
+    /// Set custom deserializer for type `T`.
+    ///
+    /// Deserializer is called with complete request's body, it could be used
+    /// for alternative json parsers or for stricter parsing rules.
+    /// By default `serde_json::from_slice()` is used.
+    pub fn deserializer<T, F, E>(mut self, f: F) -> Self
+    where
+        T: 'static,
+        F: Fn(&[u8]) -> Result<T, E> + Send + Sync + 'static,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let f: Deserializer<T> =
+            Arc::new(move |body| f(body).map_err(|e| JsonPayloadError::Custom(e.into())));
+        self.deserializers.insert(TypeId::of::<T>(), Arc::new(f));
+        self
+    }
+
+    fn get_deserializer<T: 'static>(&self) -> Option<Deserializer<T>> {
+        self.deserializers
+            .get(&TypeId::of::<T>())
+            .and_then(|f| f.downcast_ref::<Deserializer<T>>())
+            .cloned()
+    }
 }
 
 impl Default for JsonConfig {
@@ -246,6 +283,7 @@ impl Default for JsonConfig {
         JsonConfig {
             limit: 32768,
             content_type: None,
+            deserializers: HashMap::new(),
         }
     }
 }
@@ -261,6 +299,7 @@ impl fmt::Debug for JsonConfig {
                     .as_ref()
                     .map(|_| "Arc<dyn Fn(mime::Mime) -> bool + Send + Sync>"),
             )
+            .field("deserializers", &self.deserializers.len())
             .finish()
     }
 }
@@ -280,6 +319,7 @@ struct JsonBody<U> {
     #[cfg(not(feature = "compress"))]
     stream: Option<Payload>,
     err: Option<JsonPayloadError>,
+    deserializer: Option<Deserializer<U>>,
     fut: Option<BoxFuture<'static, Result<U, JsonPayloadError>>>,
 }
 
@@ -308,6 +348,7 @@ where
                 length: None,
                 stream: None,
                 fut: None,
+                deserializer: None,
                 err: Some(JsonPayloadError::ContentType),
             };
         }
@@ -328,6 +369,7 @@ where
             length: len,
             stream: Some(payload),
             fut: None,
+            deserializer: None,
             err: None,
         }
     }
@@ -337,6 +379,12 @@ where
         self.limit = limit;
         self
     }
+
+    /// Set custom deserializer
+    fn deserializer(mut self, deserializer: Option<Deserializer<U>>) -> Self {
+        self.deserializer = deserializer;
+        self
+    }
 }
 
 impl<U> Future for JsonBody<U>
@@ -361,6 +409,7 @@ where
             return Poll::Ready(Err(JsonPayloadError::Overflow));
         }
         let mut stream = self.stream.take().unwrap();
+        let deserializer = self.deserializer.take();
 
         self.fut = Some(Box::pin(async move {
             let mut body = BytesMut::with_capacity(8192);
@@ -372,7 +421,11 @@ where
                 }
                 body.extend_from_slice(&chunk);
             }
-            Ok(serde_json::from_slice::<U>(&body)?)
+            if let Some(deserializer) = deserializer {
+                deserializer(&body)
+            } else {
+                Ok(serde_json::from_slice::<U>(&body)?)
+            }
         }));
 
         self.poll(cx)
@@ -496,6 +549,44 @@ mod tests {
         assert!(format!("{:?}", s.err().unwrap()).contains("Deserialize(Error("));
     }
 
+    #[crate::rt_test]
+    async fn test_custom_deserializer() {
+        let cfg = JsonConfig::default()
+            .deserializer(|body: &[u8]| {
+                let name = std::str::from_utf8(body)?.trim_matches('"').to_string();
+                Ok::<_, std::str::Utf8Error>(MyObject { name })
+            })
+            .deserializer(|_: &[u8]| Err::<u32, _>("not supported"));
+        assert!(format!("{cfg:?}").contains("deserializers: 2"));
+
+        let (req, mut pl) = TestRequest::default()
+            .header(header::CONTENT_TYPE, "application/json")
+            .set_payload(Bytes::from_static(b"\"test\""))
+            .state(cfg.clone())
+            .to_http_parts();
+        let s = from_request::<Json<MyObject>>(&req, &mut pl).await.unwrap();
+        assert_eq!(s.name, "test");
+
+        let (req, mut pl) = TestRequest::default()
+            .header(header::CONTENT_TYPE, "application/json")
+            .set_payload(Bytes::from_static(b"1"))
+            .state(cfg.clone())
+            .to_http_parts();
+        let s = from_request::<Json<u32>>(&req, &mut pl).await;
+        let err = s.err().unwrap();
+        assert!(matches!(err, JsonPayloadError::Custom(_)));
+        assert_eq!(err.to_string(), "Json deserialize error: not supported");
+
+        // other types use serde_json
+        let (req, mut pl) = TestRequest::default()
+            .header(header::CONTENT_TYPE, "application/json")
+            .set_payload(Bytes::from_static(b"[1]"))
+            .state(cfg)
+            .to_http_parts();
+        let s = from_request::<Json<Vec<u32>>>(&req, &mut pl).await.unwrap();
+        assert_eq!(s.into_inner(), vec![1]);
+    }
+
     #[crate::rt_test]
     async fn test_json_body() {
         let (req, mut pl) = TestRequest::default().to_http_parts();
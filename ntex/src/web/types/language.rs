@@ -0,0 +1,310 @@
+use std::str::FromStr;
+
+use crate::http::Payload;
+use crate::http::header::{ACCEPT_LANGUAGE, HeaderMap};
+use crate::web::error::ErrorRenderer;
+use crate::web::extract::FromRequest;
+use crate::web::httprequest::HttpRequest;
+
+/// Preferred language extractor.
+///
+/// Parses language ranges and quality values of the request
+/// `Accept-Language` header. Ranges are sorted by quality, ranges with zero
+/// quality are not acceptable. Supported languages and default language
+/// could be configured with [`LanguageConfig`](struct.LanguageConfig.html)
+/// stored in app state.
+///
+/// ```rust
+/// use ntex::web::{self, App, types::{LanguageConfig, PreferredLanguage}};
+///
+/// async fn index(lang: PreferredLanguage) -> String {
+///     match lang.language() {
+///         Some("fr") => "Bonjour".to_string(),
+///         _ => "Hello".to_string(),
+///     }
+/// }
+///
+/// fn main() {
+///     let app = App::new()
+///         .state(
+///             LanguageConfig::default()
+///                 .supported(["en", "fr"])
+///                 .default_language("en"),
+///         )
+///         .service(web::resource("/index.html").to(index));
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PreferredLanguage {
+    ranges: Vec<LanguageRange>,
+    cfg: LanguageConfig,
+}
+
+#[derive(Clone, Debug)]
+struct LanguageRange {
+    tag: String,
+    quality: f32,
+}
+
+impl LanguageRange {
+    fn parse(item: &str) -> Option<LanguageRange> {
+        let mut parts = item.split(';');
+        let tag = parts.next()?.trim();
+        if tag.is_empty() {
+            return None;
+        }
+
+        let mut quality = 1.0;
+        for param in parts {
+            if let Some((name, value)) = param.split_once('=')
+                && name.trim().eq_ignore_ascii_case("q")
+            {
+                quality = f32::from_str(value.trim()).ok()?.clamp(0.0, 1.0);
+            }
+        }
+        Some(LanguageRange {
+            tag: tag.to_ascii_lowercase(),
+            quality,
+        })
+    }
+
+    /// Specificity of the range if it matches language tag
+    fn matches(&self, tag: &str) -> Option<u8> {
+        let tag = tag.to_ascii_lowercase();
+        if self.tag == "*" {
+            Some(0)
+        } else if self.tag == tag {
+            Some(3)
+        } else if is_prefix(&self.tag, &tag) {
+            // `en` range matches `en-us` tag
+            Some(2)
+        } else if is_prefix(&tag, &self.tag) {
+            // `en-us` range matches `en` tag
+            Some(1)
+        } else {
+            None
+        }
+    }
+}
+
+fn is_prefix(prefix: &str, tag: &str) -> bool {
+    tag.len() > prefix.len()
+        && tag.starts_with(prefix)
+        && tag.as_bytes()[prefix.len()] == b'-'
+}
+
+impl PreferredLanguage {
+    /// Parse `Accept-Language` headers
+    ///
+    /// Invalid language ranges are ignored.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let mut ranges: Vec<_> = headers
+            .get_all(ACCEPT_LANGUAGE)
+            .filter_map(|hdr| hdr.to_str().ok())
+            .flat_map(|val| val.split(','))
+            .filter_map(LanguageRange::parse)
+            .collect();
+        ranges.sort_by(|a, b| b.quality.total_cmp(&a.quality));
+
+        PreferredLanguage {
+            ranges,
+            cfg: LanguageConfig::default(),
+        }
+    }
+
+    #[must_use]
+    /// Use supported languages and default language from config
+    pub fn config(mut self, cfg: LanguageConfig) -> Self {
+        self.cfg = cfg;
+        self
+    }
+
+    /// Acceptable language ranges, sorted by quality
+    pub fn ranges(&self) -> impl Iterator<Item = &str> {
+        self.ranges
+            .iter()
+            .filter(|range| range.quality > 0.0)
+            .map(|range| range.tag.as_str())
+    }
+
+    /// Quality of the language tag
+    ///
+    /// Returns `0.0` if language is not acceptable.
+    pub fn quality(&self, tag: &str) -> f32 {
+        if self.ranges.is_empty() {
+            return 1.0;
+        }
+
+        let mut result: Option<(u8, f32)> = None;
+        for range in &self.ranges {
+            if let Some(spec) = range.matches(tag)
+                && result.is_none_or(|(s, _)| spec > s)
+            {
+                result = Some((spec, range.quality));
+            }
+        }
+        result.map_or(0.0, |(_, q)| q)
+    }
+
+    /// Select best match from the provided language tags
+    ///
+    /// Tags with equal quality are preferred in the order of the list. If
+    /// request does not contain `Accept-Language` header or none of the
+    /// tags is acceptable, configured default language is returned.
+    pub fn best_match<'a>(&'a self, available: &[&'a str]) -> Option<&'a str> {
+        if self.ranges.is_empty() && self.cfg.default.is_some() {
+            return self.cfg.default.as_deref();
+        }
+
+        let mut best: Option<(&str, f32)> = None;
+        for tag in available {
+            let quality = self.quality(tag);
+            if quality > 0.0 && best.is_none_or(|(_, q)| quality > q) {
+                best = Some((tag, quality));
+            }
+        }
+        best.map(|(tag, _)| tag).or(self.cfg.default.as_deref())
+    }
+
+    /// Select best match from the configured supported languages
+    pub fn language(&self) -> Option<&str> {
+        let available: Vec<&str> = self.cfg.supported.iter().map(String::as_str).collect();
+        self.best_match(&available)
+    }
+}
+
+impl<Err: ErrorRenderer> FromRequest<Err> for PreferredLanguage {
+    type Error = Err::Container;
+
+    #[inline]
+    async fn from_request(req: &HttpRequest, _: &mut Payload) -> Result<Self, Self::Error> {
+        let lang = PreferredLanguage::from_headers(req.headers());
+        if let Some(cfg) = req.app_state::<LanguageConfig>() {
+            Ok(lang.config(cfg.clone()))
+        } else {
+            Ok(lang)
+        }
+    }
+}
+
+/// `PreferredLanguage` extractor configuration
+#[derive(Clone, Debug, Default)]
+pub struct LanguageConfig {
+    supported: Vec<String>,
+    default: Option<String>,
+}
+
+impl LanguageConfig {
+    #[must_use]
+    /// Set list of supported languages
+    ///
+    /// Languages are preferred in the order of the list if client accepts
+    /// them with equal quality.
+    pub fn supported<I, T>(mut self, langs: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.supported = langs.into_iter().map(Into::into).collect();
+        self
+    }
+
+    #[must_use]
+    /// Set default language
+    ///
+    /// Default language is used if request does not contain `Accept-Language`
+    /// header or none of the languages is acceptable.
+    pub fn default_language(mut self, lang: impl Into<String>) -> Self {
+        self.default = Some(lang.into());
+        self
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::http::header::HeaderValue;
+    use crate::util::Bytes;
+    use crate::web::test::{TestRequest, call_service, init_service, read_body};
+    use crate::web::{self, App};
+
+    fn lang(accept: &str) -> PreferredLanguage {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT_LANGUAGE, HeaderValue::from_str(accept).unwrap());
+        PreferredLanguage::from_headers(&headers)
+    }
+
+    #[test]
+    fn test_quality() {
+        let l = PreferredLanguage::default();
+        assert_eq!(l.quality("en"), 1.0);
+
+        let l = lang("fr-CH, fr;q=0.9, en;q=0.8, de;q=0.7, *;q=0.5");
+        assert_eq!(
+            l.ranges().collect::<Vec<_>>(),
+            ["fr-ch", "fr", "en", "de", "*"]
+        );
+        assert_eq!(l.quality("fr-CH"), 1.0);
+        assert_eq!(l.quality("fr"), 0.9);
+        assert_eq!(l.quality("en-US"), 0.8);
+        assert_eq!(l.quality("it"), 0.5);
+
+        let l = lang("en-US, de;q=0, invalid;q=bad");
+        assert_eq!(l.ranges().collect::<Vec<_>>(), ["en-us"]);
+        assert_eq!(l.quality("en"), 1.0);
+        assert_eq!(l.quality("de"), 0.0);
+        assert_eq!(l.quality("english"), 0.0);
+    }
+
+    #[test]
+    fn test_best_match() {
+        let l = lang("fr;q=0.9, en;q=0.8");
+        assert_eq!(l.best_match(&["en", "fr"]), Some("fr"));
+
+        let l = lang("en-GB, fr;q=0.9");
+        assert_eq!(l.best_match(&["en", "fr"]), Some("en"));
+        assert_eq!(l.best_match(&["de"]), None);
+
+        let l = PreferredLanguage::default();
+        assert_eq!(l.best_match(&["en", "fr"]), Some("en"));
+        assert_eq!(l.best_match(&[]), None);
+
+        // default language
+        let cfg = LanguageConfig::default().default_language("de");
+        let l = PreferredLanguage::default().config(cfg.clone());
+        assert_eq!(l.best_match(&["en", "fr"]), Some("de"));
+        let l = lang("it, fr;q=0.1").config(cfg);
+        assert_eq!(l.best_match(&["en", "fr"]), Some("fr"));
+        assert_eq!(l.best_match(&["en"]), Some("de"));
+    }
+
+    #[crate::rt_test]
+    async fn test_extractor() {
+        let srv = init_service(
+            App::new()
+                .state(
+                    LanguageConfig::default()
+                        .supported(["en", "fr"])
+                        .default_language("en"),
+                )
+                .service(web::resource("/").to(|l: PreferredLanguage| async move {
+                    l.language().unwrap_or_default().to_string()
+                })),
+        )
+        .await;
+
+        let req = TestRequest::with_header(ACCEPT_LANGUAGE, "fr-FR, en;q=0.5").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"fr"));
+
+        let req = TestRequest::with_header(ACCEPT_LANGUAGE, "de").to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"en"));
+
+        let req = TestRequest::default().to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"en"));
+        assert!(format!("{:?}", LanguageConfig::default()).contains("LanguageConfig"));
+    }
+}
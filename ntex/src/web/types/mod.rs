@@ -1,15 +1,38 @@
 //! Extractor types
 
+mod anybody;
+mod cached;
+mod collect;
 pub(in crate::web) mod form;
+mod header;
 pub(in crate::web) mod json;
+mod language;
+#[cfg(feature = "msgpack")]
+mod msgpack;
+mod ndjson;
 mod path;
 pub(in crate::web) mod payload;
 mod query;
+mod reqdata;
+mod sni;
 pub(in crate::web) mod state;
+mod target;
 
+pub use self::anybody::AnyBody;
+pub use self::cached::CachedBody;
+pub use self::collect::{Collect, CollectError};
 pub use self::form::{Form, FormConfig};
+pub use self::header::{RequireHeader, RequiredHeader};
 pub use self::json::{Json, JsonConfig};
+pub use self::language::{LanguageConfig, PreferredLanguage};
+#[cfg(feature = "msgpack")]
+pub use self::msgpack::{MsgPack, MsgPackConfig};
+pub use self::ndjson::NdJson;
 pub use self::path::Path;
 pub use self::payload::{Payload, PayloadConfig};
-pub use self::query::Query;
-pub use self::state::State;
+pub use self::query::{Query, QueryMulti};
+pub use self::reqdata::{ReqData, ReqLocal};
+pub use self::sni::Sni;
+pub use self::state::{Keyed, KeyedState, State};
+pub use self::target::RequestTarget;
+pub use crate::web::info::{ConnectionInfo, ProxyHeaders};
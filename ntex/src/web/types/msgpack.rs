@@ -0,0 +1,411 @@
+//! MessagePack extractor/responder
+use std::{fmt, ops, sync::Arc};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+#[cfg(feature = "compress")]
+use crate::http::encoding::Decoder;
+use crate::http::header::CONTENT_LENGTH;
+use crate::http::{HttpMessage, Payload, Response, StatusCode};
+use crate::util::{BytesMut, stream_recv};
+use crate::web::error::{ErrorRenderer, MsgPackPayloadError, WebResponseError};
+use crate::web::{FromRequest, HttpRequest, Responder};
+
+/// MessagePack helper
+///
+/// MsgPack can be used for messagepack response generation and
+/// for extracting typed information from request's payload.
+///
+/// To extract typed information from request's body, the type `T` must
+/// implement the `Deserialize` trait from *serde*. Request content type must
+/// be `application/msgpack`, `application/x-msgpack` or
+/// `application/vnd.msgpack`.
+///
+/// [**`MsgPackConfig`**](struct.MsgPackConfig.html) allows to configure
+/// extraction process.
+///
+/// ## Example
+///
+/// ```rust
+/// use ntex::web::{self, types::MsgPack};
+///
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Info {
+///     username: String,
+/// }
+///
+/// /// deserialize `Info` from request's body and send it back
+/// async fn index(info: MsgPack<Info>) -> MsgPack<Info> {
+///     info
+/// }
+///
+/// fn main() {
+///     let app = web::App::new().service(
+///         web::resource("/index.html").route(web::post().to(index))
+///     );
+/// }
+/// ```
+pub struct MsgPack<T>(pub T);
+
+impl<T> MsgPack<T> {
+    /// Deconstruct to an inner value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> ops::Deref for MsgPack<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> ops::DerefMut for MsgPack<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> fmt::Debug for MsgPack<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MsgPack: {:?}", self.0)
+    }
+}
+
+impl<T: Serialize, Err: ErrorRenderer> Responder<Err> for MsgPack<T>
+where
+    Err::Container: From<rmp_serde::encode::Error>,
+{
+    async fn respond_to(self, req: &HttpRequest) -> Response {
+        let body = match rmp_serde::to_vec_named(&self.0) {
+            Ok(body) => body,
+            Err(e) => return e.error_response(req),
+        };
+
+        Response::build(StatusCode::OK)
+            .content_type("application/msgpack")
+            .body(body)
+    }
+}
+
+impl<T, Err: ErrorRenderer> FromRequest<Err> for MsgPack<T>
+where
+    T: DeserializeOwned + 'static,
+{
+    type Error = MsgPackPayloadError;
+
+    async fn from_request(
+        req: &HttpRequest,
+        payload: &mut Payload,
+    ) -> Result<Self, Self::Error> {
+        let (limit, ctype) = req
+            .app_state::<MsgPackConfig>()
+            .map_or((32768, None), |c| (c.limit, c.content_type.as_ref()));
+
+        match decode_body(req, payload, limit, ctype).await {
+            Err(e) => {
+                log::debug!(
+                    "Failed to deserialize MessagePack from payload. \
+                     Request path: {}",
+                    req.path()
+                );
+                Err(e)
+            }
+            Ok(data) => Ok(MsgPack(data)),
+        }
+    }
+}
+
+/// MessagePack extractor configuration
+///
+/// ```rust
+/// use ntex::web::{self, App, types::MsgPack};
+///
+/// #[derive(serde::Deserialize)]
+/// struct Info {
+///     username: String,
+/// }
+///
+/// /// deserialize `Info` from request's body, max payload size is 4kb
+/// async fn index(info: MsgPack<Info>) -> String {
+///     format!("Welcome {}!", info.username)
+/// }
+///
+/// fn main() {
+///     let app = App::new().service(
+///         web::resource("/index.html")
+///             .state(
+///                 // change messagepack extractor configuration
+///                 web::types::MsgPackConfig::default()
+///                    .limit(4096)
+///                    .content_type(|mime| {  // <- accept application/octet-stream
+///                        mime == mime::APPLICATION_OCTET_STREAM
+///                    })
+///             )
+///             .route(web::post().to(index))
+///     );
+/// }
+/// ```
+#[derive(Clone)]
+pub struct MsgPackConfig {
+    limit: usize,
+    content_type: Option<Arc<dyn Fn(mime::Mime) -> bool + Send + Sync>>,
+}
+
+impl MsgPackConfig {
+    #[must_use]
+    /// Change max size of payload.
+    ///
+    /// By default max size is 32Kb
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    #[must_use]
+    /// Set predicate for allowed content types.
+    pub fn content_type<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(mime::Mime) -> bool + Send + Sync + 'static,
+    {
+        self.content_type = Some(Arc::new(predicate));
+        self
+    }
+}
+
+impl Default for MsgPackConfig {
+    fn default() -> Self {
+        MsgPackConfig {
+            limit: 32768,
+            content_type: None,
+        }
+    }
+}
+
+impl fmt::Debug for MsgPackConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MsgPackConfig")
+            .field("limit", &self.limit)
+            .field(
+                "content_type",
+                &self
+                    .content_type
+                    .as_ref()
+                    .map(|_| "Arc<dyn Fn(mime::Mime) -> bool + Send + Sync>"),
+            )
+            .finish()
+    }
+}
+
+/// Read request's payload and deserialize it from messagepack
+async fn decode_body<U: DeserializeOwned>(
+    req: &HttpRequest,
+    payload: &mut Payload,
+    limit: usize,
+    ctype: Option<&Arc<dyn Fn(mime::Mime) -> bool + Send + Sync>>,
+) -> Result<U, MsgPackPayloadError> {
+    // check content-type
+    let msgpack = if let Ok(Some(mime)) = req.mime_type() {
+        (mime.type_() == mime::APPLICATION
+            && matches!(
+                mime.subtype().as_str(),
+                "msgpack" | "x-msgpack" | "vnd.msgpack"
+            ))
+            || ctype.as_ref().is_some_and(|predicate| predicate(mime))
+    } else {
+        false
+    };
+    if !msgpack {
+        return Err(MsgPackPayloadError::ContentType);
+    }
+
+    let len = req
+        .headers()
+        .get(&CONTENT_LENGTH)
+        .and_then(|l| l.to_str().ok())
+        .and_then(|s| s.parse::<usize>().ok());
+    if let Some(len) = len
+        && len > limit
+    {
+        return Err(MsgPackPayloadError::Overflow);
+    }
+
+    #[cfg(feature = "compress")]
+    let mut stream = Decoder::from_headers(payload.take(), req.headers());
+    #[cfg(not(feature = "compress"))]
+    let mut stream = payload.take();
+
+    let mut body = BytesMut::with_capacity(8192);
+    while let Some(item) = stream_recv(&mut stream).await {
+        let chunk = item?;
+        if (body.len() + chunk.len()) > limit {
+            return Err(MsgPackPayloadError::Overflow);
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(rmp_serde::from_slice::<U>(&body)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::header;
+    use crate::util::Bytes;
+    use crate::web::test::{
+        TestRequest, call_service, from_request, init_service, read_body, respond_to,
+    };
+    use crate::web::{self, App};
+
+    #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+    struct MyObject {
+        name: String,
+    }
+
+    fn encoded() -> Bytes {
+        Bytes::from(
+            rmp_serde::to_vec_named(&MyObject {
+                name: "test".to_string(),
+            })
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_msgpack() {
+        let mut m = MsgPack(MyObject {
+            name: "test2".to_string(),
+        });
+        assert_eq!(m.name, "test2");
+        m.name = "test".to_string();
+        assert_eq!(m.name, "test");
+        assert!(format!("{m:?}").contains("MsgPack"));
+
+        let cfg = MsgPackConfig::default()
+            .content_type(|mime: mime::Mime| mime == mime::APPLICATION_OCTET_STREAM);
+        assert!(format!("{cfg:?}").contains("MsgPackConfig"));
+    }
+
+    #[crate::rt_test]
+    async fn test_responder() {
+        let req = TestRequest::default().to_http_request();
+
+        let m = MsgPack(MyObject {
+            name: "test".to_string(),
+        });
+        let resp = respond_to(m, &req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            header::HeaderValue::from_static("application/msgpack")
+        );
+        assert_eq!(resp.get_body_ref(), &encoded()[..]);
+    }
+
+    #[crate::rt_test]
+    async fn test_extract() {
+        let (req, mut pl) = TestRequest::default()
+            .header(header::CONTENT_TYPE, "application/msgpack")
+            .set_payload(encoded())
+            .to_http_parts();
+        let s = from_request::<MsgPack<MyObject>>(&req, &mut pl)
+            .await
+            .unwrap();
+        assert_eq!(
+            s.into_inner(),
+            MyObject {
+                name: "test".to_string()
+            }
+        );
+
+        let (req, mut pl) = TestRequest::default()
+            .header(header::CONTENT_TYPE, "application/x-msgpack")
+            .set_payload(encoded())
+            .to_http_parts();
+        let s = from_request::<MsgPack<MyObject>>(&req, &mut pl).await;
+        assert!(s.is_ok());
+
+        // content type
+        let (req, mut pl) = TestRequest::default()
+            .header(header::CONTENT_TYPE, "application/json")
+            .set_payload(encoded())
+            .to_http_parts();
+        let s = from_request::<MsgPack<MyObject>>(&req, &mut pl).await;
+        assert!(matches!(s, Err(MsgPackPayloadError::ContentType)));
+
+        let (req, mut pl) = TestRequest::default()
+            .header(header::CONTENT_TYPE, "text/msgpack")
+            .set_payload(encoded())
+            .to_http_parts();
+        let s = from_request::<MsgPack<MyObject>>(&req, &mut pl).await;
+        assert!(matches!(s, Err(MsgPackPayloadError::ContentType)));
+
+        let (req, mut pl) = TestRequest::default()
+            .header(header::CONTENT_TYPE, "application/octet-stream")
+            .set_payload(encoded())
+            .state(
+                MsgPackConfig::default()
+                    .content_type(|mime| mime == mime::APPLICATION_OCTET_STREAM),
+            )
+            .to_http_parts();
+        let s = from_request::<MsgPack<MyObject>>(&req, &mut pl).await;
+        assert!(s.is_ok());
+
+        // limit
+        let (req, mut pl) = TestRequest::default()
+            .header(header::CONTENT_TYPE, "application/msgpack")
+            .header(header::CONTENT_LENGTH, "10")
+            .set_payload(encoded())
+            .state(MsgPackConfig::default().limit(4))
+            .to_http_parts();
+        let s = from_request::<MsgPack<MyObject>>(&req, &mut pl).await;
+        assert!(matches!(s, Err(MsgPackPayloadError::Overflow)));
+
+        let (req, mut pl) = TestRequest::default()
+            .header(header::CONTENT_TYPE, "application/msgpack")
+            .set_payload(encoded())
+            .state(MsgPackConfig::default().limit(4))
+            .to_http_parts();
+        let s = from_request::<MsgPack<MyObject>>(&req, &mut pl).await;
+        assert!(matches!(s, Err(MsgPackPayloadError::Overflow)));
+
+        // decode error
+        let (req, mut pl) = TestRequest::default()
+            .header(header::CONTENT_TYPE, "application/msgpack")
+            .set_payload(Bytes::from_static(b"\xc1"))
+            .to_http_parts();
+        let s = from_request::<MsgPack<MyObject>>(&req, &mut pl).await;
+        assert!(matches!(s, Err(MsgPackPayloadError::Deserialize(_))));
+    }
+
+    #[crate::rt_test]
+    async fn test_handler() {
+        let srv = init_service(App::new().service(web::resource("/").to(
+            |m: MsgPack<MyObject>| async move {
+                MsgPack(MyObject {
+                    name: m.name.clone(),
+                })
+            },
+        )))
+        .await;
+
+        let req = TestRequest::post()
+            .header(header::CONTENT_TYPE, "application/msgpack")
+            .set_payload(encoded())
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(read_body(resp).await, encoded());
+
+        let req = TestRequest::post()
+            .header(header::CONTENT_TYPE, "application/msgpack")
+            .set_payload(Bytes::from_static(b"\xc1"))
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+}
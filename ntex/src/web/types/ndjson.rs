@@ -0,0 +1,167 @@
+//! Newline delimited json responder
+use std::{
+    error::Error, fmt, marker::PhantomData, pin::Pin, rc::Rc, task::Context, task::Poll,
+};
+
+use serde::Serialize;
+
+use crate::http::body::{Body, BodySize, MessageBody};
+use crate::http::{Response, StatusCode};
+use crate::util::{Bytes, Stream};
+use crate::web::{ErrorRenderer, HttpRequest, Responder};
+
+/// Newline delimited json responder
+///
+/// Every item of the stream is serialized to json and is sent as separate
+/// line, response has `application/x-ndjson` content type. Next item is
+/// requested from the stream only when peer is ready to accept more data.
+///
+/// Stream error or serialization error terminates response body.
+///
+/// ```rust
+/// use ntex::web::{self, types::NdJson};
+/// use futures_util::stream::iter;
+///
+/// #[derive(serde::Serialize)]
+/// struct Row {
+///     id: u32,
+/// }
+///
+/// async fn rows() -> impl web::Responder {
+///     NdJson::new(iter(
+///         (0..3).map(|id| Ok::<_, std::io::Error>(Row { id })),
+///     ))
+/// }
+/// # fn main() {}
+/// ```
+pub struct NdJson<S, T, E> {
+    stream: S,
+    _t: PhantomData<(T, E)>,
+}
+
+impl<S, T, E> NdJson<S, T, E>
+where
+    S: Stream<Item = Result<T, E>> + Unpin + 'static,
+    T: Serialize + 'static,
+    E: Error + 'static,
+{
+    /// Create ndjson responder from the stream of serializable items
+    pub fn new(stream: S) -> Self {
+        NdJson {
+            stream,
+            _t: PhantomData,
+        }
+    }
+}
+
+impl<S, T, E> fmt::Debug for NdJson<S, T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NdJson").finish()
+    }
+}
+
+impl<S, T, E> MessageBody for NdJson<S, T, E>
+where
+    S: Stream<Item = Result<T, E>> + Unpin + 'static,
+    T: Serialize + 'static,
+    E: Error + 'static,
+{
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    fn poll_next_chunk(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Rc<dyn Error>>>> {
+        match Pin::new(&mut self.stream).poll_next(cx) {
+            Poll::Ready(Some(Ok(item))) => {
+                Poll::Ready(Some(match serde_json::to_vec(&item) {
+                    Ok(mut buf) => {
+                        buf.push(b'\n');
+                        Ok(Bytes::from(buf))
+                    }
+                    Err(e) => {
+                        log::error!("Cannot serialize ndjson item: {e}");
+                        Err(Rc::new(e))
+                    }
+                }))
+            }
+            Poll::Ready(Some(Err(e))) => {
+                log::error!("Ndjson stream error: {e}");
+                Poll::Ready(Some(Err(Rc::new(e))))
+            }
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<S, T, E, Err: ErrorRenderer> Responder<Err> for NdJson<S, T, E>
+where
+    S: Stream<Item = Result<T, E>> + Unpin + 'static,
+    T: Serialize + 'static,
+    E: Error + 'static,
+{
+    async fn respond_to(self, _: &HttpRequest) -> Response {
+        Response::build(StatusCode::OK)
+            .content_type("application/x-ndjson")
+            .body(Body::from_message(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use futures_util::stream::iter;
+
+    use super::*;
+    use crate::http::header::CONTENT_TYPE;
+    use crate::web::test::{TestRequest, read_body_chunks, respond_to};
+
+    #[derive(Serialize)]
+    struct Item {
+        id: u32,
+    }
+
+    #[crate::rt_test]
+    async fn test_ndjson() {
+        let req = TestRequest::default().to_http_request();
+        let stream = iter((0..3).map(|id| Ok::<_, io::Error>(Item { id })));
+        let resp = respond_to(NdJson::new(stream), &req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(CONTENT_TYPE).unwrap(),
+            "application/x-ndjson"
+        );
+
+        let chunks = read_body_chunks(crate::web::WebResponse::new(resp, req))
+            .await
+            .unwrap();
+        assert_eq!(
+            chunks,
+            vec![
+                Bytes::from_static(b"{\"id\":0}\n"),
+                Bytes::from_static(b"{\"id\":1}\n"),
+                Bytes::from_static(b"{\"id\":2}\n"),
+            ]
+        );
+    }
+
+    #[crate::rt_test]
+    async fn test_ndjson_error() {
+        let req = TestRequest::default().to_http_request();
+        let stream = iter(vec![
+            Ok(Item { id: 0 }),
+            Err(io::Error::other("err")),
+            Ok(Item { id: 1 }),
+        ]);
+        let resp = respond_to(NdJson::new(stream), &req).await;
+        assert!(
+            read_body_chunks(crate::web::WebResponse::new(resp, req))
+                .await
+                .is_err()
+        );
+    }
+}
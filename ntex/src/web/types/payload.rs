@@ -234,7 +234,7 @@ impl<Err: ErrorRenderer> FromRequest<Err> for String {
 /// Payload configuration for request's payload.
 #[derive(Clone, Debug)]
 pub struct PayloadConfig {
-    limit: usize,
+    pub(super) limit: usize,
     mimetype: Option<Mime>,
 }
 
@@ -1,7 +1,10 @@
 //! Query extractor
-use std::{fmt, ops};
+use std::{collections::BTreeMap, fmt, ops};
 
-use serde::de;
+use serde::de::{self, IntoDeserializer, value::Error};
+use serde::{
+    de::value::MapDeserializer, de::value::SeqDeserializer, forward_to_deserialize_any,
+};
 
 use crate::http::Payload;
 use crate::web::error::{ErrorRenderer, QueryPayloadError};
@@ -147,6 +150,322 @@ where
     }
 }
 
+/// Extract typed information from the request's query, repeated keys are
+/// collected into sequences.
+///
+/// `Query` extractor can not deserialize repeated keys, i.e. `?tag=a&tag=b`,
+/// into a `Vec`. `QueryMulti` groups values of repeated keys, so such
+/// keys could be deserialized into any sequence type. Keys with single value
+/// could be deserialized into sequences as well. Deserialization of repeated
+/// keys into non-sequence types fails.
+///
+/// ## Example
+///
+/// ```rust
+/// use ntex::web;
+///
+/// #[derive(serde::Deserialize)]
+/// pub struct Search {
+///    q: String,
+///    #[serde(default, rename = "tag")]
+///    tags: Vec<String>,
+/// }
+///
+/// // The correct request for this handler would be `/search?q=ntex&tag=a&tag=b"`.
+/// async fn index(search: web::types::QueryMulti<Search>) -> String {
+///     format!("Search {} with tags {:?}", search.q, search.tags)
+/// }
+///
+/// fn main() {
+///     let app = web::App::new().service(
+///        web::resource("/search").route(web::get().to(index)));
+/// }
+/// ```
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+pub struct QueryMulti<T>(pub T);
+
+impl<T> QueryMulti<T> {
+    /// Deconstruct to a inner value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+
+    /// Get query parameters from the path
+    pub fn from_query(query_str: &str) -> Result<Self, QueryPayloadError>
+    where
+        T: de::DeserializeOwned,
+    {
+        from_query_multi::<T>(query_str).map_or_else(
+            |e| Err(QueryPayloadError::Deserialize(e)),
+            |val| Ok(QueryMulti(val)),
+        )
+    }
+}
+
+impl<T> ops::Deref for QueryMulti<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> ops::DerefMut for QueryMulti<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for QueryMulti<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for QueryMulti<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T, Err> FromRequest<Err> for QueryMulti<T>
+where
+    T: de::DeserializeOwned,
+    Err: ErrorRenderer,
+{
+    type Error = QueryPayloadError;
+
+    #[inline]
+    async fn from_request(req: &HttpRequest, _: &mut Payload) -> Result<Self, Self::Error> {
+        from_query_multi::<T>(req.query_string()).map_or_else(
+            move |e| {
+                let e = QueryPayloadError::Deserialize(e);
+                log::debug!(
+                    "Failed during QueryMulti extractor deserialization. \
+                     Request path: {:?}",
+                    req.path()
+                );
+                Err(e)
+            },
+            |val| Ok(QueryMulti(val)),
+        )
+    }
+}
+
+/// Deserialize query string, values of repeated keys are grouped
+fn from_query_multi<T: de::DeserializeOwned>(query: &str) -> Result<T, Error> {
+    let pairs = serde_urlencoded::from_str::<Vec<(String, String)>>(query)?;
+
+    let mut items: BTreeMap<String, Values> = BTreeMap::new();
+    for (key, val) in pairs {
+        items.entry(key).or_default().0.push(val);
+    }
+    T::deserialize(MapDeserializer::new(items.into_iter()))
+}
+
+/// All values of the key
+#[derive(Default)]
+struct Values(Vec<String>);
+
+impl Values {
+    fn single(mut self) -> Result<Value, Error> {
+        if self.0.len() == 1 {
+            Ok(Value(self.0.pop().unwrap()))
+        } else {
+            Err(de::Error::custom("multiple values for a key"))
+        }
+    }
+
+    fn seq(self) -> SeqDeserializer<std::vec::IntoIter<Value>, Error> {
+        let values: Vec<_> = self.0.into_iter().map(Value).collect();
+        SeqDeserializer::new(values.into_iter())
+    }
+}
+
+impl IntoDeserializer<'_, Error> for Values {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! forward_to_single {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                self.single()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Values {
+    type Error = Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.0.len() == 1 {
+            self.single()?.deserialize_any(visitor)
+        } else {
+            self.seq().deserialize_any(visitor)
+        }
+    }
+
+    fn deserialize_seq<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.seq().deserialize_any(visitor)
+    }
+
+    fn deserialize_tuple<V: de::Visitor<'de>>(
+        self,
+        _: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.seq().deserialize_any(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: de::Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.seq().deserialize_any(visitor)
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_ignored_any<V: de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.single()?.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_struct<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.single()?.deserialize_struct(name, fields, visitor)
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.single()?.deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_single! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_unit
+        deserialize_map deserialize_identifier
+    }
+}
+
+/// Single value of the key
+struct Value(String);
+
+impl IntoDeserializer<'_, Error> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! parse_value {
+    ($($method:ident => $visit:ident)*) => {
+        $(
+            fn $method<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                match self.0.parse() {
+                    Ok(val) => visitor.$visit(val),
+                    Err(e) => Err(de::Error::custom(e)),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(self.0)
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    parse_value! {
+        deserialize_bool => visit_bool
+        deserialize_i8 => visit_i8
+        deserialize_i16 => visit_i16
+        deserialize_i32 => visit_i32
+        deserialize_i64 => visit_i64
+        deserialize_i128 => visit_i128
+        deserialize_u8 => visit_u8
+        deserialize_u16 => visit_u16
+        deserialize_u32 => visit_u32
+        deserialize_u64 => visit_u64
+        deserialize_u128 => visit_u128
+        deserialize_f32 => visit_f32
+        deserialize_f64 => visit_f64
+    }
+
+    forward_to_deserialize_any! {
+        char str string bytes byte_buf unit unit_struct seq tuple tuple_struct
+        map struct identifier ignored_any
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -174,6 +493,57 @@ mod tests {
         assert_eq!(s.id, "test1");
     }
 
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    enum Sort {
+        Asc,
+        Desc,
+    }
+
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Search {
+        q: String,
+        #[serde(default, rename = "tag")]
+        tags: Vec<String>,
+        ids: Vec<u32>,
+        page: Option<u32>,
+        sort: Option<Sort>,
+    }
+
+    #[crate::rt_test]
+    async fn test_query_multi() {
+        let req =
+            TestRequest::with_uri("/?q=ntex%20web&tag=a&ids=1&tag=b&sort=Desc&x=1&x=2")
+                .to_srv_request();
+        let (req, mut pl) = req.into_parts();
+        let s = from_request::<QueryMulti<Search>>(&req, &mut pl)
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(
+            s,
+            Search {
+                q: "ntex web".to_string(),
+                tags: vec!["a".to_string(), "b".to_string()],
+                ids: vec![1],
+                page: None,
+                sort: Some(Sort::Desc),
+            }
+        );
+
+        let s = QueryMulti::<Search>::from_query("q=1&ids=1&ids=2&page=3").unwrap();
+        assert!(s.tags.is_empty());
+        assert_eq!(s.ids, vec![1, 2]);
+        assert_eq!(s.page, Some(3));
+
+        // type mismatch
+        assert!(QueryMulti::<Search>::from_query("q=1&ids=1&ids=a").is_err());
+        // repeated key for non-sequence
+        assert!(QueryMulti::<Search>::from_query("q=1&q=2&ids=1").is_err());
+        assert!(QueryMulti::<Search>::from_query("q=1&ids=1&page=1&page=2").is_err());
+        // missing key
+        assert!(QueryMulti::<Search>::from_query("tag=a").is_err());
+    }
+
     #[crate::rt_test]
     async fn test_request_extract() {
         let req = TestRequest::with_uri("/name/user1/").to_srv_request();
@@ -0,0 +1,275 @@
+use std::ops::Deref;
+
+use crate::http::Payload;
+use crate::web::error::{ErrorRenderer, ReqDataExtractorError, ReqLocalError};
+use crate::web::extract::FromRequest;
+use crate::web::httprequest::HttpRequest;
+
+/// Request-local data extractor.
+///
+/// Request-local data is stored in request extensions by guards or
+/// middlewares, `ReqData<T>` extractor returns clone of the stored value.
+/// It allows to parse request data once, for example in a guard,
+/// and reuse it in a handler.
+///
+/// If data is not stored, using `ReqData<T>` extractor would
+/// cause *Internal Server Error* response.
+///
+/// ```rust
+/// use ntex::http::RequestHead;
+/// use ntex::web::{self, guard, types::ReqData, App, HttpResponse};
+///
+/// #[derive(Clone, Debug)]
+/// struct ApiVersion(u32);
+///
+/// fn version_guard(head: &RequestHead) -> bool {
+///     let version = head
+///         .headers()
+///         .get("x-api-version")
+///         .and_then(|val| val.to_str().ok())
+///         .and_then(|val| val.parse().ok());
+///     if let Some(version) = version {
+///         head.extensions_mut().insert(ApiVersion(version));
+///         true
+///     } else {
+///         false
+///     }
+/// }
+///
+/// async fn index(version: ReqData<ApiVersion>) -> HttpResponse {
+///     HttpResponse::Ok().body(format!("version: {}", version.0))
+/// }
+///
+/// fn main() {
+///     let app = App::new().service(
+///         web::resource("/index.html")
+///             .route(web::get().guard(guard::fn_guard(version_guard)).to(index)),
+///     );
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReqData<T>(pub T);
+
+impl<T> ReqData<T> {
+    /// Deconstruct to an inner value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for ReqData<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Clone + 'static, E: ErrorRenderer> FromRequest<E> for ReqData<T> {
+    type Error = ReqDataExtractorError;
+
+    #[inline]
+    async fn from_request(req: &HttpRequest, _: &mut Payload) -> Result<Self, Self::Error> {
+        if let Some(data) = req.extensions().get::<T>() {
+            Ok(ReqData(data.clone()))
+        } else {
+            log::debug!(
+                "Failed to construct request data extractor, {} is not found. \
+                 Request path: {:?}",
+                std::any::type_name::<T>(),
+                req.path()
+            );
+            Err(ReqDataExtractorError::NotFound)
+        }
+    }
+}
+
+/// Required request-local value extractor.
+///
+/// Middlewares share typed values within one request by storing them
+/// in request extensions, `Extensions::insert_if_absent()` keeps value
+/// stored by outer middleware. `ReqLocal<T>` returns clone of the stored
+/// value. If middleware did not set the value, extractor fails with
+/// `ReqLocalError` that contains name of the missing type and request
+/// is responded with *Internal Server Error*.
+///
+/// ```rust
+/// use ntex::web::{self, types::ReqLocal, App, HttpResponse};
+///
+/// #[derive(Clone, Debug)]
+/// struct UserId(u64);
+///
+/// async fn index(user: ReqLocal<UserId>) -> HttpResponse {
+///     HttpResponse::Ok().body(format!("user: {}", user.0))
+/// }
+///
+/// fn main() {
+///     let app = App::new().service(web::resource("/index.html").to(index));
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReqLocal<T>(pub T);
+
+impl<T> ReqLocal<T> {
+    /// Deconstruct to an inner value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for ReqLocal<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Clone + 'static, E: ErrorRenderer> FromRequest<E> for ReqLocal<T> {
+    type Error = ReqLocalError;
+
+    #[inline]
+    async fn from_request(req: &HttpRequest, _: &mut Payload) -> Result<Self, Self::Error> {
+        if let Some(data) = req.extensions().get::<T>() {
+            Ok(ReqLocal(data.clone()))
+        } else {
+            Err(ReqLocalError(std::any::type_name::<T>()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::{RequestHead, StatusCode};
+    use crate::service::fn_service;
+    use crate::util::{Bytes, Ready};
+    use crate::web::test::{TestRequest, call_service, init_service, read_body};
+    use crate::web::{self, App, DefaultError, HttpResponse, WebRequest, guard};
+
+    #[derive(Clone, Debug)]
+    struct ApiVersion(u32);
+
+    fn version_guard(head: &RequestHead) -> bool {
+        let version = head
+            .headers()
+            .get("x-api-version")
+            .and_then(|val| val.to_str().ok())
+            .and_then(|val| val.parse().ok());
+        if let Some(version) = version {
+            head.extensions_mut().insert(ApiVersion(version));
+            true
+        } else {
+            false
+        }
+    }
+
+    #[crate::rt_test]
+    async fn test_req_data_extractor() {
+        let srv = init_service(
+            App::new().service(
+                web::resource("/")
+                    .route(web::get().guard(guard::fn_guard(version_guard)).to(
+                        |v: ReqData<ApiVersion>| async move {
+                            HttpResponse::Ok().body(format!("{}", v.0))
+                        },
+                    ))
+                    .route(
+                        web::post()
+                            .to(|_: ReqData<ApiVersion>| async { HttpResponse::Ok() }),
+                    ),
+            ),
+        )
+        .await;
+
+        let req = TestRequest::default()
+            .header("x-api-version", "2")
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"2"));
+
+        let req = TestRequest::default().to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        let req = TestRequest::post().to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[crate::rt_test]
+    async fn test_req_data_rejected_route() {
+        let srv = init_service(
+            App::new().service(
+                web::resource("/")
+                    .route(
+                        web::get()
+                            .guard(guard::fn_guard(version_guard))
+                            .guard(guard::Header("x-admin", "1"))
+                            .to(|| async { HttpResponse::Ok().body("admin") }),
+                    )
+                    .route(
+                        web::get()
+                            .guard(guard::fn_guard(version_guard))
+                            .guard(guard::async_guard(async |head: &RequestHead| {
+                                head.headers().contains_key("x-token")
+                            }))
+                            .to(|| async { HttpResponse::Ok().body("token") }),
+                    )
+                    .route(web::get().to(|req: HttpRequest| async move {
+                        HttpResponse::Ok()
+                            .body(format!("{}", req.extensions().contains::<ApiVersion>()))
+                    })),
+            ),
+        )
+        .await;
+
+        let req = TestRequest::default()
+            .header("x-api-version", "2")
+            .header("x-admin", "1")
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"admin"));
+
+        // values stored by guards of rejected routes are removed
+        let req = TestRequest::default()
+            .header("x-api-version", "2")
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"false"));
+    }
+
+    #[crate::rt_test]
+    async fn test_req_local_extractor() {
+        let srv = init_service(
+            App::new()
+                .filter(fn_service(|req: WebRequest<DefaultError>| {
+                    if req.headers().contains_key("x-api-version") {
+                        req.extensions_mut().insert(ApiVersion(1));
+                        // value stored by outer middleware is kept
+                        req.extensions_mut().insert_if_absent(ApiVersion(2));
+                    }
+                    Ready::Ok(req)
+                }))
+                .service(web::resource("/").to(|v: ReqLocal<ApiVersion>| async move {
+                    HttpResponse::Ok().body(format!("{}", v.into_inner().0))
+                })),
+        )
+        .await;
+
+        let req = TestRequest::default()
+            .header("x-api-version", "2")
+            .to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(read_body(resp).await, Bytes::from_static(b"1"));
+
+        let req = TestRequest::default().to_request();
+        let resp = call_service(&srv, req).await;
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let body = read_body(resp).await;
+        assert!(std::str::from_utf8(&body).unwrap().contains("ApiVersion"));
+    }
+}
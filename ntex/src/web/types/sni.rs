@@ -0,0 +1,75 @@
+use std::fmt;
+
+use crate::http::Payload;
+use crate::tls::Servername;
+use crate::web::error::ErrorRenderer;
+use crate::web::extract::FromRequest;
+use crate::web::httprequest::HttpRequest;
+
+/// TLS server name extractor.
+///
+/// Extracts server name that client requested with SNI extension during
+/// TLS handshake. Server name is available before routing and does not
+/// depend on `Host` header. Server name is `None` for plain text
+/// connections and if client did not send SNI extension.
+///
+/// ```rust
+/// use ntex::web::{self, App, types::Sni};
+///
+/// async fn index(sni: Sni) -> String {
+///     format!("tenant: {}", sni.name().unwrap_or("default"))
+/// }
+///
+/// fn main() {
+///     let app = App::new().service(
+///         web::resource("/").route(web::get().to(index))
+///     );
+/// }
+/// ```
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Sni(Option<String>);
+
+impl Sni {
+    /// Server name requested by the client
+    pub fn name(&self) -> Option<&str> {
+        self.0.as_deref()
+    }
+
+    /// Deconstruct to an inner value
+    pub fn into_inner(self) -> Option<String> {
+        self.0
+    }
+}
+
+impl fmt::Debug for Sni {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Sni").field(&self.0).finish()
+    }
+}
+
+impl<Err: ErrorRenderer> FromRequest<Err> for Sni {
+    type Error = Err::Container;
+
+    #[inline]
+    async fn from_request(req: &HttpRequest, _: &mut Payload) -> Result<Self, Self::Error> {
+        let name = req
+            .io()
+            .and_then(|io| io.query::<Servername>().as_ref().map(|name| name.0.clone()));
+        Ok(Sni(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::web::test::{TestRequest, from_request};
+
+    #[crate::rt_test]
+    async fn test_sni() {
+        let (req, mut pl) = TestRequest::default().to_http_parts();
+        let sni = from_request::<Sni>(&req, &mut pl).await.unwrap();
+        assert_eq!(sni.name(), None);
+        assert_eq!(sni.clone().into_inner(), None);
+        assert_eq!(format!("{sni:?}"), "Sni(None)");
+    }
+}
@@ -1,4 +1,4 @@
-use std::{marker::PhantomData, ops::Deref};
+use std::{fmt, marker::PhantomData, ops::Deref};
 
 use crate::http::Payload;
 use crate::web::error::{ErrorRenderer, StateExtractorError};
@@ -98,6 +98,122 @@ impl<T: 'static, E: ErrorRenderer> FromRequest<E> for State<T> {
     }
 }
 
+/// State value keyed by a tag type.
+///
+/// `App::state()` stores one value per type, `Keyed<K, T>` allows to store
+/// several values of the same type `T` distinguished by tag type `K`.
+/// Keyed state could be added with `App::state_keyed()` or with
+/// `.state(Keyed::<K, _>::new(value))` and accessed with
+/// `KeyedState<K, T>` extractor.
+pub struct Keyed<K, T>(T, PhantomData<K>);
+
+impl<K, T> Keyed<K, T> {
+    /// Create keyed state value
+    pub fn new(value: T) -> Self {
+        Keyed(value, PhantomData)
+    }
+
+    /// Get reference to inner value
+    pub fn get_ref(&self) -> &T {
+        &self.0
+    }
+
+    /// Deconstruct to an inner value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<K, T: fmt::Debug> fmt::Debug for Keyed<K, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Keyed").field(&self.0).finish()
+    }
+}
+
+/// Keyed application state.
+///
+/// Extracts state value of type `T` registered with tag `K`, tag is
+/// an arbitrary type that is used only to distinguish state values.
+///
+/// If keyed state is not set for a handler, using `KeyedState<K, T>`
+/// extractor would cause *Internal Server Error* response.
+///
+/// ```rust
+/// use ntex::web::{self, types::KeyedState, App, HttpResponse};
+///
+/// struct DbPool(&'static str);
+///
+/// struct Primary;
+/// struct Replica;
+///
+/// async fn index(
+///     primary: KeyedState<Primary, DbPool>,
+///     replica: KeyedState<Replica, DbPool>,
+/// ) -> HttpResponse {
+///     HttpResponse::Ok().body(format!("{} {}", primary.0, replica.0))
+/// }
+///
+/// fn main() {
+///     let app = App::new()
+///         .state_keyed::<Primary, _>(DbPool("primary"))
+///         .state_keyed::<Replica, _>(DbPool("replica"))
+///         .service(web::resource("/index.html").to(index));
+/// }
+/// ```
+pub struct KeyedState<K, T>(AppState, PhantomData<(K, T)>);
+
+impl<K: 'static, T: 'static> KeyedState<K, T> {
+    /// Get reference to inner app data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is not stored in app state with key `K`
+    pub fn get_ref(&self) -> &T {
+        self.0
+            .get::<Keyed<K, T>>()
+            .expect("Unexpected state")
+            .get_ref()
+    }
+}
+
+impl<K: 'static, T: 'static> Deref for KeyedState<K, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.get_ref()
+    }
+}
+
+impl<K, T> Clone for KeyedState<K, T> {
+    fn clone(&self) -> KeyedState<K, T> {
+        KeyedState(self.0.clone(), PhantomData)
+    }
+}
+
+impl<K, T> fmt::Debug for KeyedState<K, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("KeyedState").field(&self.0).finish()
+    }
+}
+
+impl<K: 'static, T: 'static, E: ErrorRenderer> FromRequest<E> for KeyedState<K, T> {
+    type Error = StateExtractorError;
+
+    #[inline]
+    async fn from_request(req: &HttpRequest, _: &mut Payload) -> Result<Self, Self::Error> {
+        if req.0.app_state.contains::<Keyed<K, T>>() {
+            Ok(Self(req.0.app_state.clone(), PhantomData))
+        } else {
+            log::debug!(
+                "Failed to construct App-level KeyedState extractor. \
+                 Request path: {:?}",
+                req.path()
+            );
+            Err(StateExtractorError::NotConfigured)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::web::test::{TestRequest, init_service};
@@ -221,4 +337,46 @@ mod tests {
         let resp = srv.call(req).await.unwrap();
         assert_eq!(resp.status(), StatusCode::OK);
     }
+
+    #[crate::rt_test]
+    async fn test_keyed_state() {
+        use crate::web::types::{Keyed, KeyedState};
+
+        struct Primary;
+        struct Replica;
+
+        let srv =
+            init_service(
+                App::new()
+                    .state_keyed::<Primary, _>(1usize)
+                    .state(Keyed::<Replica, _>::new(2usize))
+                    .service(
+                        web::resource("/").to(
+                            |p: KeyedState<Primary, usize>,
+                             r: KeyedState<Replica, usize>| async move {
+                                assert_eq!(*p, 1);
+                                assert_eq!(*r.clone(), 2);
+                                HttpResponse::Ok()
+                            },
+                        ),
+                    )
+                    .service(
+                        web::resource("/missing")
+                            .to(|_: KeyedState<Primary, u32>| async { HttpResponse::Ok() }),
+                    ),
+            )
+            .await;
+
+        let req = TestRequest::default().to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = TestRequest::with_uri("/missing").to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let keyed = Keyed::<Primary, _>::new(1usize);
+        assert!(format!("{keyed:?}").contains("Keyed"));
+        assert_eq!(keyed.into_inner(), 1);
+    }
 }
@@ -0,0 +1,97 @@
+use std::{fmt, ops::Deref};
+
+use crate::http::Payload;
+use crate::util::ByteString;
+use crate::web::error::ErrorRenderer;
+use crate::web::extract::FromRequest;
+use crate::web::httprequest::HttpRequest;
+
+/// Request target extractor.
+///
+/// Extracts request target exactly as it was received in http/1 request
+/// line, without normalization. Original request target is preserved only
+/// if `HttpServiceConfig::set_raw_headers()` is enabled, otherwise request
+/// target is constructed from request uri.
+///
+/// ```rust
+/// use ntex::web::{self, App, types::RequestTarget};
+///
+/// async fn index(target: RequestTarget) -> String {
+///     format!("target: {}", target.as_str())
+/// }
+///
+/// fn main() {
+///     let app = App::new().service(
+///         web::resource("/{path}*").route(web::get().to(index))
+///     );
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct RequestTarget(ByteString);
+
+impl RequestTarget {
+    /// Request target as a string
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// Deconstruct to an inner value
+    pub fn into_inner(self) -> ByteString {
+        self.0
+    }
+}
+
+impl Deref for RequestTarget {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl fmt::Debug for RequestTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.0.as_str(), f)
+    }
+}
+
+impl fmt::Display for RequestTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.0.as_str(), f)
+    }
+}
+
+impl<Err: ErrorRenderer> FromRequest<Err> for RequestTarget {
+    type Error = Err::Container;
+
+    #[inline]
+    async fn from_request(req: &HttpRequest, _: &mut Payload) -> Result<Self, Self::Error> {
+        let target = req
+            .head()
+            .raw_target()
+            .and_then(|target| ByteString::try_from(target).ok())
+            .unwrap_or_else(|| ByteString::from(req.uri().to_string()));
+        Ok(RequestTarget(target))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::Bytes;
+    use crate::web::test::{TestRequest, from_request};
+
+    #[crate::rt_test]
+    async fn test_request_target() {
+        let (req, mut pl) = TestRequest::with_uri("/a%2Fb?x=1").to_http_parts();
+        let target = from_request::<RequestTarget>(&req, &mut pl).await.unwrap();
+        assert_eq!(target.as_str(), "/a%2Fb?x=1");
+
+        let (mut req, mut pl) = TestRequest::with_uri("/a%2Fb").to_http_parts();
+        req.head_mut().raw_target = Some(Bytes::from_static(b"/a%2Fb/../c?x"));
+        let target = from_request::<RequestTarget>(&req, &mut pl).await.unwrap();
+        assert_eq!(&*target, "/a%2Fb/../c?x");
+        assert_eq!(format!("{target}"), "/a%2Fb/../c?x");
+        assert_eq!(format!("{target:?}"), "\"/a%2Fb/../c?x\"");
+    }
+}
@@ -0,0 +1,80 @@
+//! Connection upgrade support for custom protocols
+use crate::http::{Response, StatusCode, body::BodySize, h1, header};
+use crate::io::{IoBoxed, IoConfig};
+use crate::web::{HttpRequest, error::UpgradeError};
+use crate::{SharedCfg, time::Seconds};
+
+thread_local! {
+    static CFG: SharedCfg = SharedCfg::new("UPGRADE")
+        .add(IoConfig::new().set_keepalive_timeout(Seconds::ZERO))
+        .into();
+}
+
+/// Take over connection for a custom protocol.
+///
+/// Sends *101 Switching Protocols* response with `Upgrade: {protocol}` header
+/// and returns connection's io object, after that http dispatcher does not
+/// use connection anymore. Request must be http/1 upgrade request, handler
+/// is responsible for checking requested protocol. Returned io object could be
+/// used directly or could be passed to a custom dispatcher, response returned
+/// from the handler is ignored.
+///
+/// By default http dispatcher reads bytes sent by the client after upgrade
+/// request as request payload, use `HttpServiceConfig::set_keep_upgrade_payload()`
+/// to leave them for the upgraded io.
+///
+/// ```rust,no_run
+/// use ntex::codec::BytesCodec;
+/// use ntex::web::{self, HttpRequest, HttpResponse};
+///
+/// async fn handler(req: HttpRequest) -> Result<HttpResponse, web::Error> {
+///     if !web::upgrade::is_requested(&req, "echo") {
+///         return Ok(HttpResponse::BadRequest().finish());
+///     }
+///     let io = web::upgrade::upgrade(&req, "echo")?;
+///
+///     ntex::rt::spawn(async move {
+///         while let Ok(Some(item)) = io.recv(&BytesCodec).await {
+///             if io.send(item, &BytesCodec).await.is_err() {
+///                 break;
+///             }
+///         }
+///     });
+///
+///     // response is ignored, connection is already upgraded
+///     Ok(HttpResponse::Ok().finish())
+/// }
+/// ```
+pub fn upgrade(req: &HttpRequest, protocol: &str) -> Result<IoBoxed, UpgradeError> {
+    if !req.head().upgrade() {
+        return Err(UpgradeError::NoUpgrade);
+    }
+    let res = Response::build(StatusCode::SWITCHING_PROTOCOLS)
+        .upgrade(protocol)
+        .finish()
+        .into_parts()
+        .0;
+
+    let (io, codec) = req.head().take_io().ok_or(UpgradeError::NoUpgrade)?;
+    io.encode(h1::Message::Item((res, BodySize::Empty)), &codec)
+        .map_err(|_| UpgradeError::Send)?;
+    io.set_config(CFG.with(Clone::clone));
+
+    // the h1 dispatcher may have started a headers-read timer on this io
+    io.stop_timer();
+
+    log::trace!(
+        "Connection is upgraded to {protocol:?} for {:?}",
+        req.path()
+    );
+    Ok(io)
+}
+
+/// Check if request asks for upgrade to the specified protocol
+pub fn is_requested(req: &HttpRequest, protocol: &str) -> bool {
+    req.headers()
+        .get_all(header::UPGRADE)
+        .filter_map(|val| val.to_str().ok())
+        .flat_map(|val| val.split(','))
+        .any(|val| val.trim().eq_ignore_ascii_case(protocol))
+}
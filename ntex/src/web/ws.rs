@@ -118,6 +118,16 @@ where
     if let Some(protocol) = subprotocol {
         res.set_header(header::SEC_WEBSOCKET_PROTOCOL, protocol.as_ref());
     }
+
+    // per-message compression, enabled with `ws::DeflateConfig` state
+    #[cfg(feature = "compress")]
+    let deflate = req
+        .app_state::<ws::DeflateConfig>()
+        .and_then(|cfg| cfg.negotiate(req.head()))
+        .map(|(cfg, hdr)| {
+            res.set_header(header::SEC_WEBSOCKET_EXTENSIONS, hdr);
+            cfg
+        });
     let res = res.finish().into_parts().0;
 
     // extract io
@@ -134,6 +144,12 @@ where
 
     // create sink
     let codec = ws::Codec::new();
+    #[cfg(feature = "compress")]
+    let codec = if let Some(cfg) = deflate {
+        codec.deflate(cfg)
+    } else {
+        codec
+    };
     let sink = WsSink::new(io.get_ref(), codec.clone());
 
     // create ws service
@@ -1,12 +1,20 @@
 use std::cell::Cell;
+#[cfg(feature = "compress")]
+use std::{cell::RefCell, rc::Rc};
 
 use crate::codec::{Decoder, Encoder};
 use crate::util::{BytePage, BytePages, ByteString, Bytes, BytesMut};
 
+#[cfg(feature = "compress")]
+use super::deflate::{Deflate, DeflateConfig};
 use super::error::ProtocolError;
 use super::frame::Parser;
 use super::proto::{CloseReason, OpCode};
 
+#[cfg(feature = "compress")]
+/// RSV1 bit, marks compressed message
+const RSV1: u8 = 0x40;
+
 /// WebSocket message
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Message {
@@ -55,6 +63,8 @@ pub enum Item {
 pub struct Codec {
     flags: Cell<Flags>,
     max_size: usize,
+    #[cfg(feature = "compress")]
+    deflate: Option<Rc<RefCell<Deflate>>>,
 }
 
 bitflags::bitflags! {
@@ -64,6 +74,7 @@ bitflags::bitflags! {
         const R_CONTINUATION = 0b0000_0010;
         const W_CONTINUATION = 0b0000_0100;
         const CLOSED         = 0b0000_1000;
+        const R_DEFLATE      = 0b0001_0000;
     }
 }
 
@@ -74,6 +85,8 @@ impl Codec {
         Codec {
             max_size: 65_536,
             flags: Cell::new(Flags::SERVER),
+            #[cfg(feature = "compress")]
+            deflate: None,
         }
     }
 
@@ -95,6 +108,17 @@ impl Codec {
         self
     }
 
+    #[cfg(feature = "compress")]
+    /// Enable per-message compression
+    ///
+    /// Configuration must be negotiated with the peer,
+    /// see `DeflateConfig::negotiate()`.
+    #[must_use]
+    pub fn deflate(mut self, cfg: DeflateConfig) -> Self {
+        self.deflate = Some(Rc::new(RefCell::new(Deflate::new(cfg))));
+        self
+    }
+
     /// Check if codec encoded `Close` message
     pub fn is_closed(&self) -> bool {
         self.flags.get().contains(Flags::CLOSED)
@@ -112,6 +136,83 @@ impl Codec {
         self.flags.set(flags);
     }
 
+    /// Write data frame, compress payload if compression is enabled
+    #[cfg_attr(not(feature = "compress"), allow(unused_variables))]
+    fn write_data(
+        &self,
+        dst: &mut BytePages,
+        data: Bytes,
+        op: OpCode,
+        fin: bool,
+        first: bool,
+    ) -> Result<(), ProtocolError> {
+        let server = self.flags.get().contains(Flags::SERVER);
+
+        #[cfg(feature = "compress")]
+        if let Some(ref deflate) = self.deflate {
+            // only first frame of the message is marked with RSV1 bit
+            let data = deflate.borrow_mut().compress(&data, fin, server)?;
+            Parser::write_frame(dst, data, op, fin, first, !server);
+            return Ok(());
+        }
+
+        Parser::write_message(dst, data, op, fin, !server);
+        Ok(())
+    }
+
+    /// Decompress data frame payload, check reserved bits
+    #[cfg_attr(not(feature = "compress"), allow(unused_variables))]
+    fn read_data(
+        &self,
+        finished: bool,
+        rsv: u8,
+        opcode: OpCode,
+        payload: Option<Bytes>,
+    ) -> Result<Option<Bytes>, ProtocolError> {
+        #[cfg(feature = "compress")]
+        if let Some(ref deflate) = self.deflate {
+            // control frames are never compressed
+            let compressed = match opcode {
+                OpCode::Text | OpCode::Binary if rsv == RSV1 => {
+                    if !finished {
+                        self.insert_flags(Flags::R_DEFLATE);
+                    }
+                    true
+                }
+                OpCode::Continue if rsv == 0 => {
+                    let compressed = self.flags.get().contains(Flags::R_DEFLATE);
+                    if finished {
+                        self.remove_flags(Flags::R_DEFLATE);
+                    }
+                    compressed
+                }
+                _ if rsv == 0 => false,
+                _ => return Err(ProtocolError::InvalidRsv),
+            };
+
+            return if compressed {
+                deflate
+                    .borrow_mut()
+                    .decompress(
+                        payload.as_deref().unwrap_or_default(),
+                        finished,
+                        self.flags.get().contains(Flags::SERVER),
+                        self.max_size,
+                    )
+                    .map(Some)
+            } else {
+                Ok(payload)
+            };
+        }
+
+        // reserved bits must be clear if no extension is negotiated
+        if rsv == 0 {
+            Ok(payload)
+        } else {
+            Err(ProtocolError::InvalidRsv)
+        }
+    }
+
     /// Encode binary message
     pub fn encode_page(&self, page: BytePage, dst: &mut BytePages) {
         Parser::write_message(
@@ -136,20 +237,12 @@ impl Encoder for Codec {
 
     fn encodev(&self, item: Message, dst: &mut BytePages) -> Result<(), Self::Error> {
         match item {
-            Message::Text(txt) => Parser::write_message(
-                dst,
-                txt,
-                OpCode::Text,
-                true,
-                !self.flags.get().contains(Flags::SERVER),
-            ),
-            Message::Binary(bin) => Parser::write_message(
-                dst,
-                bin,
-                OpCode::Binary,
-                true,
-                !self.flags.get().contains(Flags::SERVER),
-            ),
+            Message::Text(txt) => {
+                self.write_data(dst, txt.into_bytes(), OpCode::Text, true, true)?;
+            }
+            Message::Binary(bin) => {
+                self.write_data(dst, bin, OpCode::Binary, true, true)?;
+            }
             Message::Ping(txt) => Parser::write_message(
                 dst,
                 txt,
@@ -174,36 +267,18 @@ impl Encoder for Codec {
                         return Err(ProtocolError::ContinuationStarted);
                     }
                     self.insert_flags(Flags::W_CONTINUATION);
-                    Parser::write_message(
-                        dst,
-                        data,
-                        OpCode::Text,
-                        false,
-                        !self.flags.get().contains(Flags::SERVER),
-                    );
+                    self.write_data(dst, data, OpCode::Text, false, true)?;
                 }
                 Item::FirstBinary(data) => {
                     if self.flags.get().contains(Flags::W_CONTINUATION) {
                         return Err(ProtocolError::ContinuationStarted);
                     }
                     self.insert_flags(Flags::W_CONTINUATION);
-                    Parser::write_message(
-                        dst,
-                        data,
-                        OpCode::Binary,
-                        false,
-                        !self.flags.get().contains(Flags::SERVER),
-                    );
+                    self.write_data(dst, data, OpCode::Binary, false, true)?;
                 }
                 Item::Continue(data) => {
                     if self.flags.get().contains(Flags::W_CONTINUATION) {
-                        Parser::write_message(
-                            dst,
-                            data,
-                            OpCode::Continue,
-                            false,
-                            !self.flags.get().contains(Flags::SERVER),
-                        );
+                        self.write_data(dst, data, OpCode::Continue, false, false)?;
                     } else {
                         return Err(ProtocolError::ContinuationNotStarted);
                     }
@@ -211,13 +286,7 @@ impl Encoder for Codec {
                 Item::Last(data) => {
                     if self.flags.get().contains(Flags::W_CONTINUATION) {
                         self.remove_flags(Flags::W_CONTINUATION);
-                        Parser::write_message(
-                            dst,
-                            data,
-                            OpCode::Continue,
-                            true,
-                            !self.flags.get().contains(Flags::SERVER),
-                        );
+                        self.write_data(dst, data, OpCode::Continue, true, false)?;
                     } else {
                         return Err(ProtocolError::ContinuationNotStarted);
                     }
@@ -233,8 +302,14 @@ impl Decoder for Codec {
     type Error = ProtocolError;
 
     fn decode(&self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        match Parser::parse(src, self.flags.get().contains(Flags::SERVER), self.max_size) {
-            Ok(Some((finished, opcode, payload))) => {
+        match Parser::parse_frame(
+            src,
+            self.flags.get().contains(Flags::SERVER),
+            self.max_size,
+        ) {
+            Ok(Some((finished, rsv, opcode, payload))) => {
+                let payload = self.read_data(finished, rsv, opcode, payload)?;
+
                 // handle continuation
                 if finished {
                     match opcode {
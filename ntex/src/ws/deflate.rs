@@ -0,0 +1,513 @@
+//! Per-message deflate extension ([RFC 7692](https://tools.ietf.org/html/rfc7692))
+use std::fmt;
+
+use flate2::{Compress, Compression, Decompress, FlushCompress, FlushDecompress, Status};
+
+use crate::http::RequestHead;
+use crate::http::header::{HeaderValue, SEC_WEBSOCKET_EXTENSIONS};
+use crate::util::Bytes;
+
+use super::error::ProtocolError;
+
+const EXTENSION: &str = "permessage-deflate";
+const TAIL: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+const MAX_WINDOW_BITS: u8 = 15;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// `permessage-deflate` extension configuration
+///
+/// Compression is enabled for `web::ws` handlers if configuration is
+/// registered as application, scope or resource state.
+///
+/// Compressor always uses 32Kb window, offers that limit server window
+/// (`server_max_window_bits` below 15) are declined.
+///
+/// ```rust
+/// use ntex::web::{self, App};
+/// use ntex::ws::DeflateConfig;
+///
+/// let app = App::new().service(
+///     web::scope("/ws")
+///         .state(DeflateConfig::new().server_no_context_takeover(true))
+/// );
+/// ```
+pub struct DeflateConfig {
+    level: u32,
+    client_max_window_bits: Option<u8>,
+    server_no_context_takeover: bool,
+    client_no_context_takeover: bool,
+}
+
+impl Default for DeflateConfig {
+    fn default() -> Self {
+        DeflateConfig::new()
+    }
+}
+
+impl DeflateConfig {
+    /// Create default `permessage-deflate` configuration.
+    pub const fn new() -> Self {
+        DeflateConfig {
+            level: 6,
+            client_max_window_bits: None,
+            server_no_context_takeover: false,
+            client_no_context_takeover: false,
+        }
+    }
+
+    #[must_use]
+    /// Set compression level, 0-9.
+    ///
+    /// By default level is set to 6.
+    pub fn compression_level(mut self, level: u32) -> Self {
+        self.level = level.min(9);
+        self
+    }
+
+    #[must_use]
+    /// Limit LZ77 window size of the client compressor, 8-15.
+    ///
+    /// Limit is sent only if client supports `client_max_window_bits`
+    /// parameter. By default window size is not limited.
+    ///
+    /// Panics if `bits` is out of range.
+    pub fn client_max_window_bits(mut self, bits: u8) -> Self {
+        assert!(
+            (8..=MAX_WINDOW_BITS).contains(&bits),
+            "Window bits must be 8-15"
+        );
+        self.client_max_window_bits = Some(bits);
+        self
+    }
+
+    #[must_use]
+    /// Reset server compression context after each message.
+    ///
+    /// Reduces memory usage for the price of compression ratio.
+    /// By default context takeover is enabled.
+    pub fn server_no_context_takeover(mut self, val: bool) -> Self {
+        self.server_no_context_takeover = val;
+        self
+    }
+
+    #[must_use]
+    /// Request client to reset compression context after each message.
+    ///
+    /// By default context takeover is enabled.
+    pub fn client_no_context_takeover(mut self, val: bool) -> Self {
+        self.client_no_context_takeover = val;
+        self
+    }
+
+    /// Negotiate extension parameters with client's offers.
+    ///
+    /// Returns negotiated configuration and value for `Sec-WebSocket-Extensions`
+    /// response header, or `None` if client did not offer acceptable
+    /// `permessage-deflate` extension.
+    pub fn negotiate(&self, req: &RequestHead) -> Option<(DeflateConfig, HeaderValue)> {
+        req.headers
+            .get_all(SEC_WEBSOCKET_EXTENSIONS)
+            .filter_map(|val| val.to_str().ok())
+            .flat_map(|val| val.split(','))
+            .find_map(|offer| self.accept(offer))
+    }
+
+    fn accept(&self, offer: &str) -> Option<(DeflateConfig, HeaderValue)> {
+        let mut params = offer.split(';').map(str::trim);
+        if params.next()? != EXTENSION {
+            return None;
+        }
+
+        let mut cfg = *self;
+        let mut seen = Vec::new();
+        let mut client_window = None;
+        for param in params {
+            let (name, value) = match param.split_once('=') {
+                Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"'))),
+                None => (param, None),
+            };
+            // parameters must not be repeated
+            if seen.contains(&name) {
+                return None;
+            }
+            seen.push(name);
+
+            match (name, value) {
+                ("server_no_context_takeover", None) => {
+                    cfg.server_no_context_takeover = true
+                }
+                ("client_no_context_takeover", None) => {
+                    cfg.client_no_context_takeover = true
+                }
+                ("server_max_window_bits", Some(bits)) => {
+                    // compressor window could not be limited
+                    if parse_window_bits(bits)? != MAX_WINDOW_BITS {
+                        return None;
+                    }
+                }
+                ("client_max_window_bits", None) => client_window = Some(MAX_WINDOW_BITS),
+                ("client_max_window_bits", Some(bits)) => {
+                    client_window = Some(parse_window_bits(bits)?);
+                }
+                _ => return None,
+            }
+        }
+
+        let mut hdr = EXTENSION.to_string();
+        if cfg.server_no_context_takeover {
+            hdr.push_str("; server_no_context_takeover");
+        }
+        if cfg.client_no_context_takeover {
+            hdr.push_str("; client_no_context_takeover");
+        }
+        if let Some(offered) = client_window
+            && let Some(bits) = self.client_max_window_bits
+        {
+            hdr.push_str(&format!("; client_max_window_bits={}", bits.min(offered)));
+        }
+        Some((cfg, HeaderValue::try_from(hdr).ok()?))
+    }
+}
+
+fn parse_window_bits(val: &str) -> Option<u8> {
+    val.parse::<u8>()
+        .ok()
+        .filter(|bits| (8..=MAX_WINDOW_BITS).contains(bits))
+}
+
+/// Compression context
+pub(super) struct Deflate {
+    cfg: DeflateConfig,
+    compress: Compress,
+    decompress: Decompress,
+}
+
+impl fmt::Debug for Deflate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Deflate").field("cfg", &self.cfg).finish()
+    }
+}
+
+impl Deflate {
+    pub(super) fn new(cfg: DeflateConfig) -> Self {
+        Deflate {
+            cfg,
+            compress: Compress::new(Compression::new(cfg.level), false),
+            decompress: Decompress::new(false),
+        }
+    }
+
+    /// Compress message fragment, `fin` marks last fragment of the message
+    pub(super) fn compress(
+        &mut self,
+        data: &[u8],
+        fin: bool,
+        server: bool,
+    ) -> Result<Bytes, ProtocolError> {
+        let mut out = Vec::with_capacity(data.len() / 2 + 64);
+        let start = self.compress.total_in();
+
+        loop {
+            let consumed = (self.compress.total_in() - start) as usize;
+            if consumed == data.len() && out.len() < out.capacity() && !out.is_empty() {
+                break;
+            }
+            if out.len() == out.capacity() {
+                out.reserve(out.capacity().max(64));
+            }
+            self.compress
+                .compress_vec(&data[consumed..], &mut out, FlushCompress::Sync)
+                .map_err(|_| ProtocolError::Compression)?;
+        }
+
+        if fin {
+            // remove empty deflate block, peer appends it back
+            if out.ends_with(&TAIL) {
+                out.truncate(out.len() - TAIL.len());
+            }
+            let reset = if server {
+                self.cfg.server_no_context_takeover
+            } else {
+                self.cfg.client_no_context_takeover
+            };
+            if reset {
+                self.compress.reset();
+            }
+        }
+        Ok(Bytes::from(out))
+    }
+
+    /// Decompress message fragment, `fin` marks last fragment of the message
+    pub(super) fn decompress(
+        &mut self,
+        data: &[u8],
+        fin: bool,
+        server: bool,
+        max_size: usize,
+    ) -> Result<Bytes, ProtocolError> {
+        let mut out = Vec::with_capacity((data.len() * 2).min(max_size) + 64);
+        self.inflate(data, &mut out, max_size)?;
+
+        if fin {
+            self.inflate(&TAIL, &mut out, max_size)?;
+            let reset = if server {
+                self.cfg.client_no_context_takeover
+            } else {
+                self.cfg.server_no_context_takeover
+            };
+            if reset {
+                self.decompress.reset(false);
+            }
+        }
+
+        if out.len() > max_size {
+            Err(ProtocolError::Overflow)
+        } else {
+            Ok(Bytes::from(out))
+        }
+    }
+
+    fn inflate(
+        &mut self,
+        data: &[u8],
+        out: &mut Vec<u8>,
+        max_size: usize,
+    ) -> Result<(), ProtocolError> {
+        let start = self.decompress.total_in();
+
+        loop {
+            let consumed = (self.decompress.total_in() - start) as usize;
+            if consumed == data.len() && out.len() < out.capacity() {
+                return Ok(());
+            }
+            if out.len() == out.capacity() {
+                if out.len() > max_size {
+                    return Err(ProtocolError::Overflow);
+                }
+                out.reserve(out.capacity().max(64));
+            }
+
+            let out_len = out.len();
+            let status = self
+                .decompress
+                .decompress_vec(&data[consumed..], out, FlushDecompress::Sync)
+                .map_err(|_| ProtocolError::Compression)?;
+
+            match status {
+                Status::StreamEnd => return Ok(()),
+                Status::BufError
+                    if out_len == out.len()
+                        && consumed == (self.decompress.total_in() - start) as usize =>
+                {
+                    return Err(ProtocolError::Compression);
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::{Decoder, Encoder};
+    use crate::http::{header, test::TestRequest};
+    use crate::util::{BytePages, ByteString, BytesMut};
+    use crate::ws::{Codec, Frame, Item, Message};
+
+    fn negotiate(cfg: DeflateConfig, offer: &'static str) -> Option<String> {
+        let req = TestRequest::default()
+            .header(header::SEC_WEBSOCKET_EXTENSIONS, offer)
+            .finish();
+        cfg.negotiate(req.head())
+            .map(|(_, hdr)| hdr.to_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let cfg = DeflateConfig::new();
+        assert_eq!(negotiate(cfg, "permessage-deflate").unwrap(), EXTENSION);
+        assert_eq!(
+            negotiate(cfg, "permessage-deflate; client_max_window_bits").unwrap(),
+            EXTENSION
+        );
+        assert_eq!(
+            negotiate(
+                cfg.client_max_window_bits(10),
+                "permessage-deflate; client_max_window_bits"
+            )
+            .unwrap(),
+            "permessage-deflate; client_max_window_bits=10"
+        );
+        assert_eq!(
+            negotiate(
+                cfg.client_max_window_bits(12),
+                "permessage-deflate; client_max_window_bits=9"
+            )
+            .unwrap(),
+            "permessage-deflate; client_max_window_bits=9"
+        );
+        assert_eq!(
+            negotiate(
+                cfg,
+                "permessage-deflate; server_no_context_takeover; server_max_window_bits=15"
+            )
+            .unwrap(),
+            "permessage-deflate; server_no_context_takeover"
+        );
+        assert_eq!(
+            negotiate(cfg.client_no_context_takeover(true), "permessage-deflate").unwrap(),
+            "permessage-deflate; client_no_context_takeover"
+        );
+
+        // server window could not be limited, use next offer
+        assert_eq!(
+            negotiate(
+                cfg,
+                "permessage-deflate; server_max_window_bits=10, permessage-deflate"
+            )
+            .unwrap(),
+            EXTENSION
+        );
+        assert!(negotiate(cfg, "permessage-deflate; server_max_window_bits=10").is_none());
+        assert!(negotiate(cfg, "permessage-deflate; unknown").is_none());
+        assert!(
+            negotiate(
+                cfg,
+                "permessage-deflate; server_no_context_takeover; server_no_context_takeover"
+            )
+            .is_none()
+        );
+        assert!(negotiate(cfg, "x-webkit-deflate-frame").is_none());
+    }
+
+    #[test]
+    fn test_compress() {
+        let data = "compressed message ".repeat(100);
+
+        for no_takeover in [false, true] {
+            let cfg = DeflateConfig::new()
+                .server_no_context_takeover(no_takeover)
+                .client_no_context_takeover(no_takeover);
+            let mut server = Deflate::new(cfg);
+            let mut client = Deflate::new(cfg);
+
+            for _ in 0..3 {
+                let pl = server.compress(data.as_bytes(), true, true).unwrap();
+                assert!(pl.len() < data.len());
+                assert!(!pl.ends_with(&TAIL));
+                let msg = client.decompress(&pl, true, false, 65_536).unwrap();
+                assert_eq!(msg, data.as_bytes());
+            }
+
+            // fragmented message
+            let first = client
+                .compress(&data.as_bytes()[..1000], false, false)
+                .unwrap();
+            let last = client
+                .compress(&data.as_bytes()[1000..], true, false)
+                .unwrap();
+            let mut msg = BytesMut::new();
+            msg.extend_from_slice(&server.decompress(&first, false, true, 65_536).unwrap());
+            msg.extend_from_slice(&server.decompress(&last, true, true, 65_536).unwrap());
+            assert_eq!(msg, data.as_bytes());
+
+            // empty message
+            let pl = server.compress(b"", true, true).unwrap();
+            assert!(
+                client
+                    .decompress(&pl, true, false, 65_536)
+                    .unwrap()
+                    .is_empty()
+            );
+        }
+    }
+
+    #[test]
+    fn test_decompress_overflow() {
+        let data = vec![b'a'; 10_000];
+        let mut server = Deflate::new(DeflateConfig::new());
+        let mut client = Deflate::new(DeflateConfig::new());
+
+        let pl = server.compress(&data, true, true).unwrap();
+        assert!(matches!(
+            client.decompress(&pl, true, false, 1024),
+            Err(ProtocolError::Overflow)
+        ));
+
+        let mut client = Deflate::new(DeflateConfig::new());
+        assert!(matches!(
+            client.decompress(b"\xff\xff\xff", true, false, 1024),
+            Err(ProtocolError::Compression)
+        ));
+    }
+
+    fn encode(codec: &Codec, msgs: Vec<Message>) -> BytesMut {
+        let mut buf = BytePages::default();
+        for msg in msgs {
+            codec.encodev(msg, &mut buf).unwrap();
+        }
+        BytesMut::from(&Bytes::from(buf)[..])
+    }
+
+    #[test]
+    fn test_codec() {
+        let server = Codec::new().deflate(DeflateConfig::new());
+        let client = Codec::new().client_mode().deflate(DeflateConfig::new());
+        let data = Bytes::from("{\"message\": \"hello\"}".repeat(100));
+
+        let mut buf = encode(
+            &server,
+            vec![
+                Message::Text(ByteString::try_from(data.clone()).unwrap()),
+                Message::Ping(Bytes::from_static(b"ping")),
+            ],
+        );
+        assert!(buf.len() < data.len());
+        // compressed message
+        assert_eq!(buf[0] & 0x70, 0x40);
+        assert_eq!(
+            client.decode(&mut buf).unwrap(),
+            Some(Frame::Text(data.clone()))
+        );
+        // control frames are not compressed
+        assert_eq!(buf[0] & 0x70, 0);
+        assert_eq!(
+            client.decode(&mut buf).unwrap(),
+            Some(Frame::Ping(Bytes::from_static(b"ping")))
+        );
+
+        // fragmented message, only first frame has RSV1 bit
+        let mut buf = encode(
+            &client,
+            vec![
+                Message::Continuation(Item::FirstBinary(data.slice(..1000))),
+                Message::Continuation(Item::Last(data.slice(1000..))),
+            ],
+        );
+        assert_eq!(buf[0] & 0x70, 0x40);
+        assert_eq!(
+            server.decode(&mut buf).unwrap(),
+            Some(Frame::Continuation(Item::FirstBinary(data.slice(..1000))))
+        );
+        assert_eq!(buf[0] & 0x70, 0);
+        assert_eq!(
+            server.decode(&mut buf).unwrap(),
+            Some(Frame::Continuation(Item::Last(data.slice(1000..))))
+        );
+
+        // RSV1 without negotiated extension
+        let mut buf = encode(&client, vec![Message::Binary(data.clone())]);
+        assert!(matches!(
+            Codec::new().decode(&mut buf),
+            Err(ProtocolError::InvalidRsv)
+        ));
+
+        // RSV1 on control frame
+        let mut buf = BytesMut::from(&[0b1100_1001u8, 0b1000_0000u8, 0, 0, 0, 0][..]);
+        assert!(matches!(
+            server.decode(&mut buf),
+            Err(ProtocolError::InvalidRsv)
+        ));
+    }
+}
@@ -47,6 +47,12 @@ pub enum ProtocolError {
     /// A payload reached size limit.
     #[error("A payload reached size limit.")]
     Overflow,
+    /// Invalid reserved bits
+    #[error("Invalid reserved bits")]
+    InvalidRsv,
+    /// Per-message compression error
+    #[error("Compression error")]
+    Compression,
     /// Continuation is not started
     #[error("Continuation is not started.")]
     ContinuationNotStarted,
@@ -13,7 +13,7 @@ impl Parser {
         src: &[u8],
         server: bool,
         max_size: usize,
-    ) -> Result<Option<(usize, bool, OpCode, usize, Option<u32>)>, ProtocolError> {
+    ) -> Result<Option<(usize, bool, u8, OpCode, usize, Option<u32>)>, ProtocolError> {
         let chunk_len = src.len();
 
         let mut idx = 2;
@@ -24,6 +24,7 @@ impl Parser {
         let first = src[0];
         let second = src[1];
         let finished = first & 0x80 != 0;
+        let rsv = first & 0x70;
 
         // check masking
         let masked = second & 0x80 != 0;
@@ -81,7 +82,7 @@ impl Parser {
             None
         };
 
-        Ok(Some((idx, finished, opcode, length, mask)))
+        Ok(Some((idx, finished, rsv, opcode, length, mask)))
     }
 
     /// Parse the input stream into a frame.
@@ -90,8 +91,18 @@ impl Parser {
         server: bool,
         max_size: usize,
     ) -> Result<Option<(bool, OpCode, Option<Bytes>)>, ProtocolError> {
+        Ok(Parser::parse_frame(src, server, max_size)?
+            .map(|(finished, _, opcode, payload)| (finished, opcode, payload)))
+    }
+
+    /// Parse the input stream into a frame, returns reserved bits of the frame.
+    pub(super) fn parse_frame(
+        src: &mut BytesMut,
+        server: bool,
+        max_size: usize,
+    ) -> Result<Option<(bool, u8, OpCode, Option<Bytes>)>, ProtocolError> {
         // try to parse ws frame metadata
-        let Some((idx, finished, opcode, length, mask)) =
+        let Some((idx, finished, rsv, opcode, length, mask)) =
             Parser::parse_metadata(src, server, max_size)?
         else {
             return Ok(None);
@@ -107,7 +118,7 @@ impl Parser {
 
         // no need for body
         if length == 0 {
-            return Ok(Some((finished, opcode, None)));
+            return Ok(Some((finished, rsv, opcode, None)));
         }
 
         // control frames must have length <= 125
@@ -119,7 +130,7 @@ impl Parser {
                 log::trace!(
                     "Received close frame with payload length exceeding 125. Morphing to protocol close frame."
                 );
-                return Ok(Some((true, OpCode::Close, None)));
+                return Ok(Some((true, rsv, OpCode::Close, None)));
             }
             _ => (),
         }
@@ -129,7 +140,7 @@ impl Parser {
             apply_mask(&mut src[..length], mask);
         }
 
-        Ok(Some((finished, opcode, Some(src.split_to(length)))))
+        Ok(Some((finished, rsv, opcode, Some(src.split_to(length)))))
     }
 
     /// Parse the payload of a close frame.
@@ -152,13 +163,30 @@ impl Parser {
     pub fn write_message<B>(dst: &mut BytePages, pl: B, op: OpCode, fin: bool, mask: bool)
     where
         BytePage: From<B>,
+    {
+        Parser::write_frame(dst, pl, op, fin, false, mask);
+    }
+
+    /// Generate binary representation, `rsv1` marks compressed message
+    pub(super) fn write_frame<B>(
+        dst: &mut BytePages,
+        pl: B,
+        op: OpCode,
+        fin: bool,
+        rsv1: bool,
+        mask: bool,
+    ) where
+        BytePage: From<B>,
     {
         let payload = BytePage::from(pl);
-        let one: u8 = if fin {
+        let mut one: u8 = if fin {
             0x80 | Into::<u8>::into(op)
         } else {
             op.into()
         };
+        if rsv1 {
+            one |= 0x40;
+        }
         let payload_len = payload.len();
         let two = if mask { 0x80 } else { 0 };
 
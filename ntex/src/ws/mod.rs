@@ -5,6 +5,8 @@
 //! communicate with the peer.
 mod client;
 mod codec;
+#[cfg(feature = "compress")]
+mod deflate;
 mod frame;
 mod handshake;
 mod mask;
@@ -16,6 +18,8 @@ pub mod error;
 
 pub use self::client::{WsClient, WsClientBuilder, WsConnection};
 pub use self::codec::{Codec, Frame, Item, Message};
+#[cfg(feature = "compress")]
+pub use self::deflate::DeflateConfig;
 pub use self::frame::Parser;
 pub use self::handshake::{handshake, handshake_response, verify_handshake};
 pub use self::proto::{CloseCode, CloseReason, OpCode, hash_key};
@@ -274,6 +274,67 @@ async fn test_rustls_string() {
     assert!(io.recv(&BytesCodec).await.unwrap().is_none());
 }
 
+#[cfg(feature = "rustls")]
+#[ntex::test]
+async fn test_rustls_reload() {
+    use std::sync::Arc;
+
+    use ntex::{io::types::HttpProtocol, server::rustls};
+    use ntex_tls::rustls::TlsConnector;
+
+    let mut config = rustls_utils::tls_acceptor();
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    let acceptor = rustls::TlsAcceptor::new(Arc::new(config));
+
+    let acc = acceptor.clone();
+    let srv = test_server(async move || {
+        chain_factory(acc.clone().map_err(|e| {
+            log::error!("tls negotiation is failed: {e:?}");
+            e
+        }))
+        .and_then(
+            fn_service(|io: Io<_>| async move {
+                io.send(Bytes::from_static(b"test"), &BytesCodec)
+                    .await
+                    .unwrap();
+                Ok::<_, io::Error>(())
+            })
+            .map_init_err(|_| ()),
+        )
+    });
+
+    let mut config = rustls_utils::tls_connector();
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    let conn = Pipeline::new(
+        TlsConnector::new(config)
+            .create(SharedCfg::new("CLIENT").into())
+            .await
+            .unwrap(),
+    );
+    let addr = format!("localhost:{}", srv.addr().port());
+
+    let io = conn.call(addr.clone().into()).await.unwrap();
+    assert_eq!(
+        io.query::<HttpProtocol>().get().unwrap(),
+        HttpProtocol::Http1
+    );
+    let item = io.recv(&BytesCodec).await.unwrap().unwrap();
+    assert_eq!(item, Bytes::from_static(b"test"));
+
+    // new handshakes use new config
+    let mut config = rustls_utils::tls_acceptor();
+    config.alpn_protocols = vec![b"h2".to_vec()];
+    acceptor.reload_tls(Arc::new(config));
+
+    let io2 = conn.call(addr.into()).await.unwrap();
+    assert_eq!(
+        io2.query::<HttpProtocol>().get().unwrap(),
+        HttpProtocol::Http2
+    );
+    let item = io2.recv(&BytesCodec).await.unwrap().unwrap();
+    assert_eq!(item, Bytes::from_static(b"test"));
+}
+
 #[cfg(feature = "rustls")]
 #[ntex::test]
 async fn test_rustls_peer_close_notify_closes_io() {
@@ -98,6 +98,35 @@ async fn test_form() {
     assert!(response.status().is_success());
 }
 
+#[ntex::test]
+async fn test_stream_chunked() {
+    let srv = test::server(async || {
+        App::new().service(web::resource("/").route(web::to(
+            |req: HttpRequest, body: Bytes| async move {
+                assert!(!req.headers().contains_key(header::CONTENT_LENGTH));
+                assert_eq!(
+                    req.headers().get(header::TRANSFER_ENCODING).unwrap(),
+                    "chunked"
+                );
+                HttpResponse::Ok().body(body)
+            },
+        )))
+    })
+    .await;
+
+    let chunks = (0..10).map(|_| Ok::<_, io::Error>(Bytes::from_static(STR.as_ref())));
+    let response = srv
+        .post("/")
+        .send_stream(futures_util::stream::iter(chunks))
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    // read response
+    let bytes = response.body().limit(STR.len() * 10).await.unwrap();
+    assert_eq!(bytes, Bytes::from(STR.repeat(10)));
+}
+
 #[ntex::test]
 async fn test_timeout() {
     let srv = test::server(async || {
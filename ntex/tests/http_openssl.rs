@@ -101,6 +101,7 @@ async fn test_h2_1() -> io::Result<()> {
         HttpService::new(|req: Request| {
             assert!(req.peer_addr().is_some());
             assert_eq!(req.version(), Version::HTTP_2);
+            assert!(req.is_h2());
             Ready::Ok::<_, io::Error>(Response::Ok().finish())
         })
         .openssl(ssl_acceptor())
@@ -62,6 +62,28 @@ async fn test_h1() -> io::Result<()> {
     Ok(())
 }
 
+#[ntex::test]
+async fn test_alpn_h1_only() -> io::Result<()> {
+    let srv = test_server(async move || {
+        let mut config = tls_acceptor();
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+        HttpService::new(|req: Request| {
+            assert_eq!(req.version(), Version::HTTP_11);
+            assert!(!req.is_h2());
+            Ready::Ok::<_, io::Error>(Response::Ok().finish())
+        })
+        .rustls(config)
+        .map_err(|_| ())
+    })
+    .await;
+
+    let response = srv.srequest(Method::GET, "/").send().await.unwrap();
+    assert!(response.status().is_success());
+    assert_eq!(response.version(), Version::HTTP_11);
+    Ok(())
+}
+
 #[ntex::test]
 async fn test_h2_1() -> io::Result<()> {
     let srv = test_server(async move || {
@@ -13,7 +13,9 @@ use ntex::http::{
 use ntex::http::{body, h1::Control, test, test::server as test_server};
 use ntex::time::{Millis, Seconds, sleep, timeout};
 use ntex::util::{Bytes, Ready};
-use ntex::{SharedCfg, channel::oneshot, rt, service::fn_service, web::error};
+use ntex::{
+    SharedCfg, channel::oneshot, io::IoConfig, rt, service::fn_service, web::error,
+};
 
 #[ntex::test]
 async fn test_h1() {
@@ -62,6 +64,111 @@ async fn test_h1_2() {
     assert!(!hdr.to_str().unwrap().starts_with("000"));
 }
 
+#[ntex::test]
+async fn test_h2c_prior_knowledge() {
+    let srv = test::server_with_config(
+        async || {
+            HttpService::new(|req: Request| {
+                assert_eq!(req.version(), Version::HTTP_2);
+                Ready::Ok::<_, io::Error>(Response::Ok().finish())
+            })
+        },
+        SharedCfg::new("SRV").add(HttpServiceConfig::new().set_h2c(true)),
+    )
+    .await;
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n");
+    // empty SETTINGS frame
+    let _ = stream.write_all(&[0, 0, 0, 4, 0, 0, 0, 0, 0]);
+
+    // server responds with SETTINGS frame
+    let mut data = [0; 9];
+    stream.read_exact(&mut data).unwrap();
+    assert_eq!(data[3], 4);
+    assert_eq!(&data[5..9], &[0, 0, 0, 0]);
+}
+
+#[ntex::test]
+async fn test_h2_settings_push() {
+    let srv = test::server_with_config(
+        async || {
+            HttpService::new(|_: Request| {
+                Ready::Ok::<_, io::Error>(Response::Ok().finish())
+            })
+        },
+        SharedCfg::new("SRV").add(HttpServiceConfig::new().set_h2c(true)),
+    )
+    .await;
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n");
+    let _ = stream.write_all(&[0, 0, 0, 4, 0, 0, 0, 0, 0]);
+
+    // initial SETTINGS frame must not enable push
+    let mut head = [0; 9];
+    stream.read_exact(&mut head).unwrap();
+    assert_eq!(head[3], 4);
+    let len = u32::from_be_bytes([0, head[0], head[1], head[2]]) as usize;
+    let mut payload = vec![0; len];
+    stream.read_exact(&mut payload).unwrap();
+    for setting in payload.chunks(6) {
+        let id = u16::from_be_bytes([setting[0], setting[1]]);
+        let value = u32::from_be_bytes([setting[2], setting[3], setting[4], setting[5]]);
+        // SETTINGS_ENABLE_PUSH
+        if id == 2 {
+            assert_eq!(value, 0);
+        }
+    }
+}
+
+#[ntex::test]
+async fn test_h2c_http1() {
+    let srv = test::server_with_config(
+        async || {
+            HttpService::new(|req: Request| {
+                assert_eq!(req.version(), Version::HTTP_11);
+                Ready::Ok::<_, io::Error>(Response::Ok().finish())
+            })
+        },
+        SharedCfg::new("SRV").add(HttpServiceConfig::new().set_h2c(true)),
+    )
+    .await;
+
+    let response = srv.request(Method::GET, "/").send().await.unwrap();
+    assert!(response.status().is_success());
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(b"GET /test HTTP/1.1\r\nconnection: close\r\n\r\n");
+    let mut data = String::new();
+    let _ = stream.read_to_string(&mut data);
+    assert!(data.starts_with("HTTP/1.1 200 OK"));
+}
+
+#[ntex::test]
+async fn test_h2c_upgrade() {
+    let srv = test::server_with_config(
+        async || {
+            HttpService::new(|req: Request| {
+                assert_eq!(req.version(), Version::HTTP_11);
+                Ready::Ok::<_, io::Error>(Response::Ok().finish())
+            })
+        },
+        SharedCfg::new("SRV").add(HttpServiceConfig::new().set_h2c(true)),
+    )
+    .await;
+
+    // upgrade is ignored, request is served over http/1.1
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(
+        b"GET /test HTTP/1.1\r\nconnection: upgrade, http2-settings\r\n\
+          upgrade: h2c\r\nhttp2-settings: AAMAAABkAAQAAP__\r\n\r\n",
+    );
+    let mut data = String::new();
+    let _ = stream.read_to_string(&mut data);
+    assert!(data.starts_with("HTTP/1.1 200 OK"), "{data}");
+}
+
 #[ntex::test]
 async fn test_expect_continue() {
     let srv = test::server_with_config(
@@ -112,6 +219,103 @@ async fn test_expect_continue() {
     assert!(data.starts_with("HTTP/1.1 200 OK\r\n"));
 }
 
+#[ntex::test]
+async fn test_informational_response() {
+    let srv = test::server_with_config(
+        async || {
+            HttpService::h1(fn_service(|req: Request| async move {
+                let mut hints = header::HeaderMap::new();
+                hints.insert(
+                    header::LINK,
+                    HeaderValue::from_static("</style.css>; rel=preload; as=style"),
+                );
+                let res = req
+                    .head()
+                    .send_informational(StatusCode::EARLY_HINTS, &hints)
+                    .and_then(|()| {
+                        req.head().send_informational(
+                            StatusCode::EARLY_HINTS,
+                            &header::HeaderMap::new(),
+                        )
+                    });
+                assert!(
+                    req.head()
+                        .send_informational(StatusCode::OK, &hints)
+                        .is_err()
+                );
+                if res.is_ok() {
+                    Ok::<_, io::Error>(Response::Ok().body("final"))
+                } else {
+                    Ok::<_, io::Error>(Response::Ok().body("unsupported"))
+                }
+            }))
+        },
+        SharedCfg::new("SRV")
+            .add(HttpServiceConfig::new().set_keepalive(KeepAlive::Disabled)),
+    )
+    .await;
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(b"GET /test HTTP/1.1\r\n\r\n");
+    let mut data = String::new();
+    let _ = stream.read_to_string(&mut data);
+    assert!(data.starts_with(
+        "HTTP/1.1 103 Early Hints\r\nlink: </style.css>; rel=preload; as=style\r\n\r\n\
+         HTTP/1.1 103 Early Hints\r\n\r\n\
+         HTTP/1.1 200 OK\r\n"
+    ));
+    assert!(data.ends_with("final"));
+
+    // not supported for http/1.0
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(b"GET /test HTTP/1.0\r\n\r\n");
+    let mut data = String::new();
+    let _ = stream.read_to_string(&mut data);
+    assert!(data.starts_with("HTTP/1.0 200 OK\r\n"));
+    assert!(data.ends_with("unsupported"));
+}
+
+#[ntex::test]
+async fn test_io_buffer_sizes() {
+    let srv = test::server_with_config(
+        async || {
+            HttpService::h1(fn_service(|mut req: Request| async move {
+                let cfg = req.io().unwrap().cfg();
+                assert_eq!(cfg.read_buf().high, 64 * 1024);
+                assert_eq!(cfg.read_buf().low, 4 * 1024);
+                assert_eq!(cfg.write_buf().high, 128 * 1024);
+                // other io settings are preserved
+                assert_eq!(cfg.disconnect_timeout(), Seconds(3));
+
+                let mut size = 0;
+                while let Some(chunk) = req.payload().next().await {
+                    size += chunk.unwrap().len();
+                }
+                Ok::<_, io::Error>(Response::Ok().body(vec![b'x'; size]))
+            }))
+        },
+        SharedCfg::new("SRV")
+            .add(
+                HttpServiceConfig::new()
+                    .set_keepalive(KeepAlive::Disabled)
+                    .set_read_buf_size(64 * 1024, 4 * 1024)
+                    .set_write_buf_size(128 * 1024, 4 * 1024),
+            )
+            .add(IoConfig::new().set_disconnect_timeout(Seconds(3))),
+    )
+    .await;
+
+    let data = Bytes::from(vec![b'a'; 512 * 1024]);
+    let mut response = srv
+        .request(Method::POST, "/")
+        .send_body(data)
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let body = response.body().limit(1024 * 1024).await.unwrap();
+    assert_eq!(body.len(), 512 * 1024);
+}
+
 #[ntex::test]
 async fn test_chunked_payload() {
     let chunk_sizes = [32768, 32, 32768];
@@ -219,6 +423,80 @@ async fn test_slow_request2() {
     assert!(data.starts_with("HTTP/1.1 408 Request Timeout"));
 }
 
+/// Send data byte by byte, until server responds
+fn trickle(stream: &mut net::TcpStream, data: &[u8]) -> Vec<u8> {
+    stream
+        .set_read_timeout(Some(std::time::Duration::from_millis(250)))
+        .unwrap();
+
+    let mut buf = vec![0; 1024];
+    for b in data {
+        if stream.write_all(&[*b]).is_err() {
+            break;
+        }
+        if let Ok(size) = stream.read(&mut buf) {
+            buf.truncate(size);
+            return buf;
+        }
+    }
+    Vec::new()
+}
+
+#[ntex::test]
+async fn test_headers_timeout() {
+    const DATA: &[u8] = b"GET /test/tests/test HTTP/1.1\r\nx-header: 0123456789\r\n\r\n";
+
+    let srv = test::server_with_config(
+        async || HttpService::new(|_| Ready::Ok::<_, io::Error>(Response::Ok().finish())),
+        SharedCfg::new("SRV").add(
+            HttpServiceConfig::new()
+                .set_headers_timeout(Seconds(1))
+                .set_payload_timeout(Seconds(5)),
+        ),
+    )
+    .await;
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let data = trickle(&mut stream, DATA);
+    assert!(data.starts_with(b"HTTP/1.1 408 Request Timeout"));
+}
+
+#[ntex::test]
+async fn test_payload_timeout() {
+    let count = Arc::new(AtomicUsize::new(0));
+    let count2 = count.clone();
+
+    let srv = test::server_with_config(
+        async move || {
+            let count = count2.clone();
+            HttpService::h1(move |mut req: Request| {
+                let count = count.clone();
+                async move {
+                    let mut pl = req.take_payload();
+                    while let Some(item) = pl.recv().await {
+                        if item.is_err() {
+                            count.fetch_add(1, Ordering::Relaxed);
+                            break;
+                        }
+                    }
+                    Ok::<_, io::Error>(Response::Ok().finish())
+                }
+            })
+        },
+        SharedCfg::new("SRV").add(
+            HttpServiceConfig::new()
+                .set_headers_timeout(Seconds(5))
+                .set_payload_timeout(Seconds(1)),
+        ),
+    )
+    .await;
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(b"POST /test HTTP/1.1\r\ncontent-length: 64\r\n\r\n");
+    let _ = trickle(&mut stream, &[b'x'; 64]);
+    assert_eq!(count.load(Ordering::Acquire), 1);
+}
+
 #[ntex::test]
 async fn test_http1_malformed_request() {
     let srv = test_server(async || {
@@ -275,6 +553,50 @@ async fn test_http1_keepalive_timeout() {
     );
 }
 
+#[ntex::test]
+async fn test_http1_keepalive_fn() {
+    let peer = Arc::new(Mutex::new(None));
+    let peer2 = peer.clone();
+    let srv = test::server_with_config(
+        async || HttpService::h1(|_| Ready::Ok::<_, io::Error>(Response::Ok().finish())),
+        SharedCfg::new("SRV").add(
+            HttpServiceConfig::new()
+                .set_keepalive(1)
+                .set_keepalive_fn(move |req, _| {
+                    *peer2.lock().unwrap() = req.peer_addr();
+                    if req.headers.contains_key(header::AUTHORIZATION) {
+                        Some(Seconds(3))
+                    } else {
+                        None
+                    }
+                }),
+        ),
+    )
+    .await;
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(b"GET /test HTTP/1.1\r\nauthorization: token\r\n\r\n");
+    let mut data = vec![0; 1024];
+    let _ = stream.read(&mut data);
+    assert_eq!(&data[..17], b"HTTP/1.1 200 OK\r\n");
+    assert_eq!(*peer.lock().unwrap(), Some(stream.local_addr().unwrap()));
+    sleep(Millis(1500)).await;
+
+    // connection is still alive, next request uses default keep-alive
+    let _ = stream.write_all(b"GET /test HTTP/1.1\r\n\r\n");
+    let mut data = vec![0; 1024];
+    let _ = stream.read(&mut data);
+    assert_eq!(&data[..17], b"HTTP/1.1 200 OK\r\n");
+    sleep(Millis(1100)).await;
+
+    let mut data = vec![0; 1024];
+    let _ = stream.read(&mut data).unwrap();
+    assert_eq!(
+        &data[..49],
+        b"HTTP/1.1 408 Request Timeout\r\ncontent-length: 0\r\n"
+    );
+}
+
 /// Keep-alive must occure only while waiting complete request
 #[ntex::test]
 async fn test_http1_no_keepalive_during_response() {
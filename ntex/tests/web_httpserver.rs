@@ -255,6 +255,82 @@ async fn test_bind_uds() {
     sys.stop();
 }
 
+#[ntex::test]
+#[cfg(unix)]
+async fn test_bind_tcp_and_uds() {
+    let addr = TestServer::unused_addr();
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let sys = ntex::rt::System::new("test", ntex::rt::DefaultRuntime);
+
+        sys.run(move || {
+            let srv = HttpServer::new(async || {
+                App::new().service(
+                    web::resource("/")
+                        .route(web::to(|| async { HttpResponse::Ok().body("test") })),
+                )
+            })
+            .workers(1)
+            .shutdown_timeout(Seconds(1))
+            .stop_runtime()
+            .disable_signals()
+            .bind(format!("{addr}"))
+            .unwrap()
+            .bind_uds("/tmp/uds-test3")
+            .unwrap()
+            .run();
+            let _ = tx.send((srv, ntex::rt::System::current()));
+            Ok(())
+        })
+    });
+    let (srv, sys) = rx.recv().unwrap();
+
+    use ntex::{ServiceFactory, client};
+
+    // tcp
+    let client = client::Client::builder()
+        .connector::<&str>(client::Connector::default())
+        .build(ntex::SharedCfg::default())
+        .await
+        .unwrap();
+    let response = client.get(format!("http://{addr}")).send().await.unwrap();
+    assert!(response.status().is_success());
+    assert_eq!(
+        response.body().await.unwrap(),
+        ntex::util::Bytes::from_static(b"test")
+    );
+
+    // unix domain socket
+    let client = client::Client::builder()
+        .connector::<&str>(
+            client::Connector::default().connector(
+                ntex::service::fn_service(|_| async {
+                    Ok(
+                        rt::unix_connect("/tmp/uds-test3", ntex::SharedCfg::default())
+                            .await?,
+                    )
+                })
+                .map_init_err(|_| unreachable!()),
+            ),
+        )
+        .build(ntex::SharedCfg::default())
+        .await
+        .unwrap();
+    let response = client.get("http://localhost").send().await.unwrap();
+    assert!(response.status().is_success());
+    assert_eq!(
+        response.body().await.unwrap(),
+        ntex::util::Bytes::from_static(b"test")
+    );
+
+    // stop
+    srv.stop(false).await;
+
+    sleep(Duration::from_millis(25)).await;
+    sys.stop();
+}
+
 #[ntex::test]
 #[cfg(unix)]
 async fn test_listen_uds() {
@@ -12,7 +12,7 @@ use ntex::http::header::{
 };
 use ntex::http::{ConnectionType, HttpServiceConfig, Method, StatusCode, body::Body};
 use ntex::time::{Millis, Seconds, Sleep, sleep};
-use ntex::util::{Bytes, Ready, Stream};
+use ntex::util::{Bytes, Ready, Stream, stream_recv};
 use ntex::{SharedCfg, client, io::IoConfig};
 
 use ntex::web::{self, middleware::Compress, test};
@@ -325,6 +325,51 @@ async fn test_body_chunked_implicit() {
     assert_eq!(Bytes::from(dec), Bytes::from_static(STR.as_ref()));
 }
 
+#[ntex::test]
+async fn test_body_unbuffered() {
+    let srv = test::server_with(test::config().h1(), async || {
+        App::new()
+            .middleware(Compress::new(ContentEncoding::Gzip))
+            .service(web::resource("/").route(web::get().to(move || async {
+                let events = futures_util::stream::unfold(0, |n| async move {
+                    match n {
+                        0 => Some((
+                            Ok::<_, io::Error>(Bytes::from_static(b"data: 1\n\n")),
+                            1,
+                        )),
+                        1 => {
+                            sleep(Millis(500)).await;
+                            Some((Ok(Bytes::from_static(b"data: 2\n\n")), 2))
+                        }
+                        _ => None,
+                    }
+                });
+                HttpResponse::Ok().unbuffered().streaming(Box::pin(events))
+            })))
+    })
+    .await;
+
+    let mut response = srv
+        .get("/")
+        .no_decompress()
+        .header(ACCEPT_ENCODING, "gzip")
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    assert!(!response.headers().contains_key(CONTENT_ENCODING));
+
+    // first event arrives before second one is produced
+    let start = std::time::Instant::now();
+    let chunk = stream_recv(&mut response).await.unwrap().unwrap();
+    assert_eq!(chunk, Bytes::from_static(b"data: 1\n\n"));
+    assert!(start.elapsed() < std::time::Duration::from_millis(400));
+
+    let chunk = stream_recv(&mut response).await.unwrap().unwrap();
+    assert_eq!(chunk, Bytes::from_static(b"data: 2\n\n"));
+    assert!(stream_recv(&mut response).await.is_none());
+}
+
 #[ntex::test]
 async fn test_head_binary() {
     let srv = test::server_with(test::config().h1(), async || {
@@ -602,6 +647,38 @@ async fn test_reading_deflate_encoding_large_random() {
     assert_eq!(bytes, Bytes::from(data));
 }
 
+#[cfg(all(feature = "rustls", feature = "openssl"))]
+#[ntex::test]
+async fn test_sni_rustls() {
+    use ntex::web::types::Sni;
+
+    let srv = test::server_with(
+        test::config().rustls(rustls_utils::tls_acceptor()),
+        async || {
+            App::new().service(web::resource("/").route(web::to(|sni: Sni| async move {
+                HttpResponse::Ok().body(sni.into_inner().unwrap_or_default())
+            })))
+        },
+    )
+    .await;
+
+    let response = srv.get("/").send().await.unwrap();
+    assert!(response.status().is_success());
+    let bytes = response.body().await.unwrap();
+    assert_eq!(bytes, Bytes::from_static(b"localhost"));
+
+    let srv = test::server(async || {
+        App::new().service(web::resource("/").route(web::to(|sni: Sni| async move {
+            HttpResponse::Ok().body(format!("{:?}", sni.name()))
+        })))
+    })
+    .await;
+
+    let response = srv.get("/").send().await.unwrap();
+    let bytes = response.body().await.unwrap();
+    assert_eq!(bytes, Bytes::from_static(b"None"));
+}
+
 #[cfg(all(feature = "rustls", feature = "openssl"))]
 #[ntex::test]
 async fn test_reading_deflate_encoding_large_random_rustls() {
@@ -798,6 +875,140 @@ async fn test_slow_request() {
     assert!(data.starts_with("HTTP/1.1 408 Request Timeout"));
 }
 
+#[ntex::test]
+async fn test_expect_continue_limit() {
+    use std::net;
+
+    let srv = test::server(async || {
+        App::new()
+            .state(web::types::JsonConfig::default().limit(16))
+            .service(web::resource("/").route(web::post().to(
+                |body: web::types::Json<serde_json::Value>| async move {
+                    HttpResponse::Ok().json(&body.into_inner())
+                },
+            )))
+    })
+    .await;
+
+    // declared size is over the extractor's limit, payload is not requested
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(
+        b"POST / HTTP/1.1\r\ncontent-type: application/json\r\n\
+          content-length: 1024\r\nexpect: 100-continue\r\n\r\n",
+    );
+    let mut data = String::new();
+    let _ = stream.read_to_string(&mut data);
+    assert!(data.starts_with("HTTP/1.1 413 "), "{data}");
+    assert!(data.contains("connection: close\r\n"), "{data}");
+
+    // payload is requested after `100 Continue`
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(
+        b"POST / HTTP/1.1\r\ncontent-type: application/json\r\n\
+          content-length: 4\r\nexpect: 100-continue\r\nconnection: close\r\n\r\n",
+    );
+    let mut data = [0; 25];
+    let _ = stream.read_exact(&mut data[..]);
+    assert_eq!(&data, b"HTTP/1.1 100 Continue\r\n\r\n");
+
+    let _ = stream.write_all(b"true");
+    let mut data = String::new();
+    let _ = stream.read_to_string(&mut data);
+    assert!(data.starts_with("HTTP/1.1 200 OK\r\n"), "{data}");
+    assert!(data.ends_with("true"), "{data}");
+}
+
+#[ntex::test]
+async fn test_custom_upgrade() {
+    use ntex::codec::BytesCodec;
+    use std::net;
+
+    let srv = test::server(async || {
+        App::new().service(web::resource("/").route(web::to(
+            |req: HttpRequest| async move {
+                if !web::upgrade::is_requested(&req, "echo") {
+                    return Ok(HttpResponse::BadRequest().finish());
+                }
+                let io = web::upgrade::upgrade(&req, "echo")?;
+                ntex::rt::spawn(async move {
+                    while let Ok(Some(item)) = io.recv(&BytesCodec).await {
+                        if io.send(item, &BytesCodec).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+                Ok::<_, web::Error>(HttpResponse::Ok().finish())
+            },
+        )))
+    })
+    .await;
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    stream
+        .write_all(b"GET / HTTP/1.1\r\nconnection: upgrade\r\nupgrade: echo\r\n\r\n")
+        .unwrap();
+    let mut buf = [0; 1024];
+    let n = stream.read(&mut buf).unwrap();
+    let data = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+    assert!(data.starts_with("http/1.1 101 switching protocols"));
+    assert!(data.contains("upgrade: echo"));
+    assert!(data.ends_with("\r\n\r\n"));
+
+    stream.write_all(b"hello").unwrap();
+    let n = stream.read(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"hello");
+
+    // not an upgrade request
+    let response = srv.get("/").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[ntex::test]
+async fn test_custom_upgrade_pipelined_payload() {
+    use ntex::codec::BytesCodec;
+    use std::net;
+
+    let srv = test::server_with(
+        test::config().h1().server_cfg(
+            SharedCfg::new("SRV")
+                .add(HttpServiceConfig::new().set_keep_upgrade_payload(true)),
+        ),
+        async || {
+            App::new().service(web::resource("/").route(web::to(
+                |req: HttpRequest| async move {
+                    let io = web::upgrade::upgrade(&req, "echo")?;
+                    ntex::rt::spawn(async move {
+                        while let Ok(Some(item)) = io.recv(&BytesCodec).await {
+                            if io.send(item, &BytesCodec).await.is_err() {
+                                break;
+                            }
+                        }
+                    });
+                    Ok::<_, web::Error>(HttpResponse::Ok().finish())
+                },
+            )))
+        },
+    )
+    .await;
+
+    // payload is sent with upgrade request
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    stream
+        .write_all(b"GET / HTTP/1.1\r\nconnection: upgrade\r\nupgrade: echo\r\n\r\nhello")
+        .unwrap();
+
+    let mut data = Vec::new();
+    let mut buf = [0; 1024];
+    while !data.ends_with(b"hello") {
+        let n = stream.read(&mut buf).unwrap();
+        assert!(n > 0);
+        data.extend_from_slice(&buf[..n]);
+    }
+    let data = String::from_utf8_lossy(&data).to_lowercase();
+    assert!(data.starts_with("http/1.1 101 switching protocols"));
+    assert!(data.ends_with("\r\n\r\nhello"));
+}
+
 #[ntex::test]
 async fn test_custom_error() {
     #[derive(Debug, thiserror::Error)]